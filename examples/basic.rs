@@ -11,10 +11,7 @@ fn main() {
         .run();
 }
 
-fn setup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-) {
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Camera
     commands.spawn((
         Camera3d::default(),
@@ -35,4 +32,4 @@ fn setup(
 
     // Spawn the scene
     commands.spawn(SceneRoot(scene));
-}
\ No newline at end of file
+}
@@ -0,0 +1,31 @@
+//! Offline conversion of an FBX file to a Bevy `.scn.ron` scene.
+//!
+//! Usage: `cargo run --example export_scene --features scene_export -- input.fbx output.scn.ron`
+
+use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
+use bevy::prelude::*;
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxPlugin, write_fbx_scene};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let (Some(input), Some(output)) = (args.next(), args.next()) else {
+        eprintln!("usage: export_scene <input.fbx> <output.scn.ron>");
+        std::process::exit(2);
+    };
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), FbxPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<AnimationClip>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Image>()
+        .init_asset::<Scene>()
+        .init_asset::<SkinnedMeshInverseBindposes>();
+
+    let bytes = std::fs::read(&input).expect("Failed to read FBX file");
+    let fbx = Fbx::from_bytes(&bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import FBX file");
+    write_fbx_scene(&fbx, app.world(), &output).expect("Failed to write scene");
+
+    println!("Wrote {output}");
+}
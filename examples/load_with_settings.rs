@@ -1,7 +1,7 @@
 //! Example demonstrating custom loader settings for FBX files.
 
 use bevy::prelude::*;
-use bevy_ufbx::{FbxPlugin, FbxLoaderSettings, Fbx};
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxPlugin};
 
 fn main() {
     App::new()
@@ -12,10 +12,7 @@ fn main() {
         .run();
 }
 
-fn setup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-) {
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Camera
     commands.spawn((
         Camera3d::default(),
@@ -75,4 +72,4 @@ fn handle_loaded_fbx(
             *spawned = true;
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,47 @@
+//! Example loading an FBX model in the browser.
+//!
+//! Build for `wasm32-unknown-unknown` and serve it with e.g.
+//! [`wasm-server-runner`](https://github.com/jakobhellermann/wasm-server-runner):
+//!
+//! ```sh
+//! CC_wasm32_unknown_unknown=clang cargo run --example web --target wasm32-unknown-unknown
+//! ```
+
+use bevy::asset::AssetMetaCheck;
+use bevy::prelude::*;
+use bevy_ufbx::FbxPlugin;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(AssetPlugin {
+                    // Web servers answer missing `.meta` files with errors
+                    meta_check: AssetMetaCheck::Never,
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        fit_canvas_to_parent: true,
+                        ..default()
+                    }),
+                    ..default()
+                }),
+        )
+        .add_plugins(FbxPlugin)
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn(SceneRoot(asset_server.load("cube.fbx#Scene0")));
+}
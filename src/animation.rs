@@ -0,0 +1,1551 @@
+//! Animation clip extraction for FBX files.
+//!
+//! Each FBX animation stack is baked with ufbx and converted into a Bevy
+//! [`AnimationClip`] with translation/rotation/scale curves per node.
+
+use crate::bind_pose::bone_node_ids;
+use crate::error::FbxError;
+use crate::label::{FbxAssetLabel, label_names};
+use crate::light::FbxLightDecay;
+use crate::loader::FbxLoaderSettings;
+use crate::material::convert_property_value;
+use crate::mesh::has_blend_shapes;
+use crate::morph::{blend_channels, morph_weight_keys};
+use crate::sink::FbxAssetSink;
+use crate::types::{FbxPropertyValue, FbxSceneMap};
+use crate::utils::{camera_lens, convert_matrix, convert_transform, glob_match};
+use bevy::animation::animatable::Animatable;
+use bevy::animation::animation_curves::{
+    AnimatableCurve, AnimatableKeyframeCurve, AnimatableProperty, AnimationEntityMut, EvaluatorId,
+    WeightsCurve,
+};
+use bevy::animation::gltf_curves::WideLinearKeyframeCurve;
+use bevy::animation::{
+    AnimatedBy, AnimationClip, AnimationEvaluationError, AnimationEvent, AnimationTargetId,
+    animated_field,
+};
+use bevy::asset::Handle;
+use bevy::ecs::component::Mutable;
+#[cfg(feature = "depth_of_field")]
+use bevy::post_process::dof::DepthOfField;
+use bevy::prelude::*;
+use core::any::TypeId;
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Sample rate used when baking constraints, in frames per second.
+const CONSTRAINT_SAMPLE_RATE: f64 = 30.0;
+
+/// Sample rate used when resampling rotations of nodes with pre/post
+/// rotations or a non-XYZ rotation order, in frames per second.
+const ROTATION_SAMPLE_RATE: f64 = 30.0;
+
+/// Baked property keys by element id and property name.
+pub type PropertyKeys = HashMap<u32, HashMap<String, Vec<(f32, Vec3)>>>;
+
+/// Baked translation/rotation/scale keys of a single node.
+#[derive(Debug, Clone, Default)]
+pub struct NodeKeys {
+    pub translation: Vec<(f32, Vec3)>,
+    pub rotation: Vec<(f32, Quat)>,
+    pub scale: Vec<(f32, Vec3)>,
+}
+
+/// Event marker authored in an FBX animation, e.g. a footstep or VFX trigger.
+///
+/// Registered on clips as an animation event when
+/// [`FbxLoaderSettings::register_animation_events`] is set.
+#[derive(AnimationEvent, Debug, Clone, PartialEq, Reflect)]
+pub struct FbxAnimationEvent {
+    /// Time in seconds
+    pub time: f32,
+    /// Event name with the event prefix removed
+    pub name: String,
+    /// Key value of an event property, or the `payload` user property of an
+    /// event null
+    pub payload: Option<FbxPropertyValue>,
+}
+
+/// How root bone translation is handled in baked clips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RootMotionMode {
+    /// Leave the root bone's translation in the clip
+    #[default]
+    Keep,
+    /// Remove the root bone's planar (XZ) translation from the clip and store
+    /// it in [`Fbx::root_motion`](crate::Fbx::root_motion)
+    ExtractToCurve,
+    /// Remove the root bone's planar (XZ) translation from the clip
+    Remove,
+}
+
+/// Planar root motion extracted from a clip.
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxRootMotion {
+    /// Name of the root bone the motion was taken from
+    pub bone: String,
+    /// Planar translation relative to the first key, as `(time, offset)` keys
+    pub translation: Vec<(f32, Vec3)>,
+}
+
+impl FbxRootMotion {
+    /// Sample the root motion offset at `time`, clamping outside the keys.
+    pub fn sample(&self, time: f32) -> Vec3 {
+        let keys = &self.translation;
+        let Some(&(first_time, first)) = keys.first() else {
+            return Vec3::ZERO;
+        };
+        if time <= first_time {
+            return first;
+        }
+        for pair in keys.windows(2) {
+            let ((t0, v0), (t1, v1)) = (pair[0], pair[1]);
+            if time <= t1 {
+                let s = if t1 > t0 {
+                    (time - t0) / (t1 - t0)
+                } else {
+                    1.0
+                };
+                return v0.lerp(v1, s);
+            }
+        }
+        keys.last().map_or(Vec3::ZERO, |&(_, v)| v)
+    }
+}
+
+/// Remove the planar translation from a root bone's keys.
+///
+/// The X and Z components of every translation key are pinned to the first
+/// key; the removed offsets are returned as `(time, offset)` keys.
+pub fn extract_root_motion(keys: &mut NodeKeys) -> Vec<(f32, Vec3)> {
+    let Some(&(_, start)) = keys.translation.first() else {
+        return Vec::new();
+    };
+
+    keys.translation
+        .iter_mut()
+        .map(|(time, translation)| {
+            let offset = Vec3::new(translation.x - start.x, 0.0, translation.z - start.z);
+            translation.x = start.x;
+            translation.z = start.z;
+            (*time, offset)
+        })
+        .collect()
+}
+
+/// Index of the root bone used for root motion.
+///
+/// This is the node named [`FbxLoaderSettings::root_motion_bone`], or else the
+/// first bone whose parent is not a bone.
+pub fn find_root_bone(scene: &ufbx::Scene, settings: &FbxLoaderSettings) -> Option<usize> {
+    let nodes = scene.nodes.as_ref();
+    match settings.root_motion_bone.as_deref() {
+        Some(name) => nodes
+            .iter()
+            .position(|node| node.element.name.as_ref() as &str == name),
+        None => nodes.iter().position(|node| {
+            node.bone.is_some()
+                && node
+                    .parent
+                    .as_ref()
+                    .is_none_or(|parent| parent.bone.is_none())
+        }),
+    }
+}
+
+/// Tolerances for removing keys that linear interpolation reproduces.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxKeyframeReduction {
+    /// Maximum translation error in meters
+    pub translation_tolerance: f32,
+    /// Maximum rotation error in radians
+    pub rotation_tolerance: f32,
+    /// Maximum scale error
+    pub scale_tolerance: f32,
+}
+
+impl Default for FbxKeyframeReduction {
+    fn default() -> Self {
+        Self {
+            translation_tolerance: 0.0001,
+            rotation_tolerance: 0.0001,
+            scale_tolerance: 0.0001,
+        }
+    }
+}
+
+/// Nodes whose tracks of one channel are kept by an [`FbxTrackFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FbxTrackMask {
+    /// Keep the tracks of every node
+    #[default]
+    All,
+    /// Keep the tracks of the root bone (see [`find_root_bone`]) and of
+    /// nodes that are not bones
+    RootBoneOnly,
+    /// Drop the tracks of every node
+    None,
+}
+
+impl FbxTrackMask {
+    /// Whether tracks of a node are kept.
+    pub fn keeps(self, is_bone: bool, is_root_bone: bool) -> bool {
+        match self {
+            FbxTrackMask::All => true,
+            FbxTrackMask::RootBoneOnly => !is_bone || is_root_bone,
+            FbxTrackMask::None => false,
+        }
+    }
+}
+
+/// Translation, rotation and scale tracks kept in baked clips.
+///
+/// Dropped channels keep the node's rest value when the clip plays, e.g.
+/// stripping scale removes the squash artifacts of noisy scale keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxTrackFilter {
+    pub translation: FbxTrackMask,
+    pub rotation: FbxTrackMask,
+    pub scale: FbxTrackMask,
+}
+
+impl FbxTrackFilter {
+    /// Filter dropping scale tracks and the translation of every bone but
+    /// the root, the usual cleanup for Maya character clips.
+    pub fn rotation_only_bones() -> Self {
+        Self {
+            translation: FbxTrackMask::RootBoneOnly,
+            rotation: FbxTrackMask::All,
+            scale: FbxTrackMask::None,
+        }
+    }
+
+    /// Remove the keys of the channels this filter drops for a node.
+    pub fn apply(&self, keys: &mut NodeKeys, is_bone: bool, is_root_bone: bool) {
+        if !self.translation.keeps(is_bone, is_root_bone) {
+            keys.translation.clear();
+        }
+        if !self.rotation.keeps(is_bone, is_root_bone) {
+            keys.rotation.clear();
+        }
+        if !self.scale.keeps(is_bone, is_root_bone) {
+            keys.scale.clear();
+        }
+    }
+}
+
+/// Track filter of the clip baked from the animation stack `name`.
+///
+/// The first rule of [`FbxLoaderSettings::clip_track_filters`] matching the
+/// name applies; otherwise [`FbxLoaderSettings::track_filter`].
+pub fn clip_track_filter<'a>(name: &str, settings: &'a FbxLoaderSettings) -> &'a FbxTrackFilter {
+    settings
+        .clip_track_filters
+        .iter()
+        .find(|(pattern, _)| glob_match(pattern, name))
+        .map_or(&settings.track_filter, |(_, filter)| filter)
+}
+
+/// Bone subset an additional clip is generated for, e.g. the upper body for
+/// aim offsets layered over locomotion, see
+/// [`FbxLoaderSettings::bone_masks`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxBoneMask {
+    /// Name of the mask, used in the `Animation{}/Mask/{}` label
+    pub name: String,
+    /// Glob patterns (`*`, `?`) of node names in the mask
+    pub include: Vec<String>,
+    /// Glob patterns of node names left out of the mask
+    pub exclude: Vec<String>,
+    /// Whether nodes below a matching node follow it, e.g. the whole upper
+    /// body from `Spine` with `Neck` and its children excluded
+    pub include_children: bool,
+}
+
+impl FbxBoneMask {
+    /// Whether the node with the given names on its path from the scene
+    /// root belongs to the mask.
+    ///
+    /// The node's own name decides first; with `include_children` the
+    /// nearest matching ancestor decides otherwise. Exclusion wins over
+    /// inclusion on the same node.
+    pub fn contains_path(&self, path: &[&str]) -> bool {
+        let matches = |patterns: &[String], name: &str| {
+            patterns.iter().any(|pattern| glob_match(pattern, name))
+        };
+        let ancestors = if self.include_children { path.len() } else { 1 };
+        for &name in path.iter().rev().take(ancestors) {
+            if matches(&self.exclude, name) {
+                return false;
+            }
+            if matches(&self.include, name) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether `node` belongs to the mask.
+    pub fn contains(&self, node: &ufbx::Node) -> bool {
+        let names = node_path_names(node);
+        let path: Vec<&str> = names.iter().map(String::as_str).collect();
+        self.contains_path(&path)
+    }
+}
+
+/// Key counts of a clip before and after keyframe reduction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub struct FbxKeyframeStats {
+    pub keys_before: usize,
+    pub keys_after: usize,
+}
+
+impl FbxKeyframeStats {
+    /// Number of keys removed by reduction.
+    pub fn removed(&self) -> usize {
+        self.keys_before - self.keys_after
+    }
+}
+
+/// Time range and frame rate of a clip, for showing DCC frame numbers.
+///
+/// Times are in seconds on the FBX timeline, matching the clip's key times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+pub struct FbxClipTiming {
+    /// Start of the animation stack
+    pub start: f32,
+    /// End of the animation stack
+    pub end: f32,
+    /// Frames per second of the file's time mode
+    pub frame_rate: f32,
+}
+
+impl FbxClipTiming {
+    /// Length of the clip in seconds.
+    pub fn duration(&self) -> f32 {
+        self.end - self.start
+    }
+
+    /// DCC frame number at `time`, e.g. 24.0 at one second into a 24 fps file.
+    pub fn frame_at(&self, time: f32) -> f32 {
+        time * self.frame_rate
+    }
+
+    /// First frame of the clip.
+    pub fn start_frame(&self) -> f32 {
+        self.frame_at(self.start)
+    }
+
+    /// Last frame of the clip.
+    pub fn end_frame(&self) -> f32 {
+        self.frame_at(self.end)
+    }
+}
+
+impl NodeKeys {
+    /// Total number of translation, rotation and scale keys.
+    pub fn len(&self) -> usize {
+        self.translation.len() + self.rotation.len() + self.scale.len()
+    }
+
+    /// Whether there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove keys that linear interpolation reproduces within the tolerances.
+    pub fn reduce(&mut self, reduction: &FbxKeyframeReduction) {
+        self.translation = reduce_keys(
+            &self.translation,
+            reduction.translation_tolerance,
+            Vec3::lerp,
+            Vec3::distance,
+        );
+        self.rotation = reduce_keys(
+            &self.rotation,
+            reduction.rotation_tolerance,
+            Quat::slerp,
+            Quat::angle_between,
+        );
+        self.scale = reduce_keys(
+            &self.scale,
+            reduction.scale_tolerance,
+            Vec3::lerp,
+            Vec3::distance,
+        );
+    }
+}
+
+/// Remove keys whose value is within `tolerance` of the interpolation between
+/// the surrounding kept keys. The first and last keys are always kept.
+pub fn reduce_keys<T: Copy>(
+    keys: &[(f32, T)],
+    tolerance: f32,
+    interpolate: impl Fn(T, T, f32) -> T,
+    distance: impl Fn(T, T) -> f32,
+) -> Vec<(f32, T)> {
+    if keys.len() <= 2 {
+        return keys.to_vec();
+    }
+
+    // Whether every key strictly between `start` and `end` can be dropped
+    let fits = |start: usize, end: usize| {
+        let (t0, v0) = keys[start];
+        let (t1, v1) = keys[end];
+        keys[start + 1..end].iter().all(|&(t, v)| {
+            let s = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
+            distance(interpolate(v0, v1, s), v) <= tolerance
+        })
+    };
+
+    let mut reduced = vec![keys[0]];
+    let mut start = 0;
+    for end in 2..keys.len() {
+        if !fits(start, end) {
+            start = end - 1;
+            reduced.push(keys[start]);
+        }
+    }
+    reduced.push(keys[keys.len() - 1]);
+    reduced
+}
+
+/// Names of the nodes on the path from the scene root to `node`.
+pub fn node_path_names(node: &ufbx::Node) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut current = Some(node);
+    while let Some(node) = current {
+        if node.is_root {
+            break;
+        }
+        names.push(node.element.name.to_string());
+        current = node.parent.as_deref();
+    }
+    names.reverse();
+    names
+}
+
+/// Animation target id of a node, built from the names on its path from the root.
+pub fn node_target_id(node: &ufbx::Node) -> AnimationTargetId {
+    let names: Vec<Name> = node_path_names(node).into_iter().map(Name::new).collect();
+    AnimationTargetId::from_names(names.iter())
+}
+
+/// Animation target id used in clips, with bone names retargeted through
+/// [`FbxLoaderSettings::bone_strip_prefixes`] and
+/// [`FbxLoaderSettings::bone_rename_map`].
+pub fn clip_target_id(node: &ufbx::Node, settings: &FbxLoaderSettings) -> AnimationTargetId {
+    let names: Vec<Name> = node_path_names(node)
+        .iter()
+        .map(|name| Name::new(retarget_bone_name(name, settings)))
+        .collect();
+    AnimationTargetId::from_names(names.iter())
+}
+
+/// Rewrite a bone name for retargeting.
+///
+/// The rename map is consulted with the original name first, then with the
+/// first matching strip prefix removed.
+pub fn retarget_bone_name(name: &str, settings: &FbxLoaderSettings) -> String {
+    if let Some(renamed) = settings.bone_rename_map.get(name) {
+        return renamed.clone();
+    }
+
+    let stripped = settings
+        .bone_strip_prefixes
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix.as_str()))
+        .unwrap_or(name);
+
+    settings
+        .bone_rename_map
+        .get(stripped)
+        .cloned()
+        .unwrap_or_else(|| stripped.to_string())
+}
+
+/// Animation target id of the node at a `/`-separated [`FbxNodePath`](crate::FbxNodePath),
+/// retargeted like [`clip_target_id`].
+pub fn path_target_id(path: &str, settings: &FbxLoaderSettings) -> AnimationTargetId {
+    let names: Vec<Name> = path
+        .split('/')
+        .map(|name| Name::new(retarget_bone_name(name, settings)))
+        .collect();
+    AnimationTargetId::from_names(names.iter())
+}
+
+/// Make the entities of a spawned scene animatable by `player`.
+///
+/// Inserts an [`AnimationTargetId`] and [`AnimatedBy`] on every entity of
+/// `scene_map`, derived from its node path the same way clip targets are, so
+/// clips from any file sharing the node names play on the scene, e.g.
+/// animation-only files exported from a shared rig. `settings` should be the
+/// ones the clips were loaded with, so bone renames match.
+pub fn bind_animation_targets(
+    commands: &mut Commands,
+    scene_map: &FbxSceneMap,
+    player: Entity,
+    settings: &FbxLoaderSettings,
+) {
+    for (path, &entity) in &scene_map.entities {
+        commands
+            .entity(entity)
+            .insert((path_target_id(path, settings), AnimatedBy(player)));
+    }
+}
+
+/// Animation clips of a file, from [`process_animations`].
+///
+/// Per-clip data is indexed like [`ProcessedAnimations::animations`].
+#[derive(Default)]
+pub struct ProcessedAnimations {
+    pub animations: Vec<Handle<AnimationClip>>,
+    pub named_animations: HashMap<Box<str>, Handle<AnimationClip>>,
+    /// Clips restricted to each of [`FbxLoaderSettings::bone_masks`], keyed
+    /// by mask name
+    pub masked_animations: HashMap<Box<str>, Vec<Handle<AnimationClip>>>,
+    /// Event markers of each clip
+    pub animation_events: Vec<Vec<FbxAnimationEvent>>,
+    /// Extracted root motion of each clip
+    pub root_motion: Vec<Option<FbxRootMotion>>,
+    /// Keyframe reduction stats of each clip
+    pub keyframe_stats: Vec<FbxKeyframeStats>,
+    /// Timing of each clip
+    pub clip_timings: Vec<FbxClipTiming>,
+}
+
+/// Process all animation stacks into animation clips.
+pub fn process_animations(
+    scene: &ufbx::Scene,
+    settings: &FbxLoaderSettings,
+    load_context: &mut impl FbxAssetSink,
+) -> Result<ProcessedAnimations, FbxError> {
+    let mut animations = Vec::new();
+    let mut named_animations = HashMap::new();
+    let mut masked_animations: HashMap<Box<str>, Vec<Handle<AnimationClip>>> = HashMap::new();
+    let mut animation_events = Vec::new();
+    let mut root_motion = Vec::new();
+    let mut keyframe_stats = Vec::new();
+    let mut clip_timings = Vec::new();
+    let frame_rate = scene.settings.frames_per_second as f32;
+    let root_bone = find_root_bone(scene, settings);
+
+    let names = label_names(
+        settings,
+        scene
+            .anim_stacks
+            .as_ref()
+            .iter()
+            .map(|stack| stack.element.name.as_ref() as &str),
+    );
+    for (index, stack) in scene.anim_stacks.as_ref().iter().enumerate() {
+        let (mut node_keys, property_keys) = bake_animation(scene, &stack.anim)?;
+        resample_rotations(
+            scene,
+            &stack.anim,
+            stack.time_begin,
+            stack.time_end,
+            &mut node_keys,
+        );
+
+        if settings.bake_constraints {
+            bake_constraints(
+                scene,
+                &stack.anim,
+                stack.time_begin,
+                stack.time_end,
+                &mut node_keys,
+            )?;
+        }
+        convert_world_space_keys(
+            scene,
+            &stack.anim,
+            stack.time_begin,
+            stack.time_end,
+            &mut node_keys,
+        );
+
+        let motion = root_bone
+            .filter(|_| settings.root_motion != RootMotionMode::Keep)
+            .and_then(|root_bone| {
+                let keys = node_keys.get_mut(&root_bone)?;
+                let translation = extract_root_motion(keys);
+                (settings.root_motion == RootMotionMode::ExtractToCurve).then(|| FbxRootMotion {
+                    bone: scene.nodes[root_bone].element.name.to_string(),
+                    translation,
+                })
+            });
+        root_motion.push(motion);
+
+        let track_filter = clip_track_filter(stack.element.name.as_ref(), settings);
+        for (&node_index, keys) in node_keys.iter_mut() {
+            let is_bone = scene
+                .nodes
+                .as_ref()
+                .get(node_index)
+                .is_some_and(|node| node.bone.is_some());
+            track_filter.apply(keys, is_bone, Some(node_index) == root_bone);
+        }
+
+        let keys_before = node_keys.values().map(NodeKeys::len).sum();
+        if let Some(reduction) = &settings.keyframe_reduction {
+            for keys in node_keys.values_mut() {
+                keys.reduce(reduction);
+            }
+        }
+        keyframe_stats.push(FbxKeyframeStats {
+            keys_before,
+            keys_after: node_keys.values().map(NodeKeys::len).sum(),
+        });
+
+        // Node transforms only; morph weights and properties stay in the full clip
+        for mask in &settings.bone_masks {
+            let masked_keys: HashMap<usize, NodeKeys> = node_keys
+                .iter()
+                .filter(|&(&node_index, _)| {
+                    scene
+                        .nodes
+                        .as_ref()
+                        .get(node_index)
+                        .is_some_and(|node| mask.contains(node))
+                })
+                .map(|(&node_index, keys)| (node_index, keys.clone()))
+                .collect();
+            let label = FbxAssetLabel::MaskedAnimation {
+                animation: index,
+                mask: mask.name.clone(),
+            };
+            let handle = load_context
+                .add_labeled_asset(label.to_string(), build_clip(scene, &masked_keys, settings));
+            masked_animations
+                .entry(Box::from(mask.name.as_str()))
+                .or_default()
+                .push(handle);
+        }
+
+        let mut clip = build_clip(scene, &node_keys, settings);
+        add_morph_weight_curves(&mut clip, scene, &property_keys, settings);
+        if settings.animate_properties {
+            add_property_curves(&mut clip, scene, &property_keys, settings);
+        }
+        let events = match settings.animation_event_prefix.as_deref() {
+            Some(prefix) => extract_animation_events(scene, stack, prefix),
+            None => Vec::new(),
+        };
+        if settings.register_animation_events {
+            for event in &events {
+                clip.add_event(event.time, event.clone());
+            }
+        }
+        animation_events.push(events);
+
+        let handle = load_context.add_named_labeled_asset(
+            FbxAssetLabel::Animation(index),
+            names.get(index).and_then(Option::as_deref),
+            clip,
+        );
+
+        if !stack.element.name.is_empty() {
+            named_animations.insert(Box::from(stack.element.name.as_ref()), handle.clone());
+        }
+        animations.push(handle);
+        clip_timings.push(FbxClipTiming {
+            start: stack.time_begin as f32,
+            end: stack.time_end as f32,
+            frame_rate,
+        });
+    }
+
+    Ok(ProcessedAnimations {
+        animations,
+        named_animations,
+        masked_animations,
+        animation_events,
+        root_motion,
+        keyframe_stats,
+        clip_timings,
+    })
+}
+
+/// Extract event markers from an animation stack.
+///
+/// Every key of an animated property whose name starts with `prefix` becomes
+/// an event named after the property. Every key time of an animated node
+/// whose name starts with `prefix` (an event null) becomes an event named
+/// after the node. Events are sorted by time.
+pub fn extract_animation_events(
+    scene: &ufbx::Scene,
+    stack: &ufbx::AnimStack,
+    prefix: &str,
+) -> Vec<FbxAnimationEvent> {
+    let mut events = Vec::new();
+    let mut null_keys: BTreeMap<usize, Vec<f64>> = BTreeMap::new();
+
+    for layer in stack.layers.as_ref().iter() {
+        for anim_prop in layer.anim_props.as_ref().iter() {
+            let element = &anim_prop.element;
+            let prop_name: &str = anim_prop.prop_name.as_ref();
+            let element_name: &str = element.name.as_ref();
+            let curves = anim_prop
+                .anim_value
+                .curves
+                .iter()
+                .flatten()
+                .filter(|curve| !curve.keyframes.is_empty());
+
+            if let Some(name) = prop_name.strip_prefix(prefix) {
+                // Event property: one event per key
+                for curve in curves.take(1) {
+                    for key in curve.keyframes.as_ref().iter() {
+                        events.push(FbxAnimationEvent {
+                            time: key.time as f32,
+                            name: name.to_string(),
+                            payload: Some(FbxPropertyValue::Number(key.value)),
+                        });
+                    }
+                }
+            } else if element.type_ == ufbx::ElementType::Node && element_name.starts_with(prefix) {
+                // Event null: one event per distinct key time
+                let times = null_keys.entry(element.typed_id as usize).or_default();
+                for curve in curves {
+                    times.extend(curve.keyframes.as_ref().iter().map(|key| key.time));
+                }
+            }
+        }
+    }
+
+    for (node_index, mut times) in null_keys {
+        let Some(node) = scene.nodes.as_ref().get(node_index) else {
+            continue;
+        };
+        let node_name: &str = node.element.name.as_ref();
+        let name = node_name.strip_prefix(prefix).unwrap_or_default();
+        let payload = node
+            .element
+            .props
+            .props
+            .as_ref()
+            .iter()
+            .find(|prop| prop.name.as_ref() as &str == "payload")
+            .and_then(convert_property_value);
+
+        times.sort_by(f64::total_cmp);
+        times.dedup();
+        events.extend(times.into_iter().map(|time| FbxAnimationEvent {
+            time: time as f32,
+            name: name.to_string(),
+            payload: payload.clone(),
+        }));
+    }
+
+    events.sort_by(|a, b| a.time.total_cmp(&b.time).then_with(|| a.name.cmp(&b.name)));
+    events
+}
+
+/// Bake the animated nodes of an animation, keyed by node index.
+pub fn bake_node_keys(
+    scene: &ufbx::Scene,
+    anim: &ufbx::Anim,
+) -> Result<HashMap<usize, NodeKeys>, FbxError> {
+    bake_animation(scene, anim).map(|(node_keys, _)| node_keys)
+}
+
+/// Bake the animated nodes and element properties of an animation.
+///
+/// Node keys are keyed by node index and property keys by element id and
+/// property name; scalar properties are stored in the `x` component.
+pub fn bake_animation(
+    scene: &ufbx::Scene,
+    anim: &ufbx::Anim,
+) -> Result<(HashMap<usize, NodeKeys>, PropertyKeys), FbxError> {
+    let baked = ufbx::bake_anim(scene, anim, ufbx::BakeOpts::default())
+        .map_err(|e| FbxError::UfbxError(format!("{:?}", e)))?;
+
+    let mut node_keys = HashMap::new();
+    for baked_node in baked.nodes.as_ref().iter() {
+        let keys = NodeKeys {
+            translation: baked_node
+                .translation_keys
+                .as_ref()
+                .iter()
+                .map(|key| (key.time as f32, convert_vec3(&key.value)))
+                .collect(),
+            rotation: baked_node
+                .rotation_keys
+                .as_ref()
+                .iter()
+                .map(|key| (key.time as f32, convert_quat(&key.value)))
+                .collect(),
+            scale: baked_node
+                .scale_keys
+                .as_ref()
+                .iter()
+                .map(|key| (key.time as f32, convert_vec3(&key.value)))
+                .collect(),
+        };
+        node_keys.insert(baked_node.typed_id as usize, keys);
+    }
+
+    let mut property_keys: PropertyKeys = HashMap::new();
+    for baked_element in baked.elements.as_ref().iter() {
+        let properties = property_keys.entry(baked_element.element_id).or_default();
+        for baked_prop in baked_element.props.as_ref().iter() {
+            let keys = baked_prop
+                .keys
+                .as_ref()
+                .iter()
+                .map(|key| (key.time as f32, convert_vec3(&key.value)))
+                .collect();
+            properties.insert(baked_prop.name.to_string(), keys);
+        }
+    }
+
+    Ok((node_keys, property_keys))
+}
+
+/// Resample the rotation keys of nodes whose rotation isn't a plain XYZ Euler
+/// rotation.
+///
+/// FBX interpolates each Euler channel separately and then composes it with
+/// the node's pre/post rotation (see [`compose_fbx_rotation`]). Between keys
+/// this differs from slerping the composed quaternions, so such nodes are
+/// evaluated through ufbx's transform model at a fixed sample rate instead.
+pub fn resample_rotations(
+    scene: &ufbx::Scene,
+    anim: &ufbx::Anim,
+    time_begin: f64,
+    time_end: f64,
+    node_keys: &mut HashMap<usize, NodeKeys>,
+) {
+    let frame_count = (((time_end - time_begin) * ROTATION_SAMPLE_RATE).ceil() as usize).max(1);
+    for (&node_index, keys) in node_keys.iter_mut() {
+        let Some(node) = scene.nodes.as_ref().get(node_index) else {
+            continue;
+        };
+        if keys.rotation.len() < 2 || !needs_rotation_resampling(node) {
+            continue;
+        }
+
+        keys.rotation = (0..=frame_count)
+            .map(|frame| {
+                let time = (time_begin + frame as f64 / ROTATION_SAMPLE_RATE).min(time_end);
+                let transform = ufbx::evaluate_transform(anim, node, time);
+                (time as f32, convert_quat(&transform.rotation))
+            })
+            .collect();
+    }
+}
+
+/// Node indices of the nodes spawned as bones parented to another bone,
+/// whose entities use local transforms; every other node is spawned with
+/// its world transform.
+pub fn parented_bone_nodes(scene: &ufbx::Scene) -> HashSet<usize> {
+    let skinned = bone_node_ids(scene);
+    let is_bone = |node: &ufbx::Node| {
+        skinned.contains(&node.element.element_id) || node.attrib_type == ufbx::ElementType::Bone
+    };
+    scene
+        .nodes
+        .as_ref()
+        .iter()
+        .filter(|node| is_bone(node) && node.parent.as_deref().is_some_and(is_bone))
+        .map(|node| node.element.typed_id as usize)
+        .collect()
+}
+
+/// Convert the keys of animated nodes spawned with world transforms (meshes,
+/// nulls, lights, cameras and root bones) to world space, and add keys for
+/// such nodes moved by an animated ancestor, e.g. the blades under a
+/// rotating fan hub.
+///
+/// Mesh keys include the node's geometric transform, like the spawned
+/// entity. Keys are transformed one channel at a time while the parent is
+/// static with uniform scale; otherwise the node is evaluated through ufbx
+/// at a fixed sample rate.
+pub fn convert_world_space_keys(
+    scene: &ufbx::Scene,
+    anim: &ufbx::Anim,
+    time_begin: f64,
+    time_end: f64,
+    node_keys: &mut HashMap<usize, NodeKeys>,
+) {
+    let parented_bones = parented_bone_nodes(scene);
+    let animated: HashSet<usize> = node_keys.keys().copied().collect();
+    let has_animated_ancestor = |node: &ufbx::Node| {
+        std::iter::successors(node.parent.as_deref(), |node| node.parent.as_deref())
+            .any(|ancestor| animated.contains(&(ancestor.element.typed_id as usize)))
+    };
+    let frame_count = (((time_end - time_begin) * ROTATION_SAMPLE_RATE).ceil() as usize).max(1);
+
+    for node in scene.nodes.as_ref().iter() {
+        let node_index = node.element.typed_id as usize;
+        if node.is_root || parented_bones.contains(&node_index) {
+            continue;
+        }
+        let ancestor_animated = has_animated_ancestor(node);
+        if !ancestor_animated && !animated.contains(&node_index) {
+            continue;
+        }
+        let geometry = if node.mesh.is_some() || node.attrib_type == ufbx::ElementType::LineCurve {
+            convert_matrix(&node.geometry_to_node)
+        } else {
+            Mat4::IDENTITY
+        };
+        let parent = node.parent.as_deref().map_or(Mat4::IDENTITY, |parent| {
+            convert_matrix(&parent.node_to_world)
+        });
+        let parent_transform = Transform::from_matrix(parent);
+        let uniform_scale = parent_transform.scale.abs_diff_eq(
+            Vec3::splat(parent_transform.scale.x),
+            1e-4 * parent_transform.scale.x.abs(),
+        );
+
+        if !ancestor_animated
+            && geometry.abs_diff_eq(Mat4::IDENTITY, 1e-6)
+            && uniform_scale
+            && let Some(keys) = node_keys.get_mut(&node_index)
+        {
+            for (_, translation) in &mut keys.translation {
+                *translation = parent_transform.transform_point(*translation);
+            }
+            for (_, rotation) in &mut keys.rotation {
+                *rotation = parent_transform.rotation * *rotation;
+            }
+            for (_, scale) in &mut keys.scale {
+                *scale *= parent_transform.scale;
+            }
+            continue;
+        }
+
+        let mut keys = NodeKeys::default();
+        for frame in 0..=frame_count {
+            let time = (time_begin + frame as f64 / ROTATION_SAMPLE_RATE).min(time_end);
+            let world = evaluated_node_to_world(anim, node, time, &animated) * geometry;
+            let (scale, rotation, translation) = world.to_scale_rotation_translation();
+            keys.translation.push((time as f32, translation));
+            keys.rotation.push((time as f32, rotation));
+            keys.scale.push((time as f32, scale));
+        }
+        node_keys.insert(node_index, keys);
+    }
+}
+
+/// World matrix of `node` at `time`, evaluating the local transforms of the
+/// nodes in `animated` and their descendants.
+fn evaluated_node_to_world(
+    anim: &ufbx::Anim,
+    node: &ufbx::Node,
+    time: f64,
+    animated: &HashSet<usize>,
+) -> Mat4 {
+    let chain_animated = std::iter::successors(Some(node), |node| node.parent.as_deref())
+        .any(|node| animated.contains(&(node.element.typed_id as usize)));
+    match node.parent.as_deref() {
+        Some(parent) if chain_animated && !node.is_root => {
+            let local = convert_transform(&ufbx::evaluate_transform(anim, node, time));
+            evaluated_node_to_world(anim, parent, time, animated) * local.to_matrix()
+        }
+        _ => convert_matrix(&node.node_to_world),
+    }
+}
+
+/// Whether a node has pre/post rotations or a non-XYZ rotation order.
+pub fn needs_rotation_resampling(node: &ufbx::Node) -> bool {
+    let has_offset = |name: &str| {
+        node.element
+            .props
+            .props
+            .as_ref()
+            .iter()
+            .find(|prop| prop.name.as_ref() as &str == name)
+            .is_some_and(|prop| convert_vec3(&prop.value_vec3) != Vec3::ZERO)
+    };
+    node.rotation_order != ufbx::RotationOrder::Xyz
+        || has_offset("PreRotation")
+        || has_offset("PostRotation")
+}
+
+/// Convert FBX Euler angles in degrees to a quaternion.
+///
+/// The order names the axes in the order they are applied, so `Xyz` rotates
+/// around X first and Z last. Spheric rotation is treated as XYZ.
+pub fn fbx_euler_to_quat(degrees: Vec3, order: ufbx::RotationOrder) -> Quat {
+    let [x, y, z] = degrees.to_array().map(f32::to_radians);
+    let rx = Quat::from_rotation_x(x);
+    let ry = Quat::from_rotation_y(y);
+    let rz = Quat::from_rotation_z(z);
+    match order {
+        ufbx::RotationOrder::Xzy => ry * rz * rx,
+        ufbx::RotationOrder::Yzx => rx * rz * ry,
+        ufbx::RotationOrder::Yxz => rz * rx * ry,
+        ufbx::RotationOrder::Zxy => ry * rx * rz,
+        ufbx::RotationOrder::Zyx => rx * ry * rz,
+        _ => rz * ry * rx,
+    }
+}
+
+/// Compose a node rotation following the FBX transform model:
+/// `PreRotation * LclRotation * PostRotation⁻¹`.
+///
+/// Pre and post rotations are always applied in XYZ order; `order` only
+/// affects the animated `Lcl Rotation`.
+pub fn compose_fbx_rotation(
+    pre_rotation: Vec3,
+    lcl_rotation: Vec3,
+    post_rotation: Vec3,
+    order: ufbx::RotationOrder,
+) -> Quat {
+    fbx_euler_to_quat(pre_rotation, ufbx::RotationOrder::Xyz)
+        * fbx_euler_to_quat(lcl_rotation, order)
+        * fbx_euler_to_quat(post_rotation, ufbx::RotationOrder::Xyz).inverse()
+}
+
+/// Build an animation clip from baked node keys.
+pub fn build_clip(
+    scene: &ufbx::Scene,
+    node_keys: &HashMap<usize, NodeKeys>,
+    settings: &FbxLoaderSettings,
+) -> AnimationClip {
+    let mut clip = AnimationClip::default();
+
+    // Curves are added in node order so clips are identical across loads
+    let mut node_indices: Vec<usize> = node_keys.keys().copied().collect();
+    node_indices.sort_unstable();
+    for node_index in node_indices {
+        let keys = &node_keys[&node_index];
+        let Some(node) = scene.nodes.as_ref().get(node_index) else {
+            continue;
+        };
+        let target = clip_target_id(node, settings);
+
+        if let Some(curve) = keyframe_curve(&keys.translation) {
+            clip.add_curve_to_target(
+                target,
+                AnimatableCurve::new(animated_field!(Transform::translation), curve),
+            );
+        }
+        if let Some(curve) = keyframe_curve(&keys.rotation) {
+            clip.add_curve_to_target(
+                target,
+                AnimatableCurve::new(animated_field!(Transform::rotation), curve),
+            );
+        }
+        if let Some(curve) = keyframe_curve(&keys.scale) {
+            clip.add_curve_to_target(
+                target,
+                AnimatableCurve::new(animated_field!(Transform::scale), curve),
+            );
+        }
+    }
+
+    clip
+}
+
+/// Build a keyframe curve, if there are enough keys to animate.
+fn keyframe_curve<T: Animatable + Clone>(keys: &[(f32, T)]) -> Option<AnimatableKeyframeCurve<T>> {
+    if keys.len() < 2 {
+        return None;
+    }
+    AnimatableKeyframeCurve::new(keys.iter().cloned()).ok()
+}
+
+/// Add [`MorphWeights`](bevy::mesh::morph::MorphWeights) curves for the
+/// animated blend channel weights (`DeformPercent`) of mesh nodes with
+/// imported blend shapes.
+///
+/// Curves target the node, whose entity holds the morph weights of the
+/// spawned primitives; in-between shapes are weighted like
+/// [`FbxMesh::morph_weights`](crate::FbxMesh::morph_weights).
+pub fn add_morph_weight_curves(
+    clip: &mut AnimationClip,
+    scene: &ufbx::Scene,
+    property_keys: &PropertyKeys,
+    settings: &FbxLoaderSettings,
+) {
+    for node in scene.nodes.as_ref().iter() {
+        let Some(mesh) = node.mesh.as_ref() else {
+            continue;
+        };
+        if !has_blend_shapes(mesh, settings) {
+            continue;
+        }
+        let channel_keys: Vec<Vec<(f32, f32)>> = mesh
+            .blend_deformers
+            .as_ref()
+            .iter()
+            .flat_map(|deformer| deformer.channels.as_ref().iter())
+            .map(|channel| {
+                property_keys
+                    .get(&channel.element.element_id)
+                    .and_then(|properties| properties.get("DeformPercent"))
+                    .into_iter()
+                    .flatten()
+                    .map(|&(time, value)| (time, value.x / 100.0))
+                    .collect()
+            })
+            .collect();
+        let Some((times, weights)) = morph_weight_keys(&blend_channels(mesh), &channel_keys) else {
+            continue;
+        };
+        match WideLinearKeyframeCurve::new(times, weights) {
+            Ok(curve) => {
+                clip.add_curve_to_target(clip_target_id(node, settings), WeightsCurve(curve));
+            }
+            Err(error) => warn!(
+                "Skipping blend shape animation of node '{}': {:?}",
+                node.element.name.as_ref() as &str,
+                error
+            ),
+        }
+    }
+}
+
+/// Vertical field of view of a perspective [`Projection`], for animating the
+/// FBX `FocalLength` and `FieldOfView` camera properties.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PerspectiveFovProperty;
+
+impl AnimatableProperty for PerspectiveFovProperty {
+    type Property = f32;
+
+    fn get_mut<'a>(
+        &self,
+        entity: &'a mut AnimationEntityMut,
+    ) -> Result<&'a mut f32, AnimationEvaluationError> {
+        let projection = entity
+            .get_mut::<Projection>()
+            .ok_or(AnimationEvaluationError::ComponentNotPresent(TypeId::of::<
+                Projection,
+            >(
+            )))?
+            .into_inner();
+        match projection {
+            Projection::Perspective(perspective) => Ok(&mut perspective.fov),
+            _ => Err(AnimationEvaluationError::PropertyNotPresent(TypeId::of::<
+                PerspectiveProjection,
+            >(
+            ))),
+        }
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        EvaluatorId::Type(TypeId::of::<Self>())
+    }
+}
+
+/// Light components whose color can be animated by [`LightColorProperty`].
+pub trait FbxAnimatedLight: Component<Mutability = Mutable> + Clone + core::fmt::Debug {
+    fn color_mut(&mut self) -> &mut Color;
+}
+
+impl FbxAnimatedLight for PointLight {
+    fn color_mut(&mut self) -> &mut Color {
+        &mut self.color
+    }
+}
+
+impl FbxAnimatedLight for SpotLight {
+    fn color_mut(&mut self) -> &mut Color {
+        &mut self.color
+    }
+}
+
+impl FbxAnimatedLight for DirectionalLight {
+    fn color_mut(&mut self) -> &mut Color {
+        &mut self.color
+    }
+}
+
+/// sRGB color of a light, for animating the FBX `Color` light property.
+///
+/// Spawned lights always hold [`Color::Srgba`]; other color spaces fail to
+/// evaluate.
+#[derive(Debug, Clone, Default)]
+pub struct LightColorProperty<L>(PhantomData<L>);
+
+impl<L: FbxAnimatedLight> AnimatableProperty for LightColorProperty<L> {
+    type Property = Srgba;
+
+    fn get_mut<'a>(
+        &self,
+        entity: &'a mut AnimationEntityMut,
+    ) -> Result<&'a mut Srgba, AnimationEvaluationError> {
+        let light = entity
+            .get_mut::<L>()
+            .ok_or(AnimationEvaluationError::ComponentNotPresent(
+                TypeId::of::<L>(),
+            ))?
+            .into_inner();
+        match light.color_mut() {
+            Color::Srgba(color) => Ok(color),
+            _ => Err(AnimationEvaluationError::PropertyNotPresent(TypeId::of::<
+                Srgba,
+            >(
+            ))),
+        }
+    }
+
+    fn evaluator_id(&self) -> EvaluatorId<'_> {
+        EvaluatorId::Type(TypeId::of::<Self>())
+    }
+}
+
+/// Vertical field of view in radians for a focal length, with the film
+/// height in millimeters.
+pub fn focal_length_to_fov(focal_length_mm: f32, film_height_mm: f32) -> f32 {
+    2.0 * (film_height_mm * 0.5 / focal_length_mm).atan()
+}
+
+/// Add curves for the animated properties of camera and light nodes to a
+/// clip, targeting the same ids as the nodes' transform curves.
+///
+/// Cameras get their perspective field of view from `FocalLength` or
+/// `FieldOfView` (the vertical field of view ignores lens shift) and, with
+/// the `depth_of_field` feature, their focus distance. Lights get their
+/// `Color` and `Intensity`, converted like the spawned lights.
+pub fn add_property_curves(
+    clip: &mut AnimationClip,
+    scene: &ufbx::Scene,
+    property_keys: &PropertyKeys,
+    settings: &FbxLoaderSettings,
+) {
+    for node in scene.nodes.as_ref().iter() {
+        let target = clip_target_id(node, settings);
+        if let Some(camera) = node.camera.as_ref()
+            && let Some(properties) = property_keys.get(&camera.element.element_id)
+        {
+            add_camera_curves(clip, target, camera, properties);
+        }
+        if let Some(light) = node.light.as_ref()
+            && let Some(properties) = property_keys.get(&light.element.element_id)
+        {
+            add_light_curves(clip, target, light, properties, settings);
+        }
+    }
+}
+
+/// Add the field of view and focus distance curves of an animated camera.
+fn add_camera_curves(
+    clip: &mut AnimationClip,
+    target: AnimationTargetId,
+    camera: &ufbx::Camera,
+    properties: &HashMap<String, Vec<(f32, Vec3)>>,
+) {
+    if camera.projection_mode == ufbx::ProjectionMode::Orthographic {
+        return;
+    }
+    let lens = camera_lens(camera);
+    let fov_keys: Option<Vec<(f32, f32)>> = if let Some(keys) = properties.get("FocalLength")
+        && lens.film_size_mm.y > 0.0
+    {
+        Some(
+            keys.iter()
+                .filter(|(_, value)| value.x > 0.0)
+                .map(|&(time, value)| (time, focal_length_to_fov(value.x, lens.film_size_mm.y)))
+                .collect(),
+        )
+    } else if let Some(keys) = properties.get("FieldOfView") {
+        // The property may be horizontal or vertical depending on the
+        // aperture mode; scale it like the static field of view
+        let static_fov = camera
+            .element
+            .props
+            .props
+            .as_ref()
+            .iter()
+            .find(|prop| prop.name.as_ref() as &str == "FieldOfView")
+            .map_or(camera.field_of_view_deg.y, |prop| prop.value_real)
+            as f32;
+        let half_tan = |degrees: f32| (degrees.to_radians() * 0.5).tan();
+        let scale = half_tan(camera.field_of_view_deg.y as f32) / half_tan(static_fov);
+        scale.is_finite().then(|| {
+            keys.iter()
+                .map(|&(time, value)| (time, 2.0 * (half_tan(value.x) * scale).atan()))
+                .collect()
+        })
+    } else {
+        None
+    };
+    if let Some(curve) = fov_keys.as_deref().and_then(keyframe_curve) {
+        clip.add_curve_to_target(target, AnimatableCurve::new(PerspectiveFovProperty, curve));
+    }
+
+    #[cfg(feature = "depth_of_field")]
+    if lens.depth_of_field
+        && let Some(keys) = properties.get("FocusDistance")
+    {
+        let keys: Vec<(f32, f32)> = keys.iter().map(|&(time, value)| (time, value.x)).collect();
+        if let Some(curve) = keyframe_curve(&keys) {
+            clip.add_curve_to_target(
+                target,
+                AnimatableCurve::new(animated_field!(DepthOfField::focal_distance), curve),
+            );
+        }
+    }
+}
+
+/// Add the color and intensity curves of an animated light.
+fn add_light_curves(
+    clip: &mut AnimationClip,
+    target: AnimationTargetId,
+    light: &ufbx::Light,
+    properties: &HashMap<String, Vec<(f32, Vec3)>>,
+    settings: &FbxLoaderSettings,
+) {
+    let conversion = &settings.light_conversion;
+    let decay = FbxLightDecay::from(light.decay);
+    let colors: Vec<(f32, Srgba)> = properties
+        .get("Color")
+        .into_iter()
+        .flatten()
+        .map(|&(time, value)| (time, Srgba::rgb(value.x, value.y, value.z)))
+        .collect();
+    // ufbx normalizes the static intensity by dividing it by 100
+    let intensities = |convert: &dyn Fn(f32) -> f32| -> Vec<(f32, f32)> {
+        properties
+            .get("Intensity")
+            .into_iter()
+            .flatten()
+            .map(|&(time, value)| (time, convert(value.x / 100.0)))
+            .collect()
+    };
+
+    match light.type_ {
+        ufbx::LightType::Directional => {
+            if let Some(curve) = keyframe_curve(&colors) {
+                let property = LightColorProperty::<DirectionalLight>::default();
+                clip.add_curve_to_target(target, AnimatableCurve::new(property, curve));
+            }
+            let keys = intensities(&|intensity| conversion.illuminance(intensity));
+            if let Some(curve) = keyframe_curve(&keys) {
+                clip.add_curve_to_target(
+                    target,
+                    AnimatableCurve::new(animated_field!(DirectionalLight::illuminance), curve),
+                );
+            }
+        }
+        ufbx::LightType::Spot => {
+            if let Some(curve) = keyframe_curve(&colors) {
+                let property = LightColorProperty::<SpotLight>::default();
+                clip.add_curve_to_target(target, AnimatableCurve::new(property, curve));
+            }
+            let keys = intensities(&|intensity| conversion.luminous_power(intensity, decay));
+            if let Some(curve) = keyframe_curve(&keys) {
+                clip.add_curve_to_target(
+                    target,
+                    AnimatableCurve::new(animated_field!(SpotLight::intensity), curve),
+                );
+            }
+        }
+        // Area lights are approximated by point lights
+        ufbx::LightType::Point | ufbx::LightType::Area => {
+            if let Some(curve) = keyframe_curve(&colors) {
+                let property = LightColorProperty::<PointLight>::default();
+                clip.add_curve_to_target(target, AnimatableCurve::new(property, curve));
+            }
+            let keys = intensities(&|intensity| conversion.luminous_power(intensity, decay));
+            if let Some(curve) = keyframe_curve(&keys) {
+                clip.add_curve_to_target(
+                    target,
+                    AnimatableCurve::new(animated_field!(PointLight::intensity), curve),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Bake the effect of aim, parent, position, rotation and scale constraints
+/// into the keys of the constrained nodes.
+///
+/// The scene is evaluated through ufbx at a fixed sample rate and each
+/// constraint is applied on top of the evaluated target transforms.
+/// Single-chain IK constraints are not evaluated.
+pub fn bake_constraints(
+    scene: &ufbx::Scene,
+    anim: &ufbx::Anim,
+    time_begin: f64,
+    time_end: f64,
+    node_keys: &mut HashMap<usize, NodeKeys>,
+) -> Result<(), FbxError> {
+    let constraints: Vec<&ufbx::Constraint> = scene
+        .constraints
+        .as_ref()
+        .iter()
+        .map(|constraint| &**constraint)
+        .filter(|constraint| {
+            constraint.active
+                && constraint.node.is_some()
+                && !constraint.targets.is_empty()
+                && constraint.type_ != ufbx::ConstraintType::SingleChainIk
+                && constraint.type_ != ufbx::ConstraintType::Unknown
+        })
+        .collect();
+    if constraints.is_empty() {
+        return Ok(());
+    }
+
+    let frame_count = (((time_end - time_begin) * CONSTRAINT_SAMPLE_RATE).ceil() as usize).max(1);
+    for frame in 0..=frame_count {
+        let time = (time_begin + frame as f64 / CONSTRAINT_SAMPLE_RATE).min(time_end);
+        let evaluated = ufbx::evaluate_scene(scene, anim, time, ufbx::EvaluateOpts::default())
+            .map_err(|e| FbxError::UfbxError(format!("{:?}", e)))?;
+
+        for constraint in &constraints {
+            let Some(node) = constraint.node.as_ref() else {
+                continue;
+            };
+            let node_index = node.element.typed_id as usize;
+            let Some(local) = evaluate_constraint(&evaluated, constraint, node_index) else {
+                continue;
+            };
+
+            let keys = node_keys.entry(node_index).or_default();
+            if frame == 0 {
+                *keys = NodeKeys::default();
+            }
+            keys.translation.push((time as f32, local.translation));
+            keys.rotation.push((time as f32, local.rotation));
+            keys.scale.push((time as f32, local.scale));
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the constrained local transform of a node in an evaluated scene.
+fn evaluate_constraint(
+    evaluated: &ufbx::Scene,
+    constraint: &ufbx::Constraint,
+    node_index: usize,
+) -> Option<Transform> {
+    let node = evaluated.nodes.as_ref().get(node_index)?;
+    let current = Transform::from_matrix(convert_matrix(&node.node_to_world));
+    let parent_to_world = node
+        .parent
+        .as_ref()
+        .map(|parent| convert_matrix(&parent.node_to_world))
+        .unwrap_or(Mat4::IDENTITY);
+
+    let mut total_weight = 0.0;
+    let mut translation = Vec3::ZERO;
+    let mut scale = Vec3::ZERO;
+    let mut rotation: Option<Quat> = None;
+    for target in constraint.targets.as_ref().iter() {
+        let weight = target.weight as f32;
+        let target_index = target.node.element.typed_id as usize;
+        let Some(target_node) = evaluated.nodes.as_ref().get(target_index) else {
+            continue;
+        };
+        if weight <= 0.0 {
+            continue;
+        }
+
+        let mut target_world = convert_matrix(&target_node.node_to_world);
+        if constraint.type_ == ufbx::ConstraintType::Parent {
+            target_world *= convert_transform(&target.transform).to_matrix();
+        }
+        let target_world = Transform::from_matrix(target_world);
+
+        total_weight += weight;
+        translation += target_world.translation * weight;
+        scale += target_world.scale * weight;
+        rotation = Some(match rotation {
+            None => target_world.rotation,
+            Some(rotation) => rotation.slerp(target_world.rotation, weight / total_weight),
+        });
+    }
+    if total_weight <= 0.0 {
+        return None;
+    }
+    translation /= total_weight;
+    scale /= total_weight;
+    let rotation = rotation.unwrap_or(Quat::IDENTITY);
+
+    let mut world = current;
+    match constraint.type_ {
+        ufbx::ConstraintType::Position => {
+            world.translation = select_axes(
+                current.translation,
+                translation,
+                &constraint.constrain_translation,
+            );
+        }
+        ufbx::ConstraintType::Rotation => {
+            if constraint.constrain_rotation.iter().any(|&axis| axis) {
+                world.rotation = rotation;
+            }
+        }
+        ufbx::ConstraintType::Scale => {
+            world.scale = select_axes(current.scale, scale, &constraint.constrain_scale);
+        }
+        ufbx::ConstraintType::Parent => {
+            world.translation = select_axes(
+                current.translation,
+                translation,
+                &constraint.constrain_translation,
+            );
+            if constraint.constrain_rotation.iter().any(|&axis| axis) {
+                world.rotation = rotation;
+            }
+        }
+        ufbx::ConstraintType::Aim => {
+            let aim = convert_vec3(&constraint.aim_vector).normalize_or(Vec3::X);
+            let up = convert_vec3(&constraint.aim_up_vector).normalize_or(Vec3::Y);
+            let world_up = match constraint.aim_up_type {
+                ufbx::ConstraintAimUpType::ToNode => constraint
+                    .aim_up_node
+                    .as_ref()
+                    .and_then(|up_node| {
+                        let up_index = up_node.element.typed_id as usize;
+                        evaluated.nodes.as_ref().get(up_index)
+                    })
+                    .map(|up_node| {
+                        Transform::from_matrix(convert_matrix(&up_node.node_to_world)).translation
+                            - current.translation
+                    })
+                    .unwrap_or(Vec3::Y),
+                ufbx::ConstraintAimUpType::Vector => up,
+                _ => Vec3::Y,
+            };
+            let direction = translation - current.translation;
+            if direction.length_squared() > f32::EPSILON {
+                let look = Transform::IDENTITY.looking_to(direction, world_up).rotation;
+                let local_look = Transform::IDENTITY.looking_to(aim, up).rotation;
+                world.rotation = look * local_look.inverse();
+            }
+        }
+        _ => return None,
+    }
+
+    // Blend with the unconstrained transform by the constraint weight
+    let weight = (constraint.weight as f32).clamp(0.0, 1.0);
+    world.translation = current.translation.lerp(world.translation, weight);
+    world.rotation = current.rotation.slerp(world.rotation, weight);
+    world.scale = current.scale.lerp(world.scale, weight);
+
+    Some(Transform::from_matrix(
+        parent_to_world.inverse() * world.to_matrix(),
+    ))
+}
+
+/// Take the components of `constrained` whose axis flag is set.
+fn select_axes(current: Vec3, constrained: Vec3, axes: &[bool; 3]) -> Vec3 {
+    Vec3::new(
+        if axes[0] { constrained.x } else { current.x },
+        if axes[1] { constrained.y } else { current.y },
+        if axes[2] { constrained.z } else { current.z },
+    )
+}
+
+fn convert_vec3(v: &ufbx::Vec3) -> Vec3 {
+    Vec3::new(v.x as f32, v.y as f32, v.z as f32)
+}
+
+fn convert_quat(q: &ufbx::Quat) -> Quat {
+    Quat::from_xyzw(q.x as f32, q.y as f32, q.z as f32, q.w as f32)
+}
@@ -0,0 +1,109 @@
+//! Attaching entities such as weapons and props to bones of spawned FBX
+//! scenes.
+//!
+//! Spawn an entity with [`FbxBoneAttachment`] as a child of a
+//! [`SceneRoot`] holding an FBX scene. Once the scene instance is ready, the
+//! entity is reparented to the bone of that name, with the attachment's
+//! offset as its transform. Attachments added to scenes that are already
+//! spawned are attached right away.
+
+use crate::scene::FbxSceneInstanceReady;
+use crate::types::{FbxBone, FbxSceneMap};
+use bevy::prelude::*;
+
+/// Attaches the entity to the bone named `bone_name` of the FBX scene
+/// instance it is a child of.
+///
+/// `offset` is the transform relative to the bone, so it includes the unit
+/// scale of the bone's hierarchy. Entities whose scene has no such bone stay
+/// where they are.
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct FbxBoneAttachment {
+    pub bone_name: String,
+    pub offset: Transform,
+}
+
+impl FbxBoneAttachment {
+    pub fn new(bone_name: impl Into<String>) -> Self {
+        Self {
+            bone_name: bone_name.into(),
+            offset: Transform::IDENTITY,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Transform) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// First [`FbxBone`] below `root` named `bone_name`.
+pub fn find_bone(
+    root: Entity,
+    bone_name: &str,
+    children: &Query<&Children>,
+    bones: &Query<&Name, With<FbxBone>>,
+) -> Option<Entity> {
+    children.iter_descendants(root).find(|&entity| {
+        bones
+            .get(entity)
+            .is_ok_and(|name| name.as_str() == bone_name)
+    })
+}
+
+/// Reparent `entity` to its bone below `root`. Returns whether the bone was
+/// found.
+pub fn attach_to_bone(
+    entity: Entity,
+    attachment: &FbxBoneAttachment,
+    root: Entity,
+    children: &Query<&Children>,
+    bones: &Query<&Name, With<FbxBone>>,
+    commands: &mut Commands,
+) -> bool {
+    let Some(bone) = find_bone(root, &attachment.bone_name, children, bones) else {
+        return false;
+    };
+    commands
+        .entity(entity)
+        .insert((ChildOf(bone), attachment.offset));
+    true
+}
+
+/// Attach the [`FbxBoneAttachment`] children of a scene root once its FBX
+/// scene instance is ready.
+pub fn attach_on_scene_ready(
+    ready: On<FbxSceneInstanceReady>,
+    attachments: Query<&FbxBoneAttachment>,
+    children: Query<&Children>,
+    bones: Query<&Name, With<FbxBone>>,
+    mut commands: Commands,
+) {
+    let root = ready.entity;
+    let Ok(root_children) = children.get(root) else {
+        return;
+    };
+    for &entity in root_children {
+        if let Ok(attachment) = attachments.get(entity) {
+            attach_to_bone(entity, attachment, root, &children, &bones, &mut commands);
+        }
+    }
+}
+
+/// Attach [`FbxBoneAttachment`]s added as children of scene roots whose FBX
+/// scene instance is already spawned.
+pub fn attach_added_attachments(
+    attachments: Query<(Entity, &FbxBoneAttachment, &ChildOf), Added<FbxBoneAttachment>>,
+    scene_roots: Query<(), With<FbxSceneMap>>,
+    children: Query<&Children>,
+    bones: Query<&Name, With<FbxBone>>,
+    mut commands: Commands,
+) {
+    for (entity, attachment, child_of) in &attachments {
+        let root = child_of.parent();
+        if scene_roots.contains(root) {
+            attach_to_bone(entity, attachment, root, &children, &bones, &mut commands);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Custom vertex attributes filled from FBX geometry layers.
+//!
+//! Shaders often read extra per-vertex data that DCC tools can only store
+//! as additional color or UV sets, e.g. wind weights painted into a second
+//! color set. [`FbxCustomAttribute`] routes such a layer to a
+//! [`MeshVertexAttribute`] of the material's choosing.
+
+use bevy::mesh::{MeshVertexAttribute, VertexAttributeValues, VertexFormat};
+use std::fmt;
+
+/// Geometry layer of a mesh, by the name it has in the FBX file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FbxVertexLayer {
+    /// Vertex color set
+    Color(String),
+    /// UV set
+    Uv(String),
+}
+
+/// Mapping of a geometry layer to a custom vertex attribute, see
+/// [`FbxLoaderSettings::custom_attributes`](crate::FbxLoaderSettings::custom_attributes).
+///
+/// Layer values are read as four components (UVs as `(u, v, 0, 0)`) and
+/// truncated to the attribute format, which must be `Float32`, `Float32x2`,
+/// `Float32x3`, `Float32x4` or `Unorm8x4`.
+#[derive(Clone)]
+pub struct FbxCustomAttribute {
+    /// Layer the values are read from
+    pub layer: FbxVertexLayer,
+    /// Attribute the values are stored in
+    pub attribute: MeshVertexAttribute,
+}
+
+impl FbxCustomAttribute {
+    /// Fill `attribute` from the vertex color set named `name`.
+    pub fn color(name: impl Into<String>, attribute: MeshVertexAttribute) -> Self {
+        Self {
+            layer: FbxVertexLayer::Color(name.into()),
+            attribute,
+        }
+    }
+
+    /// Fill `attribute` from the UV set named `name`.
+    pub fn uv(name: impl Into<String>, attribute: MeshVertexAttribute) -> Self {
+        Self {
+            layer: FbxVertexLayer::Uv(name.into()),
+            attribute,
+        }
+    }
+}
+
+impl fmt::Debug for FbxCustomAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FbxCustomAttribute")
+            .field("layer", &self.layer)
+            .field("attribute", &self.attribute.name)
+            .field("format", &self.attribute.format)
+            .finish()
+    }
+}
+
+impl PartialEq for FbxCustomAttribute {
+    fn eq(&self, other: &Self) -> bool {
+        self.layer == other.layer
+            && self.attribute.id == other.attribute.id
+            && self.attribute.format == other.attribute.format
+    }
+}
+
+/// Values of `layer` per face corner, or `None` if `mesh` has no such layer.
+pub fn layer_values(mesh: &ufbx::Mesh, layer: &FbxVertexLayer) -> Option<Vec<[f32; 4]>> {
+    match layer {
+        FbxVertexLayer::Color(name) => {
+            let color_set = mesh
+                .color_sets
+                .as_ref()
+                .iter()
+                .find(|color_set| color_set.name.as_ref() as &str == name.as_str())?;
+            let values = (0..mesh.num_indices)
+                .map(|corner| {
+                    let c = color_set.vertex_color[corner];
+                    [c.x as f32, c.y as f32, c.z as f32, c.w as f32]
+                })
+                .collect();
+            Some(values)
+        }
+        FbxVertexLayer::Uv(name) => {
+            let uv_set = mesh
+                .uv_sets
+                .as_ref()
+                .iter()
+                .find(|uv_set| uv_set.name.as_ref() as &str == name.as_str())?;
+            let values = (0..mesh.num_indices)
+                .map(|corner| {
+                    let uv = uv_set.vertex_uv[corner];
+                    [uv.x as f32, uv.y as f32, 0.0, 0.0]
+                })
+                .collect();
+            Some(values)
+        }
+    }
+}
+
+/// Convert four-component values to `format`, or `None` if the format is
+/// not supported.
+pub fn attribute_values(
+    values: impl Iterator<Item = [f32; 4]>,
+    format: VertexFormat,
+) -> Option<VertexAttributeValues> {
+    let values = match format {
+        VertexFormat::Float32 => VertexAttributeValues::Float32(values.map(|v| v[0]).collect()),
+        VertexFormat::Float32x2 => {
+            VertexAttributeValues::Float32x2(values.map(|v| [v[0], v[1]]).collect())
+        }
+        VertexFormat::Float32x3 => {
+            VertexAttributeValues::Float32x3(values.map(|v| [v[0], v[1], v[2]]).collect())
+        }
+        VertexFormat::Float32x4 => VertexAttributeValues::Float32x4(values.collect()),
+        VertexFormat::Unorm8x4 => VertexAttributeValues::Unorm8x4(
+            values
+                .map(|v| v.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+                .collect(),
+        ),
+        _ => return None,
+    };
+    Some(values)
+}
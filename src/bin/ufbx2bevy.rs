@@ -0,0 +1,138 @@
+//! Command-line conversion of FBX files to Bevy scenes, for build steps
+//! that shouldn't launch the game. Requires the `cli` feature.
+//!
+//! Usage: `ufbx2bevy <input.fbx> [--out-dir <dir>] [--settings <settings.ron>]`
+//!
+//! Writes `<name>.scn.ron`, `<name>.materials.ron` and `<name>.manifest.ron`
+//! to the output directory, which defaults to the input's directory.
+//! `--settings` reads [`FbxLoaderSettings`] in the RON format of `.meta`
+//! files.
+
+use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
+use bevy::prelude::*;
+use bevy_ufbx::{
+    Fbx, FbxLoaderSettings, FbxPlugin, serialize_fbx_manifest, serialize_fbx_materials,
+    write_fbx_scene,
+};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: ufbx2bevy <input.fbx> [--out-dir <dir>] [--settings <settings.ron>]";
+
+struct Args {
+    input: PathBuf,
+    out_dir: Option<PathBuf>,
+    settings: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut input = None;
+    let mut out_dir = None;
+    let mut settings = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--out-dir" => {
+                out_dir = Some(args.next().ok_or("--out-dir needs a directory")?.into());
+            }
+            "-s" | "--settings" => {
+                settings = Some(args.next().ok_or("--settings needs a file")?.into());
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {arg}\n{USAGE}")),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {arg}\n{USAGE}")),
+        }
+    }
+    Ok(Args {
+        input: input.ok_or(USAGE)?,
+        out_dir,
+        settings,
+    })
+}
+
+fn convert(args: &Args) -> Result<Vec<PathBuf>, String> {
+    let settings = match &args.settings {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+            ron::from_str::<FbxLoaderSettings>(&text)
+                .map_err(|error| format!("invalid settings {}: {error}", path.display()))?
+        }
+        None => FbxLoaderSettings::default(),
+    };
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), FbxPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<AnimationClip>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Image>()
+        .init_asset::<Scene>()
+        .init_asset::<SkinnedMeshInverseBindposes>()
+        .register_type::<StandardMaterial>();
+
+    let bytes = std::fs::read(&args.input)
+        .map_err(|error| format!("failed to read {}: {error}", args.input.display()))?;
+    let fbx = Fbx::from_bytes(&bytes, settings, app.world_mut())
+        .map_err(|error| format!("failed to import {}: {error}", args.input.display()))?;
+    for warning in &fbx.warnings.0 {
+        eprintln!("warning: {warning}");
+    }
+
+    let stem = args
+        .input
+        .file_stem()
+        .ok_or_else(|| format!("{} has no file name", args.input.display()))?
+        .to_string_lossy();
+    let out_dir = match &args.out_dir {
+        Some(dir) => dir.clone(),
+        None => args
+            .input
+            .parent()
+            .map_or_else(PathBuf::new, Path::to_path_buf),
+    };
+    std::fs::create_dir_all(&out_dir)
+        .map_err(|error| format!("failed to create {}: {error}", out_dir.display()))?;
+
+    let world = app.world();
+    let scene = out_dir.join(format!("{stem}.scn.ron"));
+    let materials = out_dir.join(format!("{stem}.materials.ron"));
+    let manifest = out_dir.join(format!("{stem}.manifest.ron"));
+    write_fbx_scene(&fbx, world, &scene).map_err(|error| error.to_string())?;
+    let write = |path: &Path, text: String| {
+        std::fs::write(path, text)
+            .map_err(|error| format!("failed to write {}: {error}", path.display()))
+    };
+    write(
+        &materials,
+        serialize_fbx_materials(&fbx, world).map_err(|e| e.to_string())?,
+    )?;
+    write(
+        &manifest,
+        serialize_fbx_manifest(&fbx, world).map_err(|e| e.to_string())?,
+    )?;
+    Ok(vec![scene, materials, manifest])
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::from(2);
+        }
+    };
+    match convert(&args) {
+        Ok(paths) => {
+            for path in paths {
+                println!("Wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
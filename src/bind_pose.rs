@@ -0,0 +1,144 @@
+//! Skin bind poses and resetting spawned skeletons to them.
+//!
+//! Each [`FbxSkin`](crate::FbxSkin) gets an [`FbxBindPose`] sub-asset with
+//! the pose its mesh was bound in, e.g. to initialize a ragdoll or calibrate
+//! retargeting. [`reset_to_bind_pose`] applies it to spawned bone entities,
+//! and [`FbxResetToBindPose`] does so from a component once the asset has
+//! loaded.
+
+use crate::types::FbxBone;
+use crate::utils::convert_matrix;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// Bind pose of a skin, indexed by joint like the skin's inverse bind
+/// matrices.
+#[derive(Asset, Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxBindPose {
+    /// Node name of each joint
+    pub joint_names: Vec<String>,
+    /// World-space matrix of each joint when the mesh was bound
+    pub global_matrices: Vec<Mat4>,
+    /// Transform of each joint relative to its parent bone, or its world
+    /// transform if the parent is not a bone, matching the spawned bone
+    /// hierarchy
+    pub local_transforms: Vec<Transform>,
+}
+
+impl FbxBindPose {
+    /// Index of the joint named `name`.
+    pub fn joint_index(&self, name: &str) -> Option<usize> {
+        self.joint_names.iter().position(|joint| joint == name)
+    }
+
+    /// Bind pose transform of the joint named `name`, relative to its parent
+    /// bone.
+    pub fn local_transform(&self, name: &str) -> Option<Transform> {
+        self.local_transforms.get(self.joint_index(name)?).copied()
+    }
+}
+
+/// Collect the bind pose of a skin deformer.
+///
+/// `bone_nodes` are the element ids of every node used as a joint by any
+/// skin, which are spawned as bone entities. Parents that are bones of
+/// another skin use their pose at load time.
+pub fn extract_bind_pose(skin: &ufbx::SkinDeformer, bone_nodes: &HashSet<u32>) -> FbxBindPose {
+    let mut bind_pose = FbxBindPose::default();
+    let clusters = skin.clusters.as_ref();
+    for cluster in clusters.iter() {
+        let Some(bone_node) = cluster.bone_node.as_ref() else {
+            continue;
+        };
+        let global = convert_matrix(&cluster.bind_to_world);
+        let parent_matrix = bone_node
+            .parent
+            .as_ref()
+            .filter(|parent| bone_nodes.contains(&parent.element.element_id))
+            .map(|parent| {
+                clusters
+                    .iter()
+                    .find(|cluster| {
+                        cluster.bone_node.as_ref().is_some_and(|node| {
+                            node.element.element_id == parent.element.element_id
+                        })
+                    })
+                    .map_or_else(
+                        || convert_matrix(&parent.node_to_world),
+                        |cluster| convert_matrix(&cluster.bind_to_world),
+                    )
+            });
+        let local = parent_matrix.map_or(global, |parent| parent.inverse() * global);
+
+        bind_pose
+            .joint_names
+            .push(bone_node.element.name.to_string());
+        bind_pose.global_matrices.push(global);
+        bind_pose
+            .local_transforms
+            .push(Transform::from_matrix(local));
+    }
+    bind_pose
+}
+
+/// Element ids of every node used as a skin joint.
+pub fn bone_node_ids(scene: &ufbx::Scene) -> HashSet<u32> {
+    scene
+        .skin_deformers
+        .as_ref()
+        .iter()
+        .flat_map(|skin| skin.clusters.as_ref().iter())
+        .filter_map(|cluster| cluster.bone_node.as_ref())
+        .map(|node| node.element.element_id)
+        .collect()
+}
+
+/// Set the [`Transform`] of every [`FbxBone`] below `root` to its bind pose,
+/// matching bones to joints by name. Returns the number of bones reset.
+///
+/// Bones with no joint in `bind_pose` keep their transform.
+pub fn reset_to_bind_pose(
+    root: Entity,
+    bind_pose: &FbxBindPose,
+    children: &Query<&Children>,
+    bones: &mut Query<(&Name, &mut Transform), With<FbxBone>>,
+) -> usize {
+    let mut count = 0;
+    for entity in children.iter_descendants(root) {
+        let Ok((name, mut transform)) = bones.get_mut(entity) else {
+            continue;
+        };
+        if let Some(local) = bind_pose.local_transform(name.as_str()) {
+            *transform = local;
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Resets the bones below the entity to a bind pose once it has loaded,
+/// then removes itself.
+///
+/// Insert it on a scene root after the scene is spawned, e.g. from an
+/// [`FbxSceneInstanceReady`](crate::FbxSceneInstanceReady) observer.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct FbxResetToBindPose(pub Handle<FbxBindPose>);
+
+/// Apply and remove [`FbxResetToBindPose`] components whose bind pose has
+/// loaded.
+pub fn apply_bind_pose_resets(
+    mut commands: Commands,
+    bind_poses: Res<Assets<FbxBindPose>>,
+    resets: Query<(Entity, &FbxResetToBindPose)>,
+    children: Query<&Children>,
+    mut bones: Query<(&Name, &mut Transform), With<FbxBone>>,
+) {
+    for (entity, reset) in &resets {
+        let Some(bind_pose) = bind_poses.get(&reset.0) else {
+            continue;
+        };
+        reset_to_bind_pose(entity, bind_pose, &children, &mut bones);
+        commands.entity(entity).remove::<FbxResetToBindPose>();
+    }
+}
@@ -0,0 +1,378 @@
+//! Vertex cache (PC2 and Maya MC) import and playback.
+//!
+//! Cloth and fluid simulations are exported as a geometry cache next to the
+//! FBX file: the mesh gets a cache deformer pointing at a `.pc2` or `.mc`
+//! file holding the position of every vertex at each frame. The file is
+//! loaded as an [`FbxVertexCache`] by [`FbxVertexCacheLoader`], and
+//! [`FbxVertexCachePlayer`] components on the spawned primitives play it back
+//! by rewriting the mesh positions.
+
+use crate::error::FbxError;
+use crate::loader::FbxLoaderSettings;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Magic bytes at the start of a `.pc2` file.
+const PC2_MAGIC: &[u8; 12] = b"POINTCACHE2\0";
+
+/// Size of the `.pc2` header in bytes.
+const PC2_HEADER_SIZE: usize = 32;
+
+/// Maya time units per second.
+const MAYA_TICKS_PER_SECOND: f32 = 6000.0;
+
+/// Vertex positions of a baked simulation, one frame per sample time.
+///
+/// Positions are indexed by the unique ufbx vertex of the cached mesh, in the
+/// mesh's local space.
+#[derive(Asset, Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxVertexCache {
+    /// Sample time of each frame in seconds, ascending
+    pub times: Vec<f32>,
+    /// Position of every cached point at each frame
+    pub frames: Vec<Vec<Vec3>>,
+}
+
+impl FbxVertexCache {
+    /// Time of the first frame in seconds.
+    pub fn start_time(&self) -> f32 {
+        self.times.first().copied().unwrap_or_default()
+    }
+
+    /// Time between the first and last frame in seconds.
+    pub fn duration(&self) -> f32 {
+        self.times.last().copied().unwrap_or_default() - self.start_time()
+    }
+
+    /// Positions at `time`, interpolated linearly between frames and clamped
+    /// to the cached range. `None` if the cache has no frames.
+    pub fn sample(&self, time: f32) -> Option<Vec<Vec3>> {
+        let last = self.frames.len().checked_sub(1)?;
+        let next = self.times.partition_point(|&frame_time| frame_time <= time);
+        if next == 0 {
+            return Some(self.frames[0].clone());
+        }
+        if next > last {
+            return Some(self.frames[last].clone());
+        }
+
+        let (start, end) = (self.times[next - 1], self.times[next]);
+        let t = if end > start {
+            (time - start) / (end - start)
+        } else {
+            1.0
+        };
+        Some(
+            self.frames[next - 1]
+                .iter()
+                .zip(&self.frames[next])
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+        )
+    }
+}
+
+/// Plays an [`FbxVertexCache`] on the mesh of the entity, added to spawned
+/// primitives of meshes with a vertex cache.
+///
+/// Instances of one mesh share the mesh asset, so they show the pose of
+/// whichever player ran last.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct FbxVertexCachePlayer {
+    pub cache: Handle<FbxVertexCache>,
+    /// Cache point of each mesh vertex
+    pub vertices: Vec<u32>,
+    /// Playback position in seconds
+    pub time: f32,
+    /// Playback speed multiplier
+    pub speed: f32,
+    pub playing: bool,
+    /// Whether to wrap around at the end of the cache instead of holding the
+    /// last frame
+    pub looping: bool,
+}
+
+impl FbxVertexCachePlayer {
+    /// Player starting at the beginning of `cache`, looping at normal speed.
+    pub fn new(cache: Handle<FbxVertexCache>, vertices: Vec<u32>) -> Self {
+        Self {
+            cache,
+            vertices,
+            time: 0.0,
+            speed: 1.0,
+            playing: true,
+            looping: true,
+        }
+    }
+}
+
+/// Advance [`FbxVertexCachePlayer`]s and write the sampled positions into
+/// their meshes. Meshes with normals get smooth normals recomputed.
+pub fn play_vertex_caches(
+    time: Res<Time>,
+    caches: Res<Assets<FbxVertexCache>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut players: Query<(&mut FbxVertexCachePlayer, &Mesh3d)>,
+) {
+    for (mut player, mesh) in &mut players {
+        let Some(cache) = caches.get(&player.cache) else {
+            continue;
+        };
+        if player.playing {
+            player.time += time.delta_secs() * player.speed;
+        }
+        let duration = cache.duration();
+        if player.looping && duration > 0.0 {
+            let start = cache.start_time();
+            player.time = start + (player.time - start).rem_euclid(duration);
+        }
+
+        let Some(points) = cache.sample(player.time) else {
+            continue;
+        };
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+        let positions: Vec<[f32; 3]> = player
+            .vertices
+            .iter()
+            .map(|&vertex| {
+                points
+                    .get(vertex as usize)
+                    .copied()
+                    .unwrap_or_default()
+                    .into()
+            })
+            .collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        if mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_some() {
+            mesh.compute_smooth_normals();
+        }
+    }
+}
+
+/// Whether a vertex cache is loaded for `mesh`.
+pub fn has_vertex_cache(mesh: &ufbx::Mesh, settings: &FbxLoaderSettings) -> bool {
+    settings.load_vertex_caches && vertex_cache_file(mesh).is_some()
+}
+
+/// First cache file referenced by a cache deformer of `mesh`.
+pub fn vertex_cache_file(mesh: &ufbx::Mesh) -> Option<&ufbx::CacheFile> {
+    mesh.cache_deformers
+        .as_ref()
+        .iter()
+        .find_map(|deformer| deformer.file.as_deref())
+}
+
+/// Asset path of a cache file, relative to the FBX file's directory.
+///
+/// Maya caches are referenced through their `.xml` description; the data is
+/// read from the `.mc` file of the same name, so only one-file caches are
+/// supported. Returns `None` for unnamed files.
+pub fn vertex_cache_path(
+    relative_filename: &str,
+    filename: &str,
+    base_dir: &Path,
+) -> Option<PathBuf> {
+    let filename = if relative_filename.is_empty() {
+        // Absolute paths from the authoring machine; look next to the FBX
+        filename.replace('\\', "/").rsplit('/').next()?.to_string()
+    } else {
+        relative_filename.replace('\\', "/")
+    };
+    if filename.is_empty() {
+        return None;
+    }
+    let path = base_dir.join(filename);
+    let is_xml = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"));
+    Some(if is_xml {
+        path.with_extension("mc")
+    } else {
+        path
+    })
+}
+
+/// Parse a `.pc2` point cache.
+///
+/// PC2 sample times are in frames and converted to seconds with
+/// `frame_rate`.
+pub fn parse_pc2(bytes: &[u8], frame_rate: f32) -> Result<FbxVertexCache, FbxError> {
+    let invalid = |message: &str| FbxError::InvalidData(format!("PC2 cache: {message}"));
+    let header = bytes
+        .get(..PC2_HEADER_SIZE)
+        .ok_or_else(|| invalid("header is truncated"))?;
+    if !header.starts_with(PC2_MAGIC) {
+        return Err(invalid("missing POINTCACHE2 signature"));
+    }
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            header[offset],
+            header[offset + 1],
+            header[offset + 2],
+            header[offset + 3],
+        ])
+    };
+    let point_count = read_u32(16) as usize;
+    let start_frame = f32::from_bits(read_u32(20));
+    let sample_rate = f32::from_bits(read_u32(24));
+    let sample_count = read_u32(28) as usize;
+
+    let data = &bytes[PC2_HEADER_SIZE..];
+    let frame_size = point_count * 12;
+    if frame_size.checked_mul(sample_count) != Some(data.len()) {
+        return Err(invalid("data size does not match the header"));
+    }
+
+    let frame_rate = if frame_rate > 0.0 { frame_rate } else { 30.0 };
+    let times = (0..sample_count)
+        .map(|sample| (start_frame + sample as f32 * sample_rate) / frame_rate)
+        .collect();
+    let frames = data
+        .chunks_exact(frame_size.max(1))
+        .take(sample_count)
+        .map(|frame| {
+            frame
+                .chunks_exact(12)
+                .map(|point| {
+                    let component = |i: usize| {
+                        f32::from_le_bytes([point[i], point[i + 1], point[i + 2], point[i + 3]])
+                    };
+                    Vec3::new(component(0), component(4), component(8))
+                })
+                .collect()
+        })
+        .collect();
+    Ok(FbxVertexCache { times, frames })
+}
+
+/// Parse a one-file Maya `.mc` cache.
+///
+/// Only the first channel is read, and 64-bit (`FOR8`) caches are rejected.
+pub fn parse_mc(bytes: &[u8]) -> Result<FbxVertexCache, FbxError> {
+    let mut cache = FbxVertexCache::default();
+    let mut ticks = 0;
+    let mut first_channel: Option<Vec<u8>> = None;
+    let mut in_first_channel = true;
+
+    visit_iff_chunks(bytes, &mut |tag, data| match &tag {
+        b"STIM" | b"TIME" => {
+            if let Some(value) = data.first_chunk::<4>() {
+                ticks = i32::from_be_bytes(*value);
+            }
+        }
+        b"CHNM" => {
+            let name = data.split(|&byte| byte == 0).next().unwrap_or_default();
+            in_first_channel = *first_channel.get_or_insert_with(|| name.to_vec()) == name;
+        }
+        b"FVCA" if in_first_channel => {
+            let points = data.chunks_exact(12).map(|point| {
+                let component = |i: usize| {
+                    f32::from_be_bytes([point[i], point[i + 1], point[i + 2], point[i + 3]])
+                };
+                Vec3::new(component(0), component(4), component(8))
+            });
+            cache.times.push(ticks as f32 / MAYA_TICKS_PER_SECOND);
+            cache.frames.push(points.collect());
+        }
+        b"DVCA" if in_first_channel => {
+            let points = data.chunks_exact(24).map(|point| {
+                let component = |i: usize| {
+                    let mut bytes = [0; 8];
+                    bytes.copy_from_slice(&point[i..i + 8]);
+                    f64::from_be_bytes(bytes) as f32
+                };
+                Vec3::new(component(0), component(8), component(16))
+            });
+            cache.times.push(ticks as f32 / MAYA_TICKS_PER_SECOND);
+            cache.frames.push(points.collect());
+        }
+        _ => {}
+    })?;
+
+    if cache.frames.is_empty() {
+        return Err(FbxError::InvalidData(
+            "MC cache: no vertex data found".to_string(),
+        ));
+    }
+    Ok(cache)
+}
+
+/// Visit the data chunks of a Maya IFF file, descending into `FOR4` groups.
+fn visit_iff_chunks(
+    mut bytes: &[u8],
+    visit: &mut impl FnMut([u8; 4], &[u8]),
+) -> Result<(), FbxError> {
+    let invalid = |message: &str| FbxError::InvalidData(format!("MC cache: {message}"));
+    while bytes.len() >= 8 {
+        let tag = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        let size = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+        let data = bytes
+            .get(8..8 + size)
+            .ok_or_else(|| invalid("chunk is truncated"))?;
+        match &tag {
+            // Groups start with their type, e.g. `CACH` or `MYCH`
+            b"FOR4" => visit_iff_chunks(data.get(4..).unwrap_or_default(), visit)?,
+            b"FOR8" => return Err(invalid("64-bit caches are not supported")),
+            _ => visit(tag, data),
+        }
+        bytes = bytes
+            .get((8 + size).next_multiple_of(4)..)
+            .unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Settings of [`FbxVertexCacheLoader`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxVertexCacheLoaderSettings {
+    /// Frame rate used to convert `.pc2` frame numbers to seconds; the FBX
+    /// loader passes the scene's frame rate
+    pub frame_rate: f32,
+}
+
+impl Default for FbxVertexCacheLoaderSettings {
+    fn default() -> Self {
+        Self { frame_rate: 30.0 }
+    }
+}
+
+/// Loader for `.pc2` and one-file Maya `.mc` vertex caches.
+#[derive(Default, TypePath)]
+pub struct FbxVertexCacheLoader;
+
+impl AssetLoader for FbxVertexCacheLoader {
+    type Asset = FbxVertexCache;
+    type Settings = FbxVertexCacheLoaderSettings;
+    type Error = FbxError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<FbxVertexCache, FbxError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let is_mc = load_context
+            .path()
+            .path()
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("mc"));
+        if is_mc {
+            parse_mc(&bytes)
+        } else {
+            parse_pc2(&bytes, settings.frame_rate)
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pc2", "mc"]
+    }
+}
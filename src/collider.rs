@@ -0,0 +1,200 @@
+//! Physics collider extraction using the `UCX_`/`UBX_`/`USP_`/`UCP_` naming convention.
+//!
+//! Nodes whose names start with one of these prefixes are treated as collision
+//! geometry: they are not rendered and their shapes are collected into an
+//! [`FbxColliders`] sub-asset.
+
+use crate::utils::convert_matrix;
+use bevy::prelude::*;
+
+/// Collider kinds identified by node name prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum FbxColliderKind {
+    /// `UCX_`: convex hull of the mesh vertices
+    ConvexHull,
+    /// `UBX_`: box fitted to the mesh bounds
+    Box,
+    /// `USP_`: sphere fitted to the mesh bounds
+    Sphere,
+    /// `UCP_`: Y-aligned capsule fitted to the mesh bounds
+    Capsule,
+}
+
+impl FbxColliderKind {
+    /// Determine the collider kind from a node name, if it follows the convention.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.starts_with("UCX_") {
+            Some(FbxColliderKind::ConvexHull)
+        } else if name.starts_with("UBX_") {
+            Some(FbxColliderKind::Box)
+        } else if name.starts_with("USP_") {
+            Some(FbxColliderKind::Sphere)
+        } else if name.starts_with("UCP_") {
+            Some(FbxColliderKind::Capsule)
+        } else {
+            None
+        }
+    }
+}
+
+/// Collider shape in the collider's local space.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum FbxColliderShape {
+    ConvexHull { points: Vec<Vec3> },
+    Box { half_extents: Vec3 },
+    Sphere { radius: f32 },
+    Capsule { radius: f32, half_height: f32 },
+}
+
+impl FbxColliderShape {
+    /// Fit a shape of the given kind to a set of points.
+    ///
+    /// Returns the shape together with its center, which should be applied
+    /// as an offset to the collider transform.
+    pub fn fit(kind: FbxColliderKind, points: &[Vec3]) -> Option<(Self, Vec3)> {
+        let first = *points.first()?;
+        let (min, max) = points
+            .iter()
+            .fold((first, first), |(min, max), p| (min.min(*p), max.max(*p)));
+        let center = (min + max) * 0.5;
+        let half_extents = (max - min) * 0.5;
+
+        let shape = match kind {
+            FbxColliderKind::ConvexHull => FbxColliderShape::ConvexHull {
+                points: points.iter().map(|p| *p - center).collect(),
+            },
+            FbxColliderKind::Box => FbxColliderShape::Box { half_extents },
+            FbxColliderKind::Sphere => FbxColliderShape::Sphere {
+                radius: points
+                    .iter()
+                    .map(|p| p.distance(center))
+                    .fold(0.0, f32::max),
+            },
+            FbxColliderKind::Capsule => {
+                let radius = half_extents.x.max(half_extents.z);
+                FbxColliderShape::Capsule {
+                    radius,
+                    half_height: (half_extents.y - radius).max(0.0),
+                }
+            }
+        };
+
+        Some((shape, center))
+    }
+}
+
+/// Collider extracted from a node following the naming convention.
+#[derive(Debug, Clone, Reflect)]
+pub struct FbxCollider {
+    pub name: String,
+    /// Index of the source node
+    pub node_index: usize,
+    /// World transform of the collider, including the shape center
+    pub transform: Transform,
+    pub shape: FbxColliderShape,
+}
+
+/// All colliders extracted from an FBX file.
+#[derive(Asset, Debug, Clone, Default, Reflect)]
+pub struct FbxColliders {
+    pub colliders: Vec<FbxCollider>,
+}
+
+/// Collect colliders from nodes following the naming convention.
+pub fn process_colliders(scene: &ufbx::Scene) -> FbxColliders {
+    let mut colliders = Vec::new();
+
+    for (index, node) in scene.nodes.as_ref().iter().enumerate() {
+        let Some(kind) = FbxColliderKind::from_name(&node.element.name) else {
+            continue;
+        };
+        let Some(mesh) = node.mesh.as_ref() else {
+            continue;
+        };
+
+        let points: Vec<Vec3> = mesh
+            .vertex_position
+            .values
+            .as_ref()
+            .iter()
+            .map(|v| Vec3::new(v.x as f32, v.y as f32, v.z as f32))
+            .collect();
+        let Some((shape, center)) = FbxColliderShape::fit(kind, &points) else {
+            continue;
+        };
+
+        let transform = Transform::from_matrix(
+            convert_matrix(&node.geometry_to_world) * Mat4::from_translation(center),
+        );
+
+        colliders.push(FbxCollider {
+            name: node.element.name.to_string(),
+            node_index: index,
+            transform,
+            shape,
+        });
+    }
+
+    FbxColliders { colliders }
+}
+
+/// Create an Avian collider component for a shape.
+#[cfg(feature = "avian")]
+pub fn avian_collider(shape: &FbxColliderShape) -> Option<avian3d::prelude::Collider> {
+    use avian3d::prelude::Collider;
+
+    match shape {
+        FbxColliderShape::ConvexHull { points } => Collider::convex_hull(points.clone()),
+        FbxColliderShape::Box { half_extents } => {
+            let size = *half_extents * 2.0;
+            Some(Collider::cuboid(size.x, size.y, size.z))
+        }
+        FbxColliderShape::Sphere { radius } => Some(Collider::sphere(*radius)),
+        FbxColliderShape::Capsule {
+            radius,
+            half_height,
+        } => Some(Collider::capsule(*radius, *half_height * 2.0)),
+    }
+}
+
+/// Create a Rapier collider component for a shape.
+#[cfg(feature = "rapier")]
+pub fn rapier_collider(shape: &FbxColliderShape) -> Option<bevy_rapier3d::prelude::Collider> {
+    use bevy_rapier3d::prelude::Collider;
+
+    match shape {
+        FbxColliderShape::ConvexHull { points } => Collider::convex_hull(points),
+        FbxColliderShape::Box { half_extents } => Some(Collider::cuboid(
+            half_extents.x,
+            half_extents.y,
+            half_extents.z,
+        )),
+        FbxColliderShape::Sphere { radius } => Some(Collider::ball(*radius)),
+        FbxColliderShape::Capsule {
+            radius,
+            half_height,
+        } => Some(Collider::capsule_y(*half_height, *radius)),
+    }
+}
+
+/// Spawn entities carrying physics collider components into the scene world.
+#[cfg(any(feature = "avian", feature = "rapier"))]
+pub fn spawn_colliders(colliders: &FbxColliders, world: &mut World) {
+    for collider in &colliders.colliders {
+        let mut entity = world.spawn((
+            Name::new(collider.name.clone()),
+            collider.transform,
+            GlobalTransform::default(),
+        ));
+
+        #[cfg(feature = "avian")]
+        if let Some(component) = avian_collider(&collider.shape) {
+            entity.insert(component);
+        }
+
+        #[cfg(feature = "rapier")]
+        if let Some(component) = rapier_collider(&collider.shape) {
+            entity.insert(component);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Conversion of positions, rotations and meshes between axis systems.
+//!
+//! The loader has ufbx convert every file to [`FbxAxisSystem::BEVY`] in
+//! meters, with the parse options from [`axis_load_opts`]. Meshes, node
+//! transforms, skin bind matrices and animation keys are all read from that
+//! one converted scene, so they agree with each other whichever
+//! [`FbxSpaceConversion`] stores the change of basis.
+//! [`FbxCoordinateConverter`] applies the same change of basis to data
+//! created at runtime, e.g. procedural content authored in the file's
+//! original axes that has to line up with the loaded scene.
+
+use crate::animation::FbxRootMotion;
+use crate::loader::FbxLoaderSettings;
+use crate::mesh::transform_vertex_attribute;
+use crate::types::{Fbx, FbxAxisSystem};
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the change of basis to Bevy's axes and units is stored.
+///
+/// Every option places the scene identically in world space; they differ in
+/// which transforms and vertices carry the conversion. This matters for
+/// code reading local transforms, e.g. retargeting that expects Y-up bone
+/// axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FbxSpaceConversion {
+    /// Convert through the transform of the scene root, keeping node
+    /// transforms and vertices as authored; top-level nodes and root bones
+    /// take it from their world transform
+    #[default]
+    TransformRoot,
+    /// Fold the conversion into the transforms of top-level nodes
+    AdjustTransforms,
+    /// Convert vertices, node transforms, bind matrices and animation
+    /// directly, leaving every transform in Bevy's axes
+    ModifyGeometry,
+}
+
+impl From<FbxSpaceConversion> for ufbx::SpaceConversion {
+    fn from(conversion: FbxSpaceConversion) -> Self {
+        match conversion {
+            FbxSpaceConversion::TransformRoot => ufbx::SpaceConversion::TransformRoot,
+            FbxSpaceConversion::AdjustTransforms => ufbx::SpaceConversion::AdjustTransforms,
+            FbxSpaceConversion::ModifyGeometry => ufbx::SpaceConversion::ModifyGeometry,
+        }
+    }
+}
+
+/// Parse options converting a file to [`FbxAxisSystem::BEVY`] in meters,
+/// stored per [`FbxLoaderSettings::space_conversion`].
+///
+/// The loader builds on these, so `ufbx::load_memory` with them sees a file
+/// in the same space as the loaded assets.
+pub fn axis_load_opts<'a>(settings: &FbxLoaderSettings) -> ufbx::LoadOpts<'a> {
+    ufbx::LoadOpts {
+        target_unit_meters: 1.0,
+        target_axes: ufbx::CoordinateAxes::right_handed_y_up(),
+        // Bevy cameras look down -Z
+        target_camera_axes: ufbx::CoordinateAxes::right_handed_y_up(),
+        space_conversion: settings.space_conversion.into(),
+        ..Default::default()
+    }
+}
+
+/// Change of basis from one [`FbxAxisSystem`] to another, with an optional
+/// unit scale.
+///
+/// Axes are matched by meaning: the source's up axis maps onto the target's
+/// up axis, and likewise for front and right. Converting between systems of
+/// different handedness mirrors, which [`convert_mesh`](Self::convert_mesh)
+/// compensates by flipping the triangle winding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FbxCoordinateConverter {
+    /// Orthonormal basis change, with a determinant of -1 when mirroring
+    basis: Mat3,
+    /// Scale applied to lengths
+    scale: f32,
+}
+
+impl Default for FbxCoordinateConverter {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl FbxCoordinateConverter {
+    /// Converter leaving everything unchanged.
+    pub const IDENTITY: Self = Self {
+        basis: Mat3::IDENTITY,
+        scale: 1.0,
+    };
+
+    /// Converter from the `from` axis system to `to`.
+    pub fn new(from: FbxAxisSystem, to: FbxAxisSystem) -> Self {
+        let axes = |system: FbxAxisSystem| Mat3::from_cols(system.right(), system.up, system.front);
+        Self {
+            basis: axes(to) * axes(from).transpose(),
+            scale: 1.0,
+        }
+    }
+
+    /// Converter from the original axes of a loaded file to Bevy's, as
+    /// applied by the loader.
+    pub fn from_fbx(fbx: &Fbx) -> Self {
+        Self::new(fbx.axis_system, FbxAxisSystem::BEVY)
+    }
+
+    /// Converter from Bevy's axes to the original axes of a loaded file,
+    /// e.g. to write runtime data back in the file's conventions.
+    pub fn to_fbx(fbx: &Fbx) -> Self {
+        Self::from_fbx(fbx).inverse()
+    }
+
+    /// Also scale lengths by `scale`, e.g. `0.01` for centimeters to meters.
+    pub fn with_unit_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Converter undoing this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            basis: self.basis.transpose(),
+            scale: self.scale.recip(),
+        }
+    }
+
+    /// Whether the conversion changes nothing.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Whether the conversion mirrors, i.e. the axis systems differ in
+    /// handedness.
+    pub fn flips_handedness(&self) -> bool {
+        self.basis.determinant() < 0.0
+    }
+
+    /// Conversion as a matrix, including the unit scale.
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_mat3(self.basis * self.scale)
+    }
+
+    /// Transform of a parent entity converting everything below it.
+    ///
+    /// Mirroring conversions are expressed as a negative scale.
+    pub fn root_transform(&self) -> Transform {
+        Transform::from_matrix(self.matrix())
+    }
+
+    /// Convert a position, scaling it by the unit scale.
+    pub fn convert_point(&self, point: Vec3) -> Vec3 {
+        self.basis * point * self.scale
+    }
+
+    /// Convert a direction or normal, keeping its length.
+    pub fn convert_direction(&self, direction: Vec3) -> Vec3 {
+        self.basis * direction
+    }
+
+    /// Convert a rotation.
+    ///
+    /// Mirroring conversions also reverse the rotation's sense, so the
+    /// result is a proper rotation about the converted axis.
+    pub fn convert_rotation(&self, rotation: Quat) -> Quat {
+        let matrix = self.basis * Mat3::from_quat(rotation) * self.basis.transpose();
+        Quat::from_mat3(&matrix).normalize()
+    }
+
+    /// Convert a per-axis scale.
+    ///
+    /// Axis systems only swap and negate axes, so the factors are permuted
+    /// and keep their sign.
+    pub fn convert_scale(&self, scale: Vec3) -> Vec3 {
+        self.basis.abs() * scale
+    }
+
+    /// Convert a local transform.
+    pub fn convert_transform(&self, transform: &Transform) -> Transform {
+        Transform {
+            translation: self.convert_point(transform.translation),
+            rotation: self.convert_rotation(transform.rotation),
+            scale: self.convert_scale(transform.scale),
+        }
+    }
+
+    /// Convert an affine matrix, such as a node's world transform.
+    pub fn convert_matrix(&self, matrix: Mat4) -> Mat4 {
+        let basis = Mat4::from_mat3(self.basis);
+        let translation = self.convert_point(matrix.w_axis.truncate());
+        let mut converted = basis * matrix * basis.transpose();
+        converted.w_axis = translation.extend(1.0);
+        converted
+    }
+
+    /// Convert the positions, normals and tangents of a mesh in place.
+    ///
+    /// Triangle winding is flipped for mirroring conversions so faces keep
+    /// facing outwards.
+    pub fn convert_mesh(&self, mesh: &mut Mesh) {
+        let matrix = self.matrix();
+        let attributes: Vec<_> = mesh
+            .attributes()
+            .map(|(attribute, values)| {
+                let values = transform_vertex_attribute(attribute.id, values, matrix);
+                (*attribute, values)
+            })
+            .collect();
+        for (attribute, values) in attributes {
+            mesh.insert_attribute(attribute, values);
+        }
+
+        if !self.flips_handedness() || mesh.primitive_topology() != PrimitiveTopology::TriangleList
+        {
+            return;
+        }
+        let mut indices: Vec<u32> = match mesh.indices() {
+            Some(Indices::U16(values)) => values.iter().map(|&i| i as u32).collect(),
+            Some(Indices::U32(values)) => values.clone(),
+            None => (0..mesh.count_vertices() as u32).collect(),
+        };
+        for triangle in indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+        mesh.insert_indices(Indices::U32(indices));
+    }
+
+    /// Convert the keys of extracted root motion.
+    ///
+    /// Bevy [`AnimationClip`] curves can't be rewritten once built; convert
+    /// animated content by spawning it below an entity with
+    /// [`root_transform`](Self::root_transform) instead.
+    pub fn convert_root_motion(&self, root_motion: &mut FbxRootMotion) {
+        for (_, offset) in &mut root_motion.translation {
+            *offset = self.convert_point(*offset);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Gizmo visualization of spawned FBX scenes for diagnosing rig imports.
+
+use crate::types::{FbxBone, FbxNull};
+use bevy::color::palettes::css::{DEEP_SKY_BLUE, ORANGE, YELLOW};
+use bevy::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes};
+use bevy::prelude::*;
+
+/// Plugin drawing gizmos for entities with [`FbxDebugDraw`] and their
+/// descendants.
+///
+/// Requires the `debug` feature.
+#[derive(Default)]
+pub struct FbxDebugPlugin;
+
+impl Plugin for FbxDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<FbxDebugDraw>()
+            .add_systems(Update, draw_fbx_debug);
+    }
+}
+
+/// Enables debug drawing for an entity (usually a [`SceneRoot`]) and all of
+/// its descendants.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct FbxDebugDraw {
+    /// Draw a line from each [`FbxBone`] to its parent bone
+    pub bones: bool,
+    /// Draw the bind pose of each [`SkinnedMesh`] joint as a short line along
+    /// the joint's Y axis
+    pub bind_poses: bool,
+    /// Draw the local axes of bones and [`FbxNull`] nodes
+    pub node_axes: bool,
+    /// Length of drawn axes in world units
+    pub axis_length: f32,
+}
+
+impl Default for FbxDebugDraw {
+    fn default() -> Self {
+        Self {
+            bones: true,
+            bind_poses: false,
+            node_axes: false,
+            axis_length: 0.1,
+        }
+    }
+}
+
+/// Draw gizmos for every entity below an [`FbxDebugDraw`] root.
+#[allow(clippy::type_complexity)]
+pub fn draw_fbx_debug(
+    mut gizmos: Gizmos,
+    roots: Query<(Entity, &FbxDebugDraw)>,
+    children: Query<&Children>,
+    nodes: Query<(
+        &GlobalTransform,
+        Option<&ChildOf>,
+        Has<FbxBone>,
+        Has<FbxNull>,
+    )>,
+    bones: Query<&GlobalTransform, With<FbxBone>>,
+    skinned_meshes: Query<(&GlobalTransform, &SkinnedMesh)>,
+    inverse_bindposes: Res<Assets<SkinnedMeshInverseBindposes>>,
+) {
+    for (root, draw) in &roots {
+        for entity in std::iter::once(root).chain(children.iter_descendants(root)) {
+            let Ok((transform, parent, is_bone, is_null)) = nodes.get(entity) else {
+                continue;
+            };
+
+            if draw.bones && is_bone {
+                let parent_bone = parent.and_then(|parent| bones.get(parent.parent()).ok());
+                match parent_bone {
+                    Some(parent_transform) => gizmos.line(
+                        parent_transform.translation(),
+                        transform.translation(),
+                        YELLOW,
+                    ),
+                    // Root bones get a short vertical tick so they remain visible
+                    None => gizmos.line(
+                        transform.translation(),
+                        transform.translation() + Vec3::Y * draw.axis_length,
+                        ORANGE,
+                    ),
+                }
+            }
+
+            if draw.node_axes && (is_bone || is_null) {
+                gizmos.axes(*transform, draw.axis_length);
+            }
+        }
+
+        if draw.bind_poses {
+            for bind_pose in joint_bind_poses(root, &children, &skinned_meshes, &inverse_bindposes)
+            {
+                let origin = bind_pose.transform_point3(Vec3::ZERO);
+                gizmos.line(
+                    origin,
+                    origin + bind_pose.transform_vector3(Vec3::Y) * draw.axis_length,
+                    DEEP_SKY_BLUE,
+                );
+            }
+        }
+    }
+}
+
+/// World-space bind pose of each joint of the [`SkinnedMesh`]es at or below
+/// `root`, as drawn with [`FbxDebugDraw::bind_poses`].
+pub fn joint_bind_poses(
+    root: Entity,
+    children: &Query<&Children>,
+    skinned_meshes: &Query<(&GlobalTransform, &SkinnedMesh)>,
+    inverse_bindposes: &Assets<SkinnedMeshInverseBindposes>,
+) -> Vec<Mat4> {
+    let mut bind_poses = Vec::new();
+    for entity in std::iter::once(root).chain(children.iter_descendants(root)) {
+        let Ok((mesh_transform, skinned_mesh)) = skinned_meshes.get(entity) else {
+            continue;
+        };
+        let Some(bindposes) = inverse_bindposes.get(&skinned_mesh.inverse_bindposes) else {
+            continue;
+        };
+        let mesh_matrix = Mat4::from(mesh_transform.affine());
+        bind_poses.extend(
+            bindposes
+                .iter()
+                .map(|inverse_bindpose| mesh_matrix * inverse_bindpose.inverse()),
+        );
+    }
+    bind_poses
+}
@@ -0,0 +1,195 @@
+//! Load statistics and timing diagnostics for FBX files.
+
+use crate::types::Fbx;
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Statistics gathered while loading an FBX file.
+///
+/// Stored on [`Fbx::diagnostics`] so expensive assets can be identified.
+#[derive(Debug, Clone, Default, Reflect)]
+pub struct FbxLoadDiagnostics {
+    /// Number of vertices across all meshes in the file
+    pub vertex_count: usize,
+    /// Number of triangles across all meshes in the file
+    pub triangle_count: usize,
+    /// Number of materials in the file
+    pub material_count: usize,
+    /// Number of bones in the file
+    pub bone_count: usize,
+    /// Time spent parsing the file with ufbx
+    pub parse_time: Duration,
+    /// Whether the parsed scene came from the
+    /// [`parse_cache`](crate::parse_cache) instead of a new parse
+    pub parse_cache_hit: bool,
+    /// Time spent converting meshes
+    pub mesh_time: Duration,
+    /// Time spent converting materials and textures
+    pub material_time: Duration,
+    /// Time spent building the scene
+    pub scene_time: Duration,
+}
+
+impl FbxLoadDiagnostics {
+    /// Total time spent across all load stages.
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.mesh_time + self.material_time + self.scene_time
+    }
+
+    /// Fill in the element counts from a parsed ufbx scene.
+    pub fn count_scene(&mut self, scene: &ufbx::Scene) {
+        self.vertex_count = scene.meshes.iter().map(|mesh| mesh.num_vertices).sum();
+        self.triangle_count = scene.meshes.iter().map(|mesh| mesh.num_triangles).sum();
+        self.material_count = scene.materials.len();
+        self.bone_count = scene.bones.len();
+    }
+}
+
+/// Non-fatal problem reported by ufbx while parsing, such as bad indices or
+/// truncated arrays, or by the loader while building the scenes.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub struct FbxWarning {
+    /// ufbx warning type, e.g. `IndexClamped`, or `TakeScene` for a skipped
+    /// take scene
+    pub kind: String,
+    pub description: String,
+    /// Element the warning refers to, if any
+    pub element_id: Option<u32>,
+    /// Number of times the warning occurred
+    pub count: usize,
+}
+
+impl core::fmt::Display for FbxWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.kind, self.description)?;
+        if self.count > 1 {
+            write!(f, " (x{})", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Warnings ufbx produced while parsing an FBX file, along with take scenes
+/// the loader had to skip.
+///
+/// Stored on [`Fbx::warnings`] so broken exports can be caught in art QA.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Reflect)]
+pub struct FbxWarnings(pub Vec<FbxWarning>);
+
+impl FbxWarnings {
+    /// Collect the warnings of a parsed ufbx scene.
+    pub fn from_scene(scene: &ufbx::Scene) -> Self {
+        Self(
+            scene
+                .metadata
+                .warnings
+                .as_ref()
+                .iter()
+                .map(|warning| FbxWarning {
+                    kind: format!("{:?}", warning.type_),
+                    description: warning.description.to_string(),
+                    // ufbx uses `u32::MAX` for warnings not tied to an element
+                    element_id: (warning.element_id != u32::MAX).then_some(warning.element_id),
+                    count: warning.count,
+                })
+                .collect(),
+        )
+    }
+
+    /// Log every warning, prefixed with the file it came from.
+    pub fn log(&self, source: impl core::fmt::Display) {
+        for warning in &self.0 {
+            warn!("{}: {}", source, warning);
+        }
+    }
+
+    /// Whether ufbx reported no warnings.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Number of distinct warnings.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Plugin forwarding [`FbxLoadDiagnostics`] to Bevy's diagnostics store.
+///
+/// Measurements are recorded each time an [`Fbx`] asset finishes loading.
+#[derive(Default)]
+pub struct FbxDiagnosticsPlugin;
+
+impl FbxDiagnosticsPlugin {
+    /// Total load time of the most recently loaded FBX file, in milliseconds.
+    pub const LOAD_TIME: DiagnosticPath = DiagnosticPath::const_new("fbx/load_time");
+    /// Parse time of the most recently loaded FBX file, in milliseconds.
+    pub const PARSE_TIME: DiagnosticPath = DiagnosticPath::const_new("fbx/parse_time");
+    /// Mesh conversion time of the most recently loaded FBX file, in milliseconds.
+    pub const MESH_TIME: DiagnosticPath = DiagnosticPath::const_new("fbx/mesh_time");
+    /// Material conversion time of the most recently loaded FBX file, in milliseconds.
+    pub const MATERIAL_TIME: DiagnosticPath = DiagnosticPath::const_new("fbx/material_time");
+    /// Scene build time of the most recently loaded FBX file, in milliseconds.
+    pub const SCENE_TIME: DiagnosticPath = DiagnosticPath::const_new("fbx/scene_time");
+    /// Vertex count of the most recently loaded FBX file.
+    pub const VERTEX_COUNT: DiagnosticPath = DiagnosticPath::const_new("fbx/vertex_count");
+    /// Triangle count of the most recently loaded FBX file.
+    pub const TRIANGLE_COUNT: DiagnosticPath = DiagnosticPath::const_new("fbx/triangle_count");
+}
+
+impl Plugin for FbxDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        for path in [
+            Self::LOAD_TIME,
+            Self::PARSE_TIME,
+            Self::MESH_TIME,
+            Self::MATERIAL_TIME,
+            Self::SCENE_TIME,
+        ] {
+            app.register_diagnostic(Diagnostic::new(path).with_suffix("ms"));
+        }
+        app.register_diagnostic(Diagnostic::new(Self::VERTEX_COUNT))
+            .register_diagnostic(Diagnostic::new(Self::TRIANGLE_COUNT))
+            .add_systems(Update, record_load_diagnostics);
+    }
+}
+
+/// Record diagnostics for every newly loaded FBX asset.
+fn record_load_diagnostics(
+    mut events: MessageReader<AssetEvent<Fbx>>,
+    fbx_assets: Res<Assets<Fbx>>,
+    mut diagnostics: Diagnostics,
+) {
+    for event in events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+        let Some(fbx) = fbx_assets.get(*id) else {
+            continue;
+        };
+        let stats = &fbx.diagnostics;
+
+        diagnostics.add_measurement(&FbxDiagnosticsPlugin::LOAD_TIME, || {
+            stats.total_time().as_secs_f64() * 1000.0
+        });
+        diagnostics.add_measurement(&FbxDiagnosticsPlugin::PARSE_TIME, || {
+            stats.parse_time.as_secs_f64() * 1000.0
+        });
+        diagnostics.add_measurement(&FbxDiagnosticsPlugin::MESH_TIME, || {
+            stats.mesh_time.as_secs_f64() * 1000.0
+        });
+        diagnostics.add_measurement(&FbxDiagnosticsPlugin::MATERIAL_TIME, || {
+            stats.material_time.as_secs_f64() * 1000.0
+        });
+        diagnostics.add_measurement(&FbxDiagnosticsPlugin::SCENE_TIME, || {
+            stats.scene_time.as_secs_f64() * 1000.0
+        });
+        diagnostics.add_measurement(&FbxDiagnosticsPlugin::VERTEX_COUNT, || {
+            stats.vertex_count as f64
+        });
+        diagnostics.add_measurement(&FbxDiagnosticsPlugin::TRIANGLE_COUNT, || {
+            stats.triangle_count as f64
+        });
+    }
+}
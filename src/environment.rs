@@ -0,0 +1,73 @@
+//! Scene-wide ambient color and environment texture import.
+
+use crate::loader::FbxLoaderSettings;
+use crate::material::resolve_texture_path;
+use crate::sink::FbxAssetSink;
+use crate::texture::{TextureCache, TextureIndex};
+use bevy::asset::Handle;
+use bevy::prelude::*;
+
+/// Name fragments marking a texture as an environment or sky map.
+const ENVIRONMENT_NAME_HINTS: &[&str] = &["environment", "envmap", "env_", "sky", "hdri"];
+
+/// File extensions only used for environment maps in practice.
+const ENVIRONMENT_EXTENSIONS: &[&str] = &["hdr", "exr"];
+
+/// Ambient color from the FBX `GlobalSettings`, `None` if it is black.
+pub fn ambient_color(scene: &ufbx::Scene) -> Option<Color> {
+    let color = scene.settings.ambient_color;
+    (color.x > 0.0 || color.y > 0.0 || color.z > 0.0)
+        .then(|| Color::srgb(color.x as f32, color.y as f32, color.z as f32))
+}
+
+/// Ambient light for the FBX ambient color, spawned with
+/// [`FbxLoaderSettings::spawn_ambient_light`].
+pub fn ambient_light(scene: &ufbx::Scene) -> Option<AmbientLight> {
+    Some(AmbientLight {
+        color: ambient_color(scene)?,
+        ..Default::default()
+    })
+}
+
+/// Whether a texture looks like an environment or sky map, judging by its
+/// name and file extension.
+pub fn is_environment_texture(name: &str, filename: &str) -> bool {
+    let name = name.to_lowercase();
+    let filename = filename.to_lowercase().replace('\\', "/");
+    let file_name = filename.rsplit('/').next().unwrap_or_default();
+    let has_environment_extension = file_name
+        .rsplit_once('.')
+        .is_some_and(|(_, extension)| ENVIRONMENT_EXTENSIONS.contains(&extension));
+
+    has_environment_extension
+        || ENVIRONMENT_NAME_HINTS
+            .iter()
+            .any(|hint| name.contains(hint) || file_name.contains(hint))
+}
+
+/// Load the first file texture detected by [`is_environment_texture`].
+///
+/// FBX has no standard environment slot, so exporters that include a sky
+/// texture only reference it as a plain texture. The returned image can be
+/// used for an `EnvironmentMapLight` after converting it to a cubemap.
+pub fn process_environment_map(
+    scene: &ufbx::Scene,
+    settings: &FbxLoaderSettings,
+    texture_index: &TextureIndex,
+    texture_cache: &mut TextureCache,
+    load_context: &mut impl FbxAssetSink,
+) -> Option<Handle<Image>> {
+    let texture = scene.textures.as_ref().iter().find(|texture| {
+        texture.type_ == ufbx::TextureType::File
+            && is_environment_texture(texture.element.name.as_ref(), texture.filename.as_ref())
+    })?;
+    let path = resolve_texture_path(
+        texture.relative_filename.as_ref(),
+        texture.filename.as_ref(),
+        settings,
+        texture_index,
+        &load_context.base_dir(),
+        load_context.media_dir().as_deref(),
+    )?;
+    Some(texture_cache.load(&path, load_context))
+}
@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -25,4 +26,23 @@ pub enum FbxError {
 
     #[error("Unsupported FBX feature: {0}")]
     UnsupportedFeature(String),
+
+    #[error("Failed to serialize FBX scene: {0}")]
+    Serialization(String),
+
+    #[error("FBX limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    #[error("FBX node hierarchy has a cycle through node '{0}'")]
+    HierarchyCycle(String),
+
+    #[error(
+        "Unsupported FBX version {found}, supported versions are {}-{}",
+        supported_range.start(),
+        supported_range.end()
+    )]
+    UnsupportedVersion {
+        found: u32,
+        supported_range: RangeInclusive<u32>,
+    },
 }
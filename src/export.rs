@@ -0,0 +1,182 @@
+//! Conversion of imported FBX scenes into Bevy's native `.scn.ron` format.
+//!
+//! Lets content be imported once, e.g. in a build step, and shipped as a
+//! [`DynamicScene`] without the FBX loader at runtime. Requires the
+//! `scene_export` feature; the `cli` feature adds the `ufbx2bevy` binary
+//! writing everything below from the command line.
+
+use crate::error::FbxError;
+use crate::label::FbxAssetLabel;
+use crate::types::{Fbx, FbxMesh};
+use bevy::prelude::*;
+use bevy::reflect::serde::TypedReflectSerializer;
+use bevy::scene::DynamicSceneBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Convert the default scene of `fbx` into a [`DynamicScene`].
+///
+/// Falls back to the first scene if no default is set. Components whose
+/// types are not registered in the world's [`AppTypeRegistry`] are skipped.
+pub fn fbx_to_dynamic_scene(fbx: &Fbx, world: &World) -> Result<DynamicScene, FbxError> {
+    let handle = fbx
+        .default_scene
+        .as_ref()
+        .or_else(|| fbx.scenes.first())
+        .ok_or_else(|| FbxError::InvalidData("FBX has no scene to export".to_string()))?;
+    let scene = world
+        .get_resource::<Assets<Scene>>()
+        .and_then(|scenes| scenes.get(handle))
+        .ok_or_else(|| FbxError::InvalidData("FBX scene is not loaded".to_string()))?;
+
+    Ok(DynamicSceneBuilder::from_world(&scene.world)
+        .extract_entities(scene.world.iter_entities().map(|entity| entity.id()))
+        .build())
+}
+
+/// Serialize the default scene of `fbx` to `.scn.ron` text.
+///
+/// Mesh, material and other asset handles are written as references to the
+/// assets they point at, which must be shipped alongside the scene.
+pub fn serialize_fbx_scene(fbx: &Fbx, world: &World) -> Result<String, FbxError> {
+    let dynamic_scene = fbx_to_dynamic_scene(fbx, world)?;
+    let type_registry = world
+        .get_resource::<AppTypeRegistry>()
+        .ok_or_else(|| FbxError::Serialization("world has no AppTypeRegistry".to_string()))?;
+
+    dynamic_scene
+        .serialize(&type_registry.read())
+        .map_err(|error| FbxError::Serialization(error.to_string()))
+}
+
+/// Write the default scene of `fbx` to a `.scn.ron` file at `path`.
+pub fn write_fbx_scene(fbx: &Fbx, world: &World, path: impl AsRef<Path>) -> Result<(), FbxError> {
+    let ron = serialize_fbx_scene(fbx, world)?;
+    std::fs::write(path, ron)?;
+    Ok(())
+}
+
+/// Serialize the materials of `fbx` to RON text, as a map from their
+/// `Material{}` labels to the reflected [`StandardMaterial`].
+///
+/// [`StandardMaterial`] must be registered in the world's
+/// [`AppTypeRegistry`]; texture handles are written as references like the
+/// scene's.
+pub fn serialize_fbx_materials(fbx: &Fbx, world: &World) -> Result<String, FbxError> {
+    let type_registry = world
+        .get_resource::<AppTypeRegistry>()
+        .ok_or_else(|| FbxError::Serialization("world has no AppTypeRegistry".to_string()))?;
+    let type_registry = type_registry.read();
+    let materials = world
+        .get_resource::<Assets<StandardMaterial>>()
+        .ok_or_else(|| FbxError::InvalidData("world has no material assets".to_string()))?;
+
+    let mut ron = String::from("{\n");
+    for (index, handle) in fbx.materials.iter().enumerate() {
+        let Some(material) = materials.get(handle) else {
+            continue;
+        };
+        let serializer = TypedReflectSerializer::new(material, &type_registry);
+        let material = ron::ser::to_string(&serializer)
+            .map_err(|error| FbxError::Serialization(error.to_string()))?;
+        ron.push_str(&format!(
+            "    \"{}\": {material},\n",
+            FbxAssetLabel::Material(index)
+        ));
+    }
+    ron.push('}');
+    Ok(ron)
+}
+
+/// Inventory of the assets of a converted file, written next to the scene
+/// by `ufbx2bevy` so build steps can check what an export contains.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FbxExportManifest {
+    /// Meshes with the vertex and index count of each primitive
+    pub meshes: Vec<FbxExportedMesh>,
+    /// Labels of the materials, by index
+    pub materials: Vec<String>,
+    /// Animation clips with their timing
+    pub clips: Vec<FbxExportedClip>,
+}
+
+/// Mesh entry of [`FbxExportManifest`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FbxExportedMesh {
+    pub label: String,
+    pub name: String,
+    /// `(vertices, indices)` of each primitive
+    pub primitives: Vec<(usize, usize)>,
+}
+
+/// Animation clip entry of [`FbxExportManifest`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FbxExportedClip {
+    pub label: String,
+    /// Name of the animation stack, if it has one
+    pub name: Option<String>,
+    /// Length in seconds
+    pub duration: f32,
+    pub frame_rate: f32,
+}
+
+/// Inventory of the meshes, materials and clips of `fbx`.
+pub fn fbx_export_manifest(fbx: &Fbx, world: &World) -> FbxExportManifest {
+    let fbx_meshes = world.get_resource::<Assets<FbxMesh>>();
+    let meshes = world.get_resource::<Assets<Mesh>>();
+    let clip_names: HashMap<AssetId<AnimationClip>, &str> = fbx
+        .named_animations
+        .iter()
+        .map(|(name, handle)| (handle.id(), &**name))
+        .collect();
+
+    FbxExportManifest {
+        meshes: fbx
+            .meshes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, handle)| {
+                let fbx_mesh = fbx_meshes?.get(handle)?;
+                let primitives = fbx_mesh
+                    .primitives
+                    .iter()
+                    .filter_map(|primitive| meshes?.get(&primitive.mesh))
+                    .map(|mesh| {
+                        let indices = mesh.indices().map_or(0, |indices| indices.len());
+                        (mesh.count_vertices(), indices)
+                    })
+                    .collect();
+                Some(FbxExportedMesh {
+                    label: FbxAssetLabel::Mesh(index).to_string(),
+                    name: fbx_mesh.name.clone(),
+                    primitives,
+                })
+            })
+            .collect(),
+        materials: (0..fbx.materials.len())
+            .map(|index| FbxAssetLabel::Material(index).to_string())
+            .collect(),
+        clips: fbx
+            .animations
+            .iter()
+            .enumerate()
+            .map(|(index, handle)| {
+                let timing = fbx.clip_timings.get(index);
+                FbxExportedClip {
+                    label: FbxAssetLabel::Animation(index).to_string(),
+                    name: clip_names.get(&handle.id()).map(|name| name.to_string()),
+                    duration: timing.map_or(0.0, |timing| timing.duration()),
+                    frame_rate: timing.map_or(0.0, |timing| timing.frame_rate),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Serialize [`fbx_export_manifest`] to RON text.
+pub fn serialize_fbx_manifest(fbx: &Fbx, world: &World) -> Result<String, FbxError> {
+    let manifest = fbx_export_manifest(fbx, world);
+    ron::ser::to_string_pretty(&manifest, ron::ser::PrettyConfig::default())
+        .map_err(|error| FbxError::Serialization(error.to_string()))
+}
@@ -0,0 +1,52 @@
+//! Hooks for attaching game components to entities spawned from FBX nodes.
+
+use bevy::prelude::*;
+use std::sync::Arc;
+
+/// Callback run on every node entity spawned while building FBX scenes.
+///
+/// Register implementations with [`FbxPlugin::with_extension`](crate::FbxPlugin::with_extension)
+/// to insert components based on node names or custom properties, e.g. a
+/// marker for every node named `spawn_*`.
+pub trait FbxSceneExtension: Send + Sync + 'static {
+    /// Called once for each mesh, bone and light node entity of a scene.
+    fn process_node(&self, node: &ufbx::Node, entity: &mut EntityWorldMut);
+}
+
+impl<F> FbxSceneExtension for F
+where
+    F: Fn(&ufbx::Node, &mut EntityWorldMut) + Send + Sync + 'static,
+{
+    fn process_node(&self, node: &ufbx::Node, entity: &mut EntityWorldMut) {
+        self(node, entity)
+    }
+}
+
+/// Scene extensions registered on the [`FbxPlugin`](crate::FbxPlugin).
+///
+/// Also inserted as a resource so [`Fbx::from_bytes`](crate::Fbx::from_bytes)
+/// applies the same extensions as the asset loader.
+#[derive(Resource, Clone, Default)]
+pub struct FbxSceneExtensions(pub Vec<Arc<dyn FbxSceneExtension>>);
+
+impl FbxSceneExtensions {
+    /// Run every extension on the entity spawned for `node`.
+    pub fn process_node(&self, node: &ufbx::Node, entity: &mut EntityWorldMut) {
+        for extension in &self.0 {
+            extension.process_node(node, entity);
+        }
+    }
+
+    /// Whether no extensions are registered.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl core::fmt::Debug for FbxSceneExtensions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("FbxSceneExtensions")
+            .field(&self.0.len())
+            .finish()
+    }
+}
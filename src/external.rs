@@ -0,0 +1,146 @@
+//! External files referenced by FBX files, read through Bevy's asset IO.
+//!
+//! ufbx can load the geometry caches an FBX file references, but it opens
+//! them on the filesystem. With
+//! [`FbxLoaderSettings::load_external_files`](crate::FbxLoaderSettings::load_external_files),
+//! the loader instead reads them through the asset source of the FBX file
+//! before parsing, so they also load from embedded, packed or HTTP sources,
+//! and serves them to ufbx from memory.
+
+use crate::sink::source_asset_path;
+use crate::texture::texture_candidates;
+use bevy::asset::LoadContext;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Contents of the external files of one FBX file, keyed by lowercase file
+/// name.
+///
+/// Files are matched by name only, as the paths stored in FBX files usually
+/// point at the authoring machine.
+#[derive(Debug, Clone, Default)]
+pub struct FbxExternalFiles {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl FbxExternalFiles {
+    /// Read the files referenced by the FBX data `bytes` through the asset
+    /// source of `load_context`.
+    ///
+    /// Each file is looked up like a texture: at its relative path, then by
+    /// name next to the FBX file and in its `.fbm` folder. Maya `.xml` cache
+    /// descriptions also fetch the `.mc` file of the same name. Files that
+    /// cannot be read are skipped, leaving ufbx to report them as missing.
+    pub async fn fetch(bytes: &[u8], load_context: &mut LoadContext<'_>) -> Self {
+        let base_dir = match load_context.path().parent() {
+            Some(parent) => parent.path().to_path_buf(),
+            None => PathBuf::from(""),
+        };
+        let media_dirs = vec![media_dir(load_context.path().path())];
+
+        let mut external = Self::default();
+        for (relative_filename, filename) in external_file_names(bytes) {
+            let mut candidates =
+                texture_candidates(&relative_filename, &filename, &base_dir, &media_dirs);
+            let descriptions: Vec<PathBuf> = candidates
+                .iter()
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"))
+                })
+                .map(|path| path.with_extension("mc"))
+                .collect();
+            candidates.extend(descriptions);
+
+            for candidate in candidates {
+                let Some(name) = file_key(&candidate.to_string_lossy()) else {
+                    continue;
+                };
+                if external.files.contains_key(&name) {
+                    continue;
+                }
+                let path = source_asset_path(load_context, &candidate);
+                if let Ok(contents) = load_context.read_asset_bytes(path).await {
+                    external.files.insert(name, contents);
+                }
+            }
+        }
+        external
+    }
+
+    /// Add the contents of an external file.
+    pub fn insert(&mut self, name: &str, contents: Vec<u8>) {
+        if let Some(name) = file_key(name) {
+            self.files.insert(name, contents);
+        }
+    }
+
+    /// Contents of the file with the same name as `path`, if fetched.
+    pub fn get(&self, path: &str) -> Option<&[u8]> {
+        self.files.get(&file_key(path)?).map(Vec::as_slice)
+    }
+
+    /// Number of fetched files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Fetched files sorted by name.
+    pub fn sorted(&self) -> Vec<(&str, &[u8])> {
+        let mut files: Vec<(&str, &[u8])> = self
+            .files
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.as_slice()))
+            .collect();
+        files.sort_by_key(|(name, _)| *name);
+        files
+    }
+
+    /// Whether no file was fetched.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Stream for ufbx's `open_file_cb` serving the file named like `path`.
+    pub fn open(&self, path: &str) -> Option<ufbx::Stream> {
+        let contents = self.get(path)?.to_vec();
+        Some(ufbx::Stream::Read(Box::new(Cursor::new(contents))))
+    }
+}
+
+/// `.fbm` folder exporters write embedded media to, next to the FBX file,
+/// e.g. `models/car.fbm` for `models/car.fbx`.
+pub fn media_dir(fbx_path: &Path) -> PathBuf {
+    fbx_path.with_extension("fbm")
+}
+
+/// Relative and stored filename of each geometry cache referenced by the
+/// FBX data, from a parse without external files.
+///
+/// The scene is dropped before returning, as it cannot be held across the
+/// loader's await points.
+pub fn external_file_names(bytes: &[u8]) -> Vec<(String, String)> {
+    let Ok(scene) = ufbx::load_memory(bytes, ufbx::LoadOpts::default()) else {
+        return Vec::new();
+    };
+    scene
+        .cache_files
+        .as_ref()
+        .iter()
+        .map(|file| {
+            (
+                file.relative_filename.to_string(),
+                file.filename.to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Lowercase file name of a path with either separator.
+fn file_key(path: &str) -> Option<String> {
+    path.rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .map(str::to_lowercase)
+}
@@ -0,0 +1,243 @@
+//! Intermediate geometry of FBX meshes for custom processing, such as
+//! decimation or UV atlasing, before conversion to Bevy meshes.
+
+use crate::mesh::{compute_skinning_data, vertex_tangent};
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+use std::collections::BTreeSet;
+
+/// Triangulated geometry of an FBX mesh, available as the `Geometry{}`
+/// sub-asset when
+/// [`FbxLoaderSettings::load_geometry`](crate::FbxLoaderSettings::load_geometry)
+/// is set, or built from a ufbx mesh with [`extract_geometry`].
+///
+/// Every face corner is a separate vertex, so all attributes are indexed
+/// alike; vertices are neither welded nor split by material. Per-vertex
+/// attributes are either empty or as long as `positions`.
+#[derive(Asset, Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxGeometry {
+    /// Position of each vertex
+    pub positions: Vec<[f32; 3]>,
+    /// Normal of each vertex; empty if the mesh has no normals
+    pub normals: Vec<[f32; 3]>,
+    /// Tangent of each vertex with the bitangent sign in `w`; empty if the
+    /// mesh has no tangents
+    pub tangents: Vec<[f32; 4]>,
+    /// UV sets in file order
+    pub uv_sets: Vec<FbxUvLayer>,
+    /// Vertex color sets in file order
+    pub color_sets: Vec<FbxColorLayer>,
+    /// Four joint indices of each vertex into the skin's clusters; empty if
+    /// the mesh is not skinned
+    pub joint_indices: Vec<[u16; 4]>,
+    /// Normalized weights matching `joint_indices`
+    pub joint_weights: Vec<[f32; 4]>,
+    /// Source ufbx vertex of each vertex, as used by blend shapes and
+    /// vertex caches
+    pub source_vertices: Vec<u32>,
+    /// Triangle list indices into the vertices
+    pub indices: Vec<u32>,
+    /// Material index of each triangle
+    pub triangle_materials: Vec<u32>,
+}
+
+/// Named UV set of an [`FbxGeometry`].
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxUvLayer {
+    pub name: String,
+    pub uvs: Vec<[f32; 2]>,
+}
+
+/// Named vertex color set of an [`FbxGeometry`].
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxColorLayer {
+    pub name: String,
+    /// Linear RGBA color of each vertex
+    pub colors: Vec<[f32; 4]>,
+}
+
+impl FbxGeometry {
+    /// Number of triangles.
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Sorted material indices used by at least one triangle.
+    pub fn material_indices(&self) -> Vec<usize> {
+        let materials: BTreeSet<u32> = self.triangle_materials.iter().copied().collect();
+        materials
+            .into_iter()
+            .map(|material| material as usize)
+            .collect()
+    }
+
+    /// Convert the triangles using `material_index`, or all triangles for
+    /// `None`, into a `TriangleList` Bevy mesh.
+    ///
+    /// Only the vertices referenced by those triangles are kept. The first
+    /// two UV sets become `UV_0` and `UV_1` and the first color set `COLOR`.
+    /// Returns `None` if no triangle matches.
+    pub fn to_mesh(
+        &self,
+        material_index: Option<usize>,
+        asset_usage: RenderAssetUsages,
+    ) -> Option<Mesh> {
+        let mut remap = vec![u32::MAX; self.positions.len()];
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for (triangle, corners) in self.indices.chunks_exact(3).enumerate() {
+            let material = self.triangle_materials.get(triangle).copied().unwrap_or(0);
+            if material_index.is_some_and(|index| index != material as usize) {
+                continue;
+            }
+            for &vertex in corners {
+                let slot = &mut remap[vertex as usize];
+                if *slot == u32::MAX {
+                    *slot = vertices.len() as u32;
+                    vertices.push(vertex as usize);
+                }
+                indices.push(*slot);
+            }
+        }
+        if indices.is_empty() {
+            return None;
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, asset_usage);
+        let gather = |values: &[[f32; 3]]| -> Vec<[f32; 3]> {
+            vertices.iter().map(|&vertex| values[vertex]).collect()
+        };
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, gather(&self.positions));
+        if !self.normals.is_empty() {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, gather(&self.normals));
+        }
+        if !self.tangents.is_empty() {
+            let tangents: Vec<[f32; 4]> = vertices
+                .iter()
+                .map(|&vertex| self.tangents[vertex])
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
+        }
+        let uv_attributes = [Mesh::ATTRIBUTE_UV_0, Mesh::ATTRIBUTE_UV_1];
+        for (attribute, layer) in uv_attributes.into_iter().zip(&self.uv_sets) {
+            let uvs: Vec<[f32; 2]> = vertices.iter().map(|&vertex| layer.uvs[vertex]).collect();
+            mesh.insert_attribute(attribute, uvs);
+        }
+        if let Some(layer) = self.color_sets.first() {
+            let colors: Vec<[f32; 4]> = vertices
+                .iter()
+                .map(|&vertex| layer.colors[vertex])
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+        if !self.joint_indices.is_empty() {
+            let joint_indices: Vec<[u16; 4]> = vertices
+                .iter()
+                .map(|&vertex| self.joint_indices[vertex])
+                .collect();
+            let joint_weights: Vec<[f32; 4]> = vertices
+                .iter()
+                .map(|&vertex| self.joint_weights[vertex])
+                .collect();
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_JOINT_INDEX,
+                VertexAttributeValues::Uint16x4(joint_indices),
+            );
+            mesh.insert_attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT, joint_weights);
+        }
+        mesh.insert_indices(Indices::U32(indices));
+        Some(mesh)
+    }
+
+    /// One Bevy mesh per material index, sorted by material index, as
+    /// produced by [`to_mesh`](Self::to_mesh).
+    pub fn to_meshes(&self, asset_usage: RenderAssetUsages) -> Vec<(usize, Mesh)> {
+        self.material_indices()
+            .into_iter()
+            .filter_map(|material| {
+                let mesh = self.to_mesh(Some(material), asset_usage)?;
+                Some((material, mesh))
+            })
+            .collect()
+    }
+}
+
+/// Triangulate a ufbx mesh into an [`FbxGeometry`].
+///
+/// Faces with fewer than three corners are skipped.
+pub fn extract_geometry(mesh: &ufbx::Mesh) -> FbxGeometry {
+    let corners = 0..mesh.num_indices;
+    let vec3 = |v: ufbx::Vec3| [v.x as f32, v.y as f32, v.z as f32];
+    let vec2 = |v: ufbx::Vec2| [v.x as f32, v.y as f32];
+
+    let mut geometry = FbxGeometry {
+        positions: corners
+            .clone()
+            .map(|i| vec3(mesh.vertex_position[i]))
+            .collect(),
+        source_vertices: mesh.vertex_indices.as_ref().to_vec(),
+        ..Default::default()
+    };
+    if mesh.vertex_normal.exists {
+        geometry.normals = corners
+            .clone()
+            .map(|i| vec3(mesh.vertex_normal[i]))
+            .collect();
+    }
+    if mesh.vertex_tangent.exists {
+        geometry.tangents = corners
+            .clone()
+            .filter_map(|i| vertex_tangent(mesh, i))
+            .collect();
+    }
+    geometry.uv_sets = mesh
+        .uv_sets
+        .as_ref()
+        .iter()
+        .map(|uv_set| FbxUvLayer {
+            name: uv_set.name.to_string(),
+            uvs: corners.clone().map(|i| vec2(uv_set.vertex_uv[i])).collect(),
+        })
+        .collect();
+    geometry.color_sets = mesh
+        .color_sets
+        .as_ref()
+        .iter()
+        .map(|color_set| FbxColorLayer {
+            name: color_set.name.to_string(),
+            colors: corners
+                .clone()
+                .map(|i| {
+                    let c = color_set.vertex_color[i];
+                    [c.x as f32, c.y as f32, c.z as f32, c.w as f32]
+                })
+                .collect(),
+        })
+        .collect();
+    if !mesh.skin_deformers.is_empty() {
+        let (joint_indices, joint_weights) = compute_skinning_data(mesh);
+        for &vertex in &geometry.source_vertices {
+            geometry.joint_indices.push(joint_indices[vertex as usize]);
+            geometry.joint_weights.push(joint_weights[vertex as usize]);
+        }
+    }
+
+    let mut scratch = Vec::new();
+    for (face_index, &face) in mesh.faces.as_ref().iter().enumerate() {
+        scratch.clear();
+        ufbx::triangulate_face_vec(&mut scratch, mesh, face);
+        let material = mesh
+            .face_material
+            .as_ref()
+            .get(face_index)
+            .copied()
+            .unwrap_or(0);
+        geometry.indices.extend_from_slice(&scratch);
+        geometry
+            .triangle_materials
+            .extend(std::iter::repeat_n(material, scratch.len() / 3));
+    }
+
+    geometry
+}
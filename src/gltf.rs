@@ -0,0 +1,270 @@
+//! Conversion of imported materials to glTF's metallic-roughness model.
+//!
+//! FBX files store occlusion, roughness and metallic maps as separate
+//! grayscale textures, while glTF and Bevy's glTF loader expect them packed
+//! into one ORM texture. [`FbxGltfMaterial`] holds the parameters of a glTF
+//! `pbrMetallicRoughness` material, and [`fbx_gltf_materials`] converts the
+//! materials of a loaded [`Fbx`], packing the separate maps into ORM images,
+//! so content from both formats can share one material pipeline.
+
+use crate::types::Fbx;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::Image;
+use bevy::math::Affine2;
+use bevy::pbr::StandardMaterial;
+use bevy::prelude::*;
+use bevy::render::alpha::AlphaMode;
+use bevy::render::render_resource::{Extent3d, Face, TextureDimension, TextureFormat};
+use std::collections::HashMap;
+
+/// Alpha mode of a glTF material.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum FbxGltfAlphaMode {
+    Opaque,
+    /// Alpha testing against the cutoff
+    Mask(f32),
+    Blend,
+}
+
+/// Material parameters as defined by glTF's `pbrMetallicRoughness` model.
+///
+/// Colors are linear. Like in glTF, texture values are multiplied by their
+/// factors, and `metallic_roughness_texture` holds roughness in the green
+/// and metallic in the blue channel. `occlusion_texture` reads the red
+/// channel and is usually the same ORM image.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct FbxGltfMaterial {
+    pub base_color_factor: [f32; 4],
+    pub base_color_texture: Option<Handle<Image>>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub metallic_roughness_texture: Option<Handle<Image>>,
+    pub occlusion_texture: Option<Handle<Image>>,
+    pub normal_texture: Option<Handle<Image>>,
+    pub emissive_factor: [f32; 3],
+    /// `KHR_materials_emissive_strength`, scaling `emissive_factor` beyond 1
+    pub emissive_strength: f32,
+    pub emissive_texture: Option<Handle<Image>>,
+    pub alpha_mode: FbxGltfAlphaMode,
+    pub double_sided: bool,
+    /// `KHR_materials_unlit`
+    pub unlit: bool,
+    /// `KHR_texture_transform` of the base color texture, applied to all
+    /// textures like Bevy does
+    pub uv_transform: Affine2,
+}
+
+impl Default for FbxGltfMaterial {
+    /// glTF's default material.
+    fn default() -> Self {
+        Self {
+            base_color_factor: [1.0; 4],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            occlusion_texture: None,
+            normal_texture: None,
+            emissive_factor: [0.0; 3],
+            emissive_strength: 1.0,
+            emissive_texture: None,
+            alpha_mode: FbxGltfAlphaMode::Opaque,
+            double_sided: false,
+            unlit: false,
+            uv_transform: Affine2::IDENTITY,
+        }
+    }
+}
+
+impl From<&StandardMaterial> for FbxGltfMaterial {
+    /// Parameters of `material`, whether it was loaded from FBX or glTF.
+    ///
+    /// Emission brighter than 1 is split into a normalized factor and an
+    /// emissive strength. The textures are taken as they are; FBX materials
+    /// need [`fbx_gltf_materials`] to get packed ORM textures.
+    fn from(material: &StandardMaterial) -> Self {
+        let emissive = material.emissive;
+        let strength = emissive.red.max(emissive.green).max(emissive.blue).max(1.0);
+        let alpha_mode = match material.alpha_mode {
+            AlphaMode::Opaque => FbxGltfAlphaMode::Opaque,
+            AlphaMode::Mask(cutoff) => FbxGltfAlphaMode::Mask(cutoff),
+            AlphaMode::AlphaToCoverage => FbxGltfAlphaMode::Mask(0.5),
+            _ => FbxGltfAlphaMode::Blend,
+        };
+        Self {
+            base_color_factor: material.base_color.to_linear().to_f32_array(),
+            base_color_texture: material.base_color_texture.clone(),
+            metallic_factor: material.metallic,
+            roughness_factor: material.perceptual_roughness,
+            metallic_roughness_texture: material.metallic_roughness_texture.clone(),
+            occlusion_texture: material.occlusion_texture.clone(),
+            normal_texture: material.normal_map_texture.clone(),
+            emissive_factor: [emissive.red, emissive.green, emissive.blue].map(|c| c / strength),
+            emissive_strength: strength,
+            emissive_texture: material.emissive_texture.clone(),
+            alpha_mode,
+            double_sided: material.double_sided,
+            unlit: material.unlit,
+            uv_transform: material.uv_transform,
+        }
+    }
+}
+
+impl FbxGltfMaterial {
+    /// [`StandardMaterial`] set up the way Bevy's glTF loader builds it.
+    pub fn to_standard_material(&self) -> StandardMaterial {
+        let [r, g, b] = self.emissive_factor.map(|c| c * self.emissive_strength);
+        let alpha_mode = match self.alpha_mode {
+            FbxGltfAlphaMode::Opaque => AlphaMode::Opaque,
+            FbxGltfAlphaMode::Mask(cutoff) => AlphaMode::Mask(cutoff),
+            FbxGltfAlphaMode::Blend => AlphaMode::Blend,
+        };
+        StandardMaterial {
+            base_color: Color::LinearRgba(LinearRgba::from_f32_array(self.base_color_factor)),
+            base_color_texture: self.base_color_texture.clone(),
+            metallic: self.metallic_factor,
+            perceptual_roughness: self.roughness_factor,
+            metallic_roughness_texture: self.metallic_roughness_texture.clone(),
+            occlusion_texture: self.occlusion_texture.clone(),
+            normal_map_texture: self.normal_texture.clone(),
+            emissive: LinearRgba::rgb(r, g, b),
+            emissive_texture: self.emissive_texture.clone(),
+            alpha_mode,
+            double_sided: self.double_sided,
+            cull_mode: (!self.double_sided).then_some(Face::Back),
+            unlit: self.unlit,
+            uv_transform: self.uv_transform,
+            ..Default::default()
+        }
+    }
+}
+
+/// Separate occlusion, roughness and metallic maps of an FBX material, see
+/// [`Fbx::orm_textures`].
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxOrmTextures {
+    pub occlusion: Option<Handle<Image>>,
+    pub roughness: Option<Handle<Image>>,
+    pub metallic: Option<Handle<Image>>,
+}
+
+impl FbxOrmTextures {
+    /// Whether the material has none of the maps.
+    pub fn is_empty(&self) -> bool {
+        self.occlusion.is_none() && self.roughness.is_none() && self.metallic.is_none()
+    }
+}
+
+/// ORM maps of `material` by its FBX texture slots.
+pub fn material_orm_textures(
+    material: &ufbx::Material,
+    texture_handles: &HashMap<u32, Handle<Image>>,
+) -> FbxOrmTextures {
+    let mut textures = FbxOrmTextures::default();
+    for texture_ref in &material.textures {
+        let Some(handle) = texture_handles.get(&texture_ref.texture.element.element_id) else {
+            continue;
+        };
+        let slot = match texture_ref.material_prop.as_ref() {
+            "AmbientOcclusion" => &mut textures.occlusion,
+            "Roughness" => &mut textures.roughness,
+            "Metallic" => &mut textures.metallic,
+            _ => continue,
+        };
+        slot.get_or_insert_with(|| handle.clone());
+    }
+    textures
+}
+
+/// Pack grayscale occlusion, roughness and metallic maps into the red, green
+/// and blue channels of an `Rgba8Unorm` image, as glTF expects.
+///
+/// The first channel of each map is used. Maps of different sizes are
+/// sampled at the size of the largest; a missing map packs as white, so the
+/// material's factor applies unchanged. Returns `None` without any map.
+pub fn pack_orm_image(
+    occlusion: Option<&Image>,
+    roughness: Option<&Image>,
+    metallic: Option<&Image>,
+) -> Option<Image> {
+    let maps = [occlusion, roughness, metallic];
+    let width = maps.iter().flatten().map(|image| image.width()).max()?;
+    let height = maps.iter().flatten().map(|image| image.height()).max()?;
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            for map in maps {
+                let value = map.map_or(1.0, |image| {
+                    let sample_x = x * image.width() / width;
+                    let sample_y = y * image.height() / height;
+                    first_channel(image, sample_x, sample_y)
+                });
+                data.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+            data.push(255);
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    );
+    if let Some(source) = maps.iter().flatten().next() {
+        image.sampler = source.sampler.clone();
+    }
+    Some(image)
+}
+
+/// Stored value of the first channel at `(x, y)`, without color space
+/// conversion; 1 if the format can't be read.
+fn first_channel(image: &Image, x: u32, y: u32) -> f32 {
+    match image.get_color_at(x, y) {
+        Ok(Color::Srgba(color)) => color.red,
+        Ok(color) => color.to_linear().red,
+        Err(_) => 1.0,
+    }
+}
+
+/// glTF parameters of each material of `fbx`, indexed like
+/// [`Fbx::materials`].
+///
+/// Materials with separate occlusion, roughness or metallic maps get an ORM
+/// image packed from them and added to `images`, used as both
+/// `metallic_roughness_texture` and, if the material has an occlusion map,
+/// `occlusion_texture`. Call this once the FBX asset has loaded with its
+/// dependencies; maps still loading are left out of the packed image.
+pub fn fbx_gltf_materials(
+    fbx: &Fbx,
+    materials: &Assets<StandardMaterial>,
+    images: &mut Assets<Image>,
+) -> Vec<FbxGltfMaterial> {
+    fbx.materials
+        .iter()
+        .enumerate()
+        .map(|(index, handle)| {
+            let Some(material) = materials.get(handle) else {
+                return FbxGltfMaterial::default();
+            };
+            let mut gltf_material = FbxGltfMaterial::from(material);
+            let Some(orm) = fbx.orm_textures.get(index).filter(|orm| !orm.is_empty()) else {
+                return gltf_material;
+            };
+            let map = |handle: &Option<Handle<Image>>| handle.as_ref().and_then(|h| images.get(h));
+            let packed =
+                pack_orm_image(map(&orm.occlusion), map(&orm.roughness), map(&orm.metallic));
+            if let Some(packed) = packed {
+                let packed = images.add(packed);
+                gltf_material.occlusion_texture = orm.occlusion.is_some().then(|| packed.clone());
+                gltf_material.metallic_roughness_texture = Some(packed);
+            }
+            gltf_material
+        })
+        .collect()
+}
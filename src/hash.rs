@@ -0,0 +1,238 @@
+//! Content hashing of imported FBX files.
+//!
+//! [`FbxMeta::content_hash`](crate::FbxMeta::content_hash) fingerprints what
+//! a load produced: the labels and data of meshes, materials and nodes, the
+//! texture paths requested and the animation keys. Header fields such as the
+//! creation time are left out, so re-exporting an unchanged file keeps its
+//! hash while any change to the imported content alters it.
+
+use crate::cache::FbxVertexCache;
+use crate::label::FbxAssetLabel;
+use crate::sink::FbxAssetSink;
+use crate::texture::{FbxColorSpace, FbxImageSettings};
+use crate::types::FbxNode;
+use bevy::asset::{Asset, AssetPath, Handle};
+use bevy::mesh::Indices;
+use bevy::pbr::StandardMaterial;
+use bevy::prelude::*;
+use std::any::Any;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+/// 64-bit FNV-1a hasher, stable across platforms, runs and Rust versions
+/// unlike [`DefaultHasher`](std::collections::hash_map::DefaultHasher).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FbxContentHasher(u64);
+
+impl Default for FbxContentHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FbxContentHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FbxContentHasher {
+    /// Hash a string with its length, so adjacent strings can't run into
+    /// each other.
+    pub fn write_str(&mut self, value: &str) {
+        self.write_usize(value.len());
+        self.write(value.as_bytes());
+    }
+
+    /// Hash floats by their bits.
+    pub fn write_floats(&mut self, values: &[f32]) {
+        for value in values {
+            self.write_u32(value.to_bits());
+        }
+    }
+
+    /// Hash the topology, vertex attributes and indices of a mesh.
+    pub fn write_mesh(&mut self, mesh: &Mesh) {
+        self.write_str(&format!("{:?}", mesh.primitive_topology()));
+        for (attribute, values) in mesh.attributes() {
+            self.write_str(attribute.name);
+            self.write(values.get_bytes());
+        }
+        match mesh.indices() {
+            Some(Indices::U16(indices)) => indices.iter().for_each(|&i| self.write_u16(i)),
+            Some(Indices::U32(indices)) => indices.iter().for_each(|&i| self.write_u32(i)),
+            None => {}
+        }
+    }
+
+    /// Hash the parameters of a material and which texture slots it uses.
+    ///
+    /// Texture handles aren't stable between loads; the texture paths are
+    /// hashed when they are requested instead.
+    pub fn write_material(&mut self, material: &StandardMaterial) {
+        self.write_floats(&material.base_color.to_linear().to_f32_array());
+        self.write_floats(&material.emissive.to_f32_array());
+        self.write_floats(&[
+            material.perceptual_roughness,
+            material.metallic,
+            material.reflectance,
+            material.ior,
+            material.parallax_depth_scale,
+            material.depth_bias,
+        ]);
+        self.write_str(&format!("{:?}", material.alpha_mode));
+        self.write_u8(material.double_sided as u8);
+        self.write_u8(material.unlit as u8);
+        let textures = [
+            &material.base_color_texture,
+            &material.emissive_texture,
+            &material.metallic_roughness_texture,
+            &material.normal_map_texture,
+            &material.occlusion_texture,
+            &material.depth_map,
+        ];
+        for texture in textures {
+            self.write_u8(texture.is_some() as u8);
+        }
+    }
+
+    /// Hash the name, transforms and visibility of a node.
+    pub fn write_node(&mut self, node: &FbxNode) {
+        self.write_str(&node.name);
+        self.write_usize(node.children.len());
+        for transform in [&node.transform, &node.geometry_transform] {
+            self.write_floats(&transform.translation.to_array());
+            self.write_floats(&transform.rotation.to_array());
+            self.write_floats(&transform.scale.to_array());
+        }
+        self.write_u8(node.visible as u8);
+    }
+
+    /// Hash the keyframes of every animation curve of `scene`.
+    pub fn write_animation(&mut self, scene: &ufbx::Scene) {
+        for curve in scene.anim_curves.as_ref().iter() {
+            self.write_usize(curve.keyframes.len());
+            for key in curve.keyframes.as_ref().iter() {
+                self.write_u64(key.time.to_bits());
+                self.write_u64(key.value.to_bits());
+                self.write_u32(key.interpolation as u32);
+            }
+        }
+    }
+
+    /// Hash a sub-asset by its label and, for meshes, materials and nodes,
+    /// its contents.
+    pub fn write_asset<A: Asset>(&mut self, label: &str, asset: &A) {
+        self.write_str(label);
+        let asset: &dyn Any = asset;
+        if let Some(mesh) = asset.downcast_ref::<Mesh>() {
+            self.write_mesh(mesh);
+        } else if let Some(material) = asset.downcast_ref::<StandardMaterial>() {
+            self.write_material(material);
+        } else if let Some(node) = asset.downcast_ref::<FbxNode>() {
+            self.write_node(node);
+        }
+    }
+}
+
+/// Sink hashing every sub-asset and texture request on its way to `inner`.
+pub struct ContentHashSink<'a, S> {
+    inner: &'a mut S,
+    hasher: FbxContentHasher,
+}
+
+impl<'a, S: FbxAssetSink> ContentHashSink<'a, S> {
+    pub fn new(inner: &'a mut S) -> Self {
+        Self {
+            inner,
+            hasher: FbxContentHasher::default(),
+        }
+    }
+
+    /// Hasher of everything passed through so far, e.g. to add data that
+    /// doesn't go through the sink.
+    pub fn hasher(&mut self) -> &mut FbxContentHasher {
+        &mut self.hasher
+    }
+
+    /// Hash of everything passed through so far.
+    pub fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+}
+
+impl<S: FbxAssetSink> FbxAssetSink for ContentHashSink<'_, S> {
+    fn add_labeled_asset<A: Asset>(&mut self, label: String, asset: A) -> Handle<A> {
+        self.hasher.write_asset(&label, &asset);
+        self.inner.add_labeled_asset(label, asset)
+    }
+
+    fn add_labeled_alias<A: Asset + Clone>(
+        &mut self,
+        label: FbxAssetLabel,
+        name: Option<&str>,
+        handle: &Handle<A>,
+        asset: A,
+    ) -> Handle<A> {
+        // The asset itself was hashed under its first label
+        self.hasher.write_str(&label.to_string());
+        self.inner.add_labeled_alias(label, name, handle, asset)
+    }
+
+    fn load_texture(&mut self, path: AssetPath<'static>) -> Handle<Image> {
+        self.hasher.write_str(&path.to_string());
+        self.inner.load_texture(path)
+    }
+
+    fn load_texture_with_color_space(
+        &mut self,
+        path: AssetPath<'static>,
+        color_space: FbxColorSpace,
+    ) -> Handle<Image> {
+        self.hasher.write_str(&path.to_string());
+        self.hasher.write_u8(color_space.is_srgb() as u8);
+        self.inner.load_texture_with_color_space(path, color_space)
+    }
+
+    fn load_texture_with_settings(
+        &mut self,
+        path: AssetPath<'static>,
+        settings: &FbxImageSettings,
+    ) -> Handle<Image> {
+        self.hasher.write_str(&path.to_string());
+        self.hasher.write_str(&format!("{settings:?}"));
+        self.inner.load_texture_with_settings(path, settings)
+    }
+
+    fn load_vertex_cache(
+        &mut self,
+        path: AssetPath<'static>,
+        frame_rate: f32,
+    ) -> Handle<FbxVertexCache> {
+        self.hasher.write_str(&path.to_string());
+        self.inner.load_vertex_cache(path, frame_rate)
+    }
+
+    fn base_dir(&self) -> PathBuf {
+        self.inner.base_dir()
+    }
+
+    fn asset_path(&self, path: &Path) -> AssetPath<'static> {
+        self.inner.asset_path(path)
+    }
+
+    fn media_dir(&self) -> Option<PathBuf> {
+        self.inner.media_dir()
+    }
+
+    fn source_name(&self) -> String {
+        self.inner.source_name()
+    }
+}
@@ -0,0 +1,170 @@
+//! Humanoid rig detection for HumanIK/Mixamo and Unreal-style skeletons.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Semantic bones of a humanoid skeleton.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum HumanoidBone {
+    Hips,
+    Spine,
+    Chest,
+    UpperChest,
+    Neck,
+    Head,
+    LeftShoulder,
+    LeftUpperArm,
+    LeftLowerArm,
+    LeftHand,
+    RightShoulder,
+    RightUpperArm,
+    RightLowerArm,
+    RightHand,
+    LeftUpperLeg,
+    LeftLowerLeg,
+    LeftFoot,
+    LeftToes,
+    RightUpperLeg,
+    RightLowerLeg,
+    RightFoot,
+    RightToes,
+}
+
+impl HumanoidBone {
+    /// Bones that must be present for a skeleton to be considered humanoid.
+    pub const REQUIRED: [HumanoidBone; 7] = [
+        HumanoidBone::Hips,
+        HumanoidBone::Spine,
+        HumanoidBone::Head,
+        HumanoidBone::LeftUpperArm,
+        HumanoidBone::RightUpperArm,
+        HumanoidBone::LeftUpperLeg,
+        HumanoidBone::RightUpperLeg,
+    ];
+}
+
+/// Bone naming convention of a detected rig.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum FbxRigConvention {
+    /// Autodesk HumanIK naming, also used by Mixamo (`mixamorig:` prefix)
+    HumanIk,
+    /// Unreal Engine mannequin naming (`pelvis`, `upperarm_l`, ...)
+    Unreal,
+}
+
+impl FbxRigConvention {
+    /// Match a normalized bone name (lowercase, namespace stripped) for this convention.
+    fn match_bone(&self, name: &str) -> Option<HumanoidBone> {
+        use HumanoidBone::*;
+
+        let bone = match self {
+            FbxRigConvention::HumanIk => match name {
+                "hips" => Hips,
+                "spine" => Spine,
+                "spine1" => Chest,
+                "spine2" => UpperChest,
+                "neck" => Neck,
+                "head" => Head,
+                "leftshoulder" => LeftShoulder,
+                "leftarm" => LeftUpperArm,
+                "leftforearm" => LeftLowerArm,
+                "lefthand" => LeftHand,
+                "rightshoulder" => RightShoulder,
+                "rightarm" => RightUpperArm,
+                "rightforearm" => RightLowerArm,
+                "righthand" => RightHand,
+                "leftupleg" => LeftUpperLeg,
+                "leftleg" => LeftLowerLeg,
+                "leftfoot" => LeftFoot,
+                "lefttoebase" => LeftToes,
+                "rightupleg" => RightUpperLeg,
+                "rightleg" => RightLowerLeg,
+                "rightfoot" => RightFoot,
+                "righttoebase" => RightToes,
+                _ => return None,
+            },
+            FbxRigConvention::Unreal => match name {
+                "pelvis" => Hips,
+                "spine_01" => Spine,
+                "spine_02" => Chest,
+                "spine_03" => UpperChest,
+                "neck_01" => Neck,
+                "head" => Head,
+                "clavicle_l" => LeftShoulder,
+                "upperarm_l" => LeftUpperArm,
+                "lowerarm_l" => LeftLowerArm,
+                "hand_l" => LeftHand,
+                "clavicle_r" => RightShoulder,
+                "upperarm_r" => RightUpperArm,
+                "lowerarm_r" => RightLowerArm,
+                "hand_r" => RightHand,
+                "thigh_l" => LeftUpperLeg,
+                "calf_l" => LeftLowerLeg,
+                "foot_l" => LeftFoot,
+                "ball_l" => LeftToes,
+                "thigh_r" => RightUpperLeg,
+                "calf_r" => RightLowerLeg,
+                "foot_r" => RightFoot,
+                "ball_r" => RightToes,
+                _ => return None,
+            },
+        };
+        Some(bone)
+    }
+}
+
+/// Strip namespaces such as `mixamorig:` and normalize case.
+pub fn normalize_bone_name(name: &str) -> String {
+    let name = name.rsplit(':').next().unwrap_or(name);
+    name.to_ascii_lowercase()
+}
+
+/// Mapping of semantic humanoid bones to FBX node indices.
+#[derive(Asset, Debug, Clone, Reflect)]
+pub struct FbxHumanoidRig {
+    pub convention: FbxRigConvention,
+    pub bones: HashMap<HumanoidBone, usize>,
+}
+
+impl FbxHumanoidRig {
+    /// Detect a humanoid rig from node names, indexed by node index.
+    ///
+    /// Returns `None` unless every bone in [`HumanoidBone::REQUIRED`] is found.
+    pub fn detect<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let names: Vec<String> = names.into_iter().map(normalize_bone_name).collect();
+
+        [FbxRigConvention::HumanIk, FbxRigConvention::Unreal]
+            .into_iter()
+            .map(|convention| {
+                let mut bones = HashMap::new();
+                for (index, name) in names.iter().enumerate() {
+                    if let Some(bone) = convention.match_bone(name) {
+                        bones.entry(bone).or_insert(index);
+                    }
+                }
+                FbxHumanoidRig { convention, bones }
+            })
+            .filter(|rig| {
+                HumanoidBone::REQUIRED
+                    .iter()
+                    .all(|bone| rig.bones.contains_key(bone))
+            })
+            .max_by_key(|rig| rig.bones.len())
+    }
+
+    /// Node index of a semantic bone.
+    pub fn bone(&self, bone: HumanoidBone) -> Option<usize> {
+        self.bones.get(&bone).copied()
+    }
+}
+
+/// Detect a humanoid rig in the scene's node hierarchy.
+pub fn process_humanoid_rig(scene: &ufbx::Scene) -> Option<FbxHumanoidRig> {
+    FbxHumanoidRig::detect(
+        scene
+            .nodes
+            .as_ref()
+            .iter()
+            .map(|node| node.element.name.as_ref()),
+    )
+}
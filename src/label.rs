@@ -1,56 +1,171 @@
 //! Labels that can be used to load part of an FBX asset
-use bevy::asset::AssetPath;
+use crate::loader::FbxLoaderSettings;
+use crate::types::Fbx;
+use bevy::asset::{Asset, AssetPath, Handle};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use thiserror::Error;
 
 /// Labels that can be used to load part of an FBX asset
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FbxAssetLabel {
     /// `Scene{}`: FBX Scene as a Bevy [`Scene`](bevy::scene::Scene)
     Scene(usize),
-    /// `Mesh{}`: FBX Mesh as a Bevy [`Mesh`](bevy::render::mesh::Mesh)
+    /// `Mesh{}`: FBX Mesh as a [`FbxMesh`](crate::FbxMesh)
     Mesh(usize),
+    /// `Mesh{}/Primitive{}`: FBX mesh primitive as a Bevy [`Mesh`](bevy::mesh::Mesh)
+    Primitive { mesh: usize, primitive: usize },
+    /// `Mesh{}/Primitive{}/MorphTargets`: Morph targets of an FBX mesh primitive
+    /// as a Bevy [`Image`](bevy::image::Image)
+    MorphTargets { mesh: usize, primitive: usize },
+    /// `Mesh{}/Primitive{}/Lod{}`: Simplified level of detail of an FBX mesh
+    /// primitive as a Bevy [`Mesh`](bevy::mesh::Mesh), see `simplification`
+    PrimitiveLod {
+        mesh: usize,
+        primitive: usize,
+        lod: usize,
+    },
+    /// `MeshTopology{}`: Edge, crease and smoothing data of an FBX mesh as
+    /// [`FbxMeshTopology`](crate::FbxMeshTopology)
+    MeshTopology(usize),
+    /// `Geometry{}`: Triangulated source geometry of an FBX mesh as
+    /// [`FbxGeometry`](crate::FbxGeometry)
+    Geometry(usize),
+    /// `Batch{}`: Static meshes merged per material as a Bevy
+    /// [`Mesh`](bevy::mesh::Mesh), see `flatten_static_hierarchy`
+    Batch(usize),
+    /// `MergedMesh{}`: Primitives sharing a material merged into a Bevy
+    /// [`Mesh`](bevy::mesh::Mesh), see `merge_by_material`
+    MergedMesh(usize),
+    /// `LineCurve{}`: FBX line curve as a `LineList` Bevy [`Mesh`](bevy::mesh::Mesh)
+    LineCurve(usize),
     /// `Material{}`: FBX material as a Bevy [`StandardMaterial`](bevy::pbr::StandardMaterial)
     Material(usize),
+    /// `MaterialExtras{}`: Unmapped FBX material properties as [`FbxMaterialExtras`](crate::FbxMaterialExtras)
+    MaterialExtras(usize),
     /// `Animation{}`: FBX animation as a Bevy [`AnimationClip`](bevy::animation::AnimationClip)
     Animation(usize),
+    /// `Animation{}/Mask/{}`: Animation restricted to a bone mask of
+    /// [`FbxLoaderSettings::bone_masks`]
+    MaskedAnimation { animation: usize, mask: String },
     /// `AnimationStack{}`: FBX animation stack with multiple layers
     AnimationStack(usize),
     /// `Skeleton{}`: FBX skeleton for skeletal animation
     Skeleton(usize),
     /// `Node{}`: Individual FBX node in the scene hierarchy
     Node(usize),
+    /// `Node/{}`: Bevy [`Scene`](bevy::scene::Scene) of the subtree at a
+    /// `/`-separated node name path, e.g. `Node/Root/Floor2`
+    NodeScene(String),
+    /// `Take/{}`: Bevy [`Scene`](bevy::scene::Scene) of an animation stack,
+    /// see [`take_scenes`](crate::FbxLoaderSettings::take_scenes)
+    TakeScene(String),
+    /// `Take/{}/Graph`: [`AnimationGraph`](bevy::animation::graph::AnimationGraph)
+    /// playing the clip of an animated take scene
+    TakeGraph(String),
     /// `Skin{}`: FBX skin for skeletal animation
     Skin(usize),
+    /// `BindPose{}`: Joint transforms a skin was bound in as
+    /// [`FbxBindPose`](crate::FbxBindPose)
+    BindPose(usize),
     /// `Light{}`: FBX light definition
     Light(usize),
+    /// `AreaLight{}`: Unlit material of an area light's emissive quad
+    AreaLight(usize),
+    /// `AreaLightQuad`: Quad mesh shared by area light emissive quads
+    AreaLightQuad,
     /// `Camera{}`: FBX camera definition
     Camera(usize),
-    /// `Texture{}`: FBX texture reference
+    /// `Texture{}`: [`FbxTexture`](crate::FbxTexture) with the resolved path
+    /// and image
     Texture(usize),
+    /// `Media{}`: Embedded audio or other non-image file as
+    /// [`FbxMedia`](crate::FbxMedia), see `load_media`
+    Media(usize),
+    /// `MissingTexture`: Checkerboard used for unresolved textures with
+    /// [`MissingTexturePolicy::Placeholder`](crate::MissingTexturePolicy::Placeholder)
+    MissingTexture,
+    /// `Colliders`: Physics colliders extracted by node naming convention
+    Colliders,
+    /// `HumanoidRig`: Semantic bone mapping of a detected humanoid skeleton
+    HumanoidRig,
+    /// `Report`: Findings of the optional validation pass
+    Report,
     /// `DefaultScene`: Main scene with all objects
     DefaultScene,
     /// `DefaultMaterial`: Fallback material used when no material is present
     DefaultMaterial,
     /// `RootNode`: Root node of the scene hierarchy
     RootNode,
+    /// `{kind}Name/{name}`: Copy of a sub-asset addressed by element name,
+    /// e.g. `MaterialName/Brick` or `MeshName/Door/Primitive0`, with
+    /// [`FbxLoaderSettings::name_based_labels`]
+    Named { kind: String, name: String },
 }
 
+/// Label kinds with a name-based [`FbxAssetLabel::Named`] form.
+const NAMED_KINDS: &[&str] = &[
+    "Mesh",
+    "MeshTopology",
+    "Geometry",
+    "LineCurve",
+    "Material",
+    "MaterialExtras",
+    "Animation",
+    "Node",
+    "Skin",
+    "BindPose",
+];
+
 impl core::fmt::Display for FbxAssetLabel {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             FbxAssetLabel::Scene(index) => f.write_str(&format!("Scene{index}")),
             FbxAssetLabel::Mesh(index) => f.write_str(&format!("Mesh{index}")),
+            FbxAssetLabel::Primitive { mesh, primitive } => {
+                f.write_str(&format!("Mesh{mesh}/Primitive{primitive}"))
+            }
+            FbxAssetLabel::MorphTargets { mesh, primitive } => {
+                f.write_str(&format!("Mesh{mesh}/Primitive{primitive}/MorphTargets"))
+            }
+            FbxAssetLabel::PrimitiveLod {
+                mesh,
+                primitive,
+                lod,
+            } => f.write_str(&format!("Mesh{mesh}/Primitive{primitive}/Lod{lod}")),
+            FbxAssetLabel::MeshTopology(index) => f.write_str(&format!("MeshTopology{index}")),
+            FbxAssetLabel::Geometry(index) => f.write_str(&format!("Geometry{index}")),
+            FbxAssetLabel::Batch(index) => f.write_str(&format!("Batch{index}")),
+            FbxAssetLabel::MergedMesh(index) => f.write_str(&format!("MergedMesh{index}")),
+            FbxAssetLabel::LineCurve(index) => f.write_str(&format!("LineCurve{index}")),
             FbxAssetLabel::Material(index) => f.write_str(&format!("Material{index}")),
+            FbxAssetLabel::MaterialExtras(index) => f.write_str(&format!("MaterialExtras{index}")),
             FbxAssetLabel::Animation(index) => f.write_str(&format!("Animation{index}")),
+            FbxAssetLabel::MaskedAnimation { animation, mask } => {
+                f.write_str(&format!("Animation{animation}/Mask/{mask}"))
+            }
             FbxAssetLabel::AnimationStack(index) => f.write_str(&format!("AnimationStack{index}")),
             FbxAssetLabel::Skeleton(index) => f.write_str(&format!("Skeleton{index}")),
             FbxAssetLabel::Node(index) => f.write_str(&format!("Node{index}")),
+            FbxAssetLabel::NodeScene(path) => f.write_str(&format!("Node/{path}")),
+            FbxAssetLabel::TakeScene(name) => f.write_str(&format!("Take/{name}")),
+            FbxAssetLabel::TakeGraph(name) => f.write_str(&format!("Take/{name}/Graph")),
             FbxAssetLabel::Skin(index) => f.write_str(&format!("Skin{index}")),
+            FbxAssetLabel::BindPose(index) => f.write_str(&format!("BindPose{index}")),
             FbxAssetLabel::Light(index) => f.write_str(&format!("Light{index}")),
+            FbxAssetLabel::AreaLight(index) => f.write_str(&format!("AreaLight{index}")),
+            FbxAssetLabel::AreaLightQuad => f.write_str("AreaLightQuad"),
             FbxAssetLabel::Camera(index) => f.write_str(&format!("Camera{index}")),
             FbxAssetLabel::Texture(index) => f.write_str(&format!("Texture{index}")),
+            FbxAssetLabel::Media(index) => f.write_str(&format!("Media{index}")),
+            FbxAssetLabel::MissingTexture => f.write_str("MissingTexture"),
+            FbxAssetLabel::Colliders => f.write_str("Colliders"),
+            FbxAssetLabel::HumanoidRig => f.write_str("HumanoidRig"),
+            FbxAssetLabel::Report => f.write_str("Report"),
             FbxAssetLabel::DefaultScene => f.write_str("DefaultScene"),
             FbxAssetLabel::DefaultMaterial => f.write_str("DefaultMaterial"),
             FbxAssetLabel::RootNode => f.write_str("RootNode"),
+            FbxAssetLabel::Named { kind, name } => f.write_str(&format!("{kind}Name/{name}")),
         }
     }
 }
@@ -60,4 +175,237 @@ impl FbxAssetLabel {
     pub fn from_asset(&self, path: impl Into<AssetPath<'static>>) -> AssetPath<'static> {
         path.into().with_label(self.to_string())
     }
+
+    /// Name-based form of this label for an element named `name`.
+    ///
+    /// Returns the label unchanged if `name` is `None` or the label kind has
+    /// no named form. Primitives, morph targets and levels of detail are
+    /// named after their mesh.
+    pub fn named(self, name: Option<&str>) -> Self {
+        let Some(name) = name else {
+            return self;
+        };
+        let (kind, name) = match self {
+            FbxAssetLabel::Mesh(_) => ("Mesh", name.to_string()),
+            FbxAssetLabel::Primitive { primitive, .. } => {
+                ("Mesh", format!("{name}/Primitive{primitive}"))
+            }
+            FbxAssetLabel::MorphTargets { primitive, .. } => {
+                ("Mesh", format!("{name}/Primitive{primitive}/MorphTargets"))
+            }
+            FbxAssetLabel::PrimitiveLod { primitive, lod, .. } => {
+                ("Mesh", format!("{name}/Primitive{primitive}/Lod{lod}"))
+            }
+            FbxAssetLabel::MeshTopology(_) => ("MeshTopology", name.to_string()),
+            FbxAssetLabel::Geometry(_) => ("Geometry", name.to_string()),
+            FbxAssetLabel::LineCurve(_) => ("LineCurve", name.to_string()),
+            FbxAssetLabel::Material(_) => ("Material", name.to_string()),
+            FbxAssetLabel::MaterialExtras(_) => ("MaterialExtras", name.to_string()),
+            FbxAssetLabel::Animation(_) => ("Animation", name.to_string()),
+            FbxAssetLabel::Node(_) => ("Node", name.to_string()),
+            FbxAssetLabel::Skin(_) => ("Skin", name.to_string()),
+            FbxAssetLabel::BindPose(_) => ("BindPose", name.to_string()),
+            label => return label,
+        };
+        FbxAssetLabel::Named {
+            kind: kind.to_string(),
+            name,
+        }
+    }
+
+    /// Parse a label string produced by the [`Display`](core::fmt::Display) impl.
+    pub fn parse(label: &str) -> Option<Self> {
+        match label {
+            "Colliders" => return Some(FbxAssetLabel::Colliders),
+            "HumanoidRig" => return Some(FbxAssetLabel::HumanoidRig),
+            "Report" => return Some(FbxAssetLabel::Report),
+            "AreaLightQuad" => return Some(FbxAssetLabel::AreaLightQuad),
+            "MissingTexture" => return Some(FbxAssetLabel::MissingTexture),
+            "DefaultScene" => return Some(FbxAssetLabel::DefaultScene),
+            "DefaultMaterial" => return Some(FbxAssetLabel::DefaultMaterial),
+            "RootNode" => return Some(FbxAssetLabel::RootNode),
+            _ => {}
+        }
+
+        if let Some((kind, name)) = label.split_once("Name/")
+            && NAMED_KINDS.contains(&kind)
+            && !name.is_empty()
+        {
+            return Some(FbxAssetLabel::Named {
+                kind: kind.to_string(),
+                name: name.to_string(),
+            });
+        }
+        if let Some(path) = label.strip_prefix("Node/") {
+            return (!path.is_empty()).then(|| FbxAssetLabel::NodeScene(path.to_string()));
+        }
+        if let Some(name) = label.strip_prefix("Take/")
+            && !name.is_empty()
+        {
+            return Some(match name.strip_suffix("/Graph") {
+                Some(name) => FbxAssetLabel::TakeGraph(name.to_string()),
+                None => FbxAssetLabel::TakeScene(name.to_string()),
+            });
+        }
+        if let Some(primitive) = label.strip_suffix("/MorphTargets")
+            && let Some(FbxAssetLabel::Primitive { mesh, primitive }) = Self::parse(primitive)
+        {
+            return Some(FbxAssetLabel::MorphTargets { mesh, primitive });
+        }
+        if let Some((primitive, lod)) = label.rsplit_once('/')
+            && let Some(lod) = parse_index(lod, "Lod")
+            && let Some(FbxAssetLabel::Primitive { mesh, primitive }) = Self::parse(primitive)
+        {
+            return Some(FbxAssetLabel::PrimitiveLod {
+                mesh,
+                primitive,
+                lod,
+            });
+        }
+        if let Some((animation, mask)) = label.split_once("/Mask/")
+            && !mask.is_empty()
+        {
+            return Some(FbxAssetLabel::MaskedAnimation {
+                animation: parse_index(animation, "Animation")?,
+                mask: mask.to_string(),
+            });
+        }
+        if let Some((mesh, primitive)) = label.split_once('/') {
+            return Some(FbxAssetLabel::Primitive {
+                mesh: parse_index(mesh, "Mesh")?,
+                primitive: parse_index(primitive, "Primitive")?,
+            });
+        }
+
+        let indexed: [(&str, fn(usize) -> FbxAssetLabel); 20] = [
+            ("Scene", FbxAssetLabel::Scene),
+            ("Mesh", FbxAssetLabel::Mesh),
+            ("MeshTopology", FbxAssetLabel::MeshTopology),
+            ("Geometry", FbxAssetLabel::Geometry),
+            ("Batch", FbxAssetLabel::Batch),
+            ("MergedMesh", FbxAssetLabel::MergedMesh),
+            ("LineCurve", FbxAssetLabel::LineCurve),
+            ("Material", FbxAssetLabel::Material),
+            ("MaterialExtras", FbxAssetLabel::MaterialExtras),
+            ("Animation", FbxAssetLabel::Animation),
+            ("AnimationStack", FbxAssetLabel::AnimationStack),
+            ("Skeleton", FbxAssetLabel::Skeleton),
+            ("Node", FbxAssetLabel::Node),
+            ("Skin", FbxAssetLabel::Skin),
+            ("BindPose", FbxAssetLabel::BindPose),
+            ("Light", FbxAssetLabel::Light),
+            ("AreaLight", FbxAssetLabel::AreaLight),
+            ("Camera", FbxAssetLabel::Camera),
+            ("Texture", FbxAssetLabel::Texture),
+            ("Media", FbxAssetLabel::Media),
+        ];
+        indexed
+            .into_iter()
+            .find_map(|(prefix, label_fn)| parse_index(label, prefix).map(label_fn))
+    }
+}
+
+/// Error returned when parsing a string that isn't an [`FbxAssetLabel`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid FBX asset label: {0}")]
+pub struct FbxLabelParseError(pub String);
+
+impl FromStr for FbxAssetLabel {
+    type Err = FbxLabelParseError;
+
+    fn from_str(label: &str) -> Result<Self, Self::Err> {
+        FbxAssetLabel::parse(label).ok_or_else(|| FbxLabelParseError(label.to_string()))
+    }
+}
+
+impl Fbx {
+    /// Label of the scene of the node subtree at `path`, if it was built
+    /// through [`node_scenes`](crate::FbxLoaderSettings::node_scenes).
+    pub fn label_for_node_scene(&self, path: &str) -> Option<FbxAssetLabel> {
+        self.named_scenes
+            .contains_key(path)
+            .then(|| FbxAssetLabel::NodeScene(path.to_string()))
+    }
+
+    /// Label of the mesh named `name`.
+    pub fn label_for_mesh(&self, name: &str) -> Option<FbxAssetLabel> {
+        index_of(&self.meshes, &self.named_meshes, name).map(FbxAssetLabel::Mesh)
+    }
+
+    /// Label of the material named `name`.
+    pub fn label_for_material(&self, name: &str) -> Option<FbxAssetLabel> {
+        index_of(&self.materials, &self.named_materials, name).map(FbxAssetLabel::Material)
+    }
+
+    /// Label of the animation named after the animation stack `name`.
+    pub fn label_for_animation(&self, name: &str) -> Option<FbxAssetLabel> {
+        index_of(&self.animations, &self.named_animations, name).map(FbxAssetLabel::Animation)
+    }
+
+    /// Label of the texture named `name`.
+    pub fn label_for_texture(&self, name: &str) -> Option<FbxAssetLabel> {
+        index_of(&self.textures, &self.named_textures, name).map(FbxAssetLabel::Texture)
+    }
+
+    /// Label of the node named `name`.
+    pub fn label_for_node(&self, name: &str) -> Option<FbxAssetLabel> {
+        index_of(&self.nodes, &self.named_nodes, name).map(FbxAssetLabel::Node)
+    }
+
+    /// Label of the skin named `name`.
+    pub fn label_for_skin(&self, name: &str) -> Option<FbxAssetLabel> {
+        index_of(&self.skins, &self.named_skins, name).map(FbxAssetLabel::Skin)
+    }
+}
+
+/// Label names of elements with the given names, in element order, for
+/// [`FbxLoaderSettings::name_based_labels`]; empty if the setting is off.
+///
+/// Names are kept across re-exports, unlike indices. A name already taken by
+/// an earlier element gets the first free `_1`, `_2`, ... suffix, `#` (the
+/// asset path label separator) is replaced by `_`, and unnamed elements get
+/// `None` and only have their index label.
+pub fn label_names<'a>(
+    settings: &FbxLoaderSettings,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Vec<Option<String>> {
+    if !settings.name_based_labels {
+        return Vec::new();
+    }
+    let mut used = HashSet::new();
+    names
+        .into_iter()
+        .map(|name| {
+            if name.is_empty() {
+                return None;
+            }
+            let name = name.replace('#', "_");
+            let mut label = name.clone();
+            let mut suffix = 1;
+            while !used.insert(label.clone()) {
+                label = format!("{name}_{suffix}");
+                suffix += 1;
+            }
+            Some(label)
+        })
+        .collect()
+}
+
+/// Index in `handles` of the asset named `name`.
+fn index_of<A: Asset>(
+    handles: &[Handle<A>],
+    named: &HashMap<Box<str>, Handle<A>>,
+    name: &str,
+) -> Option<usize> {
+    let handle = named.get(name)?;
+    handles.iter().position(|candidate| candidate == handle)
+}
+
+/// Parse `{prefix}{index}`; the index must consist of digits only.
+fn parse_index(label: &str, prefix: &str) -> Option<usize> {
+    let index = label.strip_prefix(prefix)?;
+    if index.is_empty() || !index.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    index.parse().ok()
 }
@@ -17,37 +17,250 @@
 
 use bevy::asset::AssetApp;
 use bevy::prelude::*;
+use scene::trigger_fbx_scene_instance_ready;
+use std::sync::Arc;
 
+pub mod animation;
+pub mod attachment;
+pub mod attribute;
+pub mod bind_pose;
+pub mod cache;
+pub mod collider;
+pub mod coordinates;
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod diagnostics;
+pub mod environment;
 pub mod error;
+#[cfg(feature = "scene_export")]
+pub mod export;
+pub mod extension;
+pub mod external;
+pub mod geometry;
+pub mod gltf;
+pub mod hash;
+pub mod humanoid;
 pub mod label;
+pub mod light;
+pub mod lightmap;
+pub mod limits;
 pub mod loader;
 pub mod material;
+pub mod media;
 pub mod mesh;
+pub mod mipmap;
+pub mod morph;
 pub mod node;
+pub mod optimize;
+pub mod parse_cache;
+pub mod preset;
 pub mod scene;
+pub mod shader;
+pub mod simplify;
+pub mod sink;
+#[cfg(feature = "test-fixtures")]
+pub mod testing;
+pub mod texture;
+pub mod threads;
+pub mod topology;
 pub mod types;
 pub mod utils;
+pub mod validation;
+pub mod variant;
+pub mod version;
 
+pub use animation::{
+    FbxAnimationEvent, FbxBoneMask, FbxClipTiming, FbxKeyframeReduction, FbxKeyframeStats,
+    FbxRootMotion, FbxTrackFilter, FbxTrackMask, RootMotionMode, bind_animation_targets,
+};
+pub use attachment::FbxBoneAttachment;
+pub use attribute::{FbxCustomAttribute, FbxVertexLayer};
+pub use bind_pose::{FbxBindPose, FbxResetToBindPose, reset_to_bind_pose};
+pub use cache::{FbxVertexCache, FbxVertexCacheLoader, FbxVertexCachePlayer};
+pub use collider::{FbxCollider, FbxColliderKind, FbxColliderShape, FbxColliders};
+pub use coordinates::{FbxCoordinateConverter, FbxSpaceConversion, axis_load_opts};
+#[cfg(feature = "debug")]
+pub use debug::{FbxDebugDraw, FbxDebugPlugin};
+pub use diagnostics::{FbxDiagnosticsPlugin, FbxLoadDiagnostics, FbxWarning, FbxWarnings};
 pub use error::FbxError;
-pub use label::FbxAssetLabel;
-pub use loader::{FbxLoader, FbxLoaderSettings};
+#[cfg(feature = "scene_export")]
+pub use export::{
+    FbxExportManifest, FbxExportedClip, FbxExportedMesh, fbx_export_manifest, fbx_to_dynamic_scene,
+    serialize_fbx_manifest, serialize_fbx_materials, serialize_fbx_scene, write_fbx_scene,
+};
+pub use extension::{FbxSceneExtension, FbxSceneExtensions};
+pub use external::FbxExternalFiles;
+pub use geometry::{FbxColorLayer, FbxGeometry, FbxUvLayer};
+pub use gltf::{
+    FbxGltfAlphaMode, FbxGltfMaterial, FbxOrmTextures, fbx_gltf_materials, pack_orm_image,
+};
+pub use humanoid::{FbxHumanoidRig, FbxRigConvention, HumanoidBone};
+pub use label::{FbxAssetLabel, FbxLabelParseError};
+pub use light::{FbxAreaLightFallback, FbxLightConversion, FbxLightDecay, FbxLightPreset};
+pub use limits::FbxLoadLimits;
+pub use loader::{
+    FbxGeometryTransformHandling, FbxLoader, FbxLoaderSettings, FbxPivotHandling, load_from_bytes,
+};
+pub use material::{FbxAlphaMode, FbxDefaultMaterial, FbxMaterialOverride};
+pub use media::{FbxMedia, FbxMediaKind};
+pub use mesh::FbxSkinnedBounds;
+pub use preset::ImportPreset;
+pub use scene::{FbxSceneInstanceReady, FbxTakeScenes, FbxWorldOrigin, RecenterMode};
+pub use shader::FbxShaderModel;
+pub use simplify::{FbxLodLevel, FbxSimplification};
+pub use sink::{FbxAssetSink, WorldAssetSink};
+pub use texture::{
+    FbxColorSpace, FbxColorSpaceRule, FbxDeferredTexture, FbxImageSettings, FbxTextureLoading,
+    MissingTexturePolicy, TexturePathHook,
+};
+pub use topology::FbxMeshTopology;
 pub use types::*;
+pub use validation::{FbxIssue, FbxIssueKind, FbxReport, FbxValidation};
+pub use variant::{FbxMaterialVariants, apply_variant};
+pub use version::FbxFileFormat;
 
 pub mod prelude {
     //! Commonly used items.
-    pub use crate::{Fbx, FbxAssetLabel, FbxLoaderSettings, FbxNode, FbxPlugin, FbxSkin, Skeleton};
+    pub use crate::{
+        Fbx, FbxAssetLabel, FbxLoaderSettings, FbxMesh, FbxNode, FbxPlugin, FbxSkin, Skeleton,
+    };
 }
 
 /// Plugin adding the FBX loader to an [`App`].
+///
+/// Configure project-wide default settings or scene extensions with
+/// [`FbxPlugin::default_settings`] and [`FbxPlugin::with_extension`], which
+/// return an [`FbxPluginBuilder`].
 #[derive(Default)]
 pub struct FbxPlugin;
 
+impl FbxPlugin {
+    /// Create the plugin with project-wide default loader settings.
+    pub fn default_settings(settings: FbxLoaderSettings) -> FbxPluginBuilder {
+        FbxPluginBuilder::default().with_default_settings(settings)
+    }
+
+    /// Create the plugin with a scene extension.
+    pub fn with_extension(extension: impl FbxSceneExtension) -> FbxPluginBuilder {
+        FbxPluginBuilder::default().with_extension(extension)
+    }
+}
+
 impl Plugin for FbxPlugin {
+    fn build(&self, app: &mut App) {
+        FbxPluginBuilder::default().build(app);
+    }
+}
+
+/// [`FbxPlugin`] with project-wide default settings or scene extensions.
+#[derive(Default)]
+pub struct FbxPluginBuilder {
+    /// Settings every `.fbx` load starts from; fields a load changes from
+    /// [`FbxLoaderSettings::default`] win, see
+    /// [`FbxLoaderSettings::with_defaults`]
+    pub default_settings: Option<FbxLoaderSettings>,
+    /// Extensions run on the node entities of every built scene
+    pub extensions: FbxSceneExtensions,
+}
+
+impl FbxPluginBuilder {
+    /// Apply `settings` to every `.fbx` load unless overridden per-load.
+    pub fn with_default_settings(mut self, settings: FbxLoaderSettings) -> Self {
+        self.default_settings = Some(settings);
+        self
+    }
+
+    /// Register a scene extension, run in registration order.
+    pub fn with_extension(mut self, extension: impl FbxSceneExtension) -> Self {
+        self.extensions.0.push(Arc::new(extension));
+        self
+    }
+}
+
+impl Plugin for FbxPluginBuilder {
+    // Shares the name of `FbxPlugin`, so adding both is a duplicate plugin
+    fn name(&self) -> &str {
+        std::any::type_name::<FbxPlugin>()
+    }
+
     fn build(&self, app: &mut App) {
         app.init_asset::<Fbx>()
+            .init_asset::<FbxBindPose>()
+            .init_asset::<FbxColliders>()
+            .init_asset::<FbxGeometry>()
+            .init_asset::<FbxHumanoidRig>()
+            .init_asset::<FbxMaterialExtras>()
+            .init_asset::<FbxMedia>()
+            .init_asset::<FbxMesh>()
+            .init_asset::<FbxMeshTopology>()
             .init_asset::<FbxNode>()
+            .init_asset::<FbxReport>()
             .init_asset::<FbxSkin>()
+            .init_asset::<FbxTexture>()
+            .init_asset::<FbxVertexCache>()
             .init_asset::<Skeleton>()
-            .register_asset_loader(FbxLoader::default());
+            .register_asset_reflect::<Fbx>()
+            .register_asset_reflect::<FbxBindPose>()
+            .register_asset_reflect::<FbxColliders>()
+            .register_asset_reflect::<FbxGeometry>()
+            .register_asset_reflect::<FbxHumanoidRig>()
+            .register_asset_reflect::<FbxMaterialExtras>()
+            .register_asset_reflect::<FbxMedia>()
+            .register_asset_reflect::<FbxMesh>()
+            .register_asset_reflect::<FbxMeshTopology>()
+            .register_asset_reflect::<FbxNode>()
+            .register_asset_reflect::<FbxReport>()
+            .register_asset_reflect::<FbxSkin>()
+            .register_asset_reflect::<FbxTexture>()
+            .register_asset_reflect::<FbxVertexCache>()
+            .register_asset_reflect::<Skeleton>()
+            .register_type::<FbxAnimationEvent>()
+            .register_type::<FbxKeyframeStats>()
+            .register_type::<FbxClipTiming>()
+            .register_type::<FbxRootMotion>()
+            .register_type::<FbxAxisSystem>()
+            .register_type::<FbxBone>()
+            .register_type::<FbxNull>()
+            .register_type::<FbxBillboard>()
+            .register_type::<FbxShadowOnly>()
+            .register_type::<FbxMaterialVariants>()
+            .register_type::<FbxGroupMember>()
+            .register_type::<FbxNodePath>()
+            .register_type::<FbxTransformData>()
+            .register_type::<FbxSceneMap>()
+            .register_type::<FbxActiveCamera>()
+            .register_type::<FbxCameraLens>()
+            .register_type::<FbxMeta>()
+            .register_type::<FbxLoadDiagnostics>()
+            .register_type::<FbxWarnings>()
+            .register_type::<FbxMaterial>()
+            .register_type::<FbxMaterialExtras>()
+            .register_type::<FbxOrmTextures>()
+            .register_type::<FbxGltfMaterial>()
+            .register_type::<FbxLight>()
+            .register_type::<FbxCamera>()
+            .register_type::<FbxBlendChannel>()
+            .register_type::<FbxVertexCachePlayer>()
+            .register_type::<FbxResetToBindPose>()
+            .register_type::<FbxBoneAttachment>()
+            .insert_resource(self.extensions.clone())
+            .register_asset_loader(FbxLoader {
+                default_settings: self.default_settings.clone(),
+                extensions: self.extensions.clone(),
+            })
+            .register_asset_loader(FbxVertexCacheLoader)
+            .add_systems(
+                Update,
+                (
+                    bind_pose::apply_bind_pose_resets,
+                    attachment::attach_added_attachments,
+                    cache::play_vertex_caches.run_if(resource_exists::<Assets<Mesh>>),
+                    texture::load_deferred_textures
+                        .run_if(resource_exists::<Assets<StandardMaterial>>),
+                    mipmap::generate_fbx_mipmaps.run_if(resource_exists::<Assets<Image>>),
+                ),
+            )
+            .add_observer(trigger_fbx_scene_instance_ready)
+            .add_observer(attachment::attach_on_scene_ready);
     }
 }
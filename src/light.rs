@@ -0,0 +1,194 @@
+//! Light intensity conversion from FBX exporter units to Bevy's photometric
+//! units, and approximation of light types Bevy lacks.
+
+use crate::label::FbxAssetLabel;
+use crate::sink::FbxAssetSink;
+use bevy::asset::Handle;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+/// How FBX area lights, which Bevy cannot render, are approximated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FbxAreaLightFallback {
+    /// Drop area lights
+    Skip,
+    /// Point light at the center of the area light
+    #[default]
+    PointLight,
+    /// Point light plus an unlit quad showing the light's surface
+    EmissiveQuad,
+}
+
+/// Assets shared by the emissive quads of
+/// [`FbxAreaLightFallback::EmissiveQuad`].
+#[derive(Debug, Clone, Default)]
+pub struct AreaLightAssets {
+    /// Unit quad facing +Z, scaled by the light node's transform
+    pub quad: Option<Handle<Mesh>>,
+    /// Unlit material in the light's color, keyed by light element id
+    pub materials: HashMap<u32, Handle<StandardMaterial>>,
+}
+
+impl AreaLightAssets {
+    /// Create the quad and per-light materials if `fallback` needs them.
+    pub fn create(
+        scene: &ufbx::Scene,
+        fallback: FbxAreaLightFallback,
+        load_context: &mut impl FbxAssetSink,
+    ) -> Self {
+        let mut assets = Self::default();
+        if fallback != FbxAreaLightFallback::EmissiveQuad {
+            return assets;
+        }
+        for (index, light) in scene.lights.as_ref().iter().enumerate() {
+            if light.type_ != ufbx::LightType::Area {
+                continue;
+            }
+            if assets.quad.is_none() {
+                assets.quad = Some(load_context.add_labeled_asset(
+                    FbxAssetLabel::AreaLightQuad.to_string(),
+                    Mesh::from(Rectangle::new(1.0, 1.0)),
+                ));
+            }
+            let color = Color::srgb(
+                light.color.x as f32,
+                light.color.y as f32,
+                light.color.z as f32,
+            );
+            let material = StandardMaterial {
+                base_color: color,
+                unlit: true,
+                double_sided: true,
+                cull_mode: None,
+                ..Default::default()
+            };
+            let handle = load_context
+                .add_labeled_asset(FbxAssetLabel::AreaLight(index).to_string(), material);
+            assets.materials.insert(light.element.element_id, handle);
+        }
+        assets
+    }
+}
+
+/// Rotation turning a quad facing +Z towards an area light's emission
+/// direction.
+pub fn area_light_rotation(local_direction: Vec3) -> Quat {
+    if local_direction.length_squared() <= f32::EPSILON {
+        return Quat::IDENTITY;
+    }
+    Quat::from_rotation_arc(Vec3::Z, local_direction.normalize())
+}
+
+/// Exporter-specific interpretation of FBX light intensities.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum FbxLightPreset {
+    /// Unitless intensity: 1.0 maps to 1000 lm for point/spot lights and
+    /// 10000 lx for directional lights
+    #[default]
+    Generic,
+    /// Intensity in watts, as written by Blender
+    Blender,
+    /// Photometric intensity in candela (point/spot) and lux (directional),
+    /// as written by 3ds Max photometric lights
+    Photometric,
+    /// Explicit scale factors
+    Custom {
+        /// Lumens per unit of point/spot light intensity
+        lumens_per_unit: f32,
+        /// Lux per unit of directional light intensity
+        lux_per_unit: f32,
+    },
+}
+
+impl FbxLightPreset {
+    /// Lumens per unit of FBX point/spot light intensity.
+    pub fn lumens_per_unit(&self) -> f32 {
+        match self {
+            FbxLightPreset::Generic => 1000.0,
+            // Luminous efficacy of 683 lm/W spread over the full sphere
+            FbxLightPreset::Blender => 683.0 / (4.0 * PI),
+            FbxLightPreset::Photometric => 4.0 * PI,
+            FbxLightPreset::Custom {
+                lumens_per_unit, ..
+            } => *lumens_per_unit,
+        }
+    }
+
+    /// Lux per unit of FBX directional light intensity.
+    pub fn lux_per_unit(&self) -> f32 {
+        match self {
+            FbxLightPreset::Generic => 10000.0,
+            FbxLightPreset::Blender => 683.0,
+            FbxLightPreset::Photometric => 1.0,
+            FbxLightPreset::Custom { lux_per_unit, .. } => *lux_per_unit,
+        }
+    }
+}
+
+/// FBX light decay (attenuation) modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FbxLightDecay {
+    None,
+    Linear,
+    Quadratic,
+    Cubic,
+}
+
+impl From<ufbx::LightDecay> for FbxLightDecay {
+    fn from(decay: ufbx::LightDecay) -> Self {
+        match decay {
+            ufbx::LightDecay::None => FbxLightDecay::None,
+            ufbx::LightDecay::Linear => FbxLightDecay::Linear,
+            ufbx::LightDecay::Quadratic => FbxLightDecay::Quadratic,
+            ufbx::LightDecay::Cubic => FbxLightDecay::Cubic,
+        }
+    }
+}
+
+/// Conversion from FBX light intensities to Bevy units.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxLightConversion {
+    /// How the exporter defines light intensity
+    pub preset: FbxLightPreset,
+    /// Extra multiplier applied to every converted intensity
+    pub multiplier: f32,
+    /// Distance in meters at which non-quadratic decay modes are matched to
+    /// Bevy's inverse-square falloff
+    pub decay_reference_distance: f32,
+}
+
+impl Default for FbxLightConversion {
+    fn default() -> Self {
+        Self {
+            preset: FbxLightPreset::default(),
+            multiplier: 1.0,
+            decay_reference_distance: 1.0,
+        }
+    }
+}
+
+impl FbxLightConversion {
+    /// Convert a point or spot light intensity to lumens.
+    ///
+    /// Bevy lights always use inverse-square falloff, so lights with other
+    /// decay modes are scaled to produce the same illumination at
+    /// [`Self::decay_reference_distance`].
+    pub fn luminous_power(&self, intensity: f32, decay: FbxLightDecay) -> f32 {
+        let d = self.decay_reference_distance.max(f32::EPSILON);
+        let decay_scale = match decay {
+            FbxLightDecay::None => d * d,
+            FbxLightDecay::Linear => d,
+            FbxLightDecay::Quadratic => 1.0,
+            FbxLightDecay::Cubic => 1.0 / d,
+        };
+        intensity * self.preset.lumens_per_unit() * decay_scale * self.multiplier
+    }
+
+    /// Convert a directional light intensity to lux.
+    pub fn illuminance(&self, intensity: f32) -> f32 {
+        intensity * self.preset.lux_per_unit() * self.multiplier
+    }
+}
@@ -0,0 +1,85 @@
+//! Baked lightmap assignment for FBX nodes.
+
+use crate::loader::FbxLoaderSettings;
+use crate::material::resolve_texture_path;
+use crate::sink::FbxAssetSink;
+use crate::texture::{TextureCache, TextureIndex};
+use bevy::asset::Handle;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Find the baked lightmap of each mesh node, keyed by node element id.
+///
+/// A node's lightmap is taken from the string user property named
+/// [`FbxLoaderSettings::lightmap_property`], or else from a texture connected
+/// to the material slot of that name on one of the node's materials.
+pub fn process_lightmaps(
+    scene: &ufbx::Scene,
+    settings: &FbxLoaderSettings,
+    texture_index: &TextureIndex,
+    texture_cache: &mut TextureCache,
+    load_context: &mut impl FbxAssetSink,
+) -> HashMap<u32, Handle<Image>> {
+    let mut lightmaps = HashMap::new();
+    let Some(property) = settings.lightmap_property.as_deref() else {
+        return lightmaps;
+    };
+    let base_dir = load_context.base_dir();
+    let media_dir = load_context.media_dir();
+
+    for node in scene.nodes.as_ref().iter() {
+        if node.mesh.is_none() {
+            continue;
+        }
+
+        let Some((relative_filename, filename)) = node_lightmap_path(node, property) else {
+            continue;
+        };
+        let Some(path) = resolve_texture_path(
+            &relative_filename,
+            &filename,
+            settings,
+            texture_index,
+            &base_dir,
+            media_dir.as_deref(),
+        ) else {
+            continue;
+        };
+
+        lightmaps.insert(
+            node.element.element_id,
+            texture_cache.load(&path, load_context),
+        );
+    }
+
+    lightmaps
+}
+
+/// Relative and absolute lightmap paths referenced by a node.
+fn node_lightmap_path(node: &ufbx::Node, property: &str) -> Option<(String, String)> {
+    let user_property = node
+        .element
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == property)
+        .filter(|prop| !prop.value_str.is_empty());
+    if let Some(prop) = user_property {
+        let path = prop.value_str.to_string();
+        return Some((path.clone(), path));
+    }
+
+    node.materials.as_ref().iter().find_map(|material| {
+        let texture_ref = material
+            .textures
+            .as_ref()
+            .iter()
+            .find(|texture_ref| texture_ref.material_prop.as_ref() as &str == property)?;
+        let texture = &texture_ref.texture;
+        Some((
+            texture.relative_filename.to_string(),
+            texture.filename.to_string(),
+        ))
+    })
+}
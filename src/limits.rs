@@ -0,0 +1,132 @@
+//! Resource limits for loading untrusted FBX files.
+
+use crate::error::FbxError;
+use serde::{Deserialize, Serialize};
+
+/// Caps on the resources an FBX load may use.
+///
+/// All limits are disabled (`None`) by default. Enable them when loading
+/// user-provided files, e.g. from mods, so that malformed or hostile files fail
+/// with [`FbxError::LimitExceeded`] instead of allocating without bound.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxLoadLimits {
+    /// Maximum size of the FBX file in bytes
+    pub max_file_size: Option<usize>,
+    /// Maximum number of bytes ufbx may allocate, applied separately to its
+    /// temporary and result allocators
+    pub max_memory: Option<usize>,
+    /// Maximum number of individual allocations ufbx may make, applied
+    /// separately to its temporary and result allocators
+    pub max_allocations: Option<usize>,
+    /// Maximum number of scene elements (nodes, meshes, materials, ...)
+    pub max_elements: Option<usize>,
+    /// Maximum number of mesh vertices, counted as face corners across all
+    /// meshes before welding
+    pub max_vertices: Option<usize>,
+    /// Maximum number of textures
+    pub max_textures: Option<usize>,
+    /// Maximum number of nodes, including the root node
+    pub max_nodes: Option<usize>,
+    /// Maximum number of ancestors of any node, including the root node
+    pub max_hierarchy_depth: Option<usize>,
+}
+
+impl FbxLoadLimits {
+    /// Check the size of the raw file against [`FbxLoadLimits::max_file_size`].
+    pub fn check_file_size(&self, size: usize) -> Result<(), FbxError> {
+        check_limit("file size in bytes", size, self.max_file_size)
+    }
+
+    /// Check a parsed scene against the element, vertex and texture limits.
+    pub fn check_scene(&self, scene: &ufbx::Scene) -> Result<(), FbxError> {
+        check_limit("element count", scene.elements.len(), self.max_elements)?;
+        check_limit("texture count", scene.textures.len(), self.max_textures)?;
+
+        let vertices = scene
+            .meshes
+            .as_ref()
+            .iter()
+            .map(|mesh| mesh.num_indices)
+            .sum();
+        check_limit("vertex count", vertices, self.max_vertices)
+    }
+
+    /// Check the node hierarchy against the node count and depth limits.
+    ///
+    /// Depths are computed once per node without recursion, so deep chains
+    /// cannot overflow the stack. Parent links forming a cycle fail with
+    /// [`FbxError::HierarchyCycle`] whether or not limits are set.
+    pub fn check_hierarchy(&self, scene: &ufbx::Scene) -> Result<(), FbxError> {
+        let nodes = scene.nodes.as_ref();
+        check_limit("node count", nodes.len(), self.max_nodes)?;
+
+        let mut depths: Vec<Option<usize>> = vec![None; nodes.len()];
+        let mut on_chain = vec![false; nodes.len()];
+        let mut chain = Vec::new();
+        for start in 0..nodes.len() {
+            // Walk up to the root or the first node with a known depth
+            let mut current = Some(start);
+            let mut depth = 0;
+            while let Some(index) = current {
+                if let Some(known) = depths[index] {
+                    depth = known + 1;
+                    break;
+                }
+                if on_chain[index] {
+                    let name = nodes[index].element.name.to_string();
+                    return Err(FbxError::HierarchyCycle(name));
+                }
+                on_chain[index] = true;
+                chain.push(index);
+                current = nodes[index]
+                    .parent
+                    .as_ref()
+                    .map(|parent| parent.element.typed_id as usize)
+                    .filter(|&parent| parent < nodes.len());
+            }
+
+            // Assign depths back down the walked chain
+            while let Some(index) = chain.pop() {
+                on_chain[index] = false;
+                check_limit("hierarchy depth", depth, self.max_hierarchy_depth)?;
+                depths[index] = Some(depth);
+                depth += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Allocator options enforcing the ufbx memory and allocation limits.
+    pub fn allocator_opts(&self) -> ufbx::AllocatorOpts {
+        ufbx::AllocatorOpts {
+            memory_limit: self.max_memory.unwrap_or(0),
+            allocation_limit: self.max_allocations.unwrap_or(0),
+            ..Default::default()
+        }
+    }
+}
+
+/// Fail with [`FbxError::LimitExceeded`] if `value` is above `limit`.
+fn check_limit(what: &str, value: usize, limit: Option<usize>) -> Result<(), FbxError> {
+    match limit {
+        Some(limit) if value > limit => Err(FbxError::LimitExceeded(format!(
+            "{} {} exceeds the limit of {}",
+            what, value, limit
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Convert a ufbx error, reporting exhausted allocators as exceeded limits.
+pub fn convert_load_error(error: ufbx::Error) -> FbxError {
+    match error.type_ {
+        ufbx::ErrorType::MemoryLimit => {
+            FbxError::LimitExceeded("ufbx memory limit reached".to_string())
+        }
+        ufbx::ErrorType::AllocationLimit => {
+            FbxError::LimitExceeded("ufbx allocation limit reached".to_string())
+        }
+        _ => FbxError::UfbxError(format!("{:?}", error)),
+    }
+}
@@ -1,45 +1,613 @@
 //! FBX loader implementation for Bevy.
 
+use crate::animation::{
+    FbxBoneMask, FbxKeyframeReduction, FbxTrackFilter, ProcessedAnimations, RootMotionMode,
+    process_animations,
+};
+use crate::attribute::FbxCustomAttribute;
+use crate::collider::{FbxColliders, process_colliders};
+use crate::coordinates::{FbxSpaceConversion, axis_load_opts};
+use crate::diagnostics::{FbxLoadDiagnostics, FbxWarnings};
+use crate::environment::{ambient_color, process_environment_map};
 use crate::error::FbxError;
-use crate::material::process_materials;
-use crate::mesh::process_meshes;
-use crate::node::{process_nodes, process_skins};
-use crate::scene::build_scene;
-use crate::types::{Fbx, FbxAxisSystem, FbxMeta, Handedness};
+use crate::extension::FbxSceneExtensions;
+use crate::external::FbxExternalFiles;
+use crate::gltf::material_orm_textures;
+use crate::hash::ContentHashSink;
+use crate::humanoid::process_humanoid_rig;
+use crate::label::FbxAssetLabel;
+use crate::light::{FbxAreaLightFallback, FbxLightConversion};
+use crate::lightmap::process_lightmaps;
+use crate::limits::{FbxLoadLimits, convert_load_error};
+use crate::material::{
+    FbxDefaultMaterial, FbxMaterialOverride, ProcessedMaterials, process_materials,
+    process_textures,
+};
+use crate::media::process_media;
+use crate::mesh::{FbxSkinnedBounds, process_line_curves, process_meshes, scene_bounds};
+use crate::node::{ProcessedSkins, process_node_groups, process_nodes, process_skins};
+use crate::optimize::merge_scene_by_material;
+use crate::parse_cache::{FbxParseKey, cache_scene, cached_scene};
+use crate::preset::{ImportPreset, preset_settings};
+use crate::scene::{FbxTakeScenes, FbxWorldOrigin, RecenterMode, build_scene};
+use crate::simplify::FbxSimplification;
+use crate::sink::{FbxAssetSink, WorldAssetSink};
+use crate::texture::{
+    FbxColorSpaceRule, FbxTextureLoading, MissingTexturePolicy, TextureCache, TextureIndex,
+    TexturePathHook,
+};
+use crate::threads::thread_opts;
+use crate::types::{Fbx, FbxAxisSystem, FbxMeta};
+use crate::utils::{camera_background_color, default_camera_name, original_axis_system};
+use crate::validation::{FbxValidation, validate_scene};
+use crate::variant::scene_material_variants;
+use crate::version::{
+    FbxFileFormat, SUPPORTED_FBX_VERSIONS, convert_legacy_load_error, detect_fbx_version,
+    is_legacy_version, unsupported_version,
+};
 use bevy::asset::{AssetLoader, LoadContext, RenderAssetUsages, io::Reader};
+use bevy::image::ImageSamplerDescriptor;
+use bevy::math::DVec3;
+use bevy::platform::time::Instant;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Settings for FBX file loading.
 ///
 /// These settings allow customizing which parts of the FBX file are loaded
 /// and how they are processed.
-#[derive(Serialize, Deserialize)]
+///
+/// Project-wide defaults set with
+/// [`FbxPlugin::default_settings`](crate::FbxPlugin::default_settings) are
+/// merged into each load's settings by the loader, see
+/// [`with_defaults`](Self::with_defaults).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FbxLoaderSettings {
     /// How meshes should be loaded and used
     pub load_meshes: RenderAssetUsages,
+    /// Glob patterns (`*`, `?`) of mesh or node names with their own
+    /// [`RenderAssetUsages`], e.g. `("navmesh_*", RenderAssetUsages::all())`
+    /// to keep CPU copies of a few meshes. The first matching rule wins;
+    /// other meshes use `load_meshes`
+    pub mesh_usage_overrides: Vec<(String, RenderAssetUsages)>,
     /// How materials should be loaded and used
     pub load_materials: RenderAssetUsages,
-    /// Whether to load cameras from the FBX file
+    /// Color space overrides for textures by material slot and path pattern,
+    /// e.g. linear roughness maps stored as sRGB PNGs. The first matching
+    /// rule wins; other textures use the image loader's default
+    pub texture_color_spaces: Vec<FbxColorSpaceRule>,
+    /// Material for primitives without a material binding; `None` falls back
+    /// to the file's first material, or Bevy's default [`StandardMaterial`]
+    pub default_material: Option<FbxDefaultMaterial>,
+    /// Glob patterns (`*`, `?`) of material names with forced alpha mode,
+    /// double-sidedness or depth bias, for glass and decals the opacity
+    /// heuristic gets wrong. The first matching rule wins
+    pub material_overrides: Vec<(String, FbxMaterialOverride)>,
+    /// Whether to share one [`StandardMaterial`] between materials with
+    /// identical parameters and textures; the `Material{}` labels of
+    /// duplicates resolve to the shared material
+    pub merge_identical_materials: bool,
+    /// Whether bump textures are used as normal maps instead of parallax
+    /// depth maps, for exporters writing normal maps into the bump slot
+    pub bump_as_normal_map: bool,
+    /// Whether to load cameras from the FBX file; they are spawned inactive
     pub load_cameras: bool,
+    /// Whether to insert [`FbxActiveCamera`](crate::FbxActiveCamera) on the
+    /// camera the file was saved looking through
+    pub mark_active_camera: bool,
     /// Whether to load lights from the FBX file
     pub load_lights: bool,
     /// Whether to include raw source data in the loaded asset
     pub include_source: bool,
     /// Whether to convert coordinate systems (e.g., Y-up to Z-up)
     pub convert_coordinates: bool,
+    /// Whether to deduplicate identical position/normal/UV tuples into a
+    /// compact indexed mesh
+    pub weld_vertices: bool,
+    /// Whether to import line and point faces and line curves as
+    /// `LineList`/`PointList` meshes instead of dropping them
+    pub load_lines_and_points: bool,
+    /// Whether to import blend shapes as morph targets, including in-between
+    /// shapes
+    pub load_blend_shapes: bool,
+    /// Whether to add edge creases and smoothing groups of each mesh as an
+    /// [`FbxMeshTopology`](crate::FbxMeshTopology) sub-asset
+    pub load_mesh_topology: bool,
+    /// Whether to add the triangulated source geometry of each mesh as an
+    /// [`FbxGeometry`](crate::FbxGeometry) sub-asset for custom processing
+    pub load_geometry: bool,
+    /// Whether to keep a CPU copy of each skin's per-vertex joint indices
+    /// and weights in [`FbxSkin`](crate::FbxSkin)
+    pub keep_skin_weights: bool,
+    /// Whether to load the `.pc2` or `.mc` file of meshes with a cache
+    /// deformer as an [`FbxVertexCache`](crate::cache::FbxVertexCache) and
+    /// play it back on the spawned primitives
+    pub load_vertex_caches: bool,
+    /// Whether ufbx loads the geometry caches the file references, read
+    /// through the FBX file's asset source, see
+    /// [`FbxExternalFiles`](crate::external::FbxExternalFiles)
+    pub load_external_files: bool,
+    /// Whether to add embedded audio clips and other non-image files as
+    /// [`FbxMedia`](crate::FbxMedia) sub-assets, see [`media`](crate::media)
+    pub load_media: bool,
+    /// Whether to record the corner count of each source face on primitives,
+    /// so quads and n-gons can be reconstructed from the triangulated mesh
+    pub record_face_sizes: bool,
+    /// Culling bounds of skinned meshes, e.g. to keep characters whose bones
+    /// move them far from the bind pose from being culled
+    pub skinned_bounds: FbxSkinnedBounds,
+    /// Glob patterns (`*`, `?`) of mesh or node names with a
+    /// [`FbxSimplification`] decimating their primitives or generating LOD
+    /// levels, e.g. `("rock_*", ...)` for high-poly scans. The first matching
+    /// rule wins. Requires the `simplify` feature
+    pub simplification: Vec<(String, FbxSimplification)>,
+    /// Glob patterns (`*`, `?`) of mesh or node names with geometry layers
+    /// copied into custom vertex attributes, e.g. wind weights stored in a
+    /// second color set. Every matching rule applies; not serialized
+    #[serde(skip)]
+    pub custom_attributes: Vec<(String, FbxCustomAttribute)>,
+    /// How geometric (pivot) transforms are applied to meshes
+    pub geometry_transform_handling: FbxGeometryTransformHandling,
+    /// Whether node origins are moved to their rotation pivot, so spawned
+    /// entities and animations rotate about the authored pivot
+    pub pivot_handling: FbxPivotHandling,
+    /// Where the conversion to Bevy's axes and units is stored; the scene
+    /// lands in the same place with every option
+    pub space_conversion: FbxSpaceConversion,
+    /// Transform of an `FbxRoot` entity wrapping the default scene, e.g. to
+    /// scale or rotate assets authored at odd scales
+    #[serde(with = "optional_transform")]
+    pub root_transform_override: Option<Transform>,
+    /// How the default scene is moved relative to its origin, applied before
+    /// `root_transform_override`
+    pub recenter: RecenterMode,
+    /// Point moved to the world origin in f64 before node transforms are
+    /// converted to f32, for survey or architecture files with coordinates
+    /// in the millions. The offset is reported in
+    /// [`FbxMeta::world_offset`](crate::FbxMeta::world_offset)
+    pub world_origin: FbxWorldOrigin,
+    /// How FBX light intensities are converted to Bevy units
+    pub light_conversion: FbxLightConversion,
+    /// How area lights are approximated, since Bevy has no area lights
+    pub area_lights: FbxAreaLightFallback,
+    /// Whether to spawn an [`AmbientLight`] with the file's ambient color in
+    /// the default scene
+    pub spawn_ambient_light: bool,
+    /// Whether to look for an environment or sky texture, exposed as
+    /// [`Fbx::environment_map`](crate::Fbx::environment_map)
+    pub load_environment_map: bool,
+    /// Whether to treat `UCX_`/`UBX_`/`USP_`/`UCP_` nodes as physics colliders
+    /// instead of rendering them
+    pub extract_colliders: bool,
+    /// Whether to add [`FbxBillboard`](crate::FbxBillboard) to entities of
+    /// nodes named `BB_`/`BBS_` or with a `Billboard` user property
+    pub detect_billboards: bool,
+    /// Render layer of mesh nodes named `SHADOW_` or with a `ShadowOnly`
+    /// user property, which then only cast shadows. Lights spawned from the
+    /// file also get the layer; other lights need it to cast their shadows.
+    /// `None` spawns such nodes like any other
+    pub shadow_only_layer: Option<usize>,
+    /// Whether to skip hidden nodes entirely instead of spawning them with
+    /// [`Visibility::Hidden`]
+    pub skip_hidden_nodes: bool,
+    /// Whether to merge the static meshes of the default scene into one
+    /// world-space `Batch{}` mesh per material, for environment files with
+    /// many nodes. Animated, skinned, morphed, lightmapped and layered nodes
+    /// keep their own entities; extensions don't see merged nodes
+    pub flatten_static_hierarchy: bool,
+    /// Whether to also merge the primitives sharing a material into
+    /// `MergedMesh{}` assets with baked node transforms, listed in
+    /// [`Fbx::merged_meshes`](crate::Fbx::merged_meshes); the scene is unchanged
+    pub merge_by_material: bool,
+    /// Whether to also add meshes, primitives, materials, animations, nodes
+    /// and skins under name-based labels (e.g. `MaterialName/Brick`), so
+    /// hand-written asset paths survive re-exports that reorder elements.
+    /// Named labels hold copies of the index-labeled assets
+    pub name_based_labels: bool,
+    /// Maps FBX display layer names to Bevy [`RenderLayers`](bevy::camera::visibility::RenderLayers)
+    /// indices; nodes in unmapped layers keep the default layer
+    pub display_layers: HashMap<String, usize>,
+    /// Maps light link group names, listed in the `LightLink` user property
+    /// of light and mesh nodes, to render layer indices. Linked lights only
+    /// illuminate meshes of their groups
+    pub light_links: HashMap<String, usize>,
+    /// Glob patterns (`*`, `?`) of node names to spawn; empty spawns all nodes.
+    /// Matching a node includes its whole subtree
+    pub include_nodes: Vec<String>,
+    /// Glob patterns of node names to skip along with their subtrees, e.g.
+    /// `"_proxy*"` or `"*_blockout"`; bones are never skipped
+    pub exclude_nodes: Vec<String>,
+    /// `/`-separated node name paths (e.g. `"Root/Floor2"`) whose subtrees are
+    /// also emitted as `Node/{path}` scenes
+    pub node_scenes: Vec<String>,
+    /// Whether to also emit a `Take/{name}` scene per animation stack, posed
+    /// at the stack's start or playing its clip; added to
+    /// [`Fbx::named_scenes`](crate::Fbx::named_scenes) by stack name
+    pub take_scenes: FbxTakeScenes,
+    /// Whether to load animation stacks as animation clips
+    pub load_animations: bool,
+    /// Prefix marking animated custom properties and nulls as animation event
+    /// markers (e.g. `"EVT_"`); `None` disables event extraction
+    pub animation_event_prefix: Option<String>,
+    /// Whether to register extracted events on the clips as
+    /// [`FbxAnimationEvent`](crate::animation::FbxAnimationEvent) animation events
+    pub register_animation_events: bool,
+    /// How the root bone's planar translation is handled in clips
+    pub root_motion: RootMotionMode,
+    /// Name of the root motion bone; defaults to the topmost bone
+    pub root_motion_bone: Option<String>,
+    /// Tolerances for removing redundant keys from clips; `None` keeps every
+    /// baked key
+    pub keyframe_reduction: Option<FbxKeyframeReduction>,
+    /// Whether to add curves for animated camera field of view and focus
+    /// distance and light color and intensity to clips
+    pub animate_properties: bool,
+    /// Channels kept in every clip, e.g.
+    /// [`FbxTrackFilter::rotation_only_bones`] to strip scale tracks and
+    /// non-root bone translation
+    pub track_filter: FbxTrackFilter,
+    /// Bone subsets each clip is additionally generated for, as
+    /// `Animation{}/Mask/{}` sub-assets
+    pub bone_masks: Vec<FbxBoneMask>,
+    /// Glob patterns (`*`, `?`) of animation stack names with their own
+    /// track filter. The first matching rule wins; other clips use
+    /// `track_filter`
+    pub clip_track_filters: Vec<(String, FbxTrackFilter)>,
+    /// Whether to bake aim/parent/position/rotation/scale constraints into
+    /// the animation of the constrained nodes
+    pub bake_constraints: bool,
+    /// Bone renames applied to animation target paths, e.g. to let Mixamo
+    /// clips drive a skeleton with different bone names
+    pub bone_rename_map: HashMap<String, String>,
+    /// Prefixes stripped from bone names in animation target paths, e.g.
+    /// `"mixamorig:"`
+    pub bone_strip_prefixes: Vec<String>,
+    /// Asset directories searched for textures by file name when the path
+    /// stored in the FBX file cannot be found
+    pub texture_search_dirs: Vec<String>,
+    /// Exact texture asset path rewrites, e.g. `textures/foo.psd` →
+    /// `textures/foo.png`
+    pub texture_path_remap: HashMap<String, String>,
+    /// Texture file extension rewrites (lowercase, without the dot), e.g.
+    /// `psd` → `png`
+    pub texture_extension_remap: HashMap<String, String>,
+    /// Callback rewriting texture paths after the remap tables; not serialized
+    #[serde(skip)]
+    pub texture_path_hook: Option<TexturePathHook>,
+    /// What to do with material textures that cannot be found
+    pub missing_texture: MissingTexturePolicy,
+    /// When material textures are requested from the asset server
+    pub texture_loading: FbxTextureLoading,
+    /// Whether every texture is requested while the file loads, overriding
+    /// [`FbxTextureLoading::Deferred`], so the `Fbx` asset only counts as
+    /// loaded with its dependencies once all images have loaded
+    pub eager_dependencies: bool,
+    /// Sampler material textures are requested with, e.g. anisotropic
+    /// filtering; `None` uses the image loader's default sampler
+    pub texture_sampler: Option<ImageSamplerDescriptor>,
+    /// Whether to build mip chains for material textures without mips once
+    /// they have loaded, see [`generate_mipmaps`](crate::mipmap::generate_mipmaps)
+    pub generate_mipmaps: bool,
+    /// Resource limits for loading untrusted files
+    pub limits: FbxLoadLimits,
+    /// Whether ufbx decompresses and parses large files on Bevy's
+    /// [`AsyncComputeTaskPool`](bevy::tasks::AsyncComputeTaskPool); speeds up
+    /// files of hundreds of megabytes on multicore machines
+    pub multithreaded_parsing: bool,
+    /// Number of parsed scenes kept for later loads of the same data, see
+    /// [`parse_cache`](crate::parse_cache); 0 disables the cache
+    pub parse_cache_size: usize,
+    /// Whether the duration and element counts of each load stage are
+    /// logged at info level, tagged with the asset path
+    pub verbose_logging: bool,
+    /// Exporter conventions overriding `bump_as_normal_map` and the light
+    /// intensity preset, see [`ImportPreset`]
+    pub import_preset: Option<ImportPreset>,
+    /// Thresholds of the validation pass; when set, an
+    /// [`FbxReport`](crate::FbxReport) is added as the `Report` sub-asset
+    pub validation: Option<FbxValidation>,
+    /// Name of the UV set imported as [`Mesh::ATTRIBUTE_UV_1`] for lightmaps,
+    /// in place of a second UV set sampled by material textures
+    pub lightmap_uv_set: Option<String>,
+    /// Name of a node user property (holding a texture path) or material
+    /// texture slot pointing at a baked lightmap; primitives of such nodes get
+    /// a [`Lightmap`](bevy::pbr::Lightmap) component
+    pub lightmap_property: Option<String>,
 }
 
 impl Default for FbxLoaderSettings {
     fn default() -> Self {
         Self {
             load_meshes: RenderAssetUsages::default(),
+            mesh_usage_overrides: Vec::new(),
             load_materials: RenderAssetUsages::default(),
+            default_material: None,
+            material_overrides: Vec::new(),
+            texture_color_spaces: Vec::new(),
+            merge_identical_materials: false,
+            bump_as_normal_map: false,
             load_cameras: true,
+            mark_active_camera: false,
             load_lights: true,
             include_source: false,
             convert_coordinates: false,
+            weld_vertices: false,
+            load_lines_and_points: false,
+            load_blend_shapes: true,
+            load_mesh_topology: false,
+            load_geometry: false,
+            keep_skin_weights: false,
+            load_vertex_caches: false,
+            load_external_files: false,
+            load_media: false,
+            record_face_sizes: false,
+            skinned_bounds: FbxSkinnedBounds::default(),
+            simplification: Vec::new(),
+            custom_attributes: Vec::new(),
+            geometry_transform_handling: FbxGeometryTransformHandling::default(),
+            pivot_handling: FbxPivotHandling::default(),
+            space_conversion: FbxSpaceConversion::default(),
+            root_transform_override: None,
+            recenter: RecenterMode::default(),
+            world_origin: FbxWorldOrigin::default(),
+            light_conversion: FbxLightConversion::default(),
+            area_lights: FbxAreaLightFallback::default(),
+            spawn_ambient_light: false,
+            load_environment_map: false,
+            extract_colliders: true,
+            detect_billboards: true,
+            shadow_only_layer: None,
+            skip_hidden_nodes: false,
+            flatten_static_hierarchy: false,
+            merge_by_material: false,
+            name_based_labels: false,
+            display_layers: HashMap::new(),
+            light_links: HashMap::new(),
+            include_nodes: Vec::new(),
+            exclude_nodes: Vec::new(),
+            node_scenes: Vec::new(),
+            take_scenes: FbxTakeScenes::default(),
+            load_animations: true,
+            animation_event_prefix: None,
+            register_animation_events: false,
+            root_motion: RootMotionMode::default(),
+            root_motion_bone: None,
+            keyframe_reduction: None,
+            animate_properties: false,
+            track_filter: FbxTrackFilter::default(),
+            clip_track_filters: Vec::new(),
+            bone_masks: Vec::new(),
+            bake_constraints: true,
+            bone_rename_map: HashMap::new(),
+            bone_strip_prefixes: Vec::new(),
+            texture_search_dirs: Vec::new(),
+            texture_path_remap: HashMap::new(),
+            texture_extension_remap: HashMap::new(),
+            texture_path_hook: None,
+            missing_texture: MissingTexturePolicy::WarnAndSkip,
+            texture_loading: FbxTextureLoading::Immediate,
+            eager_dependencies: false,
+            texture_sampler: None,
+            generate_mipmaps: false,
+            limits: FbxLoadLimits::default(),
+            multithreaded_parsing: false,
+            parse_cache_size: 0,
+            verbose_logging: false,
+            import_preset: None,
+            validation: None,
+            lightmap_uv_set: None,
+            lightmap_property: None,
+        }
+    }
+}
+
+impl FbxLoaderSettings {
+    /// These settings with every field still at its [`Default`] value taken
+    /// from `defaults`, e.g. the project-wide settings of
+    /// [`FbxPlugin::default_settings`](crate::FbxPlugin::default_settings).
+    ///
+    /// Fields changed per load or in a `.meta` file are kept. A field set
+    /// back to its default value can't be told apart from one left alone, so
+    /// it takes the value from `defaults`.
+    pub fn with_defaults(self, defaults: &FbxLoaderSettings) -> Self {
+        let settings = self;
+        let builtin = Self::default();
+        // Destructuring without `..` makes a new field a compile error here
+        // until it is merged too
+        macro_rules! merge {
+            ($settings:ident, $builtin:ident, $defaults:ident; $($field:ident),* $(,)?) => {{
+                let Self { $($field),* } = $settings;
+                Self {
+                    $($field: if $field == $builtin.$field {
+                        $defaults.$field.clone()
+                    } else {
+                        $field
+                    }),*
+                }
+            }};
+        }
+        merge!(
+            settings, builtin, defaults;
+            load_meshes,
+            mesh_usage_overrides,
+            load_materials,
+            texture_color_spaces,
+            default_material,
+            material_overrides,
+            merge_identical_materials,
+            bump_as_normal_map,
+            load_cameras,
+            mark_active_camera,
+            load_lights,
+            include_source,
+            convert_coordinates,
+            weld_vertices,
+            load_lines_and_points,
+            load_blend_shapes,
+            load_mesh_topology,
+            load_geometry,
+            keep_skin_weights,
+            load_vertex_caches,
+            load_external_files,
+            load_media,
+            record_face_sizes,
+            skinned_bounds,
+            simplification,
+            custom_attributes,
+            geometry_transform_handling,
+            pivot_handling,
+            space_conversion,
+            root_transform_override,
+            recenter,
+            world_origin,
+            light_conversion,
+            area_lights,
+            spawn_ambient_light,
+            load_environment_map,
+            extract_colliders,
+            detect_billboards,
+            shadow_only_layer,
+            skip_hidden_nodes,
+            flatten_static_hierarchy,
+            merge_by_material,
+            name_based_labels,
+            display_layers,
+            light_links,
+            include_nodes,
+            exclude_nodes,
+            node_scenes,
+            take_scenes,
+            load_animations,
+            animation_event_prefix,
+            register_animation_events,
+            root_motion,
+            root_motion_bone,
+            keyframe_reduction,
+            animate_properties,
+            track_filter,
+            bone_masks,
+            clip_track_filters,
+            bake_constraints,
+            bone_rename_map,
+            bone_strip_prefixes,
+            texture_search_dirs,
+            texture_path_remap,
+            texture_extension_remap,
+            texture_path_hook,
+            missing_texture,
+            texture_loading,
+            eager_dependencies,
+            texture_sampler,
+            generate_mipmaps,
+            limits,
+            multithreaded_parsing,
+            parse_cache_size,
+            verbose_logging,
+            import_preset,
+            validation,
+            lightmap_uv_set,
+            lightmap_property,
+        )
+    }
+}
+
+/// Serde for `Option<Transform>` as translation, rotation and scale arrays,
+/// since Bevy only implements serde behind its `serialize` feature.
+mod optional_transform {
+    use bevy::prelude::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    type Parts = ([f32; 3], [f32; 4], [f32; 3]);
+
+    pub fn serialize<S: Serializer>(
+        transform: &Option<Transform>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let parts: Option<Parts> = transform.map(|transform| {
+            (
+                transform.translation.to_array(),
+                transform.rotation.to_array(),
+                transform.scale.to_array(),
+            )
+        });
+        parts.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Transform>, D::Error> {
+        let parts = Option::<Parts>::deserialize(deserializer)?;
+        Ok(parts.map(|(translation, rotation, scale)| Transform {
+            translation: Vec3::from_array(translation),
+            rotation: Quat::from_array(rotation),
+            scale: Vec3::from_array(scale),
+        }))
+    }
+}
+
+/// How geometric transforms are handled when loading.
+///
+/// Geometric transforms (3ds Max pivot offsets) affect a node's geometry but
+/// are not inherited by its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FbxGeometryTransformHandling {
+    /// Keep geometric transforms separate and apply them when spawning meshes
+    #[default]
+    Preserve,
+    /// Insert helper nodes carrying the geometric transforms
+    HelperNodes,
+    /// Bake geometric transforms into the mesh vertices, falling back to
+    /// helper nodes for instanced meshes
+    ModifyGeometry,
+    /// Bake geometric transforms into the mesh vertices, even if the mesh is
+    /// instanced
+    ModifyGeometryNoFallback,
+}
+
+impl From<FbxGeometryTransformHandling> for ufbx::GeometryTransformHandling {
+    fn from(handling: FbxGeometryTransformHandling) -> Self {
+        match handling {
+            FbxGeometryTransformHandling::Preserve => ufbx::GeometryTransformHandling::Preserve,
+            FbxGeometryTransformHandling::HelperNodes => {
+                ufbx::GeometryTransformHandling::HelperNodes
+            }
+            FbxGeometryTransformHandling::ModifyGeometry => {
+                ufbx::GeometryTransformHandling::ModifyGeometry
+            }
+            FbxGeometryTransformHandling::ModifyGeometryNoFallback => {
+                ufbx::GeometryTransformHandling::ModifyGeometryNoFallback
+            }
+        }
+    }
+}
+
+/// How rotation and scaling pivots are handled when loading.
+///
+/// 3ds Max and Maya rotate and scale nodes about pivots that may lie away
+/// from the node origin. The imported transforms always place nodes
+/// correctly, but their origin stays where it was authored, so rotating a
+/// spawned entity swings it around the wrong center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FbxPivotHandling {
+    /// Keep node origins where they were authored
+    #[default]
+    Retain,
+    /// Move node origins to their pivot, compensating the geometry with a
+    /// geometric transform handled per
+    /// [`FbxLoaderSettings::geometry_transform_handling`]; the rotation and
+    /// scaling pivots of a node must match
+    AdjustToPivot,
+}
+
+impl From<FbxPivotHandling> for ufbx::PivotHandling {
+    fn from(handling: FbxPivotHandling) -> Self {
+        match handling {
+            FbxPivotHandling::Retain => ufbx::PivotHandling::Retain,
+            FbxPivotHandling::AdjustToPivot => ufbx::PivotHandling::AdjustToPivot,
         }
     }
 }
@@ -49,7 +617,12 @@ impl Default for FbxLoaderSettings {
 /// This loader handles reading FBX files and converting them into Bevy assets,
 /// including meshes, materials, animations, and scene hierarchies.
 #[derive(Default, TypePath)]
-pub struct FbxLoader;
+pub struct FbxLoader {
+    /// Project-wide settings merged into the settings of every load
+    pub default_settings: Option<FbxLoaderSettings>,
+    /// Extensions run on the node entities of built scenes
+    pub extensions: FbxSceneExtensions,
+}
 
 impl AssetLoader for FbxLoader {
     type Asset = Fbx;
@@ -62,86 +635,530 @@ impl AssetLoader for FbxLoader {
         settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Fbx, FbxError> {
+        // Fields changed per load win over the project-wide defaults
+        let settings = &match &self.default_settings {
+            Some(defaults) => settings.clone().with_defaults(defaults),
+            None => settings.clone(),
+        };
+
         // Read file
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
 
-        // Basic validation
-        if bytes.is_empty() {
-            return Err(FbxError::InvalidData("Empty FBX file".to_string()));
-        }
-        if bytes.len() < 32 {
-            return Err(FbxError::InvalidData("FBX file too small".to_string()));
-        }
+        // Index nearby files so texture paths can be resolved
+        let mut texture_dirs = vec![load_context.base_dir()];
+        texture_dirs.extend(settings.texture_search_dirs.iter().map(PathBuf::from));
+        let texture_index = TextureIndex::scan(load_context, &texture_dirs).await;
+
+        let external_files = if settings.load_external_files {
+            FbxExternalFiles::fetch(&bytes, load_context).await
+        } else {
+            FbxExternalFiles::default()
+        };
 
-        // Parse with ufbx
-        let root = ufbx::load_memory(
+        let fbx = convert_fbx(
             &bytes,
-            ufbx::LoadOpts {
-                target_unit_meters: 1.0,
-                target_axes: ufbx::CoordinateAxes::right_handed_y_up(),
-                ..Default::default()
+            settings,
+            &texture_index,
+            &external_files,
+            &self.extensions,
+            load_context,
+        )?;
+        fbx.warnings.log(load_context.path());
+        Ok(fbx)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["fbx"]
+    }
+}
+
+impl Fbx {
+    /// Import FBX data held in memory without going through the asset server.
+    ///
+    /// Sub-assets are added directly to the [`Assets`] resources of `world`,
+    /// which must exist (e.g. via [`FbxPlugin`](crate::FbxPlugin)). Relative
+    /// texture paths are resolved against the asset root, and the world's
+    /// [`FbxSceneExtensions`] are applied to the built scenes.
+    pub fn from_bytes(
+        bytes: &[u8],
+        settings: FbxLoaderSettings,
+        world: &mut World,
+    ) -> Result<Fbx, FbxError> {
+        let extensions = world
+            .get_resource::<FbxSceneExtensions>()
+            .cloned()
+            .unwrap_or_default();
+        let fbx = load_from_bytes(
+            bytes,
+            &settings,
+            &extensions,
+            &mut WorldAssetSink::new(world, ""),
+        )?;
+        fbx.warnings.log("in-memory FBX");
+        Ok(fbx)
+    }
+}
+
+/// Convert the contents of an FBX file into an [`Fbx`] asset.
+///
+/// Sub-assets are handed to `load_context`, which is either the asset
+/// server's [`LoadContext`] or a [`WorldAssetSink`]. Texture paths are not
+/// checked for existence, and no external files are loaded.
+pub fn load_from_bytes(
+    bytes: &[u8],
+    settings: &FbxLoaderSettings,
+    extensions: &FbxSceneExtensions,
+    load_context: &mut impl FbxAssetSink,
+) -> Result<Fbx, FbxError> {
+    convert_fbx(
+        bytes,
+        settings,
+        &TextureIndex::default(),
+        &FbxExternalFiles::default(),
+        extensions,
+        load_context,
+    )
+}
+
+/// Convert the contents of an FBX file, resolving textures through
+/// `texture_index` and serving `external_files` to ufbx.
+fn convert_fbx(
+    bytes: &[u8],
+    settings: &FbxLoaderSettings,
+    texture_index: &TextureIndex,
+    external_files: &FbxExternalFiles,
+    extensions: &FbxSceneExtensions,
+    load_context: &mut impl FbxAssetSink,
+) -> Result<Fbx, FbxError> {
+    let source = load_context.source_name();
+    let _load_span = info_span!("fbx_load", path = %source, bytes = bytes.len()).entered();
+
+    // Basic validation
+    if bytes.is_empty() {
+        return Err(FbxError::InvalidData("Empty FBX file".to_string()));
+    }
+    if bytes.len() < 32 {
+        return Err(FbxError::InvalidData("FBX file too small".to_string()));
+    }
+    settings.limits.check_file_size(bytes.len())?;
+
+    let mut diagnostics = FbxLoadDiagnostics::default();
+
+    // Reject files older than ufbx supports before parsing them
+    let version = detect_fbx_version(bytes).map(|(_, version)| version);
+    if let Some(version) = version
+        && version < *SUPPORTED_FBX_VERSIONS.start()
+    {
+        return Err(unsupported_version(version));
+    }
+
+    // Parse with ufbx
+    let stage_start = Instant::now();
+    let parse_span = info_span!("fbx_parse", path = %source).entered();
+    let open_external = |path: &str, _: &ufbx::OpenFileInfo| external_files.open(path);
+    let load_opts = |legacy: bool, world_offset: DVec3| ufbx::LoadOpts {
+        geometry_transform_handling: settings.geometry_transform_handling.into(),
+        pivot_handling: settings.pivot_handling.into(),
+        temp_allocator: settings.limits.allocator_opts(),
+        result_allocator: settings.limits.allocator_opts(),
+        thread_opts: thread_opts(settings.multithreaded_parsing),
+        // Old exporters write faces and materials newer files always have
+        allow_missing_vertex_position: legacy,
+        allow_empty_faces: legacy,
+        allow_null_material: legacy,
+        // Only files fetched through the asset source are opened
+        load_external_files: settings.load_external_files,
+        ignore_missing_external_files: true,
+        open_file_cb: ufbx::OpenFileCb::Ref(&open_external),
+        // Re-originated in f64 before node transforms are converted to f32
+        use_root_transform: world_offset != DVec3::ZERO,
+        root_transform: ufbx::Transform {
+            translation: ufbx::Vec3 {
+                x: -world_offset.x,
+                y: -world_offset.y,
+                z: -world_offset.z,
+            },
+            rotation: ufbx::Quat {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                w: 1.0,
+            },
+            scale: ufbx::Vec3 {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
             },
+        },
+        ..axis_load_opts(settings)
+    };
+    let parse_uncached = |world_offset: DVec3| {
+        ufbx::load_memory(bytes, load_opts(false, world_offset)).or_else(|error| match version {
+            Some(version) if is_legacy_version(version) => {
+                ufbx::load_memory(bytes, load_opts(true, world_offset))
+                    .map_err(|error| convert_legacy_load_error(version, error))
+            }
+            Some(version) if !SUPPORTED_FBX_VERSIONS.contains(&version) => {
+                Err(unsupported_version(version))
+            }
+            _ => Err(convert_load_error(error)),
+        })
+    };
+    let parse = |world_offset: DVec3| -> Result<(Arc<ufbx::SceneRoot>, bool), FbxError> {
+        if settings.parse_cache_size == 0 {
+            return Ok((Arc::new(parse_uncached(world_offset)?), false));
+        }
+        let key = FbxParseKey::new(bytes, settings, external_files, world_offset);
+        if let Some(root) = cached_scene(&key) {
+            return Ok((root, true));
+        }
+        let root = Arc::new(parse_uncached(world_offset)?);
+        cache_scene(key, root.clone(), settings.parse_cache_size);
+        Ok((root, false))
+    };
+    let (mut root, mut cache_hit) = parse(DVec3::ZERO)?;
+    // The offset depends on the parsed scene, so re-originating parses twice
+    let world_offset = settings.world_origin.offset(&root);
+    if let Some(world_offset) = world_offset {
+        (root, cache_hit) = parse(world_offset)?;
+    }
+    let scene: &ufbx::Scene = &root;
+    diagnostics.parse_time = stage_start.elapsed();
+    diagnostics.parse_cache_hit = cache_hit;
+    drop(parse_span);
+    log_stage(settings, &source, "parse", stage_start, || {
+        let cached = if cache_hit { ", cached" } else { "" };
+        format!("{} bytes{cached}", bytes.len())
+    });
+
+    // Exporter conventions can only be detected once the file is parsed
+    let preset = preset_settings(settings, scene);
+    let settings = preset.as_ref().map_or(settings, |(_, settings)| settings);
+
+    settings.limits.check_scene(scene)?;
+    diagnostics.count_scene(scene);
+    // Fingerprint everything produced from here on, see `FbxMeta::content_hash`
+    let load_context = &mut ContentHashSink::new(load_context);
+    let mut warnings = FbxWarnings::from_scene(scene);
+    let report = settings.validation.map(|validation| {
+        let report = validate_scene(scene, &validation);
+        load_context.add_labeled_asset(FbxAssetLabel::Report.to_string(), report)
+    });
+
+    // Process materials and textures
+    let stage_start = Instant::now();
+    let material_span = info_span!(
+        "fbx_materials",
+        path = %source,
+        materials = scene.materials.len(),
+        textures = scene.textures.len()
+    )
+    .entered();
+    let mut texture_cache = TextureCache::with_sampler(settings.texture_sampler.clone());
+    let (texture_handles, textures, named_textures) = if !settings.load_materials.is_empty() {
+        process_textures(
+            scene,
+            settings,
+            texture_index,
+            &mut texture_cache,
+            load_context,
+        )?
+    } else {
+        (HashMap::new(), Vec::new(), HashMap::new())
+    };
+    let ProcessedMaterials {
+        materials,
+        named_materials,
+        materials_by_id,
+        material_extras,
+        extras_by_id,
+        extras_handles_by_id,
+    } = if !settings.load_materials.is_empty() {
+        process_materials(scene, settings, &texture_handles, load_context)?
+    } else {
+        ProcessedMaterials::default()
+    };
+    let orm_textures = if !settings.load_materials.is_empty() {
+        let materials = scene.materials.as_ref().iter();
+        materials
+            .filter(|material| material.element.element_id != 0)
+            .map(|material| material_orm_textures(material, &texture_handles))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    diagnostics.material_time = stage_start.elapsed();
+    drop(material_span);
+    log_stage(settings, &source, "materials", stage_start, || {
+        format!("{} materials, {} textures", materials.len(), textures.len())
+    });
+
+    // Process meshes
+    let stage_start = Instant::now();
+    let mesh_span = info_span!(
+        "fbx_meshes",
+        path = %source,
+        meshes = scene.meshes.len(),
+        vertices = diagnostics.vertex_count,
+        triangles = diagnostics.triangle_count
+    )
+    .entered();
+    let (fbx_meshes, meshes, named_meshes) = process_meshes(
+        scene,
+        &materials_by_id,
+        &extras_handles_by_id,
+        settings,
+        load_context,
+    )?;
+    let bounds = scene_bounds(scene, &fbx_meshes);
+    let merged_meshes = if settings.merge_by_material {
+        merge_scene_by_material(scene, &fbx_meshes, settings, load_context)
+    } else {
+        Vec::new()
+    };
+    let (line_curves, line_curves_by_id) = process_line_curves(scene, settings, load_context);
+    diagnostics.mesh_time = stage_start.elapsed();
+    drop(mesh_span);
+    log_stage(settings, &source, "meshes", stage_start, || {
+        format!(
+            "{} meshes, {} vertices, {} triangles",
+            meshes.len(),
+            diagnostics.vertex_count,
+            diagnostics.triangle_count
         )
-        .map_err(|e| FbxError::UfbxError(format!("{:?}", e)))?;
-        let scene: &ufbx::Scene = &*root;
+    });
 
-        // Process meshes
-        let (meshes, named_meshes, mesh_transforms, mesh_material_info) =
-            process_meshes(scene, settings, load_context)?;
+    // Process nodes and hierarchy
+    let stage_start = Instant::now();
+    let node_span = info_span!("fbx_nodes", path = %source, nodes = scene.nodes.len()).entered();
+    let (nodes, named_nodes, node_map) = process_nodes(
+        scene,
+        &meshes,
+        world_offset.unwrap_or_default(),
+        settings,
+        load_context,
+    )?;
+    let named_node_groups = process_node_groups(scene);
+    drop(node_span);
+    log_stage(settings, &source, "nodes", stage_start, || {
+        format!("{} nodes", nodes.len())
+    });
 
-        // Process materials and textures
-        let (materials, named_materials) = if !settings.load_materials.is_empty() {
-            process_materials(scene, settings, load_context)?
-        } else {
-            (Vec::new(), HashMap::new())
-        };
+    // Process skins
+    let stage_start = Instant::now();
+    let skin_span = info_span!(
+        "fbx_skins",
+        path = %source,
+        skins = scene.skin_deformers.len(),
+        bones = diagnostics.bone_count
+    )
+    .entered();
+    let ProcessedSkins {
+        skins,
+        named_skins,
+        primitive_bindposes,
+    } = process_skins(scene, &fbx_meshes, &node_map, settings, load_context)?;
+    drop(skin_span);
+    log_stage(settings, &source, "skins", stage_start, || {
+        format!("{} skins", skins.len())
+    });
+
+    // Process animations
+    let stage_start = Instant::now();
+    let animation_span = info_span!(
+        "fbx_animations",
+        path = %source,
+        stacks = scene.anim_stacks.len()
+    )
+    .entered();
+    let ProcessedAnimations {
+        animations,
+        named_animations,
+        masked_animations,
+        animation_events,
+        root_motion,
+        keyframe_stats,
+        clip_timings,
+    } = if settings.load_animations {
+        process_animations(scene, settings, load_context)?
+    } else {
+        ProcessedAnimations::default()
+    };
+    drop(animation_span);
+    log_stage(settings, &source, "animations", stage_start, || {
+        format!("{} clips", animations.len())
+    });
 
-        // Process nodes and hierarchy
-        let (nodes, named_nodes, node_map) = process_nodes(scene, &meshes, load_context)?;
+    // Detect humanoid rig
+    let humanoid_rig = process_humanoid_rig(scene)
+        .map(|rig| load_context.add_labeled_asset(FbxAssetLabel::HumanoidRig.to_string(), rig));
 
-        // Process skins
-        let (skins, named_skins) = process_skins(scene, &node_map, load_context)?;
+    // Find baked lightmaps
+    let lightmaps = process_lightmaps(
+        scene,
+        settings,
+        texture_index,
+        &mut texture_cache,
+        load_context,
+    );
 
-        // Build scene
-        let scene_handle = build_scene(
+    // Find an environment texture
+    let environment_map = if settings.load_environment_map {
+        process_environment_map(
             scene,
-            &meshes,
-            &materials,
-            &named_materials,
-            &mesh_transforms,
-            &mesh_material_info,
             settings,
+            texture_index,
+            &mut texture_cache,
             load_context,
-        )?;
+        )
+    } else {
+        None
+    };
 
-        // Extract metadata
-        let metadata = FbxMeta::default();
-
-        // Build final FBX asset
-        Ok(Fbx {
-            scenes: vec![scene_handle.clone()],
-            named_scenes: HashMap::new(),
-            meshes,
-            named_meshes,
-            materials,
-            named_materials,
-            nodes,
-            named_nodes,
-            skins,
-            named_skins,
-            default_scene: Some(scene_handle),
-            axis_system: FbxAxisSystem {
-                up: Vec3::Y,
-                front: Vec3::Z,
-                handedness: Handedness::Right,
-            },
-            unit_scale: 1.0,
-            metadata,
-        })
+    // Extract embedded media
+    let media = if settings.load_media {
+        process_media(scene, load_context)
+    } else {
+        Vec::new()
+    };
+
+    // Extract physics colliders
+    let colliders = if settings.extract_colliders {
+        process_colliders(scene)
+    } else {
+        FbxColliders::default()
+    };
+
+    // Build scene
+    let stage_start = Instant::now();
+    let scene_span = info_span!("fbx_scene", path = %source, nodes = scene.nodes.len()).entered();
+    let (scene_handle, node_scenes) = build_scene(
+        scene,
+        &fbx_meshes,
+        &materials,
+        &materials_by_id,
+        &extras_by_id,
+        &lightmaps,
+        &line_curves_by_id,
+        &primitive_bindposes,
+        &colliders,
+        &animations,
+        settings,
+        extensions,
+        &mut warnings,
+        load_context,
+    );
+    diagnostics.scene_time = stage_start.elapsed();
+    drop(scene_span);
+    log_stage(settings, &source, "scene", stage_start, || {
+        format!("{} node scenes", node_scenes.len())
+    });
+
+    let colliders = load_context.add_labeled_asset(FbxAssetLabel::Colliders.to_string(), colliders);
+
+    // ufbx converts everything to Bevy's axes; report what the file used
+    let axis_system = original_axis_system(scene);
+    let converted_axis_system = (axis_system != FbxAxisSystem::BEVY).then_some(FbxAxisSystem::BEVY);
+
+    // Extract metadata
+    let active_camera = default_camera_name(scene);
+    if settings.load_animations {
+        load_context.hasher().write_animation(scene);
     }
+    let metadata = FbxMeta {
+        content_hash: load_context.finish(),
+        background_color: active_camera
+            .as_deref()
+            .and_then(|name| camera_background_color(scene, name)),
+        active_camera,
+        ambient_color: ambient_color(scene),
+        frame_rate: Some(scene.settings.frames_per_second as f32),
+        time_mode: Some(format!("{:?}", scene.settings.time_mode)),
+        version: Some(scene.metadata.version),
+        creator: Some(scene.metadata.creator.to_string()).filter(|name| !name.is_empty()),
+        original_application: Some(scene.metadata.original_application.name.to_string())
+            .filter(|name| !name.is_empty()),
+        import_preset: preset.map(|(preset, _)| preset),
+        world_offset,
+        format: Some(if scene.metadata.ascii {
+            FbxFileFormat::Ascii
+        } else {
+            FbxFileFormat::Binary
+        }),
+        ..Default::default()
+    };
 
-    fn extensions(&self) -> &[&str] {
-        &["fbx"]
+    // Build final FBX asset
+    let mut scenes = vec![scene_handle.clone()];
+    let mut named_scenes = HashMap::new();
+    for (path, handle) in node_scenes {
+        scenes.push(handle.clone());
+        named_scenes.insert(path.into_boxed_str(), handle);
+    }
+
+    Ok(Fbx {
+        scenes,
+        named_scenes,
+        meshes,
+        bounds,
+        named_meshes,
+        line_curves,
+        materials,
+        named_materials,
+        material_extras,
+        textures,
+        named_textures,
+        media,
+        nodes,
+        named_nodes,
+        named_node_groups,
+        skins,
+        named_skins,
+        colliders,
+        animations,
+        named_animations,
+        masked_animations,
+        animation_events,
+        root_motion,
+        keyframe_stats,
+        clip_timings,
+        humanoid_rig,
+        default_scene: Some(scene_handle),
+        axis_system,
+        converted_axis_system,
+        unit_scale: 1.0,
+        metadata,
+        diagnostics,
+        warnings,
+        report,
+        environment_map,
+        merged_meshes,
+        material_variants: scene_material_variants(scene),
+        mipmap_images: if settings.generate_mipmaps {
+            texture_cache.images()
+        } else {
+            Vec::new()
+        },
+        deferred_textures: texture_cache.take_deferred(),
+        orm_textures,
+    })
+}
+
+/// Log how long a load stage of `source` took, with `details` such as
+/// element counts, if [`FbxLoaderSettings::verbose_logging`] is set.
+fn log_stage(
+    settings: &FbxLoaderSettings,
+    source: &str,
+    stage: &str,
+    stage_start: Instant,
+    details: impl FnOnce() -> String,
+) {
+    if settings.verbose_logging {
+        info!(
+            "{source}: {stage} took {:?} ({})",
+            stage_start.elapsed(),
+            details()
+        );
     }
 }
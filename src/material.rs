@@ -1,41 +1,259 @@
 //! Material and texture processing for FBX files.
 
 use crate::error::FbxError;
-use crate::label::FbxAssetLabel;
+use crate::hash::FbxContentHasher;
+use crate::label::{FbxAssetLabel, label_names};
 use crate::loader::FbxLoaderSettings;
-use crate::utils::convert_texture_uv_transform;
-use bevy::asset::{Handle, LoadContext};
-use bevy::pbr::StandardMaterial;
+use crate::mesh::secondary_uv_set;
+use crate::shader::{FbxShaderModel, ShaderPropertyMap, ShaderTextureSlot};
+use crate::sink::FbxAssetSink;
+use crate::texture::{
+    FbxTextureLoading, MissingTexturePolicy, TextureCache, TextureIndex, remap_texture_path,
+    texture_candidates, texture_color_space,
+};
+use crate::types::{FbxMaterialExtras, FbxPropertyValue, FbxTexture};
+use crate::utils::{convert_texture_uv_transform, glob_match};
+use bevy::asset::Handle;
+use bevy::pbr::{StandardMaterial, UvChannel};
 use bevy::prelude::*;
 use bevy::render::alpha::AlphaMode;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 
-/// Process all materials from the FBX scene.
+/// FBX material properties consumed by [`create_standard_material`].
+///
+/// `SpecularColor` is only consumed for materials other than Lambert and
+/// Phong.
+pub const MAPPED_MATERIAL_PROPERTIES: &[&str] = &[
+    "DiffuseColor",
+    "BaseColor",
+    "EmissiveColor",
+    "NormalMap",
+    "Metallic",
+    "Roughness",
+    "AmbientOcclusion",
+    "Opacity",
+    "EmissiveFactor",
+    "SpecularColor",
+    "SpecularFactor",
+    "ReflectionColor",
+    "ReflectionFactor",
+    "Bump",
+    "BumpFactor",
+    "DisplacementColor",
+    "DisplacementFactor",
+];
+
+/// Bevy's default [`StandardMaterial::parallax_depth_scale`], used for an FBX
+/// bump or displacement factor of 1.
+pub const DEFAULT_PARALLAX_DEPTH_SCALE: f32 = 0.1;
+
+/// Material assigned to primitives without a material binding, configured
+/// through [`FbxLoaderSettings::default_material`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxDefaultMaterial {
+    /// Base color as sRGBA
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub perceptual_roughness: f32,
+    /// Whether the material ignores lighting
+    pub unlit: bool,
+    /// Whether back faces are rendered too
+    pub double_sided: bool,
+}
+
+impl Default for FbxDefaultMaterial {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 0.0,
+            perceptual_roughness: 0.5,
+            unlit: false,
+            double_sided: false,
+        }
+    }
+}
+
+impl FbxDefaultMaterial {
+    /// Build the [`StandardMaterial`] described by these settings.
+    pub fn to_standard_material(&self) -> StandardMaterial {
+        let [r, g, b, a] = self.base_color;
+        StandardMaterial {
+            base_color: Color::srgba(r, g, b, a),
+            alpha_mode: if a < 1.0 {
+                AlphaMode::Blend
+            } else {
+                AlphaMode::Opaque
+            },
+            metallic: self.metallic,
+            perceptual_roughness: self.perceptual_roughness,
+            unlit: self.unlit,
+            double_sided: self.double_sided,
+            cull_mode: if self.double_sided {
+                None
+            } else {
+                StandardMaterial::default().cull_mode
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Serializable [`AlphaMode`] forced by an [`FbxMaterialOverride`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FbxAlphaMode {
+    Opaque,
+    /// Alpha testing with the given cutoff
+    Mask(f32),
+    Blend,
+    Premultiplied,
+}
+
+impl From<FbxAlphaMode> for AlphaMode {
+    fn from(mode: FbxAlphaMode) -> Self {
+        match mode {
+            FbxAlphaMode::Opaque => AlphaMode::Opaque,
+            FbxAlphaMode::Mask(cutoff) => AlphaMode::Mask(cutoff),
+            FbxAlphaMode::Blend => AlphaMode::Blend,
+            FbxAlphaMode::Premultiplied => AlphaMode::Premultiplied,
+        }
+    }
+}
+
+/// Material parameters forced for materials matching a name pattern in
+/// [`FbxLoaderSettings::material_overrides`], replacing the imported values.
+///
+/// `None` fields keep the imported value.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxMaterialOverride {
+    /// Alpha mode instead of the one guessed from the FBX opacity
+    pub alpha_mode: Option<FbxAlphaMode>,
+    /// Whether back faces are rendered too
+    pub double_sided: Option<bool>,
+    /// Depth bias, e.g. to sort decals in front of the surface they lie on
+    pub depth_bias: Option<f32>,
+}
+
+impl FbxMaterialOverride {
+    /// Overwrite the parameters set in this override on `material`.
+    pub fn apply(&self, material: &mut StandardMaterial) {
+        if let Some(alpha_mode) = self.alpha_mode {
+            material.alpha_mode = alpha_mode.into();
+        }
+        if let Some(double_sided) = self.double_sided {
+            material.double_sided = double_sided;
+            material.cull_mode = if double_sided {
+                None
+            } else {
+                StandardMaterial::default().cull_mode
+            };
+        }
+        if let Some(depth_bias) = self.depth_bias {
+            material.depth_bias = depth_bias;
+        }
+    }
+}
+
+/// Override for the material named `name`.
+///
+/// The first rule of [`FbxLoaderSettings::material_overrides`] matching the
+/// name applies.
+pub fn material_override<'a>(
+    name: &str,
+    settings: &'a FbxLoaderSettings,
+) -> Option<&'a FbxMaterialOverride> {
+    settings
+        .material_overrides
+        .iter()
+        .find(|(pattern, _)| glob_match(pattern, name))
+        .map(|(_, material_override)| material_override)
+}
+
+/// [`FbxMaterialExtras`] key of the sheen weight read by ufbx.
+pub const SHEEN_WEIGHT_PROPERTY: &str = "pbr|sheen_weight";
+/// [`FbxMaterialExtras`] key of the sheen color read by ufbx.
+pub const SHEEN_COLOR_PROPERTY: &str = "pbr|sheen_color";
+/// [`FbxMaterialExtras`] key of the sheen roughness read by ufbx.
+pub const SHEEN_ROUGHNESS_PROPERTY: &str = "pbr|sheen_roughness";
+
+/// Materials of a file, from [`process_materials`].
+#[derive(Default)]
+pub struct ProcessedMaterials {
+    pub materials: Vec<Handle<StandardMaterial>>,
+    pub named_materials: HashMap<Box<str>, Handle<StandardMaterial>>,
+    /// Material handles keyed by ufbx element id
+    pub materials_by_id: HashMap<u32, Handle<StandardMaterial>>,
+    /// Extras handles, indexed like [`ProcessedMaterials::materials`]
+    pub material_extras: Vec<Handle<FbxMaterialExtras>>,
+    /// Extras keyed by ufbx element id
+    pub extras_by_id: HashMap<u32, FbxMaterialExtras>,
+    /// Extras handles keyed by ufbx element id
+    pub extras_handles_by_id: HashMap<u32, Handle<FbxMaterialExtras>>,
+}
+
+/// Process all materials from the FBX scene, using the images of
+/// [`process_textures`].
+///
+/// With [`FbxLoaderSettings::merge_identical_materials`], materials matching
+/// an earlier one reuse its handle, and their labels are registered with
+/// [`FbxAssetSink::add_labeled_alias`].
 pub fn process_materials(
     scene: &ufbx::Scene,
-    _settings: &FbxLoaderSettings,
-    load_context: &mut LoadContext,
-) -> Result<
-    (
-        Vec<Handle<StandardMaterial>>,
-        HashMap<Box<str>, Handle<StandardMaterial>>,
-    ),
-    FbxError,
-> {
+    settings: &FbxLoaderSettings,
+    texture_handles: &HashMap<u32, Handle<bevy::prelude::Image>>,
+    load_context: &mut impl FbxAssetSink,
+) -> Result<ProcessedMaterials, FbxError> {
     let mut materials = Vec::new();
     let mut named_materials = HashMap::new();
-    let texture_handles = process_textures(scene, load_context)?;
+    let mut materials_by_id = HashMap::new();
+    let mut material_extras = Vec::new();
+    let mut extras_by_id = HashMap::new();
+    let mut extras_handles_by_id = HashMap::new();
+    let mut unique_materials: HashMap<u64, Handle<StandardMaterial>> = HashMap::new();
+    let uv1_sets = uv1_sets(scene, settings);
 
+    let names = label_names(
+        settings,
+        scene
+            .materials
+            .as_ref()
+            .iter()
+            .map(|material| material.element.name.as_ref() as &str),
+    );
     for (index, ufbx_material) in scene.materials.as_ref().iter().enumerate() {
         if ufbx_material.element.element_id == 0 {
             continue;
         }
+        let label_name = names.get(index).and_then(Option::as_deref);
 
-        let standard_material = create_standard_material(ufbx_material, &texture_handles)?;
-        let handle = load_context.add_labeled_asset(
-            FbxAssetLabel::Material(index).to_string(),
-            standard_material,
-        );
+        let mut standard_material =
+            create_standard_material(ufbx_material, texture_handles, settings)?;
+        if let Some(sets) = uv1_sets.get(&ufbx_material.element.element_id) {
+            apply_uv_channels(&mut standard_material, ufbx_material, texture_handles, sets);
+        }
+        let key = settings
+            .merge_identical_materials
+            .then(|| material_key(&standard_material));
+        let label = FbxAssetLabel::Material(index);
+        let handle = match key.and_then(|key| unique_materials.get(&key)) {
+            // Merged materials keep their labels, resolving to the shared material
+            Some(handle) => {
+                load_context.add_labeled_alias(label, label_name, handle, standard_material)
+            }
+            None => {
+                let handle =
+                    load_context.add_named_labeled_asset(label, label_name, standard_material);
+                if let Some(key) = key {
+                    unique_materials.insert(key, handle.clone());
+                }
+                handle
+            }
+        };
 
         if !ufbx_material.element.name.is_empty() {
             named_materials.insert(
@@ -44,75 +262,331 @@ pub fn process_materials(
             );
         }
 
+        let extras = extract_material_extras(ufbx_material);
+        let extras_handle = load_context.add_named_labeled_asset(
+            FbxAssetLabel::MaterialExtras(index),
+            label_name,
+            extras.clone(),
+        );
+        extras_handles_by_id.insert(ufbx_material.element.element_id, extras_handle.clone());
+        material_extras.push(extras_handle);
+        if !extras.is_empty() {
+            extras_by_id.insert(ufbx_material.element.element_id, extras);
+        }
+
+        materials_by_id.insert(ufbx_material.element.element_id, handle.clone());
         materials.push(handle);
     }
 
-    Ok((materials, named_materials))
+    Ok(ProcessedMaterials {
+        materials,
+        named_materials,
+        materials_by_id,
+        material_extras,
+        extras_by_id,
+        extras_handles_by_id,
+    })
+}
+
+/// Key identifying materials with identical parameters and textures.
+///
+/// Hashes the parameters [`create_standard_material`] and material overrides
+/// set, the UV channel of each texture and the asset ids of the textures.
+pub fn material_key(material: &StandardMaterial) -> u64 {
+    let mut hasher = FbxContentHasher::default();
+    hasher.write_material(material);
+    hasher.write_floats(&material.specular_tint.to_linear().to_f32_array());
+    hasher.write_floats(&[material.anisotropy_strength, material.anisotropy_rotation]);
+    hasher.write_floats(&material.uv_transform.to_cols_array());
+    hasher.write_str(&format!("{:?}", material.cull_mode));
+    let textures = [
+        (&material.base_color_texture, &material.base_color_channel),
+        (&material.emissive_texture, &material.emissive_channel),
+        (
+            &material.metallic_roughness_texture,
+            &material.metallic_roughness_channel,
+        ),
+        (&material.normal_map_texture, &material.normal_map_channel),
+        (&material.occlusion_texture, &material.occlusion_channel),
+        #[cfg(feature = "pbr_anisotropy_texture")]
+        (&material.anisotropy_texture, &material.anisotropy_channel),
+    ];
+    for (texture, channel) in textures {
+        texture.as_ref().map(Handle::id).hash(&mut hasher);
+        hasher.write_u8(matches!(channel, UvChannel::Uv1) as u8);
+    }
+    material
+        .depth_map
+        .as_ref()
+        .map(Handle::id)
+        .hash(&mut hasher);
+    hasher.finish()
 }
 
 /// Process textures from materials.
+///
+/// Texture paths are resolved against `texture_index`; elements resolving to
+/// the same path share one handle through `texture_cache`. Textures that cannot
+/// be found are handled according to
+/// [`FbxLoaderSettings::missing_texture`].
+///
+/// Layered textures are flattened to their top layer, see [`flatten_layered_texture`].
+///
+/// Every texture is also added as an [`FbxTexture`] sub-asset; returns
+/// the image handles keyed by element id and the texture handles both in
+/// order and by name.
+#[allow(clippy::type_complexity)]
 pub fn process_textures(
     scene: &ufbx::Scene,
-    load_context: &mut LoadContext,
-) -> Result<HashMap<u32, Handle<bevy::prelude::Image>>, FbxError> {
+    settings: &FbxLoaderSettings,
+    texture_index: &TextureIndex,
+    texture_cache: &mut TextureCache,
+    load_context: &mut impl FbxAssetSink,
+) -> Result<
+    (
+        HashMap<u32, Handle<bevy::prelude::Image>>,
+        Vec<Handle<FbxTexture>>,
+        HashMap<Box<str>, Handle<FbxTexture>>,
+    ),
+    FbxError,
+> {
     let mut texture_handles = HashMap::new();
+    let mut textures = Vec::new();
+    let mut named_textures = HashMap::new();
+    let base_dir = load_context.base_dir();
+    let media_dir = load_context.media_dir();
+    let slots = texture_slots(scene);
+    let names = label_names(
+        settings,
+        scene
+            .textures
+            .as_ref()
+            .iter()
+            .map(|texture| texture.element.name.as_ref() as &str),
+    );
+
+    for (index, layered) in scene.textures.as_ref().iter().enumerate() {
+        let texture = flatten_layered_texture(layered);
+        let slot_names = slots
+            .get(&layered.element.element_id)
+            .map_or(&[][..], Vec::as_slice);
 
-    for texture in scene.textures.as_ref().iter() {
-        if !texture.filename.is_empty() {
-            let texture_path = if !texture.absolute_filename.is_empty() {
-                texture.absolute_filename.to_string()
+        // Procedural textures have no file and get no image
+        let has_file = !texture.filename.is_empty() || !texture.relative_filename.is_empty();
+        let texture_path = has_file
+            .then(|| {
+                resolve_texture_path(
+                    texture.relative_filename.as_ref(),
+                    texture.filename.as_ref(),
+                    settings,
+                    texture_index,
+                    &base_dir,
+                    media_dir.as_deref(),
+                )
+            })
+            .flatten();
+        let image = match &texture_path {
+            Some(texture_path) => {
+                let color_space = texture_color_space(
+                    &settings.texture_color_spaces,
+                    slot_names,
+                    &texture_path.to_string_lossy(),
+                );
+                Some(match settings.texture_loading {
+                    FbxTextureLoading::Deferred if !settings.eager_dependencies => {
+                        texture_cache.defer(load_context.asset_path(texture_path), color_space)
+                    }
+                    _ => {
+                        texture_cache.load_with_color_space(texture_path, color_space, load_context)
+                    }
+                })
+            }
+            None if !has_file => None,
+            None => match settings.missing_texture {
+                MissingTexturePolicy::Error => {
+                    return Err(FbxError::TextureLoad(format!(
+                        "texture '{}' not found",
+                        texture.filename.as_ref() as &str
+                    )));
+                }
+                MissingTexturePolicy::WarnAndSkip => None,
+                MissingTexturePolicy::Placeholder => Some(texture_cache.placeholder(load_context)),
+            },
+        };
+        if let Some(image) = &image {
+            texture_handles.insert(layered.element.element_id, image.clone());
+        }
+
+        let fbx_texture = FbxTexture {
+            name: layered.element.name.to_string(),
+            filename: if texture.relative_filename.is_empty() {
+                texture.filename.to_string()
             } else {
-                let fbx_dir_buf = match load_context.path().parent() {
-                    Some(parent) => parent.path().to_path_buf(),
-                    None => std::path::PathBuf::from(""),
-                };
-                fbx_dir_buf
-                    .join(texture.filename.as_ref())
-                    .to_string_lossy()
-                    .to_string()
-            };
+                texture.relative_filename.to_string()
+            },
+            absolute_filename: texture.absolute_filename.to_string(),
+            path: texture_path.map(|path| load_context.asset_path(&path).to_string()),
+            image,
+            slots: slot_names.iter().map(|slot| slot.to_string()).collect(),
+            uv_set: texture.uv_set.to_string(),
+            uv_transform: convert_texture_uv_transform(texture),
+            wrap_u: texture.wrap_u.into(),
+            wrap_v: texture.wrap_v.into(),
+        };
+        let label_name = names.get(index).and_then(Option::as_deref);
+        let handle = load_context.add_named_labeled_asset(
+            FbxAssetLabel::Texture(index),
+            label_name,
+            fbx_texture,
+        );
+        if !layered.element.name.is_empty() {
+            named_textures.insert(Box::from(layered.element.name.as_ref()), handle.clone());
+        }
+        textures.push(handle);
+    }
 
-            let image_handle = load_context.load(texture_path);
-            texture_handles.insert(texture.element.element_id, image_handle);
+    Ok((texture_handles, textures, named_textures))
+}
+
+/// Material properties each texture is connected to, keyed by texture
+/// element id.
+pub fn texture_slots(scene: &ufbx::Scene) -> HashMap<u32, Vec<&str>> {
+    let mut slots: HashMap<u32, Vec<&str>> = HashMap::new();
+    for material in scene.materials.as_ref().iter() {
+        for material_texture in material.textures.as_ref().iter() {
+            slots
+                .entry(material_texture.texture.element.element_id)
+                .or_default()
+                .push(material_texture.material_prop.as_ref() as &str);
         }
     }
+    slots
+}
 
-    Ok(texture_handles)
+/// File texture used in place of a layered texture.
+///
+/// Bevy materials have a single texture per slot, so a layered texture is
+/// replaced by its top layer and a warning is logged when other layers are
+/// dropped. Non-layered textures are returned unchanged.
+pub fn flatten_layered_texture(texture: &ufbx::Texture) -> &ufbx::Texture {
+    if texture.type_ != ufbx::TextureType::Layered {
+        return texture;
+    }
+    // Layers are ordered bottom to top
+    let Some(top) = texture.layers.as_ref().last() else {
+        return texture;
+    };
+    if texture.layers.len() > 1 {
+        warn!(
+            "Layered FBX texture '{}' has {} layers; using only the top layer '{}'",
+            texture.element.name.as_ref() as &str,
+            texture.layers.len(),
+            top.texture.element.name.as_ref() as &str
+        );
+    }
+    flatten_layered_texture(&top.texture)
+}
+
+/// Resolve a texture path stored in the FBX file to an asset path.
+///
+/// Candidates from [`texture_candidates`] are rewritten with
+/// [`remap_texture_path`] and looked up in `texture_index`. The `.fbm`
+/// `media_dir` is searched before [`FbxLoaderSettings::texture_search_dirs`].
+/// Unresolved paths are reported as warnings.
+pub fn resolve_texture_path(
+    relative_filename: &str,
+    filename: &str,
+    settings: &FbxLoaderSettings,
+    texture_index: &TextureIndex,
+    base_dir: &Path,
+    media_dir: Option<&Path>,
+) -> Option<PathBuf> {
+    let search_dirs: Vec<PathBuf> = media_dir
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(settings.texture_search_dirs.iter().map(PathBuf::from))
+        .collect();
+    let candidates: Vec<PathBuf> =
+        texture_candidates(relative_filename, filename, base_dir, &search_dirs)
+            .iter()
+            .map(|candidate| remap_texture_path(candidate, settings))
+            .collect();
+
+    let texture_path = texture_index.resolve(&candidates);
+    if texture_path.is_none() {
+        warn!(
+            "Unresolved FBX texture '{}' (tried {:?})",
+            filename, candidates
+        );
+    }
+    texture_path
 }
 
 /// Create a StandardMaterial from ufbx material.
+///
+/// Only properties the file defines are mapped, so absent ones keep Bevy's
+/// defaults. The FBX ambient color has no [`StandardMaterial`] counterpart
+/// and is left in [`FbxMaterialExtras`]; the bump factor scales the depth map
+/// in [`apply_bump`]. The specular color of Lambert and Phong materials tints
+/// their highlights rather than the reflected light, so it is kept out of
+/// [`StandardMaterial::specular_tint`].
+///
+/// Stingray PBS and Arnold `aiStandardSurface` materials are detected with
+/// [`FbxShaderModel::detect`] and mapped through their property tables.
 pub fn create_standard_material(
     ufbx_material: &ufbx::Material,
     texture_handles: &HashMap<u32, Handle<bevy::prelude::Image>>,
+    settings: &FbxLoaderSettings,
 ) -> Result<StandardMaterial, FbxError> {
     let mut material = StandardMaterial::default();
 
+    let fbx = &ufbx_material.fbx;
+    let pbr = &ufbx_material.pbr;
+
     // Base color
-    if let Ok(diffuse) = std::panic::catch_unwind(|| ufbx_material.fbx.diffuse_color.value_vec4) {
-        material.base_color = Color::srgb(diffuse.x as f32, diffuse.y as f32, diffuse.z as f32);
-    } else if let Ok(pbr_base) =
-        std::panic::catch_unwind(|| ufbx_material.pbr.base_color.value_vec4)
+    if let Some([r, g, b]) =
+        map_color(&fbx.diffuse_color, None).or_else(|| map_color(&pbr.base_color, None))
     {
-        material.base_color = Color::srgb(pbr_base.x as f32, pbr_base.y as f32, pbr_base.z as f32);
+        material.base_color = Color::srgb(r, g, b);
     }
 
     // Metallic and roughness
-    if let Ok(metallic) = std::panic::catch_unwind(|| ufbx_material.pbr.metalness.value_vec4) {
-        material.metallic = metallic.x as f32;
+    if let Some(metallic) = map_scalar(&pbr.metalness) {
+        material.metallic = metallic;
     }
-    if let Ok(roughness) = std::panic::catch_unwind(|| ufbx_material.pbr.roughness.value_vec4) {
-        material.perceptual_roughness = roughness.x as f32;
+    if let Some(roughness) = map_scalar(&pbr.roughness) {
+        material.perceptual_roughness = roughness;
     }
 
     // Emission
-    if let Ok(emission) = std::panic::catch_unwind(|| ufbx_material.fbx.emission_color.value_vec4) {
-        material.emissive =
-            LinearRgba::rgb(emission.x as f32, emission.y as f32, emission.z as f32);
+    if let Some([r, g, b]) = map_color(&fbx.emission_color, Some(&fbx.emission_factor)) {
+        material.emissive = LinearRgba::rgb(r, g, b);
+    }
+
+    // Specular; ufbx fills the PBR color from the classic one
+    if !is_classic_material(ufbx_material)
+        && let Some([r, g, b]) = map_color(&pbr.specular_color, None)
+    {
+        material.specular_tint = Color::srgb(r, g, b);
+    }
+    if let Some(ior) = map_scalar(&pbr.specular_ior) {
+        material.ior = ior;
+    }
+    if pbr.specular_ior.has_value || pbr.specular_factor.has_value {
+        let weight = map_scalar(&pbr.specular_factor).unwrap_or(1.0);
+        material.reflectance = reflectance(material.ior, weight);
+    }
+
+    // Classic mirror reflection, as the strength of dielectric reflections
+    if let Some(color) = map_color(&fbx.reflection_color, Some(&fbx.reflection_factor)) {
+        let strength = color.into_iter().fold(0.0, f32::max);
+        material.reflectance = material.reflectance.max(reflectance_from_f0(strength));
     }
 
     // Alpha
-    if ufbx_material.pbr.opacity.value_vec4.x < 1.0 {
-        let alpha = ufbx_material.pbr.opacity.value_vec4.x as f32;
+    if let Some(alpha) = map_scalar(&pbr.opacity)
+        && alpha < 1.0
+    {
         material.alpha_mode = if alpha < 0.98 {
             AlphaMode::Blend
         } else {
@@ -140,5 +614,439 @@ pub fn create_standard_material(
         }
     }
 
+    // Anisotropy
+    apply_anisotropy(&mut material, ufbx_material, texture_handles);
+
+    // Bump and displacement
+    apply_bump(
+        &mut material,
+        ufbx_material,
+        texture_handles,
+        settings.bump_as_normal_map,
+    );
+
+    if let Some(property_map) = FbxShaderModel::detect(ufbx_material).property_map() {
+        apply_shader_properties(&mut material, ufbx_material, property_map, texture_handles);
+    }
+
+    if let Some(material_override) = material_override(&ufbx_material.element.name, settings) {
+        material_override.apply(&mut material);
+    }
+
     Ok(material)
 }
+
+/// Materials bound to `mesh`, including per-instance bindings.
+pub fn mesh_materials(mesh: &ufbx::Mesh) -> impl Iterator<Item = &ufbx::Material> {
+    let instance_materials = mesh
+        .instances
+        .as_ref()
+        .iter()
+        .flat_map(|node| node.materials.as_ref().iter());
+    mesh.materials
+        .as_ref()
+        .iter()
+        .chain(instance_materials)
+        .map(|material| &**material)
+}
+
+/// Name of the UV set `texture` samples; empty for the mesh's default set.
+///
+/// Layered textures use the set of their top layer, like
+/// [`flatten_layered_texture`].
+pub fn texture_uv_set(texture: &ufbx::Texture) -> &str {
+    match texture.layers.as_ref().last() {
+        Some(top) if texture.type_ == ufbx::TextureType::Layered => texture_uv_set(&top.texture),
+        _ => texture.uv_set.as_ref(),
+    }
+}
+
+/// Names of the UV sets sampled through [`UvChannel::Uv1`] by each
+/// material, keyed by material element id.
+///
+/// These are the sets every mesh using the material imports as
+/// [`Mesh::ATTRIBUTE_UV_1`], see [`secondary_uv_set`], and none imports as
+/// [`Mesh::ATTRIBUTE_UV_0`], so the channel never points at missing UVs.
+pub fn uv1_sets<'a>(
+    scene: &'a ufbx::Scene,
+    settings: &FbxLoaderSettings,
+) -> HashMap<u32, HashSet<&'a str>> {
+    let mut primary: HashMap<u32, HashSet<&str>> = HashMap::new();
+    let mut secondary: HashMap<u32, HashSet<&str>> = HashMap::new();
+    for mesh in scene.meshes.as_ref() {
+        let uv0 = mesh
+            .uv_sets
+            .as_ref()
+            .first()
+            .map(|uv_set| uv_set.name.as_ref() as &str);
+        let uv1 = secondary_uv_set(mesh, settings).map(|uv_set| uv_set.name.as_ref() as &str);
+        for material in mesh_materials(mesh) {
+            let id = material.element.element_id;
+            primary.entry(id).or_default().extend(uv0);
+            match secondary.entry(id) {
+                Entry::Vacant(entry) => {
+                    entry.insert(uv1.into_iter().collect());
+                }
+                Entry::Occupied(mut entry) => entry.get_mut().retain(|&name| Some(name) == uv1),
+            }
+        }
+    }
+    for (id, sets) in &mut secondary {
+        if let Some(primary) = primary.get(id) {
+            sets.retain(|name| !primary.contains(name));
+        }
+    }
+    secondary
+}
+
+/// Sample the textures of `material` bound to one of the `uv1_sets` (see
+/// [`uv1_sets`]) from [`UvChannel::Uv1`].
+///
+/// Textures are matched to material fields by image, so an image the
+/// material also samples on another UV set keeps UV0. Depth maps always
+/// sample UV0.
+pub fn apply_uv_channels(
+    material: &mut StandardMaterial,
+    ufbx_material: &ufbx::Material,
+    texture_handles: &HashMap<u32, Handle<bevy::prelude::Image>>,
+    uv1_sets: &HashSet<&str>,
+) {
+    let (uv1_images, uv0_images): (Vec<_>, Vec<_>) = ufbx_material
+        .textures
+        .as_ref()
+        .iter()
+        .filter_map(|texture_ref| {
+            let image = texture_handles.get(&texture_ref.texture.element.element_id)?;
+            Some((
+                uv1_sets.contains(texture_uv_set(&texture_ref.texture)),
+                image,
+            ))
+        })
+        .partition(|(uv1, _)| *uv1);
+    for (_, image) in uv1_images {
+        if uv0_images.iter().any(|(_, uv0_image)| *uv0_image == image) {
+            continue;
+        }
+        let samples =
+            |texture: &Option<Handle<bevy::prelude::Image>>| texture.as_ref() == Some(image);
+        if samples(&material.base_color_texture) {
+            material.base_color_channel = UvChannel::Uv1;
+        }
+        if samples(&material.normal_map_texture) {
+            material.normal_map_channel = UvChannel::Uv1;
+        }
+        if samples(&material.metallic_roughness_texture) {
+            material.metallic_roughness_channel = UvChannel::Uv1;
+        }
+        if samples(&material.emissive_texture) {
+            material.emissive_channel = UvChannel::Uv1;
+        }
+        if samples(&material.occlusion_texture) {
+            material.occlusion_channel = UvChannel::Uv1;
+        }
+        #[cfg(feature = "pbr_anisotropy_texture")]
+        if samples(&material.anisotropy_texture) {
+            material.anisotropy_channel = UvChannel::Uv1;
+        }
+    }
+}
+
+/// Whether `ufbx_material` is a classic FBX Lambert or Phong material.
+fn is_classic_material(ufbx_material: &ufbx::Material) -> bool {
+    matches!(
+        ufbx_material.shader_type,
+        ufbx::ShaderType::FbxLambert | ufbx::ShaderType::FbxPhong
+    )
+}
+
+/// Value of `map` if the file defines it.
+fn map_scalar(map: &ufbx::MaterialMap) -> Option<f32> {
+    map.has_value.then_some(map.value_vec4.x as f32)
+}
+
+/// Color of `map` scaled by `factor` if the file defines it; an undefined
+/// factor counts as 1.
+fn map_color(map: &ufbx::MaterialMap, factor: Option<&ufbx::MaterialMap>) -> Option<[f32; 3]> {
+    if !map.has_value {
+        return None;
+    }
+    let factor = factor.and_then(map_scalar).unwrap_or(1.0);
+    let v = map.value_vec4;
+    Some([v.x as f32, v.y as f32, v.z as f32].map(|c| c * factor))
+}
+
+/// [`StandardMaterial::reflectance`] for a dielectric with index of
+/// refraction `ior` and specular weight `weight`.
+///
+/// An IOR of 1.5 at full weight gives Bevy's default of 0.5.
+pub fn reflectance(ior: f32, weight: f32) -> f32 {
+    let f0 = ((ior - 1.0) / (ior + 1.0)).powi(2);
+    reflectance_from_f0(f0 * weight.max(0.0))
+}
+
+/// [`StandardMaterial::reflectance`] for a reflectance at normal incidence
+/// of `f0`, clamped to Bevy's maximum of 16%.
+pub fn reflectance_from_f0(f0: f32) -> f32 {
+    (f0.max(0.0) / 0.16).sqrt().min(1.0)
+}
+
+/// Map ufbx's specular anisotropy onto the material's anisotropy fields.
+///
+/// The anisotropy texture is only assigned with the `pbr_anisotropy_texture`
+/// feature.
+pub fn apply_anisotropy(
+    material: &mut StandardMaterial,
+    ufbx_material: &ufbx::Material,
+    texture_handles: &HashMap<u32, Handle<bevy::prelude::Image>>,
+) {
+    let anisotropy = &ufbx_material.pbr.specular_anisotropy;
+    if !anisotropy.has_value || anisotropy.value_vec4.x <= 0.0 {
+        return;
+    }
+
+    material.anisotropy_strength = anisotropy.value_vec4.x as f32;
+    let rotation = &ufbx_material.pbr.specular_rotation;
+    if rotation.has_value {
+        material.anisotropy_rotation = anisotropy_rotation(rotation.value_vec4.x as f32);
+    }
+
+    #[cfg(feature = "pbr_anisotropy_texture")]
+    if let Some(texture) = anisotropy.texture.as_ref() {
+        material.anisotropy_texture = texture_handles.get(&texture.element.element_id).cloned();
+    }
+    #[cfg(not(feature = "pbr_anisotropy_texture"))]
+    let _ = texture_handles;
+}
+
+/// Map the FBX displacement or bump texture onto the material's depth map.
+///
+/// A displacement texture takes precedence over a bump texture. With
+/// `bump_as_normal_map`, the bump texture is used as the normal map instead,
+/// for exporters that write tangent-space normal maps into the bump slot.
+pub fn apply_bump(
+    material: &mut StandardMaterial,
+    ufbx_material: &ufbx::Material,
+    texture_handles: &HashMap<u32, Handle<bevy::prelude::Image>>,
+    bump_as_normal_map: bool,
+) {
+    let handle = |map: &ufbx::MaterialMap| {
+        map.texture
+            .as_ref()
+            .and_then(|texture| texture_handles.get(&texture.element.element_id))
+            .cloned()
+    };
+    let fbx = &ufbx_material.fbx;
+    let bump = handle(&fbx.bump);
+
+    if bump_as_normal_map && material.normal_map_texture.is_none() {
+        material.normal_map_texture = bump.clone();
+    }
+
+    let depth_map = match handle(&fbx.displacement) {
+        Some(texture) => Some((texture, &fbx.displacement_factor)),
+        None if !bump_as_normal_map => bump.map(|texture| (texture, &fbx.bump_factor)),
+        None => None,
+    };
+    if let Some((texture, factor)) = depth_map {
+        material.depth_map = Some(texture);
+        let factor = if factor.has_value {
+            factor.value_vec4.x as f32
+        } else {
+            1.0
+        };
+        material.parallax_depth_scale = parallax_depth_scale(factor);
+    }
+}
+
+/// Parallax depth scale for an FBX bump or displacement factor.
+pub fn parallax_depth_scale(factor: f32) -> f32 {
+    DEFAULT_PARALLAX_DEPTH_SCALE * factor.max(0.0)
+}
+
+/// Convert a Standard Surface specular rotation (0..1 for 0..180°) to radians.
+pub fn anisotropy_rotation(rotation: f32) -> f32 {
+    rotation * std::f32::consts::PI
+}
+
+/// Override material parameters from an exporter-specific property table.
+pub fn apply_shader_properties(
+    material: &mut StandardMaterial,
+    ufbx_material: &ufbx::Material,
+    property_map: &ShaderPropertyMap,
+    texture_handles: &HashMap<u32, Handle<bevy::prelude::Image>>,
+) {
+    let scalar = |name: Option<&str>| {
+        name.and_then(|name| find_material_prop(ufbx_material, name))
+            .map_or(1.0, |prop| prop.value_vec4.x as f32)
+    };
+
+    if let Some(prop) = find_material_prop(ufbx_material, property_map.base_color) {
+        let c = prop.value_vec4;
+        let weight = scalar(property_map.base_color_weight);
+        let alpha = scalar(property_map.opacity);
+        material.base_color = Color::srgba(
+            c.x as f32 * weight,
+            c.y as f32 * weight,
+            c.z as f32 * weight,
+            alpha,
+        );
+        if alpha < 0.98 {
+            material.alpha_mode = AlphaMode::Blend;
+        }
+    }
+    if let Some(prop) = find_material_prop(ufbx_material, property_map.metallic) {
+        material.metallic = prop.value_vec4.x as f32;
+    }
+    if let Some(prop) = find_material_prop(ufbx_material, property_map.roughness) {
+        material.perceptual_roughness = prop.value_vec4.x as f32;
+    }
+    if let Some(prop) = find_material_prop(ufbx_material, property_map.emissive) {
+        let e = prop.value_vec4;
+        let weight = scalar(property_map.emissive_weight);
+        material.emissive = LinearRgba::rgb(
+            e.x as f32 * weight,
+            e.y as f32 * weight,
+            e.z as f32 * weight,
+        );
+    }
+
+    let texture = |slot: &ShaderTextureSlot| shader_texture(ufbx_material, slot, texture_handles);
+    if let Some((image, texture)) = texture(&property_map.base_color_texture) {
+        material.base_color_texture = Some(image);
+        material.uv_transform = convert_texture_uv_transform(texture);
+    }
+    if let Some((image, _)) = texture(&property_map.normal_texture) {
+        material.normal_map_texture = Some(image);
+    }
+    if let Some((image, _)) =
+        texture(&property_map.metallic_texture).or_else(|| texture(&property_map.roughness_texture))
+    {
+        material.metallic_roughness_texture = Some(image);
+    }
+    if let Some((image, _)) = texture(&property_map.emissive_texture) {
+        material.emissive_texture = Some(image);
+    }
+    if let Some((image, _)) = property_map.occlusion_texture.as_ref().and_then(texture) {
+        material.occlusion_texture = Some(image);
+    }
+}
+
+/// Texture connected to a shader texture slot, if loaded and enabled.
+fn shader_texture<'a>(
+    ufbx_material: &'a ufbx::Material,
+    slot: &ShaderTextureSlot,
+    texture_handles: &HashMap<u32, Handle<bevy::prelude::Image>>,
+) -> Option<(Handle<bevy::prelude::Image>, &'a ufbx::Texture)> {
+    if let Some(toggle) = slot.toggle {
+        let enabled = find_material_prop(ufbx_material, toggle)
+            .is_none_or(|prop| prop.value_int != 0 || prop.value_vec4.x != 0.0);
+        if !enabled {
+            return None;
+        }
+    }
+
+    ufbx_material
+        .textures
+        .as_ref()
+        .iter()
+        .find(|texture_ref| texture_ref.material_prop.as_ref() as &str == slot.property)
+        .and_then(|texture_ref| {
+            let texture: &ufbx::Texture = &texture_ref.texture;
+            texture_handles
+                .get(&texture.element.element_id)
+                .map(|image| (image.clone(), texture))
+        })
+}
+
+/// Find a material property by its FBX name.
+pub fn find_material_prop<'a>(
+    ufbx_material: &'a ufbx::Material,
+    name: &str,
+) -> Option<&'a ufbx::Prop> {
+    ufbx_material
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == name)
+}
+
+/// Collect the properties and texture slots not in [`MAPPED_MATERIAL_PROPERTIES`]
+/// or the material's shader property table.
+pub fn extract_material_extras(ufbx_material: &ufbx::Material) -> FbxMaterialExtras {
+    let mut extras = FbxMaterialExtras::default();
+    let property_map = FbxShaderModel::detect(ufbx_material).property_map();
+    let classic = is_classic_material(ufbx_material);
+    let is_mapped = |name: &str| {
+        (MAPPED_MATERIAL_PROPERTIES.contains(&name) && !(classic && name == "SpecularColor"))
+            || property_map.is_some_and(|property_map| property_map.contains(name))
+    };
+
+    for prop in ufbx_material.props.props.as_ref().iter() {
+        let name: &str = prop.name.as_ref();
+        if is_mapped(name) {
+            continue;
+        }
+        if let Some(value) = convert_property_value(prop) {
+            extras.properties.insert(name.to_string(), value);
+        }
+    }
+
+    for texture_ref in ufbx_material.textures.as_ref().iter() {
+        let name: &str = texture_ref.material_prop.as_ref();
+        if is_mapped(name) {
+            continue;
+        }
+        let texture = &texture_ref.texture;
+        let path = if texture.relative_filename.is_empty() {
+            texture.filename.to_string()
+        } else {
+            texture.relative_filename.to_string()
+        };
+        extras.textures.insert(name.to_string(), path);
+    }
+
+    // StandardMaterial has no sheen, so keep it for custom shaders
+    let pbr = &ufbx_material.pbr;
+    if pbr.sheen_factor.has_value && pbr.sheen_factor.value_vec4.x > 0.0 {
+        for (name, map) in [
+            (SHEEN_WEIGHT_PROPERTY, &pbr.sheen_factor),
+            (SHEEN_COLOR_PROPERTY, &pbr.sheen_color),
+            (SHEEN_ROUGHNESS_PROPERTY, &pbr.sheen_roughness),
+        ] {
+            let v = map.value_vec4;
+            let value = if map.value_components > 1 {
+                FbxPropertyValue::Vector(Vec4::new(v.x as f32, v.y as f32, v.z as f32, 1.0))
+            } else {
+                FbxPropertyValue::Number(v.x)
+            };
+            extras.properties.insert(name.to_string(), value);
+        }
+    }
+
+    extras
+}
+
+/// Convert a ufbx property to a [`FbxPropertyValue`], skipping compound,
+/// blob and reference properties.
+pub fn convert_property_value(prop: &ufbx::Prop) -> Option<FbxPropertyValue> {
+    let v = prop.value_vec4;
+    let value = match prop.type_ {
+        ufbx::PropType::Boolean => FbxPropertyValue::Bool(prop.value_int != 0),
+        ufbx::PropType::Integer => FbxPropertyValue::Int(prop.value_int),
+        ufbx::PropType::Number | ufbx::PropType::Distance => FbxPropertyValue::Number(v.x),
+        ufbx::PropType::Vector
+        | ufbx::PropType::Color
+        | ufbx::PropType::ColorWithAlpha
+        | ufbx::PropType::Translation
+        | ufbx::PropType::Rotation
+        | ufbx::PropType::Scaling => {
+            FbxPropertyValue::Vector(Vec4::new(v.x as f32, v.y as f32, v.z as f32, v.w as f32))
+        }
+        ufbx::PropType::String | ufbx::PropType::DateTime => {
+            FbxPropertyValue::String(prop.value_str.to_string())
+        }
+        _ => return None,
+    };
+    Some(value)
+}
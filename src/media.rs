@@ -0,0 +1,135 @@
+//! Extraction of audio and other non-image media embedded in FBX files.
+//!
+//! Files exported for lipsync or cutscenes can embed the audio track they
+//! were timed against, and some exporters embed arbitrary blobs as video
+//! elements. With
+//! [`FbxLoaderSettings::load_media`](crate::FbxLoaderSettings::load_media),
+//! each embedded blob that isn't an image is added as an [`FbxMedia`]
+//! sub-asset, so pipelines can pull it out without another FBX parser.
+
+use crate::label::FbxAssetLabel;
+use crate::sink::FbxAssetSink;
+use bevy::prelude::*;
+use std::path::Path;
+
+/// File extensions of embedded images, which are left to the texture
+/// pipeline.
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "bmp", "dds", "exr", "gif", "hdr", "jpeg", "jpg", "ktx2", "png", "psd", "tga", "tif", "tiff",
+    "webp",
+];
+
+/// FBX element an embedded media blob was stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum FbxMediaKind {
+    /// Audio clip, e.g. the dialogue track of a lipsync animation
+    Audio,
+    /// Video element holding a file that isn't an image
+    Video,
+}
+
+/// Media file embedded in an FBX file, added as a `Media{}` sub-asset.
+#[derive(Asset, Debug, Clone, PartialEq, Reflect)]
+pub struct FbxMedia {
+    pub name: String,
+    pub kind: FbxMediaKind,
+    /// Path relative to the FBX file as written by the exporter, or the
+    /// absolute path if there is none
+    pub filename: String,
+    pub absolute_filename: String,
+    /// Exporter-specific encoding from the element's `CoderType` property,
+    /// if it has one
+    pub coder_type: Option<String>,
+    /// Embedded file contents
+    pub data: Vec<u8>,
+}
+
+impl FbxMedia {
+    /// Lowercase extension of `filename`, e.g. `wav`.
+    pub fn extension(&self) -> Option<String> {
+        Path::new(&self.filename)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+    }
+}
+
+/// Whether `filename` names an image the texture pipeline handles.
+pub fn is_image_filename(filename: &str) -> bool {
+    Path::new(filename).extension().is_some_and(|extension| {
+        let extension = extension.to_string_lossy().to_lowercase();
+        IMAGE_EXTENSIONS.contains(&extension.as_str())
+    })
+}
+
+/// Embedded audio clips and non-image videos of `scene`, in that order.
+/// Media only referenced by path is left out.
+pub fn extract_media(scene: &ufbx::Scene) -> Vec<FbxMedia> {
+    let audio = scene.audio_clips.as_ref().iter().map(|clip| {
+        media(
+            FbxMediaKind::Audio,
+            &clip.element,
+            clip.relative_filename.as_ref(),
+            clip.filename.as_ref(),
+            clip.absolute_filename.as_ref(),
+            clip.content.as_ref(),
+        )
+    });
+    let videos = scene.videos.as_ref().iter().map(|video| {
+        media(
+            FbxMediaKind::Video,
+            &video.element,
+            video.relative_filename.as_ref(),
+            video.filename.as_ref(),
+            video.absolute_filename.as_ref(),
+            video.content.as_ref(),
+        )
+    });
+    audio
+        .chain(videos)
+        .filter(|media| !media.data.is_empty() && !is_image_filename(&media.filename))
+        .collect()
+}
+
+fn media(
+    kind: FbxMediaKind,
+    element: &ufbx::Element,
+    relative_filename: &str,
+    filename: &str,
+    absolute_filename: &str,
+    data: &[u8],
+) -> FbxMedia {
+    let coder_type = element
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == "CoderType")
+        .map(|prop| prop.value_str.to_string())
+        .filter(|coder_type| !coder_type.is_empty());
+    FbxMedia {
+        name: element.name.to_string(),
+        kind,
+        filename: if relative_filename.is_empty() {
+            filename.to_string()
+        } else {
+            relative_filename.to_string()
+        },
+        absolute_filename: absolute_filename.to_string(),
+        coder_type,
+        data: data.to_vec(),
+    }
+}
+
+/// Add the embedded media of `scene` as `Media{}` sub-assets.
+pub fn process_media(
+    scene: &ufbx::Scene,
+    load_context: &mut impl FbxAssetSink,
+) -> Vec<Handle<FbxMedia>> {
+    extract_media(scene)
+        .into_iter()
+        .enumerate()
+        .map(|(index, media)| {
+            load_context.add_labeled_asset(FbxAssetLabel::Media(index).to_string(), media)
+        })
+        .collect()
+}
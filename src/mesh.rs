@@ -1,77 +1,1052 @@
 //! Mesh processing functionality for FBX files.
 
+use crate::attribute::{attribute_values, layer_values};
+use crate::cache::{has_vertex_cache, vertex_cache_file, vertex_cache_path};
 use crate::error::FbxError;
-use crate::label::FbxAssetLabel;
+use crate::geometry::extract_geometry;
+use crate::label::{FbxAssetLabel, label_names};
 use crate::loader::FbxLoaderSettings;
-use bevy::asset::{Handle, LoadContext};
+use crate::material::{mesh_materials, texture_uv_set};
+use crate::morph::{blend_channels, blend_shape_offsets, build_morph_targets, morph_target_names};
+use crate::simplify::{FbxSimplification, simplification_available, simplify_mesh};
+use crate::sink::FbxAssetSink;
+use crate::topology::extract_mesh_topology;
+use crate::types::{FbxMaterialExtras, FbxMesh, FbxPrimitive};
+use crate::utils::{convert_matrix, glob_match};
+use bevy::asset::{Handle, RenderAssetUsages};
+use bevy::camera::primitives::Aabb;
+use bevy::mesh::morph::MorphAttributes;
+use bevy::mesh::{
+    Indices, MeshVertexAttribute, MeshVertexAttributeId, PrimitiveTopology, VertexAttributeValues,
+    VertexFormat,
+};
+use bevy::pbr::StandardMaterial;
 use bevy::prelude::*;
-use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
-use std::collections::HashMap;
+use bevy::tasks::AsyncComputeTaskPool;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+
+/// Source ufbx vertex of each vertex, kept on converted meshes with blend
+/// shapes or a vertex cache until they are consumed in [`process_meshes`].
+pub const ATTRIBUTE_SOURCE_VERTEX: MeshVertexAttribute =
+    MeshVertexAttribute::new("FbxSourceVertex", 0x4642_5856, VertexFormat::Uint32);
+
+/// Maximum number of poses sampled per animation stack by
+/// [`animated_skin_bounds`].
+pub const MAX_SKIN_BOUNDS_SAMPLES: usize = 256;
+
+/// Culling bounds of skinned meshes, see
+/// [`FbxLoaderSettings::skinned_bounds`].
+///
+/// Bevy computes mesh bounds from the bind pose, so skinned characters whose
+/// bones move them outside of it can be culled while still visible.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum FbxSkinnedBounds {
+    /// Let Bevy compute bounds from the bind-pose vertices
+    #[default]
+    BindPose,
+    /// Scale the bind-pose bounds around their center by a factor
+    Inflate(f32),
+    /// Conservative bounds over the rest pose and every animation stack,
+    /// see [`animated_skin_bounds`]
+    Animated,
+}
 
 /// Process all meshes from the FBX scene.
+///
+/// Each FBX mesh is split into one primitive per material section. The
+/// returned [`FbxMesh`] list is indexed like `scene.meshes`. Local-space
+/// bounding boxes are computed for every primitive and mesh. Blend shapes
+/// are added as `Mesh{}/Primitive{}/MorphTargets` images and, with
+/// [`FbxLoaderSettings::load_mesh_topology`], crease and smoothing data as
+/// `MeshTopology{}` assets; with [`FbxLoaderSettings::load_geometry`], the
+/// triangulated source geometry as `Geometry{}` assets. With
+/// [`FbxLoaderSettings::load_vertex_caches`], the cache file of a mesh is
+/// loaded as an [`FbxVertexCache`](crate::cache::FbxVertexCache). Meshes
+/// matching [`FbxLoaderSettings::simplification`] are simplified and get
+/// `Mesh{}/Primitive{}/Lod{}` levels.
 pub fn process_meshes(
     scene: &ufbx::Scene,
+    materials_by_id: &HashMap<u32, Handle<StandardMaterial>>,
+    material_extras_by_id: &HashMap<u32, Handle<FbxMaterialExtras>>,
     settings: &FbxLoaderSettings,
-    load_context: &mut LoadContext,
+    load_context: &mut impl FbxAssetSink,
 ) -> Result<
     (
-        Vec<Handle<Mesh>>,
-        HashMap<Box<str>, Handle<Mesh>>,
-        Vec<ufbx::Matrix>,
-        Vec<Vec<String>>,
+        Vec<FbxMesh>,
+        Vec<Handle<FbxMesh>>,
+        HashMap<Box<str>, Handle<FbxMesh>>,
     ),
     FbxError,
 > {
-    let mut meshes = Vec::new();
+    // Convert meshes in parallel; labeled assets are added afterwards
+    let ufbx_meshes: Vec<&ufbx::Mesh> = scene.meshes.as_ref().iter().map(|m| &**m).collect();
+    let converted = convert_meshes_parallel(&ufbx_meshes, settings);
+
+    let mut fbx_meshes = Vec::new();
+    let mut handles = Vec::new();
     let mut named_meshes = HashMap::new();
-    let mut transforms = Vec::new();
-    let mut mesh_material_info = Vec::new();
+    let names = label_names(
+        settings,
+        ufbx_meshes
+            .iter()
+            .map(|mesh| mesh.element.name.as_ref() as &str),
+    );
+    let frame_rate = scene.settings.frames_per_second as f32;
+    let animated_bounds = if settings.skinned_bounds == FbxSkinnedBounds::Animated {
+        animated_skin_bounds(scene)
+    } else {
+        HashMap::new()
+    };
+
+    if !simplification_available() && !settings.simplification.is_empty() {
+        warn!("FBX mesh simplification rules are ignored without the `simplify` feature");
+    }
+
+    for (index, (mesh, mesh_primitives)) in ufbx_meshes.iter().zip(converted).enumerate() {
+        let name = if mesh.element.name.is_empty() {
+            format!("Mesh_{}", index)
+        } else {
+            mesh.element.name.to_string()
+        };
+        let label_name = names.get(index).and_then(Option::as_deref);
+        for uv_set in missing_uv_sets(mesh, settings) {
+            warn!(
+                "FBX mesh '{}' has textures sampling UV set '{}', which is not imported; \
+                 they sample UV0 instead",
+                name, uv_set
+            );
+        }
+        let mut primitives = Vec::new();
+        let morph_targets = has_blend_shapes(mesh, settings).then(|| blend_shape_offsets(mesh));
+        let face_sizes = settings
+            .record_face_sizes
+            .then(|| face_sizes_by_material(mesh));
+
+        let vertex_cache = vertex_cache_file(mesh)
+            .filter(|_| settings.load_vertex_caches)
+            .and_then(|file| {
+                vertex_cache_path(
+                    file.relative_filename.as_ref(),
+                    file.filename.as_ref(),
+                    &load_context.base_dir(),
+                )
+            })
+            .map(|path| {
+                let path = load_context.asset_path(&path);
+                load_context.load_vertex_cache(path, frame_rate)
+            });
+
+        // Create a primitive for each material group
+        let mut asset_usage = mesh_asset_usage(mesh, settings);
+        if vertex_cache.is_some() {
+            // Playback rewrites the positions on the CPU
+            asset_usage |= RenderAssetUsages::MAIN_WORLD;
+        }
+        // Deformations are stored per source vertex
+        let simplification = mesh_simplification(mesh, settings)
+            .filter(|_| simplification_available())
+            .filter(|_| morph_targets.is_none() && vertex_cache.is_none());
+        for (material_index, joints, mut bevy_mesh) in mesh_primitives {
+            let primitive_index = primitives.len();
+            bevy_mesh.asset_usage = asset_usage;
+            let cache_vertices = vertex_cache.as_ref().and_then(|_| {
+                match bevy_mesh.attribute(ATTRIBUTE_SOURCE_VERTEX)? {
+                    VertexAttributeValues::Uint32(vertices) => Some(vertices.clone()),
+                    _ => None,
+                }
+            });
+            if let Some(targets) = &morph_targets {
+                let label = FbxAssetLabel::MorphTargets {
+                    mesh: index,
+                    primitive: primitive_index,
+                };
+                add_morph_targets(
+                    mesh,
+                    &mut bevy_mesh,
+                    targets,
+                    (label, label_name),
+                    load_context,
+                );
+            }
+            bevy_mesh.remove_attribute(ATTRIBUTE_SOURCE_VERTEX);
+            let label = FbxAssetLabel::Primitive {
+                mesh: index,
+                primitive: primitive_index,
+            };
+            // Joint splits may separate the triangles of one face
+            let mut face_sizes = face_sizes
+                .as_ref()
+                .filter(|_| {
+                    joints.is_none()
+                        && bevy_mesh.primitive_topology() == PrimitiveTopology::TriangleList
+                })
+                .and_then(|sizes| sizes.get(&material_index).cloned());
+
+            let mut lod_meshes = Vec::new();
+            if let Some(simplification) = simplification {
+                for level in &simplification.lods {
+                    let lod = simplify_mesh(&bevy_mesh, *level, simplification.lock_border);
+                    lod_meshes.push(lod);
+                }
+                if let Some(level) = simplification.base
+                    && let Some(simplified) =
+                        simplify_mesh(&bevy_mesh, level, simplification.lock_border)
+                {
+                    bevy_mesh = simplified;
+                    face_sizes = None;
+                }
+            }
+            let aabb = bevy_mesh.compute_aabb();
+            let mesh_handle = load_context.add_named_labeled_asset(label, label_name, bevy_mesh);
+            // Levels that could not be simplified reuse the primitive
+            let lods = lod_meshes
+                .into_iter()
+                .enumerate()
+                .map(|(lod, lod_mesh)| match lod_mesh {
+                    Some(lod_mesh) => {
+                        let label = FbxAssetLabel::PrimitiveLod {
+                            mesh: index,
+                            primitive: primitive_index,
+                            lod,
+                        };
+                        load_context.add_named_labeled_asset(label, label_name, lod_mesh)
+                    }
+                    None => mesh_handle.clone(),
+                })
+                .collect();
+
+            let material_id = mesh
+                .materials
+                .as_ref()
+                .get(material_index)
+                .map(|material| material.element.element_id);
+            let material = material_id.and_then(|id| materials_by_id.get(&id)).cloned();
+            let material_extras = material_id
+                .and_then(|id| material_extras_by_id.get(&id))
+                .cloned();
+
+            primitives.push(FbxPrimitive {
+                index: primitive_index,
+                mesh: mesh_handle,
+                material_index,
+                material,
+                material_extras,
+                joints,
+                aabb,
+                face_sizes,
+                cache_vertices,
+                lods,
+            });
+        }
+
+        let aabb = primitives
+            .iter()
+            .filter_map(|primitive| primitive.aabb)
+            .reduce(merge_aabbs);
+        let skinned_aabb = match settings.skinned_bounds {
+            FbxSkinnedBounds::BindPose => None,
+            FbxSkinnedBounds::Inflate(factor) => aabb
+                .filter(|_| !mesh.skin_deformers.is_empty())
+                .map(|aabb| Aabb {
+                    center: aabb.center,
+                    half_extents: aabb.half_extents * factor,
+                }),
+            FbxSkinnedBounds::Animated => animated_bounds.get(&mesh.element.element_id).copied(),
+        };
+        let topology = settings.load_mesh_topology.then(|| {
+            load_context.add_named_labeled_asset(
+                FbxAssetLabel::MeshTopology(index),
+                label_name,
+                extract_mesh_topology(mesh),
+            )
+        });
+        let geometry = settings.load_geometry.then(|| {
+            load_context.add_named_labeled_asset(
+                FbxAssetLabel::Geometry(index),
+                label_name,
+                extract_geometry(mesh),
+            )
+        });
+        let fbx_mesh = FbxMesh {
+            index,
+            name,
+            primitives,
+            aabb,
+            skinned_aabb,
+            blend_channels: if morph_targets.is_some() {
+                blend_channels(mesh)
+            } else {
+                Vec::new()
+            },
+            topology,
+            geometry,
+            vertex_cache,
+        };
+        let label = FbxAssetLabel::Mesh(index);
+        let handle = load_context.add_named_labeled_asset(label, label_name, fbx_mesh.clone());
+
+        if !mesh.element.name.is_empty() {
+            named_meshes.insert(Box::from(mesh.element.name.as_ref()), handle.clone());
+        }
+
+        fbx_meshes.push(fbx_mesh);
+        handles.push(handle);
+    }
+
+    Ok((fbx_meshes, handles, named_meshes))
+}
+
+/// Asset usage of the primitives of `mesh`.
+///
+/// The first rule of [`FbxLoaderSettings::mesh_usage_overrides`] matching
+/// the mesh name or the name of a node instancing the mesh applies;
+/// otherwise [`FbxLoaderSettings::load_meshes`].
+pub fn mesh_asset_usage(mesh: &ufbx::Mesh, settings: &FbxLoaderSettings) -> RenderAssetUsages {
+    settings
+        .mesh_usage_overrides
+        .iter()
+        .find(|(pattern, _)| mesh_matches(mesh, pattern))
+        .map_or(settings.load_meshes, |&(_, usage)| usage)
+}
+
+/// Simplification of the primitives of `mesh`: the first rule of
+/// [`FbxLoaderSettings::simplification`] matching the mesh name or the name
+/// of a node instancing the mesh.
+pub fn mesh_simplification<'a>(
+    mesh: &ufbx::Mesh,
+    settings: &'a FbxLoaderSettings,
+) -> Option<&'a FbxSimplification> {
+    settings
+        .simplification
+        .iter()
+        .find(|(pattern, _)| mesh_matches(mesh, pattern))
+        .map(|(_, simplification)| simplification)
+}
+
+/// Custom attributes of `mesh` with their values per face corner: every rule
+/// of [`FbxLoaderSettings::custom_attributes`] matching the mesh name or the
+/// name of a node instancing the mesh, if the mesh has the rule's layer.
+pub fn mesh_custom_attributes(
+    mesh: &ufbx::Mesh,
+    settings: &FbxLoaderSettings,
+) -> Vec<(MeshVertexAttribute, Vec<[f32; 4]>)> {
+    settings
+        .custom_attributes
+        .iter()
+        .filter(|(pattern, _)| mesh_matches(mesh, pattern))
+        .filter_map(|(_, custom)| Some((custom.attribute, layer_values(mesh, &custom.layer)?)))
+        .collect()
+}
+
+/// Insert `values` as `attribute`, warning if its format is not supported.
+fn insert_custom_attribute(
+    bevy_mesh: &mut Mesh,
+    attribute: MeshVertexAttribute,
+    values: impl Iterator<Item = [f32; 4]>,
+) {
+    match attribute_values(values, attribute.format) {
+        Some(values) => bevy_mesh.insert_attribute(attribute, values),
+        None => warn!(
+            "Custom attribute {} has unsupported format {:?}",
+            attribute.name, attribute.format
+        ),
+    }
+}
+
+/// Whether the glob `pattern` matches the name of `mesh` or of a node
+/// instancing it.
+fn mesh_matches(mesh: &ufbx::Mesh, pattern: &str) -> bool {
+    glob_match(pattern, mesh.element.name.as_ref())
+        || mesh
+            .instances
+            .as_ref()
+            .iter()
+            .any(|node| glob_match(pattern, node.element.name.as_ref()))
+}
 
-    for (index, node) in scene.nodes.as_ref().iter().enumerate() {
-        let Some(mesh_ref) = node.mesh.as_ref() else {
+/// Corner count of each triangulated face, grouped by material index like
+/// [`group_corners_by_material`].
+pub fn face_sizes_by_material(mesh: &ufbx::Mesh) -> HashMap<usize, Vec<u32>> {
+    let mut face_sizes: HashMap<usize, Vec<u32>> = HashMap::new();
+    for (face_index, face) in mesh.faces.as_ref().iter().enumerate() {
+        // Lines and points produce no triangles
+        if face.num_indices < 3 {
             continue;
+        }
+        let material_index = if mesh.materials.is_empty() {
+            0
+        } else {
+            mesh.face_material
+                .as_ref()
+                .get(face_index)
+                .map_or(0, |&material| material as usize)
         };
-        let mesh = mesh_ref.as_ref();
+        face_sizes
+            .entry(material_index)
+            .or_default()
+            .push(face.num_indices);
+    }
+    face_sizes
+}
+
+/// Triangle index range of each source face, for face sizes recorded in
+/// [`FbxPrimitive::face_sizes`].
+///
+/// A face with `n` corners was triangulated into `n - 2` consecutive
+/// triangles.
+pub fn face_triangle_ranges(face_sizes: &[u32]) -> Vec<Range<usize>> {
+    let mut start = 0;
+    face_sizes
+        .iter()
+        .map(|&size| {
+            let end = start + size.saturating_sub(2) as usize;
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect()
+}
+
+/// Whether morph targets are imported for `mesh`.
+pub fn has_blend_shapes(mesh: &ufbx::Mesh, settings: &FbxLoaderSettings) -> bool {
+    settings.load_blend_shapes && !mesh.blend_deformers.is_empty()
+}
+
+/// Move the blend shapes of `mesh` onto a converted primitive as a labeled
+/// morph target image, consuming its [`ATTRIBUTE_SOURCE_VERTEX`].
+fn add_morph_targets(
+    mesh: &ufbx::Mesh,
+    bevy_mesh: &mut Mesh,
+    targets: &[Vec<MorphAttributes>],
+    (label, name): (FbxAssetLabel, Option<&str>),
+    load_context: &mut impl FbxAssetSink,
+) {
+    let Some(VertexAttributeValues::Uint32(source_vertices)) =
+        bevy_mesh.remove_attribute(ATTRIBUTE_SOURCE_VERTEX)
+    else {
+        return;
+    };
+    match build_morph_targets(targets, &source_vertices, bevy_mesh.asset_usage) {
+        Ok(image) => {
+            bevy_mesh.set_morph_targets(load_context.add_named_labeled_asset(label, name, image));
+            bevy_mesh.set_morph_target_names(morph_target_names(mesh));
+        }
+        Err(error) => warn!(
+            "Skipping blend shapes of mesh '{}': {}",
+            mesh.element.name.as_ref() as &str,
+            error
+        ),
+    }
+}
 
-        if mesh.num_vertices == 0 || mesh.faces.as_ref().is_empty() {
+/// Smallest box containing both `a` and `b`.
+pub fn merge_aabbs(a: Aabb, b: Aabb) -> Aabb {
+    Aabb::from_min_max(a.min().min(b.min()).into(), a.max().max(b.max()).into())
+}
+
+/// Axis-aligned box containing `aabb` after transforming it by `matrix`.
+pub fn transform_aabb(aabb: &Aabb, matrix: Mat4) -> Aabb {
+    let center = matrix.transform_point3a(aabb.center);
+    // Each output half extent sums the absolute contributions of the input axes
+    let half_extents = Mat3A::from_mat4(matrix).abs() * aabb.half_extents;
+    Aabb {
+        center,
+        half_extents,
+    }
+}
+
+/// Conservative local-space bounds of each skinned mesh over the rest pose
+/// and every animation stack, keyed by mesh element id.
+///
+/// The vertices bound to each cluster are boxed in geometry space, and the
+/// box follows its bone through up to [`MAX_SKIN_BOUNDS_SAMPLES`] poses per
+/// stack, relative to the mesh's first instance at rest. Skinned vertices are
+/// weighted averages of their bone transforms, so they stay inside the union
+/// of these boxes; blend shape offsets are not included.
+pub fn animated_skin_bounds(scene: &ufbx::Scene) -> HashMap<u32, Aabb> {
+    // Bone node index, geometry-to-bone matrix and vertex box of each cluster
+    let mut skins = Vec::new();
+    for mesh in scene.meshes.as_ref() {
+        let (Some(skin), Some(instance)) = (
+            mesh.skin_deformers.as_ref().first(),
+            mesh.instances.as_ref().first(),
+        ) else {
             continue;
+        };
+        let clusters: Vec<(usize, Mat4, Aabb)> = skin
+            .clusters
+            .as_ref()
+            .iter()
+            .filter_map(|cluster| {
+                let bone = cluster.bone_node.as_ref()?;
+                let positions = cluster
+                    .vertices
+                    .as_ref()
+                    .iter()
+                    .zip(cluster.weights.as_ref())
+                    .filter(|&(_, &weight)| weight > 0.0)
+                    .filter_map(|(&vertex, _)| mesh.vertices.as_ref().get(vertex as usize))
+                    .map(|p| Vec3::new(p.x as f32, p.y as f32, p.z as f32));
+                let aabb = Aabb::enclosing(positions)?;
+                let geometry_to_bone = convert_matrix(&cluster.geometry_to_bone);
+                Some((bone.element.typed_id as usize, geometry_to_bone, aabb))
+            })
+            .collect();
+        let rest_inverse = convert_matrix(&instance.geometry_to_world).inverse();
+        skins.push((mesh.element.element_id, rest_inverse, clusters));
+    }
+
+    let mut bounds: HashMap<u32, Aabb> = HashMap::new();
+    if skins.is_empty() {
+        return bounds;
+    }
+    let mut add_pose = |posed: &ufbx::Scene| {
+        for (mesh_id, rest_inverse, clusters) in &skins {
+            for (bone, geometry_to_bone, aabb) in clusters {
+                let Some(node) = posed.nodes.as_ref().get(*bone) else {
+                    continue;
+                };
+                let bone_to_world = convert_matrix(&node.node_to_world);
+                let aabb = transform_aabb(aabb, *rest_inverse * bone_to_world * *geometry_to_bone);
+                bounds
+                    .entry(*mesh_id)
+                    .and_modify(|bounds| *bounds = merge_aabbs(*bounds, aabb))
+                    .or_insert(aabb);
+            }
+        }
+    };
+
+    add_pose(scene);
+    let frame_rate = scene.settings.frames_per_second.max(1.0);
+    for stack in scene.anim_stacks.as_ref() {
+        let duration = (stack.time_end - stack.time_begin).max(0.0);
+        let samples = ((duration * frame_rate).ceil() as usize + 1).min(MAX_SKIN_BOUNDS_SAMPLES);
+        for sample in 0..samples {
+            let time = stack.time_begin + duration * sample as f64 / (samples - 1).max(1) as f64;
+            match ufbx::evaluate_scene(scene, &stack.anim, time, ufbx::EvaluateOpts::default()) {
+                Ok(posed) => add_pose(&posed),
+                Err(error) => {
+                    warn!(
+                        "Skipping skin bounds of animation stack '{}': {:?}",
+                        stack.element.name.as_ref() as &str,
+                        error
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    bounds
+}
+
+/// World-space bounds of every mesh instance in the scene.
+///
+/// `meshes` must be indexed like `scene.meshes`. Returns `None` if the scene
+/// has no geometry.
+pub fn scene_bounds(scene: &ufbx::Scene, meshes: &[FbxMesh]) -> Option<Aabb> {
+    scene
+        .nodes
+        .as_ref()
+        .iter()
+        .filter_map(|node| {
+            let mesh = meshes.get(node.mesh.as_ref()?.element.typed_id as usize)?;
+            let matrix = convert_matrix(&node.geometry_to_world);
+            Some(transform_aabb(&mesh.aabb?, matrix))
+        })
+        .reduce(merge_aabbs)
+}
+
+/// Convert meshes to per-material Bevy meshes on the [`AsyncComputeTaskPool`].
+///
+/// Results are returned in the order of `meshes` and match converting each
+/// mesh with [`convert_mesh_primitives`]. The scope blocks the calling thread
+/// until every mesh is converted, so it is only used on native targets with
+/// an initialized pool of more than one thread, where the other threads run
+/// the conversions; the loading task then waits as long as the slowest mesh
+/// instead of the sum of all of them. Otherwise, e.g. on the web or outside an
+/// app, meshes are converted one by one on the calling thread.
+pub fn convert_meshes_parallel(
+    meshes: &[&ufbx::Mesh],
+    settings: &FbxLoaderSettings,
+) -> Vec<Vec<ConvertedPrimitive>> {
+    let pool = AsyncComputeTaskPool::try_get()
+        .filter(|_| !cfg!(target_arch = "wasm32"))
+        .filter(|pool| pool.thread_num() > 1);
+    let Some(pool) = pool.filter(|_| meshes.len() > 1) else {
+        return meshes
+            .iter()
+            .map(|mesh| convert_mesh_primitives(mesh, settings))
+            .collect();
+    };
+
+    // Parsed scenes are immutable and ufbx's types are `Sync`, so tasks only
+    // borrow the meshes
+    pool.scope(|scope| {
+        for mesh in meshes {
+            scope.spawn(async move { convert_mesh_primitives(mesh, settings) });
+        }
+    })
+}
+
+/// Material index, joint subset and mesh of a converted primitive.
+pub type ConvertedPrimitive = (usize, Option<Vec<u16>>, Mesh);
+
+/// Convert a ufbx mesh into one Bevy mesh per material group.
+///
+/// Returns primitives sorted by material index. Skinned groups referencing
+/// more than [`MAX_JOINTS`] joints are split with [`split_mesh_by_joints`].
+/// With [`FbxLoaderSettings::load_lines_and_points`], line and point faces are
+/// appended as extra primitives from [`build_line_and_point_meshes`].
+pub fn convert_mesh_primitives(
+    mesh: &ufbx::Mesh,
+    settings: &FbxLoaderSettings,
+) -> Vec<ConvertedPrimitive> {
+    if mesh.num_vertices == 0 || mesh.faces.as_ref().is_empty() {
+        return Vec::new();
+    }
+
+    // Group faces by material
+    let material_groups = if settings.weld_vertices {
+        group_corners_by_material(mesh)
+    } else {
+        group_faces_by_material(mesh)
+    };
+    let mut material_indices: Vec<usize> = material_groups.keys().copied().collect();
+    material_indices.sort_unstable();
+
+    let mut primitives = Vec::new();
+    for material_index in material_indices {
+        let bevy_mesh = build_mesh_from_group(mesh, &material_groups[&material_index], settings);
+        match split_mesh_by_joints(&bevy_mesh, MAX_JOINTS) {
+            Some(splits) => primitives.extend(
+                splits
+                    .into_iter()
+                    .map(|(split, joints)| (material_index, Some(joints), split)),
+            ),
+            None => primitives.push((material_index, None, bevy_mesh)),
+        }
+    }
+    if settings.load_lines_and_points {
+        primitives.extend(
+            build_line_and_point_meshes(mesh, settings)
+                .into_iter()
+                .map(|(material_index, bevy_mesh)| (material_index, None, bevy_mesh)),
+        );
+    }
+    primitives
+}
+
+/// Build `LineList` and `PointList` meshes from the two- and one-corner faces
+/// of a mesh, which are dropped by triangulation.
+///
+/// Returns one mesh per topology and material index, sorted by topology
+/// (lines first) and material index.
+pub fn build_line_and_point_meshes(
+    mesh: &ufbx::Mesh,
+    settings: &FbxLoaderSettings,
+) -> Vec<(usize, Mesh)> {
+    // Vertex indices keyed by (is point list, material index)
+    let mut groups: BTreeMap<(bool, usize), Vec<u32>> = BTreeMap::new();
+    for (face_index, face) in mesh.faces.as_ref().iter().enumerate() {
+        let is_points = match face.num_indices {
+            1 => true,
+            2 => false,
+            _ => continue,
+        };
+        let material_index = mesh
+            .face_material
+            .as_ref()
+            .get(face_index)
+            .map_or(0, |&material| material as usize);
+        let corners = face.index_begin..face.index_begin + face.num_indices;
+        groups
+            .entry((is_points, material_index))
+            .or_default()
+            .extend(corners.map(|corner| mesh.vertex_indices[corner as usize]));
+    }
+
+    let positions: Vec<[f32; 3]> = mesh
+        .vertex_position
+        .values
+        .as_ref()
+        .iter()
+        .map(|v| [v.x as f32, v.y as f32, v.z as f32])
+        .collect();
+    groups
+        .into_iter()
+        .map(|((is_points, material_index), indices)| {
+            let topology = if is_points {
+                PrimitiveTopology::PointList
+            } else {
+                PrimitiveTopology::LineList
+            };
+            let bevy_mesh = Mesh::new(topology, settings.load_meshes)
+                .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions.clone())
+                .with_inserted_indices(Indices::U32(indices));
+            (material_index, bevy_mesh)
+        })
+        .collect()
+}
+
+/// Convert every line curve (FBX `Line` geometry or tessellated NURBS curve)
+/// into a `LineList` mesh labeled `LineCurve{}`.
+///
+/// Returns the mesh handles indexed like `scene.line_curves` and keyed by
+/// ufbx element id, so nodes can find their curve.
+pub fn process_line_curves(
+    scene: &ufbx::Scene,
+    settings: &FbxLoaderSettings,
+    load_context: &mut impl FbxAssetSink,
+) -> (Vec<Handle<Mesh>>, HashMap<u32, Handle<Mesh>>) {
+    let mut handles = Vec::new();
+    let mut by_id = HashMap::new();
+    if !settings.load_lines_and_points {
+        return (handles, by_id);
+    }
+
+    let names = label_names(
+        settings,
+        scene
+            .line_curves
+            .as_ref()
+            .iter()
+            .map(|curve| curve.element.name.as_ref() as &str),
+    );
+    for (index, curve) in scene.line_curves.as_ref().iter().enumerate() {
+        let handle = load_context.add_named_labeled_asset(
+            FbxAssetLabel::LineCurve(index),
+            names.get(index).and_then(Option::as_deref),
+            build_line_curve_mesh(curve, settings),
+        );
+        by_id.insert(curve.element.element_id, handle.clone());
+        handles.push(handle);
+    }
+
+    (handles, by_id)
+}
+
+/// Convert a ufbx line curve into a `LineList` mesh, one line per pair of
+/// consecutive points in each segment.
+pub fn build_line_curve_mesh(curve: &ufbx::LineCurve, settings: &FbxLoaderSettings) -> Mesh {
+    let positions: Vec<[f32; 3]> = curve
+        .control_points
+        .as_ref()
+        .iter()
+        .map(|p| [p.x as f32, p.y as f32, p.z as f32])
+        .collect();
+    let point_indices = curve.point_indices.as_ref();
+    let mut indices = Vec::new();
+    for segment in curve.segments.as_ref().iter() {
+        let begin = segment.index_begin as usize;
+        let end = (begin + segment.num_indices as usize).min(point_indices.len());
+        for pair in point_indices[begin.min(end)..end].windows(2) {
+            indices.extend_from_slice(&[pair[0], pair[1]]);
         }
+    }
+
+    Mesh::new(PrimitiveTopology::LineList, settings.load_meshes)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Maximum number of joints a single skinned mesh may reference in Bevy.
+pub const MAX_JOINTS: usize = 256;
+
+/// Split a skinned triangle mesh into meshes referencing at most `max_joints`
+/// joints each.
+///
+/// Returns `None` if the mesh is not skinned or already within the limit.
+/// Otherwise each split contains only the vertices it uses, and its joint
+/// indices are remapped into the returned joint subset, which lists the
+/// original joint index of each local joint.
+pub fn split_mesh_by_joints(mesh: &Mesh, max_joints: usize) -> Option<Vec<(Mesh, Vec<u16>)>> {
+    let Some(VertexAttributeValues::Uint16x4(joint_indices)) =
+        mesh.attribute(Mesh::ATTRIBUTE_JOINT_INDEX)
+    else {
+        return None;
+    };
+    let Some(VertexAttributeValues::Float32x4(joint_weights)) =
+        mesh.attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT)
+    else {
+        return None;
+    };
+    let indices: Vec<u32> = match mesh.indices()? {
+        Indices::U16(indices) => indices.iter().map(|&i| i as u32).collect(),
+        Indices::U32(indices) => indices.clone(),
+    };
+
+    // Joints influencing each vertex
+    let vertex_joints = |vertex: u32| {
+        let vertex = vertex as usize;
+        (0..4)
+            .filter(move |&i| joint_weights[vertex][i] > 0.0)
+            .map(move |i| joint_indices[vertex][i])
+    };
+
+    let used_joints: BTreeSet<u16> = indices.iter().flat_map(|&v| vertex_joints(v)).collect();
+    if used_joints.len() <= max_joints {
+        return None;
+    }
+
+    // Greedily group triangles while the joint set stays within the limit
+    let mut groups: Vec<(BTreeSet<u16>, Vec<u32>)> = Vec::new();
+    let mut joints = BTreeSet::new();
+    let mut triangles = Vec::new();
+    for triangle in indices.chunks_exact(3) {
+        let triangle_joints: BTreeSet<u16> =
+            triangle.iter().flat_map(|&v| vertex_joints(v)).collect();
+        if joints.union(&triangle_joints).count() > max_joints && !triangles.is_empty() {
+            groups.push((std::mem::take(&mut joints), std::mem::take(&mut triangles)));
+        }
+        joints.extend(triangle_joints);
+        triangles.extend_from_slice(triangle);
+    }
+    if !triangles.is_empty() {
+        groups.push((joints, triangles));
+    }
 
-        // Group faces by material
-        let material_groups = group_faces_by_material(mesh);
+    let splits = groups
+        .into_iter()
+        .map(|(joints, triangles)| {
+            let joints: Vec<u16> = joints.into_iter().collect();
+            let local_joint: HashMap<u16, u16> = joints
+                .iter()
+                .enumerate()
+                .map(|(local, &joint)| (joint, local as u16))
+                .collect();
 
-        // Create mesh for each material group
-        for (material_idx, indices) in material_groups.iter() {
-            let mesh_handle = create_mesh_from_group(
-                mesh,
-                indices,
-                index,
-                *material_idx,
-                settings,
-                load_context,
-            )?;
+            // Compact the vertices used by this split
+            let mut vertex_map: HashMap<u32, u32> = HashMap::new();
+            let mut vertices = Vec::new();
+            let split_indices: Vec<u32> = triangles
+                .iter()
+                .map(|&v| {
+                    *vertex_map.entry(v).or_insert_with(|| {
+                        vertices.push(v);
+                        (vertices.len() - 1) as u32
+                    })
+                })
+                .collect();
 
-            if *material_idx == 0 && !node.element.name.is_empty() {
-                named_meshes.insert(Box::from(node.element.name.as_ref()), mesh_handle.clone());
+            let mut split = Mesh::new(PrimitiveTopology::TriangleList, mesh.asset_usage);
+            for (attribute, values) in mesh.attributes() {
+                if attribute.id == Mesh::ATTRIBUTE_JOINT_INDEX.id {
+                    let remapped: Vec<[u16; 4]> = vertices
+                        .iter()
+                        .map(|&v| {
+                            let v = v as usize;
+                            std::array::from_fn(|i| {
+                                if joint_weights[v][i] > 0.0 {
+                                    local_joint[&joint_indices[v][i]]
+                                } else {
+                                    0
+                                }
+                            })
+                        })
+                        .collect();
+                    split.insert_attribute(
+                        Mesh::ATTRIBUTE_JOINT_INDEX,
+                        VertexAttributeValues::Uint16x4(remapped),
+                    );
+                } else if let Some(values) = gather_vertex_attribute(values, &vertices) {
+                    split.insert_attribute(*attribute, values);
+                }
             }
+            split.insert_indices(Indices::U32(split_indices));
+
+            (split, joints)
+        })
+        .collect();
+
+    Some(splits)
+}
+
+/// Merge triangle meshes into one, transforming each by its matrix.
+///
+/// Positions, normals and tangents are transformed; other attributes are
+/// copied. Attributes missing from any mesh are dropped, and the winding of
+/// mirrored meshes is flipped to keep their faces pointing outwards. Returns
+/// `None` if `meshes` is empty or contains a non-triangle mesh.
+pub fn merge_meshes(meshes: &[(&Mesh, Mat4)], asset_usage: RenderAssetUsages) -> Option<Mesh> {
+    let (first, _) = meshes.first()?;
+    if meshes
+        .iter()
+        .any(|(mesh, _)| mesh.primitive_topology() != PrimitiveTopology::TriangleList)
+    {
+        return None;
+    }
 
-            meshes.push(mesh_handle);
-            transforms.push(node.geometry_to_world);
+    let mut merged = Mesh::new(PrimitiveTopology::TriangleList, asset_usage);
+    'attributes: for (attribute, _) in first.attributes() {
+        let mut merged_values: Option<VertexAttributeValues> = None;
+        for (mesh, matrix) in meshes {
+            let Some(values) = mesh.attribute(attribute.id) else {
+                continue 'attributes;
+            };
+            let values = transform_vertex_attribute(attribute.id, values, *matrix);
+            match &mut merged_values {
+                None => merged_values = Some(values),
+                Some(merged_values) => {
+                    if !append_vertex_attribute(merged_values, values) {
+                        continue 'attributes;
+                    }
+                }
+            }
+        }
+        if let Some(values) = merged_values {
+            merged.insert_attribute(*attribute, values);
+        }
+    }
 
-            let material_name = if *material_idx < mesh.materials.len() {
-                mesh.materials[*material_idx].element.name.to_string()
+    let mut indices = Vec::new();
+    let mut vertex_offset = 0;
+    for (mesh, matrix) in meshes {
+        let vertex_count = mesh.count_vertices() as u32;
+        let mesh_indices: Vec<u32> = match mesh.indices() {
+            Some(Indices::U16(values)) => values.iter().map(|&i| i as u32).collect(),
+            Some(Indices::U32(values)) => values.clone(),
+            None => (0..vertex_count).collect(),
+        };
+        let mirrored = matrix.determinant() < 0.0;
+        for triangle in mesh_indices.chunks_exact(3) {
+            let triangle = if mirrored {
+                [triangle[0], triangle[2], triangle[1]]
             } else {
-                "default".to_string()
+                [triangle[0], triangle[1], triangle[2]]
             };
-            mesh_material_info.push(vec![material_name]);
+            indices.extend(triangle.map(|i| i + vertex_offset));
         }
+        vertex_offset += vertex_count;
     }
+    merged.insert_indices(Indices::U32(indices));
+    Some(merged)
+}
 
-    Ok((meshes, named_meshes, transforms, mesh_material_info))
+/// Transform vertex positions, normals and tangents by `matrix`; other
+/// attributes are returned unchanged.
+pub(crate) fn transform_vertex_attribute(
+    id: MeshVertexAttributeId,
+    values: &VertexAttributeValues,
+    matrix: Mat4,
+) -> VertexAttributeValues {
+    let mut values = values.clone();
+    let linear = Mat3::from_mat4(matrix);
+    match &mut values {
+        VertexAttributeValues::Float32x3(positions) if id == Mesh::ATTRIBUTE_POSITION.id => {
+            for position in positions {
+                *position = matrix.transform_point3(Vec3::from(*position)).into();
+            }
+        }
+        VertexAttributeValues::Float32x3(normals) if id == Mesh::ATTRIBUTE_NORMAL.id => {
+            let normal_matrix = linear.inverse().transpose();
+            for normal in normals {
+                *normal = (normal_matrix * Vec3::from(*normal))
+                    .normalize_or_zero()
+                    .into();
+            }
+        }
+        VertexAttributeValues::Float32x4(tangents) if id == Mesh::ATTRIBUTE_TANGENT.id => {
+            // Mirroring flips the bitangent direction stored in `w`
+            let sign = linear.determinant().signum();
+            for tangent in tangents {
+                let direction = (linear * Vec3::from_slice(&tangent[..3])).normalize_or_zero();
+                *tangent = direction.extend(tangent[3] * sign).into();
+            }
+        }
+        _ => {}
+    }
+    values
+}
+
+/// Append `values` to `target`; returns `false` if their formats differ.
+fn append_vertex_attribute(
+    target: &mut VertexAttributeValues,
+    values: VertexAttributeValues,
+) -> bool {
+    match (target, values) {
+        (VertexAttributeValues::Float32(target), VertexAttributeValues::Float32(values)) => {
+            target.extend(values)
+        }
+        (VertexAttributeValues::Float32x2(target), VertexAttributeValues::Float32x2(values)) => {
+            target.extend(values)
+        }
+        (VertexAttributeValues::Float32x3(target), VertexAttributeValues::Float32x3(values)) => {
+            target.extend(values)
+        }
+        (VertexAttributeValues::Float32x4(target), VertexAttributeValues::Float32x4(values)) => {
+            target.extend(values)
+        }
+        (VertexAttributeValues::Uint16x4(target), VertexAttributeValues::Uint16x4(values)) => {
+            target.extend(values)
+        }
+        (VertexAttributeValues::Unorm8x4(target), VertexAttributeValues::Unorm8x4(values)) => {
+            target.extend(values)
+        }
+        (VertexAttributeValues::Uint32(target), VertexAttributeValues::Uint32(values)) => {
+            target.extend(values)
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Copy the values of the given vertices, for the attribute formats produced
+/// by this loader.
+pub(crate) fn gather_vertex_attribute(
+    values: &VertexAttributeValues,
+    vertices: &[u32],
+) -> Option<VertexAttributeValues> {
+    fn gather<T: Copy>(values: &[T], vertices: &[u32]) -> Vec<T> {
+        vertices.iter().map(|&v| values[v as usize]).collect()
+    }
+
+    Some(match values {
+        VertexAttributeValues::Float32(values) => {
+            VertexAttributeValues::Float32(gather(values, vertices))
+        }
+        VertexAttributeValues::Float32x2(values) => {
+            VertexAttributeValues::Float32x2(gather(values, vertices))
+        }
+        VertexAttributeValues::Float32x3(values) => {
+            VertexAttributeValues::Float32x3(gather(values, vertices))
+        }
+        VertexAttributeValues::Float32x4(values) => {
+            VertexAttributeValues::Float32x4(gather(values, vertices))
+        }
+        VertexAttributeValues::Uint16x4(values) => {
+            VertexAttributeValues::Uint16x4(gather(values, vertices))
+        }
+        VertexAttributeValues::Unorm8x4(values) => {
+            VertexAttributeValues::Unorm8x4(gather(values, vertices))
+        }
+        VertexAttributeValues::Uint32(values) => {
+            VertexAttributeValues::Uint32(gather(values, vertices))
+        }
+        _ => return None,
+    })
 }
 
 /// Group mesh faces by material index.
+///
+/// The returned indices refer to the mesh's unique vertex positions.
 pub fn group_faces_by_material(mesh: &ufbx::Mesh) -> HashMap<usize, Vec<u32>> {
+    let mut material_groups = group_corners_by_material(mesh);
+    for indices in material_groups.values_mut() {
+        for idx in indices.iter_mut() {
+            *idx = mesh.vertex_indices[*idx as usize];
+        }
+    }
+    material_groups
+}
+
+/// Group triangulated face corners by material index.
+///
+/// The returned indices refer to face corners (ufbx "indices"), which address
+/// per-polygon-vertex attributes such as normals and UVs.
+pub fn group_corners_by_material(mesh: &ufbx::Mesh) -> HashMap<usize, Vec<u32>> {
     let mut material_groups: HashMap<usize, Vec<u32>> = HashMap::new();
     let mut scratch = Vec::new();
 
@@ -83,7 +1058,7 @@ pub fn group_faces_by_material(mesh: &ufbx::Mesh) -> HashMap<usize, Vec<u32>> {
             ufbx::triangulate_face_vec(&mut scratch, mesh, face);
             for idx in &scratch {
                 if (*idx as usize) < mesh.vertex_indices.len() {
-                    all_indices.push(mesh.vertex_indices[*idx as usize]);
+                    all_indices.push(*idx);
                 }
             }
         }
@@ -104,7 +1079,7 @@ pub fn group_faces_by_material(mesh: &ufbx::Mesh) -> HashMap<usize, Vec<u32>> {
             let indices = material_groups.entry(material_idx).or_insert_with(Vec::new);
             for idx in &scratch {
                 if (*idx as usize) < mesh.vertex_indices.len() {
-                    indices.push(mesh.vertex_indices[*idx as usize]);
+                    indices.push(*idx);
                 }
             }
         }
@@ -113,68 +1088,431 @@ pub fn group_faces_by_material(mesh: &ufbx::Mesh) -> HashMap<usize, Vec<u32>> {
     material_groups
 }
 
+/// Vertex streams produced by [`weld_vertices`].
+#[derive(Debug, Clone, Default)]
+pub struct WeldedVertices {
+    /// Unique vertex positions
+    pub positions: Vec<[f32; 3]>,
+    /// Normals matching `positions`, if the mesh has normals
+    pub normals: Option<Vec<[f32; 3]>>,
+    /// UVs matching `positions`, if the mesh has UVs
+    pub uvs: Option<Vec<[f32; 2]>>,
+    /// Tangents matching `positions`, see [`vertex_tangent`]
+    pub tangents: Option<Vec<[f32; 4]>>,
+    /// UVs of the secondary UV set matching `positions`, if one was given
+    pub lightmap_uvs: Option<Vec<[f32; 2]>>,
+    /// Values of each custom layer given, matching `positions`
+    pub custom: Vec<Vec<[f32; 4]>>,
+    /// Source ufbx vertex index of each welded vertex
+    pub source_vertices: Vec<u32>,
+    /// Compact index buffer into the welded vertices
+    pub indices: Vec<u32>,
+}
+
+/// Deduplicate identical position/normal/UV tuples of the given face corners.
+///
+/// Corners whose tangents have opposite bitangent signs are kept apart, so
+/// mirrored UV seams keep their handedness.
+///
+/// Corners belonging to different source vertices are only merged when the
+/// mesh has neither skin nor blend deformers nor a loaded vertex cache, so
+/// skinning weights, blend shape offsets and cached positions stay attached
+/// to their vertices.
+/// `lightmap_uvs` (the [`secondary_uv_set`]) are welded alongside the
+/// primary UVs when given, and
+/// corners only merge if they agree on the per-corner values of each of the
+/// `custom` layers.
+pub fn weld_vertices(
+    mesh: &ufbx::Mesh,
+    corners: &[u32],
+    lightmap_uvs: Option<&ufbx::VertexVec2>,
+    custom: &[Vec<[f32; 4]>],
+    settings: &FbxLoaderSettings,
+) -> WeldedVertices {
+    let keep_source_vertex = !mesh.skin_deformers.is_empty()
+        || !mesh.blend_deformers.is_empty()
+        || has_vertex_cache(mesh, settings);
+    let has_normals = mesh.vertex_normal.exists;
+    let has_uvs = mesh.vertex_uv.exists;
+    let has_tangents = mesh.vertex_tangent.exists;
+
+    let mut welded = WeldedVertices {
+        normals: has_normals.then(Vec::new),
+        uvs: has_uvs.then(Vec::new),
+        tangents: has_tangents.then(Vec::new),
+        lightmap_uvs: lightmap_uvs.map(|_| Vec::new()),
+        custom: vec![Vec::new(); custom.len()],
+        indices: Vec::with_capacity(corners.len()),
+        ..Default::default()
+    };
+    let mut lookup: HashMap<(Option<u32>, [u32; 11], Vec<u32>), u32> = HashMap::new();
+
+    for &corner in corners {
+        let corner = corner as usize;
+        let vertex = mesh.vertex_indices[corner];
+        let p = mesh.vertex_position[corner];
+        let position = [p.x as f32, p.y as f32, p.z as f32];
+        let normal = has_normals.then(|| {
+            let n = mesh.vertex_normal[corner];
+            [n.x as f32, n.y as f32, n.z as f32]
+        });
+        let uv = has_uvs.then(|| {
+            let uv = mesh.vertex_uv[corner];
+            [uv.x as f32, uv.y as f32]
+        });
+
+        let lightmap_uv = lightmap_uvs.map(|uvs| {
+            let uv = uvs[corner];
+            [uv.x as f32, uv.y as f32]
+        });
+        let tangent = vertex_tangent(mesh, corner);
+
+        let n = normal.unwrap_or_default();
+        let t = uv.unwrap_or_default();
+        let l = lightmap_uv.unwrap_or_default();
+        let key = (
+            keep_source_vertex.then_some(vertex),
+            [
+                position[0].to_bits(),
+                position[1].to_bits(),
+                position[2].to_bits(),
+                n[0].to_bits(),
+                n[1].to_bits(),
+                n[2].to_bits(),
+                t[0].to_bits(),
+                t[1].to_bits(),
+                l[0].to_bits(),
+                l[1].to_bits(),
+                tangent.map_or(0.0, |tangent| tangent[3]).to_bits(),
+            ],
+            custom
+                .iter()
+                .flat_map(|values| values[corner].map(f32::to_bits))
+                .collect(),
+        );
+
+        let index = *lookup.entry(key).or_insert_with(|| {
+            welded.positions.push(position);
+            if let (Some(normals), Some(normal)) = (welded.normals.as_mut(), normal) {
+                normals.push(normal);
+            }
+            if let (Some(uvs), Some(uv)) = (welded.uvs.as_mut(), uv) {
+                uvs.push(uv);
+            }
+            if let (Some(tangents), Some(tangent)) = (welded.tangents.as_mut(), tangent) {
+                tangents.push(tangent);
+            }
+            if let (Some(uvs), Some(uv)) = (welded.lightmap_uvs.as_mut(), lightmap_uv) {
+                uvs.push(uv);
+            }
+            for (welded, values) in welded.custom.iter_mut().zip(custom) {
+                welded.push(values[corner]);
+            }
+            welded.source_vertices.push(vertex);
+            (welded.positions.len() - 1) as u32
+        });
+        welded.indices.push(index);
+    }
+
+    welded
+}
+
 /// Create a Bevy mesh from a material group.
+///
+/// When [`FbxLoaderSettings::weld_vertices`] is set, `indices` are face corners
+/// as returned by [`group_corners_by_material`]; otherwise they are vertex
+/// indices as returned by [`group_faces_by_material`].
 pub fn create_mesh_from_group(
     ufbx_mesh: &ufbx::Mesh,
     indices: &[u32],
     mesh_index: usize,
-    material_index: usize,
+    primitive_index: usize,
     settings: &FbxLoaderSettings,
-    load_context: &mut LoadContext,
+    load_context: &mut impl FbxAssetSink,
 ) -> Result<Handle<Mesh>, FbxError> {
-    let label = FbxAssetLabel::Mesh(mesh_index * 1000 + material_index).to_string();
+    let label = FbxAssetLabel::Primitive {
+        mesh: mesh_index,
+        primitive: primitive_index,
+    }
+    .to_string();
 
-    let handle = load_context.labeled_asset_scope(label, |_| {
-        let mut bevy_mesh = Mesh::new(PrimitiveTopology::TriangleList, settings.load_meshes);
+    let bevy_mesh = build_mesh_from_group(ufbx_mesh, indices, settings);
+    Ok(load_context.add_labeled_asset(label, bevy_mesh))
+}
 
-        // Positions
-        let positions: Vec<[f32; 3]> = ufbx_mesh
-            .vertex_position
-            .values
-            .as_ref()
+/// Convert a material group of a ufbx mesh into a Bevy mesh.
+///
+/// `indices` are interpreted as described in [`create_mesh_from_group`].
+pub fn build_mesh_from_group(
+    ufbx_mesh: &ufbx::Mesh,
+    indices: &[u32],
+    settings: &FbxLoaderSettings,
+) -> Mesh {
+    if settings.weld_vertices {
+        return create_welded_mesh(ufbx_mesh, indices, settings);
+    }
+
+    let mut bevy_mesh = Mesh::new(PrimitiveTopology::TriangleList, settings.load_meshes);
+
+    // Positions
+    let positions: Vec<[f32; 3]> = ufbx_mesh
+        .vertex_position
+        .values
+        .as_ref()
+        .iter()
+        .map(|v| [v.x as f32, v.y as f32, v.z as f32])
+        .collect();
+    bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+
+    // Per-corner attributes are sampled at one corner of each vertex, as the
+    // indices refer to vertices
+    let corners = vertex_corners(ufbx_mesh);
+
+    // Normals
+    if ufbx_mesh.vertex_normal.exists {
+        let normals: Vec<[f32; 3]> = corners
             .iter()
-            .map(|v| [v.x as f32, v.y as f32, v.z as f32])
+            .map(|&corner| {
+                let n = ufbx_mesh.vertex_normal[corner];
+                [n.x as f32, n.y as f32, n.z as f32]
+            })
             .collect();
-        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-
-        // Normals
-        if ufbx_mesh.vertex_normal.exists {
-            let normals: Vec<[f32; 3]> = (0..ufbx_mesh.num_vertices)
-                .map(|i| {
-                    let n = ufbx_mesh.vertex_normal[i];
-                    [n.x as f32, n.y as f32, n.z as f32]
-                })
-                .collect();
-            bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        }
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
 
-        // UVs
-        if ufbx_mesh.vertex_uv.exists {
-            let uvs: Vec<[f32; 2]> = (0..ufbx_mesh.num_vertices)
-                .map(|i| {
-                    let uv = ufbx_mesh.vertex_uv[i];
-                    [uv.x as f32, uv.y as f32]
-                })
-                .collect();
-            bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-        }
+    // UVs
+    if ufbx_mesh.vertex_uv.exists {
+        let uvs: Vec<[f32; 2]> = corners
+            .iter()
+            .map(|&corner| {
+                let uv = ufbx_mesh.vertex_uv[corner];
+                [uv.x as f32, uv.y as f32]
+            })
+            .collect();
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
+
+    // Tangents, unless some vertex has none
+    let tangents: Option<Vec<[f32; 4]>> = corners
+        .iter()
+        .map(|&corner| vertex_tangent(ufbx_mesh, corner))
+        .collect();
+    if let Some(tangents) = tangents {
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
+    }
+
+    // Lightmap UVs, or those of textures on a second UV set
+    if let Some(uv_set) = secondary_uv_set(ufbx_mesh, settings) {
+        let uvs: Vec<[f32; 2]> = corners
+            .iter()
+            .map(|&corner| {
+                let uv = uv_set.vertex_uv[corner];
+                [uv.x as f32, uv.y as f32]
+            })
+            .collect();
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, uvs);
+    }
+
+    // Custom attributes
+    for (attribute, values) in mesh_custom_attributes(ufbx_mesh, settings) {
+        let values = corners.iter().map(|&corner| values[corner]);
+        insert_custom_attribute(&mut bevy_mesh, attribute, values);
+    }
+
+    // Skinning
+    if !ufbx_mesh.skin_deformers.is_empty() {
+        process_skinning_data(ufbx_mesh, &mut bevy_mesh);
+    }
+
+    // Blend shapes and vertex caches
+    if has_blend_shapes(ufbx_mesh, settings) || has_vertex_cache(ufbx_mesh, settings) {
+        let source_vertices: Vec<u32> = (0..ufbx_mesh.num_vertices as u32).collect();
+        bevy_mesh.insert_attribute(ATTRIBUTE_SOURCE_VERTEX, source_vertices);
+    }
+
+    // Indices
+    bevy_mesh.insert_indices(Indices::U32(indices.to_vec()));
+
+    bevy_mesh
+}
+
+/// Build a welded Bevy mesh from the face corners of a material group.
+fn create_welded_mesh(
+    ufbx_mesh: &ufbx::Mesh,
+    corners: &[u32],
+    settings: &FbxLoaderSettings,
+) -> Mesh {
+    let secondary_uvs = secondary_uv_set(ufbx_mesh, settings).map(|uv_set| &uv_set.vertex_uv);
+    let (custom_attributes, custom_values): (Vec<_>, Vec<_>) =
+        mesh_custom_attributes(ufbx_mesh, settings)
+            .into_iter()
+            .unzip();
+    let welded = weld_vertices(ufbx_mesh, corners, secondary_uvs, &custom_values, settings);
+    let mut bevy_mesh = Mesh::new(PrimitiveTopology::TriangleList, settings.load_meshes);
+
+    bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, welded.positions);
+    if let Some(normals) = welded.normals {
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
+    if let Some(uvs) = welded.uvs {
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
+    if let Some(tangents) = welded.tangents {
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
+    }
+    if let Some(uvs) = welded.lightmap_uvs {
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, uvs);
+    }
+    for (attribute, values) in custom_attributes.into_iter().zip(welded.custom) {
+        insert_custom_attribute(&mut bevy_mesh, attribute, values.into_iter());
+    }
+
+    // Skinning
+    if !ufbx_mesh.skin_deformers.is_empty() {
+        let (joint_indices, joint_weights) = compute_skinning_data(ufbx_mesh);
+        let joint_indices: Vec<[u16; 4]> = welded
+            .source_vertices
+            .iter()
+            .map(|&v| joint_indices[v as usize])
+            .collect();
+        let joint_weights: Vec<[f32; 4]> = welded
+            .source_vertices
+            .iter()
+            .map(|&v| joint_weights[v as usize])
+            .collect();
+        bevy_mesh.insert_attribute(
+            Mesh::ATTRIBUTE_JOINT_INDEX,
+            VertexAttributeValues::Uint16x4(joint_indices),
+        );
+        bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT, joint_weights);
+    }
+
+    // Blend shapes and vertex caches
+    if has_blend_shapes(ufbx_mesh, settings) || has_vertex_cache(ufbx_mesh, settings) {
+        bevy_mesh.insert_attribute(ATTRIBUTE_SOURCE_VERTEX, welded.source_vertices);
+    }
+
+    bevy_mesh.insert_indices(Indices::U32(welded.indices));
+    bevy_mesh
+}
 
-        // Skinning
-        if !ufbx_mesh.skin_deformers.is_empty() {
-            process_skinning_data(ufbx_mesh, &mut bevy_mesh);
+/// First face corner of each vertex of `mesh`, for sampling per-corner
+/// attributes once per vertex.
+///
+/// Vertices no face uses get corner 0; no index refers to them.
+pub fn vertex_corners(mesh: &ufbx::Mesh) -> Vec<usize> {
+    let mut corners = vec![None; mesh.num_vertices];
+    for (corner, &vertex) in mesh.vertex_indices.as_ref().iter().enumerate() {
+        if let Some(first) = corners.get_mut(vertex as usize) {
+            first.get_or_insert(corner);
         }
+    }
+    corners
+        .into_iter()
+        .map(|corner| corner.unwrap_or(0))
+        .collect()
+}
 
-        // Indices
-        bevy_mesh.insert_indices(Indices::U32(indices.to_vec()));
+/// Tangent of a face corner with the bitangent sign in `w`, if the mesh has
+/// tangents.
+///
+/// Without binormals or normals to compare against, the sign defaults to 1.
+pub fn vertex_tangent(mesh: &ufbx::Mesh, index: usize) -> Option<[f32; 4]> {
+    if !mesh.vertex_tangent.exists {
+        return None;
+    }
+    let t = mesh.vertex_tangent[index];
+    let tangent = Vec3::new(t.x as f32, t.y as f32, t.z as f32);
+    if !mesh.vertex_bitangent.exists || !mesh.vertex_normal.exists {
+        return Some(tangent.normalize_or_zero().extend(1.0).into());
+    }
+    let n = mesh.vertex_normal[index];
+    let b = mesh.vertex_bitangent[index];
+    Some(tangent_with_sign(
+        Vec3::new(n.x as f32, n.y as f32, n.z as f32),
+        tangent,
+        Vec3::new(b.x as f32, b.y as f32, b.z as f32),
+    ))
+}
 
-        Ok::<_, FbxError>(bevy_mesh)
-    })?;
+/// Tangent with the bitangent sign in `w`, as used by Bevy's normal mapping.
+///
+/// Bevy reconstructs the bitangent as `cross(normal, tangent) * w`, while FBX
+/// stores the binormal itself; `w` is -1 where the binormal points against
+/// that cross product, as on the mirrored half of a symmetric mesh.
+pub fn tangent_with_sign(normal: Vec3, tangent: Vec3, binormal: Vec3) -> [f32; 4] {
+    let sign = if normal.cross(tangent).dot(binormal) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    tangent.normalize_or_zero().extend(sign).into()
+}
 
-    Ok(handle)
+/// UV set imported as [`Mesh::ATTRIBUTE_UV_1`]: the one named by
+/// [`FbxLoaderSettings::lightmap_uv_set`] if set, otherwise the first set
+/// after UV0 sampled by a texture of the mesh's materials.
+pub fn secondary_uv_set<'a>(
+    ufbx_mesh: &'a ufbx::Mesh,
+    settings: &FbxLoaderSettings,
+) -> Option<&'a ufbx::UvSet> {
+    let uv_sets = ufbx_mesh.uv_sets.as_ref();
+    if let Some(name) = settings.lightmap_uv_set.as_deref() {
+        return uv_sets
+            .iter()
+            .find(|uv_set| uv_set.name.as_ref() as &str == name);
+    }
+    let sampled = texture_uv_sets(ufbx_mesh);
+    uv_sets
+        .iter()
+        .skip(1)
+        .find(|uv_set| sampled.contains(&(uv_set.name.as_ref() as &str)))
+}
+
+/// Names of the UV sets sampled by textures of the mesh's materials, in
+/// order of first use; textures on the default set are left out.
+pub fn texture_uv_sets(ufbx_mesh: &ufbx::Mesh) -> Vec<&str> {
+    let mut names = Vec::new();
+    for material in mesh_materials(ufbx_mesh) {
+        for texture_ref in material.textures.as_ref() {
+            let name = texture_uv_set(&texture_ref.texture);
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// UV sets sampled by textures of the mesh's materials that are imported
+/// as neither [`Mesh::ATTRIBUTE_UV_0`] nor [`Mesh::ATTRIBUTE_UV_1`]; such
+/// textures sample the wrong coordinates.
+pub fn missing_uv_sets<'a>(
+    ufbx_mesh: &'a ufbx::Mesh,
+    settings: &FbxLoaderSettings,
+) -> Vec<&'a str> {
+    let name = |uv_set: &'a ufbx::UvSet| uv_set.name.as_ref() as &str;
+    let primary = ufbx_mesh.uv_sets.as_ref().first().map(name);
+    let secondary = secondary_uv_set(ufbx_mesh, settings).map(name);
+    texture_uv_sets(ufbx_mesh)
+        .into_iter()
+        .filter(|&name| Some(name) != primary && Some(name) != secondary)
+        .collect()
 }
 
 /// Process skinning data for a mesh.
 pub fn process_skinning_data(ufbx_mesh: &ufbx::Mesh, bevy_mesh: &mut Mesh) {
+    let (joint_indices, joint_weights) = compute_skinning_data(ufbx_mesh);
+
+    bevy_mesh.insert_attribute(
+        Mesh::ATTRIBUTE_JOINT_INDEX,
+        VertexAttributeValues::Uint16x4(joint_indices),
+    );
+    bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT, joint_weights);
+}
+
+/// Compute per-vertex joint indices and normalized weights for a skinned mesh.
+pub fn compute_skinning_data(ufbx_mesh: &ufbx::Mesh) -> (Vec<[u16; 4]>, Vec<[f32; 4]>) {
     let skin_deformer = &ufbx_mesh.skin_deformers[0];
     let mut joint_indices = vec![[0u16; 4]; ufbx_mesh.num_vertices];
     let mut joint_weights = vec![[0.0f32; 4]; ufbx_mesh.num_vertices];
@@ -210,9 +1548,5 @@ pub fn process_skinning_data(ufbx_mesh: &ufbx::Mesh, bevy_mesh: &mut Mesh) {
         }
     }
 
-    bevy_mesh.insert_attribute(
-        Mesh::ATTRIBUTE_JOINT_INDEX,
-        VertexAttributeValues::Uint16x4(joint_indices),
-    );
-    bevy_mesh.insert_attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT, joint_weights);
+    (joint_indices, joint_weights)
 }
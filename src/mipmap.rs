@@ -0,0 +1,142 @@
+//! Mipmap generation for textures referenced by FBX files.
+//!
+//! Bevy's image loader doesn't build mip chains, so textures stored as PNG
+//! or JPEG shimmer when minified. With
+//! [`FbxLoaderSettings::generate_mipmaps`](crate::FbxLoaderSettings::generate_mipmaps),
+//! the chains are built on the CPU once the images have loaded. Textures
+//! stored as KTX2 or DDS keep the mips they were authored with.
+
+use crate::types::Fbx;
+use bevy::image::Image;
+use bevy::prelude::*;
+use bevy::render::render_resource::{TextureDimension, TextureFormat};
+
+/// Append a box-filtered mip chain to `image`.
+///
+/// Supports single-layer 2D images in `R8Unorm`, `Rg8Unorm`, `Rgba8Unorm`
+/// and `Rgba8UnormSrgb`, filtering sRGB colors in linear space. Returns
+/// `false`, leaving the image unchanged, for other images and for images
+/// that already have mips or no CPU data.
+pub fn generate_mipmaps(image: &mut Image) -> bool {
+    let descriptor = &image.texture_descriptor;
+    let channels = match descriptor.format {
+        TextureFormat::R8Unorm => 1,
+        TextureFormat::Rg8Unorm => 2,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => 4,
+        _ => return false,
+    };
+    let srgb = descriptor.format == TextureFormat::Rgba8UnormSrgb;
+    let (mut width, mut height) = (descriptor.size.width, descriptor.size.height);
+    if descriptor.dimension != TextureDimension::D2
+        || descriptor.size.depth_or_array_layers != 1
+        || descriptor.mip_level_count != 1
+        || width.max(height) <= 1
+    {
+        return false;
+    }
+    let Some(data) = image.data.as_mut() else {
+        return false;
+    };
+    if data.len() != (width * height) as usize * channels {
+        return false;
+    }
+
+    let mut level_start = 0;
+    let mut level_count = 1;
+    while width > 1 || height > 1 {
+        let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+        let level = downsample(
+            &data[level_start..],
+            (width as usize, height as usize),
+            (next_width as usize, next_height as usize),
+            channels,
+            srgb,
+        );
+        level_start = data.len();
+        data.extend_from_slice(&level);
+        (width, height) = (next_width, next_height);
+        level_count += 1;
+    }
+    image.texture_descriptor.mip_level_count = level_count;
+    true
+}
+
+/// Average 2x2 blocks of `source` into an image of size `target`, clamping
+/// at the edges of odd-sized levels.
+fn downsample(
+    source: &[u8],
+    (width, height): (usize, usize),
+    (target_width, target_height): (usize, usize),
+    channels: usize,
+    srgb: bool,
+) -> Vec<u8> {
+    let mut level = Vec::with_capacity(target_width * target_height * channels);
+    for y in 0..target_height {
+        let rows = [(y * 2).min(height - 1), (y * 2 + 1).min(height - 1)];
+        for x in 0..target_width {
+            let columns = [(x * 2).min(width - 1), (x * 2 + 1).min(width - 1)];
+            for channel in 0..channels {
+                // Alpha is always linear
+                let color = srgb && channel < 3;
+                let mut sum = 0.0;
+                for row in rows {
+                    for column in columns {
+                        let value = source[(row * width + column) * channels + channel];
+                        let value = value as f32 / 255.0;
+                        sum += if color { srgb_to_linear(value) } else { value };
+                    }
+                }
+                let average = sum / 4.0;
+                let average = if color {
+                    linear_to_srgb(average)
+                } else {
+                    average
+                };
+                level.push((average * 255.0).round().clamp(0.0, 255.0) as u8);
+            }
+        }
+    }
+    level
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Generate mips for the [`Fbx::mipmap_images`] of every FBX asset that
+/// finished loading with its dependencies.
+pub fn generate_fbx_mipmaps(
+    mut events: MessageReader<AssetEvent<Fbx>>,
+    fbx_assets: Res<Assets<Fbx>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+        let Some(fbx) = fbx_assets.get(*id) else {
+            continue;
+        };
+        for handle in &fbx.mipmap_images {
+            // Images shared between files already have their mips
+            let needs_mips = images
+                .get(handle)
+                .is_some_and(|image| image.texture_descriptor.mip_level_count == 1);
+            if needs_mips && let Some(image) = images.get_mut(handle) {
+                generate_mipmaps(image);
+            }
+        }
+    }
+}
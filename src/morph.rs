@@ -0,0 +1,199 @@
+//! Blend shape import as Bevy morph targets.
+//!
+//! Every keyframe of a blend channel becomes its own morph target, so
+//! in-between shapes (e.g. a half-closed eyelid at 50%) are kept instead of
+//! only the full shape. [`in_between_weights`] maps a channel weight onto the
+//! weights of the channel's targets.
+
+use crate::types::FbxBlendChannel;
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::morph::{MorphAttributes, MorphBuildError, MorphTargetImage};
+use bevy::prelude::*;
+
+/// Blend channels of all blend deformers of a mesh, in morph target order.
+pub fn blend_channels(mesh: &ufbx::Mesh) -> Vec<FbxBlendChannel> {
+    let mut channels = Vec::new();
+    let mut first_target = 0;
+    for deformer in mesh.blend_deformers.as_ref().iter() {
+        for channel in deformer.channels.as_ref().iter() {
+            let target_weights: Vec<f32> = channel
+                .keyframes
+                .as_ref()
+                .iter()
+                .map(|keyframe| keyframe.target_weight as f32)
+                .collect();
+            let target_count = target_weights.len();
+            channels.push(FbxBlendChannel {
+                name: channel.element.name.to_string(),
+                first_target,
+                target_weights,
+                weight: channel.weight as f32,
+            });
+            first_target += target_count;
+        }
+    }
+    channels
+}
+
+/// Names of the morph targets of a mesh, matching [`blend_shape_offsets`].
+///
+/// The full shape of a channel uses the channel name; in-between shapes get
+/// their shape name, falling back to the channel name and target percentage.
+pub fn morph_target_names(mesh: &ufbx::Mesh) -> Vec<String> {
+    let mut names = Vec::new();
+    for deformer in mesh.blend_deformers.as_ref().iter() {
+        for channel in deformer.channels.as_ref().iter() {
+            let keyframes = channel.keyframes.as_ref();
+            for (index, keyframe) in keyframes.iter().enumerate() {
+                let shape_name: &str = keyframe.shape.element.name.as_ref();
+                let name = if index + 1 == keyframes.len() {
+                    channel.element.name.to_string()
+                } else if !shape_name.is_empty() {
+                    shape_name.to_string()
+                } else {
+                    format!(
+                        "{}_{}",
+                        channel.element.name.as_ref() as &str,
+                        (keyframe.target_weight * 100.0).round()
+                    )
+                };
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Per-vertex offsets of every morph target of a mesh, indexed by ufbx
+/// vertex.
+pub fn blend_shape_offsets(mesh: &ufbx::Mesh) -> Vec<Vec<MorphAttributes>> {
+    let mut targets = Vec::new();
+    for deformer in mesh.blend_deformers.as_ref().iter() {
+        for channel in deformer.channels.as_ref().iter() {
+            for keyframe in channel.keyframes.as_ref().iter() {
+                let shape = &keyframe.shape;
+                let mut offsets = vec![MorphAttributes::default(); mesh.num_vertices];
+                for (i, &vertex) in shape.offset_vertices.as_ref().iter().enumerate() {
+                    let Some(offset) = offsets.get_mut(vertex as usize) else {
+                        continue;
+                    };
+                    if let Some(p) = shape.position_offsets.as_ref().get(i) {
+                        offset.position = Vec3::new(p.x as f32, p.y as f32, p.z as f32);
+                    }
+                    if let Some(n) = shape.normal_offsets.as_ref().get(i) {
+                        offset.normal = Vec3::new(n.x as f32, n.y as f32, n.z as f32);
+                    }
+                }
+                targets.push(offsets);
+            }
+        }
+    }
+    targets
+}
+
+/// Build the morph target image of a primitive.
+///
+/// `source_vertices` lists the ufbx vertex of each primitive vertex. Fails for
+/// meshes with more targets than Bevy supports.
+pub fn build_morph_targets(
+    targets: &[Vec<MorphAttributes>],
+    source_vertices: &[u32],
+    asset_usage: RenderAssetUsages,
+) -> Result<Image, MorphBuildError> {
+    let primitive_targets = targets.iter().map(|offsets| {
+        source_vertices
+            .iter()
+            .map(|&vertex| offsets.get(vertex as usize).copied().unwrap_or_default())
+    });
+    MorphTargetImage::new(primitive_targets, source_vertices.len(), asset_usage)
+        .map(|image| image.0)
+}
+
+/// Morph weight keys for animated blend channel weights.
+///
+/// `channel_keys` holds the `(time, weight)` keys of each of `channels`,
+/// empty for channels that are not animated, which keep their default
+/// weight. Channels are sampled linearly at the union of all key times.
+/// Returns the key times and, per time, the weight of every morph target;
+/// `None` if no channel has keys at two different times.
+pub fn morph_weight_keys(
+    channels: &[FbxBlendChannel],
+    channel_keys: &[Vec<(f32, f32)>],
+) -> Option<(Vec<f32>, Vec<f32>)> {
+    let mut times: Vec<f32> = channel_keys
+        .iter()
+        .flatten()
+        .map(|&(time, _)| time)
+        .collect();
+    times.sort_by(f32::total_cmp);
+    times.dedup();
+    if times.len() < 2 {
+        return None;
+    }
+
+    let mut weights = Vec::new();
+    for &time in &times {
+        for (index, channel) in channels.iter().enumerate() {
+            let keys = channel_keys.get(index).map_or(&[][..], Vec::as_slice);
+            let weight = sample_weight(keys, time).unwrap_or(channel.weight);
+            weights.extend(channel.target_weights_at(weight));
+        }
+    }
+    Some((times, weights))
+}
+
+/// Linearly interpolated value of ascending `(time, value)` keys, clamped to
+/// the first and last key.
+fn sample_weight(keys: &[(f32, f32)], time: f32) -> Option<f32> {
+    let next = keys.partition_point(|&(key_time, _)| key_time <= time);
+    if next == 0 || next == keys.len() {
+        let (_, value) = keys.get(next.saturating_sub(1))?;
+        return Some(*value);
+    }
+    let ((start, a), (end, b)) = (keys[next - 1], keys[next]);
+    let t = if end > start {
+        (time - start) / (end - start)
+    } else {
+        1.0
+    };
+    Some(a + (b - a) * t)
+}
+
+/// Weights of the targets of a blend channel at channel weight `weight`.
+///
+/// `target_weights` are the ascending channel weights at which each target
+/// is fully applied. Between two targets the weight is split linearly, so
+/// with targets at `[0.5, 1.0]` a channel weight of `0.75` gives
+/// `[0.5, 0.5]`. Below the first target, its weight scales linearly from
+/// zero; above the last, the last target keeps scaling.
+pub fn in_between_weights(target_weights: &[f32], weight: f32) -> Vec<f32> {
+    let mut weights = vec![0.0; target_weights.len()];
+    let Some(&first) = target_weights.first() else {
+        return weights;
+    };
+
+    match target_weights.iter().position(|&target| weight <= target) {
+        Some(0) => {
+            if first != 0.0 {
+                weights[0] = weight / first;
+            }
+        }
+        Some(next) => {
+            let (low, high) = (target_weights[next - 1], target_weights[next]);
+            let t = if high > low {
+                (weight - low) / (high - low)
+            } else {
+                1.0
+            };
+            weights[next - 1] = 1.0 - t;
+            weights[next] = t;
+        }
+        None => {
+            let last = target_weights.len() - 1;
+            if target_weights[last] != 0.0 {
+                weights[last] = weight / target_weights[last];
+            }
+        }
+    }
+    weights
+}
@@ -1,19 +1,30 @@
 //! Node and hierarchy processing for FBX files.
 
+use crate::bind_pose::{bone_node_ids, extract_bind_pose};
 use crate::error::FbxError;
-use crate::label::FbxAssetLabel;
-use crate::types::{FbxNode, FbxSkin};
+use crate::label::{FbxAssetLabel, label_names};
+use crate::loader::FbxLoaderSettings;
+use crate::mesh::compute_skinning_data;
+use crate::sink::FbxAssetSink;
+use crate::types::{FbxMesh, FbxNode, FbxSkin, FbxTransformData};
 use crate::utils::{convert_matrix, convert_transform};
-use bevy::asset::{Handle, LoadContext};
-use bevy::prelude::*;
+use bevy::asset::Handle;
+use bevy::math::DVec3;
 use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
+use bevy::prelude::*;
 use std::collections::HashMap;
 
 /// Process nodes and build hierarchy.
+///
+/// `world_offset` is the point the scene was re-originated to, added back to
+/// [`FbxNode::world_translation`]. The hierarchy is first checked with
+/// [`FbxLoadLimits::check_hierarchy`](crate::FbxLoadLimits::check_hierarchy).
 pub fn process_nodes(
     scene: &ufbx::Scene,
-    meshes: &[Handle<Mesh>],
-    load_context: &mut LoadContext,
+    meshes: &[Handle<FbxMesh>],
+    world_offset: DVec3,
+    settings: &FbxLoaderSettings,
+    load_context: &mut impl FbxAssetSink,
 ) -> Result<
     (
         Vec<Handle<FbxNode>>,
@@ -22,9 +33,19 @@ pub fn process_nodes(
     ),
     FbxError,
 > {
+    settings.limits.check_hierarchy(scene)?;
+
     let mut nodes = Vec::new();
     let mut named_nodes = HashMap::new();
     let mut node_map = HashMap::new();
+    let names = label_names(
+        settings,
+        scene
+            .nodes
+            .as_ref()
+            .iter()
+            .map(|node| node.element.name.as_ref() as &str),
+    );
 
     // First pass: create nodes
     for (index, ufbx_node) in scene.nodes.as_ref().iter().enumerate() {
@@ -34,11 +55,11 @@ pub fn process_nodes(
             ufbx_node.element.name.to_string()
         };
 
-        let mesh_handle = if ufbx_node.mesh.is_some() && index < meshes.len() {
-            Some(meshes[index].clone())
-        } else {
-            None
-        };
+        let mesh_handle = ufbx_node
+            .mesh
+            .as_ref()
+            .and_then(|mesh| meshes.get(mesh.element.typed_id as usize))
+            .cloned();
 
         let fbx_node = FbxNode {
             index,
@@ -47,11 +68,17 @@ pub fn process_nodes(
             mesh: mesh_handle,
             skin: None,
             transform: convert_transform(&ufbx_node.local_transform),
+            world_translation: world_translation(ufbx_node) + world_offset,
+            transform_data: transform_data(ufbx_node),
+            geometry_transform: convert_transform(&ufbx_node.geometry_transform),
+            geometry_to_world: convert_matrix(&ufbx_node.geometry_to_world),
             visible: ufbx_node.visible,
+            visibility_keyframes: visibility_keyframes(scene, ufbx_node),
         };
 
+        let name = names.get(index).and_then(Option::as_deref);
         let handle =
-            load_context.add_labeled_asset(FbxAssetLabel::Node(index).to_string(), fbx_node);
+            load_context.add_named_labeled_asset(FbxAssetLabel::Node(index), name, fbx_node);
 
         node_map.insert(ufbx_node.element.element_id, handle.clone());
         nodes.push(handle.clone());
@@ -68,14 +95,163 @@ pub fn process_nodes(
     Ok((nodes, named_nodes, node_map))
 }
 
+/// Node indices of each selection set, keyed by set name.
+///
+/// Maya selection sets and object groups are exported as FBX selection sets.
+/// Nodes are listed once per set, in node order; sets without nodes are
+/// skipped.
+pub fn process_node_groups(scene: &ufbx::Scene) -> HashMap<Box<str>, Vec<usize>> {
+    let mut groups = HashMap::new();
+    for set in scene.selection_sets.as_ref().iter() {
+        let mut nodes: Vec<usize> = set
+            .nodes
+            .as_ref()
+            .iter()
+            .filter_map(|selection| selection.target_node.as_ref())
+            .map(|node| node.element.typed_id as usize)
+            .collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+        if !nodes.is_empty() {
+            groups.insert(Box::from(set.element.name.as_ref()), nodes);
+        }
+    }
+    groups
+}
+
+/// Sorted names of the groups each node belongs to, keyed by node index.
+pub fn node_group_names(groups: &HashMap<Box<str>, Vec<usize>>) -> HashMap<usize, Vec<String>> {
+    let mut names: HashMap<usize, Vec<String>> = HashMap::new();
+    for (group, nodes) in groups {
+        for &node in nodes {
+            names.entry(node).or_default().push(group.to_string());
+        }
+    }
+    for groups in names.values_mut() {
+        groups.sort_unstable();
+    }
+    names
+}
+
+/// World position of a node in f64.
+pub fn world_translation(node: &ufbx::Node) -> DVec3 {
+    let m = &node.node_to_world;
+    DVec3::new(m.m03, m.m13, m.m23)
+}
+
+/// Read the transform properties of a node as written in the file.
+///
+/// Properties missing from the file keep their FBX defaults.
+pub fn transform_data(node: &ufbx::Node) -> FbxTransformData {
+    let defaults = FbxTransformData::default();
+    let find_vec3 = |name: &str, default: Vec3| {
+        node.element
+            .props
+            .props
+            .as_ref()
+            .iter()
+            .find(|prop| prop.name.as_ref() as &str == name)
+            .map_or(default, |prop| {
+                let v = prop.value_vec3;
+                Vec3::new(v.x as f32, v.y as f32, v.z as f32)
+            })
+    };
+    FbxTransformData {
+        translation: find_vec3("Lcl Translation", defaults.translation),
+        rotation: find_vec3("Lcl Rotation", defaults.rotation),
+        scale: find_vec3("Lcl Scaling", defaults.scale),
+        rotation_order: node.rotation_order.into(),
+        pre_rotation: find_vec3("PreRotation", defaults.pre_rotation),
+        post_rotation: find_vec3("PostRotation", defaults.post_rotation),
+        rotation_pivot: find_vec3("RotationPivot", defaults.rotation_pivot),
+        rotation_offset: find_vec3("RotationOffset", defaults.rotation_offset),
+        scaling_pivot: find_vec3("ScalingPivot", defaults.scaling_pivot),
+        scaling_offset: find_vec3("ScalingOffset", defaults.scaling_offset),
+        geometric_translation: find_vec3("GeometricTranslation", defaults.geometric_translation),
+        geometric_rotation: find_vec3("GeometricRotation", defaults.geometric_rotation),
+        geometric_scale: find_vec3("GeometricScaling", defaults.geometric_scale),
+    }
+}
+
+/// Whether a node and all of its ancestors are visible.
+pub fn is_visible_in_hierarchy(node: &ufbx::Node) -> bool {
+    let mut current = Some(node);
+    while let Some(node) = current {
+        if !node.visible {
+            return false;
+        }
+        current = node.parent.as_deref();
+    }
+    true
+}
+
+/// Extract animated visibility of a node as `(time in seconds, visible)` keys.
+///
+/// Only the default animation is considered; keys are stepped, so any value
+/// above 0.5 counts as visible.
+pub fn visibility_keyframes(scene: &ufbx::Scene, node: &ufbx::Node) -> Vec<(f32, bool)> {
+    let mut keyframes = Vec::new();
+
+    for layer in scene.anim.layers.as_ref().iter() {
+        for anim_prop in layer.anim_props.as_ref().iter() {
+            if anim_prop.element.element_id != node.element.element_id
+                || anim_prop.prop_name.as_ref() != "Visibility"
+            {
+                continue;
+            }
+            let Some(curve) = anim_prop.anim_value.curves[0].as_ref() else {
+                continue;
+            };
+            keyframes.extend(
+                curve
+                    .keyframes
+                    .as_ref()
+                    .iter()
+                    .map(|key| (key.time as f32, key.value > 0.5)),
+            );
+        }
+    }
+
+    keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+    keyframes
+}
+
+/// Skins of a file, from [`process_skins`].
+pub struct ProcessedSkins {
+    pub skins: Vec<Handle<FbxSkin>>,
+    pub named_skins: HashMap<Box<str>, Handle<FbxSkin>>,
+    /// Inverse bind poses of each primitive of a skinned mesh node, keyed by
+    /// node element id and indexed like [`FbxMesh::primitives`]
+    pub primitive_bindposes: HashMap<u32, Vec<Handle<SkinnedMeshInverseBindposes>>>,
+}
+
 /// Process skins for skeletal animation.
+///
+/// Primitives split by joints get their own `Skin_{}_InverseBindposes_{}`
+/// asset, holding the inverse bind poses of their
+/// [`joints`](crate::types::FbxPrimitive::joints) in order.
 pub fn process_skins(
     scene: &ufbx::Scene,
+    meshes: &[FbxMesh],
     node_map: &HashMap<u32, Handle<FbxNode>>,
-    load_context: &mut LoadContext,
-) -> Result<(Vec<Handle<FbxSkin>>, HashMap<Box<str>, Handle<FbxSkin>>), FbxError> {
+    settings: &FbxLoaderSettings,
+    load_context: &mut impl FbxAssetSink,
+) -> Result<ProcessedSkins, FbxError> {
     let mut skins = Vec::new();
     let mut named_skins = HashMap::new();
+    let mut primitive_bindposes = HashMap::new();
+    // Skins are named after their node, like `FbxSkin::name`
+    let skin_names: Vec<String> = scene
+        .nodes
+        .as_ref()
+        .iter()
+        .map(|node| match node.element.name.as_ref() as &str {
+            "" => String::new(),
+            name => format!("{name}_Skin"),
+        })
+        .collect();
+    let names = label_names(settings, skin_names.iter().map(String::as_str));
+    let bone_nodes = bone_node_ids(scene);
 
     for (skin_index, node) in scene.nodes.as_ref().iter().enumerate() {
         let Some(mesh_ref) = &node.mesh else {
@@ -92,8 +268,9 @@ pub fn process_skins(
         let mut joint_handles = Vec::new();
 
         for cluster in &skin_deformer.clusters {
-            let bind_matrix = convert_matrix(&cluster.bind_to_world);
-            inverse_bind_matrices.push(bind_matrix.inverse());
+            // Vertices are in geometry space, which only matches world space
+            // when the mesh sits at the origin in the converted scene
+            inverse_bind_matrices.push(convert_matrix(&cluster.geometry_to_bone));
 
             if let Some(bone_node) = cluster.bone_node.as_ref() {
                 if let Some(joint_handle) = node_map.get(&bone_node.element.element_id) {
@@ -105,8 +282,27 @@ pub fn process_skins(
         if !inverse_bind_matrices.is_empty() {
             let inverse_bindposes_handle = load_context.add_labeled_asset(
                 format!("Skin_{}_InverseBindposes", skin_index),
-                SkinnedMeshInverseBindposes::from(inverse_bind_matrices),
+                SkinnedMeshInverseBindposes::from(inverse_bind_matrices.clone()),
             );
+            let primitives = meshes
+                .get(mesh.element.typed_id as usize)
+                .map_or(&[][..], |fbx_mesh| &fbx_mesh.primitives);
+            let bindposes = primitives
+                .iter()
+                .map(|primitive| match &primitive.joints {
+                    Some(joints) => load_context.add_labeled_asset(
+                        format!("Skin_{}_InverseBindposes_{}", skin_index, primitive.index),
+                        SkinnedMeshInverseBindposes::from(
+                            joints
+                                .iter()
+                                .map(|&joint| inverse_bind_matrices[joint as usize])
+                                .collect::<Vec<_>>(),
+                        ),
+                    ),
+                    None => inverse_bindposes_handle.clone(),
+                })
+                .collect();
+            primitive_bindposes.insert(node.element.element_id, bindposes);
 
             let skin_name = if node.element.name.is_empty() {
                 format!("Skin_{}", skin_index)
@@ -114,15 +310,46 @@ pub fn process_skins(
                 format!("{}_Skin", node.element.name)
             };
 
+            let label_name = names.get(skin_index).and_then(Option::as_deref);
+            let bind_pose = load_context.add_named_labeled_asset(
+                FbxAssetLabel::BindPose(skin_index),
+                label_name,
+                extract_bind_pose(skin_deformer, &bone_nodes),
+            );
+
+            let joint_names = skin_deformer
+                .clusters
+                .as_ref()
+                .iter()
+                .map(|cluster| {
+                    cluster
+                        .bone_node
+                        .as_ref()
+                        .map_or_else(String::new, |bone| bone.element.name.to_string())
+                })
+                .collect();
+            let (vertex_joints, vertex_weights) = if settings.keep_skin_weights {
+                compute_skinning_data(mesh)
+            } else {
+                (Vec::new(), Vec::new())
+            };
+
             let fbx_skin = FbxSkin {
                 index: skin_index,
                 name: skin_name.clone(),
                 joints: joint_handles,
+                joint_names,
+                vertex_joints,
+                vertex_weights,
                 inverse_bind_matrices: inverse_bindposes_handle,
+                bind_pose,
             };
 
-            let handle = load_context
-                .add_labeled_asset(FbxAssetLabel::Skin(skin_index).to_string(), fbx_skin);
+            let handle = load_context.add_named_labeled_asset(
+                FbxAssetLabel::Skin(skin_index),
+                label_name,
+                fbx_skin,
+            );
 
             skins.push(handle.clone());
             if !skin_name.starts_with("Skin_") {
@@ -131,5 +358,9 @@ pub fn process_skins(
         }
     }
 
-    Ok((skins, named_skins))
+    Ok(ProcessedSkins {
+        skins,
+        named_skins,
+        primitive_bindposes,
+    })
 }
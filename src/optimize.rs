@@ -0,0 +1,149 @@
+//! Mesh optimization passes.
+//!
+//! [`merge_by_material`] works on an already-loaded [`Fbx`]; the loader runs
+//! [`merge_scene_by_material`] when
+//! [`FbxLoaderSettings::merge_by_material`] is set. Unlike
+//! [`FbxLoaderSettings::flatten_static_hierarchy`], neither changes the
+//! spawned scene.
+
+use crate::label::FbxAssetLabel;
+use crate::loader::FbxLoaderSettings;
+use crate::mesh::{ConvertedPrimitive, convert_mesh_primitives, merge_meshes};
+use crate::sink::FbxAssetSink;
+use crate::types::{Fbx, FbxMergedMesh, FbxMesh, FbxNode};
+use crate::utils::convert_matrix;
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::PrimitiveTopology;
+use bevy::pbr::StandardMaterial;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Merge the primitives of every mesh instance of a loaded FBX file into one
+/// mesh per material, with the node transforms baked in.
+///
+/// Hidden nodes, skinned, morphed and cached meshes, and line or point primitives are
+/// skipped, as are primitives whose data is no longer in `meshes` because
+/// they were loaded without [`RenderAssetUsages::MAIN_WORLD`]. Primitives are
+/// grouped by their mesh material, since per-node material bindings are not
+/// kept in the assets.
+pub fn merge_by_material(
+    fbx: &Fbx,
+    nodes: &Assets<FbxNode>,
+    fbx_meshes: &Assets<FbxMesh>,
+    meshes: &mut Assets<Mesh>,
+) -> Vec<FbxMergedMesh> {
+    let mut instances = Vec::new();
+    for node in fbx.nodes.iter().filter_map(|handle| nodes.get(handle)) {
+        let Some(fbx_mesh) = node.mesh.as_ref().and_then(|handle| fbx_meshes.get(handle)) else {
+            continue;
+        };
+        if !node.visible || !fbx_mesh.blend_channels.is_empty() || fbx_mesh.vertex_cache.is_some() {
+            continue;
+        }
+        for primitive in &fbx_mesh.primitives {
+            let Some(mesh) = meshes.get(&primitive.mesh) else {
+                continue;
+            };
+            if mesh.attribute(Mesh::ATTRIBUTE_JOINT_INDEX).is_some() {
+                continue;
+            }
+            instances.push((primitive.material.clone(), mesh, node.geometry_to_world));
+        }
+    }
+
+    let asset_usage = instances
+        .first()
+        .map_or_else(RenderAssetUsages::default, |(_, mesh, _)| mesh.asset_usage);
+    let merged = merge_instances(instances, asset_usage);
+    merged
+        .into_iter()
+        .map(|(material, mesh)| FbxMergedMesh {
+            mesh: meshes.add(mesh),
+            material,
+        })
+        .collect()
+}
+
+/// Merge the primitives of every mesh instance into `MergedMesh{}` assets,
+/// one per material, for [`FbxLoaderSettings::merge_by_material`].
+///
+/// Follows the rules of [`merge_by_material`]; meshes are converted again
+/// since their data was already handed to `load_context`.
+pub fn merge_scene_by_material(
+    scene: &ufbx::Scene,
+    fbx_meshes: &[FbxMesh],
+    settings: &FbxLoaderSettings,
+    load_context: &mut impl FbxAssetSink,
+) -> Vec<FbxMergedMesh> {
+    let mut converted: HashMap<usize, Vec<ConvertedPrimitive>> = HashMap::new();
+    // Material, mesh index, primitive index and transform of each instance
+    let mut instances = Vec::new();
+    for node in scene.nodes.as_ref().iter() {
+        let Some(mesh_ref) = node.mesh.as_ref() else {
+            continue;
+        };
+        let mesh_index = mesh_ref.element.typed_id as usize;
+        let Some(fbx_mesh) = fbx_meshes.get(mesh_index) else {
+            continue;
+        };
+        if !node.visible
+            || !fbx_mesh.blend_channels.is_empty()
+            || fbx_mesh.vertex_cache.is_some()
+            || !mesh_ref.skin_deformers.as_ref().is_empty()
+        {
+            continue;
+        }
+        converted
+            .entry(mesh_index)
+            .or_insert_with(|| convert_mesh_primitives(mesh_ref, settings));
+        let matrix = convert_matrix(&node.geometry_to_world);
+        for primitive in &fbx_mesh.primitives {
+            instances.push((
+                primitive.material.clone(),
+                mesh_index,
+                primitive.index,
+                matrix,
+            ));
+        }
+    }
+
+    let instances = instances
+        .into_iter()
+        .filter_map(|(material, mesh, primitive, matrix)| {
+            let (_, _, mesh) = converted.get(&mesh)?.get(primitive)?;
+            Some((material, mesh, matrix))
+        })
+        .collect();
+    merge_instances(instances, settings.load_meshes)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (material, mesh))| {
+            let label = FbxAssetLabel::MergedMesh(index).to_string();
+            FbxMergedMesh {
+                mesh: load_context.add_labeled_asset(label, mesh),
+                material,
+            }
+        })
+        .collect()
+}
+
+/// Merge the triangle meshes sharing a material, in order of first use.
+fn merge_instances(
+    instances: Vec<(Option<Handle<StandardMaterial>>, &Mesh, Mat4)>,
+    asset_usage: RenderAssetUsages,
+) -> Vec<(Option<Handle<StandardMaterial>>, Mesh)> {
+    let mut groups: Vec<(Option<Handle<StandardMaterial>>, Vec<(&Mesh, Mat4)>)> = Vec::new();
+    for (material, mesh, matrix) in instances {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            continue;
+        }
+        match groups.iter_mut().find(|group| group.0 == material) {
+            Some((_, meshes)) => meshes.push((mesh, matrix)),
+            None => groups.push((material, vec![(mesh, matrix)])),
+        }
+    }
+    groups
+        .into_iter()
+        .filter_map(|(material, meshes)| Some((material, merge_meshes(&meshes, asset_usage)?)))
+        .collect()
+}
@@ -0,0 +1,105 @@
+//! Cache of parsed ufbx scenes, shared between loads of the same file.
+//!
+//! Editors and tools often load one FBX file several times, e.g. after
+//! changing its settings or through repeated
+//! [`Fbx::from_bytes`](crate::Fbx::from_bytes) calls, and each load parses
+//! the whole file again. With
+//! [`FbxLoaderSettings::parse_cache_size`](crate::FbxLoaderSettings::parse_cache_size)
+//! set, parsed scenes are kept and reused by later loads of identical data
+//! with identical parse options.
+//!
+//! Parsed scenes are immutable, so one cache is shared by every loading
+//! thread, evicting the least recently used scene once it holds more than the
+//! configured number. Cached scenes are reference counted and stay alive while
+//! a load still uses them, even after being evicted.
+
+use crate::external::FbxExternalFiles;
+use crate::hash::FbxContentHasher;
+use crate::loader::FbxLoaderSettings;
+use bevy::math::DVec3;
+use std::collections::VecDeque;
+use std::hash::Hasher;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Most recently used scene first.
+static PARSED_SCENES: Mutex<VecDeque<(FbxParseKey, Arc<ufbx::SceneRoot>)>> =
+    Mutex::new(VecDeque::new());
+
+fn parsed_scenes() -> MutexGuard<'static, VecDeque<(FbxParseKey, Arc<ufbx::SceneRoot>)>> {
+    // The cache stays consistent if a load panicked while holding it
+    PARSED_SCENES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Identity of a parse: the file contents and every option affecting what
+/// ufbx produces from them.
+///
+/// A changed file hashes differently, so stale scenes are never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FbxParseKey {
+    pub content_hash: u64,
+    pub options_hash: u64,
+}
+
+impl FbxParseKey {
+    pub fn new(
+        bytes: &[u8],
+        settings: &FbxLoaderSettings,
+        external_files: &FbxExternalFiles,
+        world_offset: DVec3,
+    ) -> Self {
+        let mut content = FbxContentHasher::default();
+        content.write(bytes);
+        if settings.load_external_files {
+            for (name, contents) in external_files.sorted() {
+                content.write_str(name);
+                content.write(contents);
+            }
+        }
+
+        let mut options = FbxContentHasher::default();
+        options.write_str(&format!("{:?}", settings.geometry_transform_handling));
+        options.write_str(&format!("{:?}", settings.pivot_handling));
+        options.write_str(&format!("{:?}", settings.space_conversion));
+        options.write_str(&format!("{:?}", settings.limits));
+        options.write_u8(settings.load_external_files as u8);
+        for value in world_offset.to_array() {
+            options.write_u64(value.to_bits());
+        }
+
+        Self {
+            content_hash: content.finish(),
+            options_hash: options.finish(),
+        }
+    }
+}
+
+/// Scene cached for `key`, marked as most recently used.
+pub fn cached_scene(key: &FbxParseKey) -> Option<Arc<ufbx::SceneRoot>> {
+    let mut scenes = parsed_scenes();
+    let index = scenes.iter().position(|(cached, _)| cached == key)?;
+    let entry = scenes.remove(index)?;
+    let scene = entry.1.clone();
+    scenes.push_front(entry);
+    Some(scene)
+}
+
+/// Cache `scene`, evicting the least recently used scenes beyond `capacity`.
+pub fn cache_scene(key: FbxParseKey, scene: Arc<ufbx::SceneRoot>, capacity: usize) {
+    let mut scenes = parsed_scenes();
+    scenes.retain(|(cached, _)| *cached != key);
+    scenes.push_front((key, scene));
+    scenes.truncate(capacity);
+}
+
+/// Number of cached scenes.
+pub fn cached_scene_count() -> usize {
+    parsed_scenes().len()
+}
+
+/// Drop the cached scenes. Loads still using one keep it alive until they
+/// finish.
+pub fn clear_parse_cache() {
+    parsed_scenes().clear();
+}
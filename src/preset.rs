@@ -0,0 +1,89 @@
+//! Import presets bundling the settings that differ between FBX exporters.
+//!
+//! ufbx already converts every file to Bevy's axes and meters, so presets
+//! only cover what the file format leaves to interpretation: which texture
+//! slot holds normal maps and what unit light intensities are in.
+
+use crate::light::FbxLightPreset;
+use crate::loader::FbxLoaderSettings;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Exporter whose conventions are applied by
+/// [`FbxLoaderSettings::import_preset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub enum ImportPreset {
+    /// Blender's FBX exporter: normal maps in the `NormalMap` slot, light
+    /// intensities in watts
+    Blender,
+    /// Autodesk Maya: normal maps in the bump slot, unitless intensities
+    Maya,
+    /// Autodesk 3ds Max: normal maps in the bump slot, photometric intensities
+    Max3ds,
+    /// Unity's FBX Exporter: normal maps in the `NormalMap` slot, unitless
+    /// intensities
+    Unity,
+    /// Detect the exporter from the file's creator and application metadata,
+    /// leaving the settings unchanged if it is not recognized
+    Auto,
+}
+
+impl ImportPreset {
+    /// Detect the exporter from the `creator` string and original
+    /// application name of a file.
+    pub fn detect(creator: &str, application: &str) -> Option<ImportPreset> {
+        [application, creator].into_iter().find_map(|text| {
+            let text = text.to_lowercase();
+            if text.contains("blender") {
+                Some(ImportPreset::Blender)
+            } else if text.contains("3ds max") || text.contains("3dsmax") {
+                Some(ImportPreset::Max3ds)
+            } else if text.contains("maya") {
+                Some(ImportPreset::Maya)
+            } else if text.contains("unity") {
+                Some(ImportPreset::Unity)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The preset applied for `scene`, resolving [`ImportPreset::Auto`] from
+    /// its metadata.
+    pub fn resolve(self, scene: &ufbx::Scene) -> Option<ImportPreset> {
+        if self != ImportPreset::Auto {
+            return Some(self);
+        }
+        let metadata = &scene.metadata;
+        ImportPreset::detect(
+            metadata.creator.as_ref(),
+            metadata.original_application.name.as_ref(),
+        )
+    }
+
+    /// Overwrite the settings this preset bundles.
+    ///
+    /// [`ImportPreset::Auto`] must be resolved first and changes nothing.
+    pub fn apply(self, settings: &mut FbxLoaderSettings) {
+        let (bump_as_normal_map, light_preset) = match self {
+            ImportPreset::Blender => (false, FbxLightPreset::Blender),
+            ImportPreset::Maya => (true, FbxLightPreset::Generic),
+            ImportPreset::Max3ds => (true, FbxLightPreset::Photometric),
+            ImportPreset::Unity => (false, FbxLightPreset::Generic),
+            ImportPreset::Auto => return,
+        };
+        settings.bump_as_normal_map = bump_as_normal_map;
+        settings.light_conversion.preset = light_preset;
+    }
+}
+
+/// `settings` with the import preset for `scene` applied, if any.
+pub fn preset_settings(
+    settings: &FbxLoaderSettings,
+    scene: &ufbx::Scene,
+) -> Option<(ImportPreset, FbxLoaderSettings)> {
+    let preset = settings.import_preset?.resolve(scene)?;
+    let mut settings = settings.clone();
+    preset.apply(&mut settings);
+    Some((preset, settings))
+}
@@ -1,130 +1,1463 @@
 //! Scene building functionality for FBX files.
 
-use crate::error::FbxError;
+use crate::animation::{node_path_names, path_target_id};
+use crate::cache::FbxVertexCachePlayer;
+#[cfg(any(feature = "avian", feature = "rapier"))]
+use crate::collider::spawn_colliders;
+use crate::collider::{FbxColliderKind, FbxColliders};
+use crate::diagnostics::{FbxWarning, FbxWarnings};
+use crate::environment::ambient_light;
+use crate::extension::FbxSceneExtensions;
 use crate::label::FbxAssetLabel;
+use crate::light::{AreaLightAssets, FbxAreaLightFallback, FbxLightDecay, area_light_rotation};
 use crate::loader::FbxLoaderSettings;
-use crate::utils::convert_matrix;
-use bevy::asset::{Handle, LoadContext};
-use bevy::pbr::StandardMaterial;
+use crate::mesh::{
+    ConvertedPrimitive, convert_mesh_primitives, merge_meshes, mesh_asset_usage,
+    mesh_simplification, scene_bounds,
+};
+use crate::node::{
+    is_visible_in_hierarchy, node_group_names, process_node_groups, world_translation,
+};
+use crate::simplify::{simplification_available, simplify_mesh};
+use crate::sink::FbxAssetSink;
+use crate::types::{
+    Fbx, FbxActiveCamera, FbxBillboard, FbxBillboardAxis, FbxBone, FbxGroupMember,
+    FbxMaterialExtras, FbxMesh, FbxNodePath, FbxNull, FbxNullLook, FbxPrimitive, FbxSceneMap,
+    FbxShadowOnly,
+};
+use crate::utils::{
+    camera_lens, convert_matrix, convert_transform, default_camera_name, glob_match,
+    lens_shift_sub_view,
+};
+use crate::variant::{FbxMaterialVariants, node_material_variants, variant_slot_material};
+use bevy::animation::AnimatedBy;
+use bevy::asset::{Handle, RenderAssetUsages};
+use bevy::camera::ScalingMode;
+use bevy::camera::primitives::Aabb;
+use bevy::camera::visibility::RenderLayers;
+use bevy::light::{NotShadowCaster, NotShadowReceiver};
+use bevy::math::DVec3;
+use bevy::mesh::PrimitiveTopology;
+use bevy::mesh::morph::{MeshMorphWeights, MorphWeights};
+use bevy::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes};
+use bevy::pbr::{Lightmap, StandardMaterial};
+#[cfg(feature = "depth_of_field")]
+use bevy::post_process::dof::DepthOfField;
 use bevy::prelude::*;
-use bevy::scene::Scene;
-use std::collections::HashMap;
+use bevy::scene::{Scene, SceneInstanceReady};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+/// Triggered on a [`SceneRoot`] entity once a scene loaded from an FBX file has
+/// finished spawning.
+///
+/// Only scenes loaded through the asset server carry the [`Fbx`] handle they
+/// came from, so scenes built with [`Fbx::from_bytes`] don't trigger it.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct FbxSceneInstanceReady {
+    /// Root entity of the spawned scene
+    pub entity: Entity,
+    /// FBX asset the scene was loaded from
+    pub fbx: Handle<Fbx>,
+}
+
+/// Insert an [`FbxSceneMap`] on scene roots with FBX nodes and forward
+/// [`SceneInstanceReady`] as [`FbxSceneInstanceReady`] for FBX scenes.
+///
+/// The map is inserted first so [`FbxSceneInstanceReady`] observers can use it.
+pub fn trigger_fbx_scene_instance_ready(
+    ready: On<SceneInstanceReady>,
+    scene_roots: Query<&SceneRoot>,
+    children: Query<&Children>,
+    node_paths: Query<&FbxNodePath>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let entity = ready.entity;
+    let scene_map = scene_map(entity, &children, &node_paths);
+    if !scene_map.entities.is_empty() {
+        commands.entity(entity).insert(scene_map);
+    }
+
+    let Ok(scene_root) = scene_roots.get(entity) else {
+        return;
+    };
+    let Some(path) = asset_server.get_path(scene_root.0.id()) else {
+        return;
+    };
+    let is_fbx = path
+        .path()
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("fbx"));
+    if !is_fbx {
+        return;
+    }
+    let Some(fbx) = asset_server.get_handle::<Fbx>(path.without_label()) else {
+        return;
+    };
+
+    commands.trigger(FbxSceneInstanceReady { entity, fbx });
+}
+
+/// Map the [`FbxNodePath`] of every entity below `root` to the entity.
+pub fn scene_map(
+    root: Entity,
+    children: &Query<&Children>,
+    node_paths: &Query<&FbxNodePath>,
+) -> FbxSceneMap {
+    let mut scene_map = FbxSceneMap::default();
+    for entity in children.iter_descendants(root) {
+        if let Ok(path) = node_paths.get(entity) {
+            scene_map.entities.entry(path.0.clone()).or_insert(entity);
+        }
+    }
+    scene_map
+}
+
+/// How the default scene is moved relative to its origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RecenterMode {
+    /// Keep the authored origin
+    #[default]
+    None,
+    /// Move the center of the scene bounds to the origin
+    Origin,
+    /// Move the bottom center of the scene bounds to the origin, so the
+    /// asset stands on the ground plane
+    BottomCenter,
+}
+
+impl RecenterMode {
+    /// Point of `bounds` moved to the origin, zero if there are no bounds.
+    pub fn pivot(self, bounds: Option<&Aabb>) -> Vec3 {
+        let Some(bounds) = bounds else {
+            return Vec3::ZERO;
+        };
+        let center = Vec3::from(bounds.center);
+        match self {
+            RecenterMode::None => Vec3::ZERO,
+            RecenterMode::Origin => center,
+            RecenterMode::BottomCenter => center.with_y(bounds.min().y),
+        }
+    }
+}
+
+/// Point moved to the world origin while parsing, see
+/// [`FbxLoaderSettings::world_origin`].
+///
+/// Unlike [`RecenterMode`], the offset is applied in f64 by ufbx, so node
+/// transforms far from the origin keep their precision. Vertex positions far
+/// from their node's origin are not affected.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum FbxWorldOrigin {
+    /// Keep the authored origin
+    #[default]
+    Authored,
+    /// Move the center of the bounds of all node positions to the origin
+    NodeBoundsCenter,
+    /// Move a fixed point, in meters in Bevy's axes, to the origin
+    Fixed([f64; 3]),
+}
+
+impl FbxWorldOrigin {
+    /// Point of `scene`, parsed without an offset, moved to the origin;
+    /// `None` if nothing moves.
+    pub fn offset(self, scene: &ufbx::Scene) -> Option<DVec3> {
+        let offset = match self {
+            FbxWorldOrigin::Authored => return None,
+            FbxWorldOrigin::NodeBoundsCenter => {
+                let positions = scene
+                    .nodes
+                    .as_ref()
+                    .iter()
+                    .filter(|node| !node.is_root)
+                    .map(|node| world_translation(node));
+                let (min, max) = positions.fold(
+                    (DVec3::INFINITY, DVec3::NEG_INFINITY),
+                    |(min, max), position| (min.min(position), max.max(position)),
+                );
+                if min.x > max.x {
+                    return None;
+                }
+                (min + max) / 2.0
+            }
+            FbxWorldOrigin::Fixed(point) => DVec3::from_array(point),
+        };
+        (offset != DVec3::ZERO).then_some(offset)
+    }
+}
+
+/// Transform of the `FbxRoot` entity wrapping the default scene, or `None`
+/// if neither [`FbxLoaderSettings::root_transform_override`] nor
+/// [`FbxLoaderSettings::recenter`] is set.
+///
+/// The scene is recentered first, then transformed by the override.
+pub fn root_transform(settings: &FbxLoaderSettings, bounds: Option<&Aabb>) -> Option<Transform> {
+    if settings.root_transform_override.is_none() && settings.recenter == RecenterMode::None {
+        return None;
+    }
+    let root = settings.root_transform_override.unwrap_or_default();
+    let pivot = settings.recenter.pivot(bounds);
+    Some(Transform::from_matrix(
+        root.to_matrix() * Mat4::from_translation(-pivot),
+    ))
+}
+
+/// Parent every top-level entity of `world` to a new `FbxRoot` entity,
+/// returning the root.
+fn wrap_in_root(world: &mut World, transform: Transform) -> Entity {
+    let top_level: Vec<Entity> = world
+        .query_filtered::<Entity, Without<ChildOf>>()
+        .iter(world)
+        .collect();
+    let root = world
+        .spawn((
+            Name::new("FbxRoot"),
+            transform,
+            GlobalTransform::default(),
+            Visibility::default(),
+        ))
+        .id();
+    for entity in top_level {
+        world.entity_mut(entity).insert(ChildOf(root));
+    }
+    root
+}
+
+/// Scenes emitted per animation stack, see
+/// [`FbxLoaderSettings::take_scenes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FbxTakeScenes {
+    /// Only the default scene
+    #[default]
+    None,
+    /// One scene per stack, posed at the stack's start time
+    Posed,
+    /// One scene per stack in the rest pose, wrapped in an `FbxRoot` entity
+    /// with an [`AnimationPlayer`] looping the stack's clip and every node
+    /// bound as its animation target
+    Animated,
+}
+
+/// Name of the take scene of the animation stack at `index`, the index
+/// itself for unnamed stacks.
+fn take_name(stack: &ufbx::AnimStack, index: usize) -> String {
+    if stack.element.name.is_empty() {
+        index.to_string()
+    } else {
+        stack.element.name.to_string()
+    }
+}
+
+/// Loop `clip` on a new `FbxRoot` entity of `world`, binding every entity
+/// with an [`FbxNodePath`] to it.
+fn wire_take_clip(
+    world: &mut World,
+    name: &str,
+    clip: &Handle<AnimationClip>,
+    settings: &FbxLoaderSettings,
+    load_context: &mut impl FbxAssetSink,
+) {
+    let (graph, node) = AnimationGraph::from_clip(clip.clone());
+    let label = FbxAssetLabel::TakeGraph(name.to_string());
+    let graph = load_context.add_labeled_asset(label.to_string(), graph);
+    let mut player = AnimationPlayer::default();
+    player.play(node).repeat();
+
+    let root = wrap_in_root(world, Transform::IDENTITY);
+    world
+        .entity_mut(root)
+        .insert((player, AnimationGraphHandle(graph)));
+    let targets: Vec<(Entity, String)> = world
+        .query::<(Entity, &FbxNodePath)>()
+        .iter(world)
+        .map(|(entity, path)| (entity, path.0.clone()))
+        .collect();
+    for (entity, path) in targets {
+        world
+            .entity_mut(entity)
+            .insert((path_target_id(&path, settings), AnimatedBy(root)));
+    }
+}
 
 /// Build the final scene with all entities.
+///
+/// Every mesh node is spawned as an entity with one child entity per
+/// primitive, mirroring the glTF loader. Primitive materials follow the
+/// node's material bindings (see [`instance_material_id`]). Primitives whose
+/// material has unmapped properties get a [`FbxMaterialExtras`] component, and
+/// primitives of nodes in `lightmaps` get a [`Lightmap`] component. Nodes whose
+/// attribute is in `line_curves` are spawned with that mesh. Primitives of
+/// nodes in `skin_bindposes` get a [`SkinnedMesh`] bound to the spawned bones.
+///
+/// With [`FbxLoaderSettings::flatten_static_hierarchy`], static meshes of the
+/// main scene are merged into one `Batch{}` mesh per material and asset usage.
+///
+/// Subtrees listed in [`FbxLoaderSettings::node_scenes`] are emitted as
+/// additional `Node/{path}` scenes, returned by path alongside the main scene.
+/// With [`FbxLoaderSettings::take_scenes`], each animation stack adds a
+/// `Take/{name}` scene using its clip in `animations`, returned by stack name;
+/// takes that cannot be posed are skipped with a warning added to `warnings`.
+/// `extensions` run on every node entity of each scene.
+///
+/// With [`FbxLoaderSettings::recenter`] or
+/// [`FbxLoaderSettings::root_transform_override`], the main scene is wrapped
+/// in an `FbxRoot` entity carrying the adjustment (see [`root_transform`]);
+/// node scenes are left unchanged.
+#[allow(clippy::too_many_arguments)]
 pub fn build_scene(
     scene: &ufbx::Scene,
-    meshes: &[Handle<Mesh>],
+    meshes: &[FbxMesh],
     materials: &[Handle<StandardMaterial>],
-    named_materials: &HashMap<Box<str>, Handle<StandardMaterial>>,
-    mesh_transforms: &[ufbx::Matrix],
-    mesh_material_info: &[Vec<String>],
+    materials_by_id: &HashMap<u32, Handle<StandardMaterial>>,
+    material_extras: &HashMap<u32, FbxMaterialExtras>,
+    lightmaps: &HashMap<u32, Handle<Image>>,
+    line_curves: &HashMap<u32, Handle<Mesh>>,
+    skin_bindposes: &HashMap<u32, Vec<Handle<SkinnedMeshInverseBindposes>>>,
+    colliders: &FbxColliders,
+    animations: &[Handle<AnimationClip>],
     settings: &FbxLoaderSettings,
-    load_context: &mut LoadContext,
-) -> Result<Handle<Scene>, FbxError> {
-    let mut world = World::new();
-
+    extensions: &FbxSceneExtensions,
+    warnings: &mut FbxWarnings,
+    load_context: &mut impl FbxAssetSink,
+) -> (Handle<Scene>, Vec<(String, Handle<Scene>)>) {
     // Create default material if needed
-    let default_material = materials.get(0).cloned().unwrap_or_else(|| {
-        load_context.add_labeled_asset(
+    let default_material = match &settings.default_material {
+        Some(material) => load_context.add_labeled_asset(
             FbxAssetLabel::DefaultMaterial.to_string(),
-            StandardMaterial::default(),
-        )
-    });
+            material.to_standard_material(),
+        ),
+        None => materials.first().cloned().unwrap_or_else(|| {
+            load_context.add_labeled_asset(
+                FbxAssetLabel::DefaultMaterial.to_string(),
+                StandardMaterial::default(),
+            )
+        }),
+    };
 
-    // Spawn meshes
-    for (mesh_index, ((mesh_handle, transform_matrix), mat_names)) in meshes
-        .iter()
-        .zip(mesh_transforms.iter())
-        .zip(mesh_material_info.iter())
-        .enumerate()
+    let mut render_layers = display_layer_render_layers(scene, settings);
+    apply_light_links(scene, settings, &mut render_layers);
+    apply_shadow_only_layer(scene, settings, &mut render_layers);
+    let mut scene_meshes = SceneMeshes {
+        meshes,
+        default_material: &default_material,
+        materials_by_id,
+        material_extras,
+        lightmaps,
+        line_curves,
+        skin_bindposes,
+        render_layers: &render_layers,
+        node_groups: node_group_names(&process_node_groups(scene)),
+        area_lights: AreaLightAssets::create(scene, settings.area_lights, load_context),
+        flattened: FlattenedMeshes::default(),
+    };
+    if settings.flatten_static_hierarchy {
+        scene_meshes.flattened =
+            flatten_static_meshes(scene, &scene_meshes, settings, load_context);
+    }
+
+    let mut world = World::new();
+    spawn_scene_nodes(scene, None, &scene_meshes, settings, extensions, &mut world);
+    if settings.spawn_ambient_light
+        && let Some(light) = ambient_light(scene)
     {
-        let transform = Transform::from_matrix(convert_matrix(transform_matrix));
+        world.spawn((Name::new("AmbientLight"), light));
+    }
+
+    // Spawn physics colliders
+    #[cfg(any(feature = "avian", feature = "rapier"))]
+    spawn_colliders(colliders, &mut world);
+    #[cfg(not(any(feature = "avian", feature = "rapier")))]
+    let _ = colliders;
+
+    if let Some(transform) = root_transform(settings, scene_bounds(scene, meshes).as_ref()) {
+        wrap_in_root(&mut world, transform);
+    }
+
+    let scene_handle =
+        load_context.add_labeled_asset(FbxAssetLabel::Scene(0).to_string(), Scene::new(world));
+
+    let mut node_scenes = Vec::new();
+    for path in &settings.node_scenes {
+        let Some(root) = find_node_by_path(scene, path) else {
+            warn!("Node scene path '{}' does not match any node", path);
+            continue;
+        };
+        let mut world = World::new();
+        spawn_scene_nodes(
+            scene,
+            Some(root),
+            &scene_meshes,
+            settings,
+            extensions,
+            &mut world,
+        );
+        let handle = load_context.add_labeled_asset(
+            FbxAssetLabel::NodeScene(path.clone()).to_string(),
+            Scene::new(world),
+        );
+        node_scenes.push((path.clone(), handle));
+    }
+
+    if settings.take_scenes != FbxTakeScenes::None {
+        for (index, stack) in scene.anim_stacks.as_ref().iter().enumerate() {
+            let name = take_name(stack, index);
+            let mut world = World::new();
+            if settings.take_scenes == FbxTakeScenes::Posed {
+                let posed = match ufbx::evaluate_scene(
+                    scene,
+                    &stack.anim,
+                    stack.time_begin,
+                    ufbx::EvaluateOpts::default(),
+                ) {
+                    Ok(posed) => posed,
+                    Err(e) => {
+                        // Skip the take rather than failing the whole load
+                        warnings.0.push(FbxWarning {
+                            kind: "TakeScene".to_string(),
+                            description: format!("Failed to pose take '{}': {:?}", name, e),
+                            element_id: Some(stack.element.element_id),
+                            count: 1,
+                        });
+                        continue;
+                    }
+                };
+                spawn_scene_nodes(
+                    &posed,
+                    None,
+                    &scene_meshes,
+                    settings,
+                    extensions,
+                    &mut world,
+                );
+            } else {
+                spawn_scene_nodes(scene, None, &scene_meshes, settings, extensions, &mut world);
+                if let Some(clip) = animations.get(index) {
+                    wire_take_clip(&mut world, &name, clip, settings, load_context);
+                }
+            }
+            let handle = load_context.add_labeled_asset(
+                FbxAssetLabel::TakeScene(name.clone()).to_string(),
+                Scene::new(world),
+            );
+            node_scenes.push((name, handle));
+        }
+    }
 
-        // Find material
-        let material = mat_names
+    (scene_handle, node_scenes)
+}
+
+/// Converted assets shared by every scene built from one file.
+struct SceneMeshes<'a> {
+    meshes: &'a [FbxMesh],
+    default_material: &'a Handle<StandardMaterial>,
+    materials_by_id: &'a HashMap<u32, Handle<StandardMaterial>>,
+    material_extras: &'a HashMap<u32, FbxMaterialExtras>,
+    lightmaps: &'a HashMap<u32, Handle<Image>>,
+    line_curves: &'a HashMap<u32, Handle<Mesh>>,
+    /// Inverse bind poses of each primitive of skinned mesh nodes
+    skin_bindposes: &'a HashMap<u32, Vec<Handle<SkinnedMeshInverseBindposes>>>,
+    render_layers: &'a HashMap<u32, RenderLayers>,
+    /// Selection set names of each node, keyed by node index
+    node_groups: HashMap<usize, Vec<String>>,
+    /// Emissive quad assets for approximated area lights
+    area_lights: AreaLightAssets,
+    /// Static meshes merged for the default scene
+    flattened: FlattenedMeshes,
+}
+
+impl SceneMeshes<'_> {
+    /// Element id and handle of the material of a primitive instanced by
+    /// `node`, falling back to the default material.
+    fn primitive_material(
+        &self,
+        node: &ufbx::Node,
+        mesh: &ufbx::Mesh,
+        primitive: &FbxPrimitive,
+    ) -> (Option<u32>, Handle<StandardMaterial>) {
+        let material_id = instance_material_id(node, mesh, primitive.material_index);
+        let material = material_id
+            .and_then(|id| self.materials_by_id.get(&id))
+            .or(primitive.material.as_ref())
+            .unwrap_or(self.default_material)
+            .clone();
+        (material_id, material)
+    }
+
+    /// Materials of a primitive in the `variants` of its node, or `None` if
+    /// no variant changes it.
+    fn primitive_variants(
+        &self,
+        scene: &ufbx::Scene,
+        variants: &[(String, Vec<Option<String>>)],
+        primitive: &FbxPrimitive,
+        default: &Handle<StandardMaterial>,
+    ) -> Option<FbxMaterialVariants> {
+        let variants: HashMap<String, Handle<StandardMaterial>> = variants
             .iter()
-            .find_map(|name| named_materials.get(name as &str))
-            .cloned()
-            .or_else(|| {
-                materials
-                    .get(mesh_index.min(materials.len().saturating_sub(1)))
-                    .cloned()
+            .filter_map(|(variant, slots)| {
+                let name = variant_slot_material(slots, primitive.material_index)?;
+                let material = scene
+                    .materials
+                    .as_ref()
+                    .iter()
+                    .find(|material| material.element.name.as_ref() as &str == name)?;
+                let handle = self.materials_by_id.get(&material.element.element_id)?;
+                Some((variant.clone(), handle.clone()))
             })
-            .unwrap_or_else(|| default_material.clone());
+            .collect();
+        (!variants.is_empty()).then(|| FbxMaterialVariants {
+            default: default.clone(),
+            variants,
+        })
+    }
+}
+
+/// Static meshes of the default scene merged per material.
+#[derive(Default)]
+struct FlattenedMeshes {
+    /// Element ids of the nodes whose meshes were merged
+    nodes: HashSet<u32>,
+    /// Merged mesh and material of each batch
+    batches: Vec<(Handle<Mesh>, Handle<StandardMaterial>)>,
+}
+
+/// Merge the meshes of static nodes into one world-space mesh per material
+/// and asset usage, added as `Batch{}` assets.
+///
+/// A node is static if neither it nor an ancestor is animated, and its mesh
+/// instance needs no per-node components: skinning, morph weights, lightmaps,
+/// render layers, shadow flags, billboards, material variants, selection sets
+/// or material extras. Meshes are converted again since only handles of the spawned
+/// primitives are kept, applying the base level of
+/// [`FbxLoaderSettings::simplification`] and the asset usage of
+/// [`FbxLoaderSettings::mesh_usage_overrides`] like [`process_meshes`].
+///
+/// [`process_meshes`]: crate::mesh::process_meshes
+fn flatten_static_meshes(
+    scene: &ufbx::Scene,
+    scene_meshes: &SceneMeshes,
+    settings: &FbxLoaderSettings,
+    load_context: &mut impl FbxAssetSink,
+) -> FlattenedMeshes {
+    let animated: HashSet<u32> = scene
+        .anim_stacks
+        .as_ref()
+        .iter()
+        .flat_map(|stack| stack.layers.as_ref().iter())
+        .flat_map(|layer| layer.anim_props.as_ref().iter())
+        .map(|anim_prop| anim_prop.element.element_id)
+        .collect();
+    let is_static = |node: &ufbx::Node| {
+        std::iter::successors(Some(node), |node| {
+            node.parent.as_ref().map(|parent| &**parent)
+        })
+        .all(|node| !animated.contains(&node.element.element_id))
+    };
+
+    let mut converted: HashMap<usize, (RenderAssetUsages, Vec<ConvertedPrimitive>)> =
+        HashMap::new();
+    let mut nodes = HashSet::new();
+    // Mesh index, primitive index and transform of the instances of each
+    // material and asset usage
+    let mut groups: Vec<(
+        Handle<StandardMaterial>,
+        RenderAssetUsages,
+        Vec<(usize, usize, Mat4)>,
+    )> = Vec::new();
+    for node in scene.nodes.as_ref().iter() {
+        let Some(mesh_ref) = node.mesh.as_ref() else {
+            continue;
+        };
+        let mesh_index = mesh_ref.element.typed_id as usize;
+        let Some(fbx_mesh) = scene_meshes.meshes.get(mesh_index) else {
+            continue;
+        };
+        let element_id = node.element.element_id;
+        let materials: Vec<_> = fbx_mesh
+            .primitives
+            .iter()
+            .map(|primitive| scene_meshes.primitive_material(node, mesh_ref, primitive))
+            .collect();
+        let has_extras = materials.iter().any(|(material_id, _)| {
+            material_id.is_some_and(|id| scene_meshes.material_extras.contains_key(&id))
+        });
+        if fbx_mesh.primitives.is_empty()
+            || !is_spawned(node, None, settings)
+            || (settings.extract_colliders
+                && FbxColliderKind::from_name(&node.element.name).is_some())
+            || !is_visible_in_hierarchy(node)
+            || !mesh_ref.skin_deformers.as_ref().is_empty()
+            || !fbx_mesh.blend_channels.is_empty()
+            || fbx_mesh.vertex_cache.is_some()
+            || scene_meshes.lightmaps.contains_key(&element_id)
+            || scene_meshes.render_layers.contains_key(&element_id)
+            || scene_meshes
+                .node_groups
+                .contains_key(&(node.element.typed_id as usize))
+            || node_shadow_flags(node) != (true, true)
+            || (settings.detect_billboards && node_billboard(node).is_some())
+            || !node_material_variants(node).is_empty()
+            || has_extras
+            || !is_static(node)
+        {
+            continue;
+        }
+
+        // Reconverted primitives must match the spawned ones
+        let (usage, primitives) = converted
+            .entry(mesh_index)
+            .or_insert_with(|| reconvert_static_mesh(mesh_ref, settings));
+        let usage = *usage;
+        if primitives.len() != fbx_mesh.primitives.len()
+            || primitives
+                .iter()
+                .any(|(_, _, mesh)| mesh.primitive_topology() != PrimitiveTopology::TriangleList)
+        {
+            continue;
+        }
+
+        let matrix = convert_matrix(&node.geometry_to_world);
+        for (primitive_index, (_, material)) in materials.into_iter().enumerate() {
+            let instance = (mesh_index, primitive_index, matrix);
+            match groups
+                .iter_mut()
+                .find(|group| group.0 == material && group.1 == usage)
+            {
+                Some((_, _, instances)) => instances.push(instance),
+                None => groups.push((material, usage, vec![instance])),
+            }
+        }
+        nodes.insert(element_id);
+    }
+
+    let mut batches = Vec::new();
+    for (material, usage, instances) in groups {
+        let meshes: Vec<(&Mesh, Mat4)> = instances
+            .iter()
+            .map(|&(mesh, primitive, matrix)| (&converted[&mesh].1[primitive].2, matrix))
+            .collect();
+        let Some(merged) = merge_meshes(&meshes, usage) else {
+            continue;
+        };
+        let label = FbxAssetLabel::Batch(batches.len()).to_string();
+        batches.push((load_context.add_labeled_asset(label, merged), material));
+    }
+    FlattenedMeshes { nodes, batches }
+}
+
+/// Convert the primitives of a static mesh again, simplified to the base
+/// level of its simplification rule, along with the mesh's asset usage.
+fn reconvert_static_mesh(
+    mesh: &ufbx::Mesh,
+    settings: &FbxLoaderSettings,
+) -> (RenderAssetUsages, Vec<ConvertedPrimitive>) {
+    let mut primitives = convert_mesh_primitives(mesh, settings);
+    let simplification = mesh_simplification(mesh, settings).filter(|_| simplification_available());
+    if let Some(simplification) = simplification
+        && let Some(level) = simplification.base
+    {
+        for (_, _, primitive) in &mut primitives {
+            if let Some(simplified) = simplify_mesh(primitive, level, simplification.lock_border) {
+                *primitive = simplified;
+            }
+        }
+    }
+    (mesh_asset_usage(mesh, settings), primitives)
+}
 
-        world.spawn((
-            Mesh3d(mesh_handle.clone()),
-            MeshMaterial3d(material),
+/// Spawn the bones, meshes and lights of the subtree at `root`, or of the
+/// whole scene if `root` is `None`.
+///
+/// Subtree entities are placed relative to `root`, so the subtree's root node
+/// ends up at the scene origin.
+fn spawn_scene_nodes(
+    scene: &ufbx::Scene,
+    root: Option<&ufbx::Node>,
+    scene_meshes: &SceneMeshes,
+    settings: &FbxLoaderSettings,
+    extensions: &FbxSceneExtensions,
+    world: &mut World,
+) {
+    let origin = subtree_origin(root);
+
+    // Spawn bones so sockets can be found by name
+    let bones = spawn_bones(scene, root, world);
+
+    // Spawn nulls as markers, reusing the bone entity if a null is also a bone
+    let mut node_entities: Vec<(usize, Entity)> = bones.clone().into_iter().collect();
+    for node in scene.nodes.as_ref().iter() {
+        let Some(null) = null_marker(node) else {
+            continue;
+        };
+        if !is_spawned(node, root, settings) {
+            continue;
+        }
+        let node_index = node.element.typed_id as usize;
+        if let Some(&entity) = bones.get(&node_index) {
+            world.entity_mut(entity).insert(null);
+            continue;
+        }
+        let Some(visibility) = node_visibility(node, settings) else {
+            continue;
+        };
+
+        let mut entity = world.spawn((
+            Name::new(node.element.name.to_string()),
+            Transform::from_matrix(origin * convert_matrix(&node.node_to_world)),
+            GlobalTransform::default(),
+            visibility,
+            null,
+        ));
+        if let Some(layers) = scene_meshes.render_layers.get(&node.element.element_id) {
+            entity.insert(layers.clone());
+        }
+        node_entities.push((node_index, entity.id()));
+    }
+
+    // Spawn meshes
+    for node in scene.nodes.as_ref().iter() {
+        let Some(mesh_ref) = node.mesh.as_ref() else {
+            continue;
+        };
+        if !is_spawned(node, root, settings) {
+            continue;
+        }
+        if settings.extract_colliders && FbxColliderKind::from_name(&node.element.name).is_some() {
+            continue;
+        }
+        if root.is_none()
+            && scene_meshes
+                .flattened
+                .nodes
+                .contains(&node.element.element_id)
+        {
+            continue;
+        }
+        let Some(fbx_mesh) = scene_meshes.meshes.get(mesh_ref.element.typed_id as usize) else {
+            continue;
+        };
+        if fbx_mesh.primitives.is_empty() {
+            continue;
+        }
+        let Some(visibility) = node_visibility(node, settings) else {
+            continue;
+        };
+
+        // `geometry_to_world` already includes the node's geometric transform
+        let transform = Transform::from_matrix(origin * convert_matrix(&node.geometry_to_world));
+
+        let layers = scene_meshes.render_layers.get(&node.element.element_id);
+        let lightmap = scene_meshes.lightmaps.get(&node.element.element_id);
+        let morph_weights =
+            (!fbx_mesh.blend_channels.is_empty()).then(|| fbx_mesh.morph_weights(&[]));
+        let (mut casts_shadows, receives_shadows) = node_shadow_flags(node);
+        let shadow_only = settings.shadow_only_layer.is_some() && is_shadow_only(node);
+        // Occluders that don't cast shadows would have no effect at all
+        casts_shadows |= shadow_only;
+        let variants = node_material_variants(node);
+        let skin = scene_meshes
+            .skin_bindposes
+            .get(&node.element.element_id)
+            .and_then(|bindposes| Some((skin_joints(mesh_ref, &bones)?, bindposes)));
+
+        let mut entity = world.spawn((
+            Name::new(node.element.name.to_string()),
             transform,
             GlobalTransform::default(),
-            Visibility::default(),
+            visibility,
         ));
+        // Animated on the node entity and copied to each primitive
+        if let Some(weights) = &morph_weights {
+            let first_mesh = fbx_mesh
+                .primitives
+                .first()
+                .map(|primitive| primitive.mesh.clone());
+            if let Ok(weights) = MorphWeights::new(weights.clone(), first_mesh) {
+                entity.insert(weights);
+            }
+        }
+        let entity = entity
+            .with_children(|parent| {
+                for (primitive_index, primitive) in fbx_mesh.primitives.iter().enumerate() {
+                    let (material_id, material) =
+                        scene_meshes.primitive_material(node, mesh_ref, primitive);
+                    let material_variants =
+                        scene_meshes.primitive_variants(scene, &variants, primitive, &material);
+
+                    let mut primitive_entity = parent.spawn((
+                        Mesh3d(primitive.mesh.clone()),
+                        MeshMaterial3d(material),
+                        Transform::default(),
+                        GlobalTransform::default(),
+                        Visibility::default(),
+                    ));
+                    // Render layers are not inherited, so they go on each primitive
+                    if let Some(layers) = layers {
+                        primitive_entity.insert(layers.clone());
+                    }
+                    if let Some(extras) =
+                        material_id.and_then(|id| scene_meshes.material_extras.get(&id))
+                    {
+                        primitive_entity.insert(extras.clone());
+                    }
+                    if let Some(material_variants) = material_variants {
+                        primitive_entity.insert(material_variants);
+                    }
+                    if let Some(image) = lightmap {
+                        primitive_entity.insert(Lightmap {
+                            image: image.clone(),
+                            ..Default::default()
+                        });
+                    }
+                    if let Some(weights) = &morph_weights
+                        && let Ok(weights) = MeshMorphWeights::new(weights.clone())
+                    {
+                        primitive_entity.insert(weights);
+                    }
+                    if let Some(cache) = &fbx_mesh.vertex_cache
+                        && let Some(vertices) = &primitive.cache_vertices
+                    {
+                        primitive_entity
+                            .insert(FbxVertexCachePlayer::new(cache.clone(), vertices.clone()));
+                    }
+                    if !casts_shadows {
+                        primitive_entity.insert(NotShadowCaster);
+                    }
+                    if !receives_shadows {
+                        primitive_entity.insert(NotShadowReceiver);
+                    }
+                    if let Some((joints, bindposes)) = &skin
+                        && let Some(inverse_bindposes) = bindposes.get(primitive_index)
+                    {
+                        // Split primitives only use their own subset of the joints
+                        let joints = match &primitive.joints {
+                            Some(subset) => {
+                                subset.iter().map(|&joint| joints[joint as usize]).collect()
+                            }
+                            None => joints.clone(),
+                        };
+                        primitive_entity.insert(SkinnedMesh {
+                            inverse_bindposes: inverse_bindposes.clone(),
+                            joints,
+                        });
+                    }
+                    // Bevy only computes bounds for entities without them
+                    if let Some(aabb) = fbx_mesh.skinned_aabb {
+                        primitive_entity.insert(aabb);
+                    }
+                }
+            })
+            .id();
+        node_entities.push((node.element.typed_id as usize, entity));
+    }
+
+    // Spawn merged static meshes
+    if root.is_none() {
+        for (index, (mesh, material)) in scene_meshes.flattened.batches.iter().enumerate() {
+            world.spawn((
+                Name::new(format!("Batch{index}")),
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::default(),
+                GlobalTransform::default(),
+                Visibility::default(),
+            ));
+        }
+    }
+
+    // Spawn line curves
+    for node in scene.nodes.as_ref().iter() {
+        let Some(curve) = node
+            .attrib
+            .as_ref()
+            .and_then(|attrib| scene_meshes.line_curves.get(&attrib.element_id))
+        else {
+            continue;
+        };
+        if !is_spawned(node, root, settings) {
+            continue;
+        }
+        let Some(visibility) = node_visibility(node, settings) else {
+            continue;
+        };
+
+        let mut entity = world.spawn((
+            Name::new(node.element.name.to_string()),
+            Mesh3d(curve.clone()),
+            MeshMaterial3d(scene_meshes.default_material.clone()),
+            Transform::from_matrix(origin * convert_matrix(&node.geometry_to_world)),
+            GlobalTransform::default(),
+            visibility,
+        ));
+        if let Some(layers) = scene_meshes.render_layers.get(&node.element.element_id) {
+            entity.insert(layers.clone());
+        }
+        let (casts_shadows, receives_shadows) = node_shadow_flags(node);
+        if !casts_shadows {
+            entity.insert(NotShadowCaster);
+        }
+        if !receives_shadows {
+            entity.insert(NotShadowReceiver);
+        }
+        node_entities.push((node.element.typed_id as usize, entity.id()));
     }
 
     // Spawn lights
     if settings.load_lights {
-        spawn_lights(scene, &mut world);
+        node_entities.extend(spawn_lights(
+            scene,
+            root,
+            settings,
+            scene_meshes.render_layers,
+            &scene_meshes.area_lights,
+            world,
+        ));
     }
 
-    let scene_handle =
-        load_context.add_labeled_asset(FbxAssetLabel::Scene(0).to_string(), Scene::new(world));
+    // Spawn cameras
+    if settings.load_cameras {
+        node_entities.extend(spawn_cameras(
+            scene,
+            root,
+            settings,
+            scene_meshes.render_layers,
+            world,
+        ));
+    }
+
+    // Tag node paths, animation targets, billboards and members of selection sets
+    for &(node_index, entity) in &node_entities {
+        let node = &scene.nodes.as_ref()[node_index];
+        let mut entity = world.entity_mut(entity);
+        let path = node_path_names(node).join("/");
+        entity.insert((path_target_id(&path, settings), FbxNodePath(path)));
+        if settings.detect_billboards
+            && let Some(billboard) = node_billboard(node)
+        {
+            entity.insert(billboard);
+        }
+        if settings.shadow_only_layer.is_some() && node.mesh.is_some() && is_shadow_only(node) {
+            entity.insert(FbxShadowOnly);
+        }
+        if let Some(groups) = scene_meshes.node_groups.get(&node_index) {
+            entity.insert(FbxGroupMember {
+                groups: groups.clone(),
+            });
+        }
+    }
+
+    // Let user extensions add their own components
+    if !extensions.is_empty() {
+        node_entities.sort_unstable();
+        for (node_index, entity) in node_entities {
+            let node = &scene.nodes.as_ref()[node_index];
+            extensions.process_node(node, &mut world.entity_mut(entity));
+        }
+    }
+}
+
+/// Element id of the material bound to `slot` of a mesh instance.
+///
+/// FBX binds materials to nodes, so instances of one mesh can use different
+/// materials per slot (e.g. color variants of a prop). The node's bindings
+/// win over the mesh's own material list.
+pub fn instance_material_id(node: &ufbx::Node, mesh: &ufbx::Mesh, slot: usize) -> Option<u32> {
+    node.materials
+        .as_ref()
+        .get(slot)
+        .or_else(|| mesh.materials.as_ref().get(slot))
+        .map(|material| material.element.element_id)
+}
+
+/// [`FbxNull`] marker of a null node, or `None` for other nodes.
+///
+/// Nodes with a `Null` attribute and plain transform nodes without any
+/// attribute count as nulls.
+pub fn null_marker(node: &ufbx::Node) -> Option<FbxNull> {
+    if node.is_root {
+        return None;
+    }
+    let attrib = match node.attrib.as_ref() {
+        None => None,
+        Some(attrib) if node.attrib_type == ufbx::ElementType::Empty => Some(attrib),
+        Some(_) => return None,
+    };
+
+    let find_prop = |name: &str| {
+        attrib?
+            .props
+            .props
+            .as_ref()
+            .iter()
+            .find(|prop| prop.name.as_ref() as &str == name)
+    };
+    Some(FbxNull {
+        size: find_prop("Size").map_or(100.0, |prop| prop.value_real as f32),
+        look: match find_prop("Look").map(|prop| prop.value_int) {
+            Some(1) => FbxNullLook::Cross,
+            _ => FbxNullLook::None,
+        },
+    })
+}
+
+/// Billboard marker of a node exported from a vegetation or imposter
+/// pipeline.
+///
+/// A `Billboard` user property decides: `spherical` or `cylindrical`, or a
+/// number where `0` disables the billboard and others make it cylindrical.
+/// Otherwise, names starting with `BBS_` are spherical and `BB_`
+/// cylindrical billboards.
+pub fn node_billboard(node: &ufbx::Node) -> Option<FbxBillboard> {
+    let billboard = |axis| Some(FbxBillboard { axis });
+    let property = node
+        .element
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == "Billboard");
+    if let Some(property) = property {
+        let value = property.value_str.as_ref() as &str;
+        return if value.eq_ignore_ascii_case("spherical") {
+            billboard(FbxBillboardAxis::Spherical)
+        } else if value.eq_ignore_ascii_case("cylindrical") || property.value_int != 0 {
+            billboard(FbxBillboardAxis::Cylindrical)
+        } else {
+            None
+        };
+    }
+
+    let name = node.element.name.as_ref() as &str;
+    if name.starts_with("BBS_") {
+        billboard(FbxBillboardAxis::Spherical)
+    } else if name.starts_with("BB_") {
+        billboard(FbxBillboardAxis::Cylindrical)
+    } else {
+        None
+    }
+}
+
+/// Find a node by its `/`-separated name path from the scene root, e.g.
+/// `Root/Floor2` for the node `Floor2` under the top-level node `Root`.
+pub fn find_node_by_path<'a>(scene: &'a ufbx::Scene, path: &str) -> Option<&'a ufbx::Node> {
+    let mut node: &ufbx::Node = &scene.root_node;
+    for name in path.split('/').filter(|name| !name.is_empty()) {
+        node = node
+            .children
+            .as_ref()
+            .iter()
+            .find(|child| child.element.name.as_ref() as &str == name)
+            .map(|child| &**child)?;
+    }
+    // An empty path would select the root node, which is the default scene
+    (!node.is_root).then_some(node)
+}
+
+/// Whether `node` belongs to the scene being built: inside `root`'s subtree
+/// and passing the node name filters.
+fn is_spawned(node: &ufbx::Node, root: Option<&ufbx::Node>, settings: &FbxLoaderSettings) -> bool {
+    is_in_subtree(node, root) && passes_node_filters(node, settings)
+}
+
+/// Whether a node passes [`FbxLoaderSettings::include_nodes`] and
+/// [`FbxLoaderSettings::exclude_nodes`].
+///
+/// Patterns are matched against the node and its ancestors, so filtering a
+/// group node applies to its whole subtree.
+pub fn passes_node_filters(node: &ufbx::Node, settings: &FbxLoaderSettings) -> bool {
+    if settings.include_nodes.is_empty() && settings.exclude_nodes.is_empty() {
+        return true;
+    }
+
+    let matches_any = |patterns: &[String]| {
+        std::iter::successors(Some(node), |node| {
+            node.parent.as_ref().map(|parent| &**parent)
+        })
+        .any(|node| {
+            let name: &str = node.element.name.as_ref();
+            patterns.iter().any(|pattern| glob_match(pattern, name))
+        })
+    };
+    (settings.include_nodes.is_empty() || matches_any(&settings.include_nodes))
+        && !matches_any(&settings.exclude_nodes)
+}
+
+/// Whether `node` is `root` or one of its descendants; always true without a root.
+fn is_in_subtree(node: &ufbx::Node, root: Option<&ufbx::Node>) -> bool {
+    let Some(root) = root else {
+        return true;
+    };
+    let mut current = Some(node);
+    while let Some(node) = current {
+        if node.element.element_id == root.element.element_id {
+            return true;
+        }
+        current = node.parent.as_ref().map(|parent| &**parent);
+    }
+    false
+}
+
+/// Transform from world space to the space of a subtree's root node.
+fn subtree_origin(root: Option<&ufbx::Node>) -> Mat4 {
+    root.map_or(Mat4::IDENTITY, |root| {
+        convert_matrix(&root.node_to_world).inverse()
+    })
+}
+
+/// Spawn an entity for every node used as a skin joint, and for skeleton
+/// bones no skin uses, e.g. in skeleton-only rig files.
+///
+/// Bones get a [`Name`], a [`Transform`] and an [`FbxBone`] marker, and are
+/// parented to their parent bone. Bones without a parent bone use their world
+/// transform, relative to `root` if given; bones outside `root`'s subtree are
+/// skipped. Returns the bone entities keyed by node index.
+pub fn spawn_bones(
+    scene: &ufbx::Scene,
+    root: Option<&ufbx::Node>,
+    world: &mut World,
+) -> HashMap<usize, Entity> {
+    let mut bones: HashMap<usize, FbxBone> = HashMap::new();
+    for (skeleton_index, skin) in scene.skin_deformers.as_ref().iter().enumerate() {
+        for (bone_index, cluster) in skin.clusters.as_ref().iter().enumerate() {
+            if let Some(bone_node) = cluster.bone_node.as_ref() {
+                if !is_in_subtree(bone_node, root) {
+                    continue;
+                }
+                bones
+                    .entry(bone_node.element.typed_id as usize)
+                    .or_insert(FbxBone {
+                        skeleton_index,
+                        bone_index,
+                    });
+            }
+        }
+    }
+    let mut unskinned_index = 0;
+    for node in scene.nodes.as_ref().iter() {
+        if node.attrib_type != ufbx::ElementType::Bone || !is_in_subtree(node, root) {
+            continue;
+        }
+        if let Entry::Vacant(entry) = bones.entry(node.element.typed_id as usize) {
+            entry.insert(FbxBone {
+                skeleton_index: scene.skin_deformers.len(),
+                bone_index: unskinned_index,
+            });
+            unskinned_index += 1;
+        }
+    }
+
+    let origin = subtree_origin(root);
+    let mut entities = HashMap::new();
+    let mut node_indices: Vec<usize> = bones.keys().copied().collect();
+    node_indices.sort_unstable();
+    for &node_index in &node_indices {
+        let Some(node) = scene.nodes.as_ref().get(node_index) else {
+            continue;
+        };
+        let has_parent_bone = node
+            .parent
+            .as_ref()
+            .is_some_and(|parent| bones.contains_key(&(parent.element.typed_id as usize)));
+        let transform = if has_parent_bone {
+            convert_transform(&node.local_transform)
+        } else {
+            Transform::from_matrix(origin * convert_matrix(&node.node_to_world))
+        };
+
+        let entity = world
+            .spawn((
+                Name::new(node.element.name.to_string()),
+                transform,
+                GlobalTransform::default(),
+                Visibility::default(),
+                bones[&node_index],
+            ))
+            .id();
+        entities.insert(node_index, entity);
+    }
+
+    for &node_index in &node_indices {
+        let parent = scene.nodes.as_ref()[node_index]
+            .parent
+            .as_ref()
+            .and_then(|parent| entities.get(&(parent.element.typed_id as usize)));
+        if let (Some(&parent), Some(&entity)) = (parent, entities.get(&node_index)) {
+            world.entity_mut(entity).insert(ChildOf(parent));
+        }
+    }
+
+    entities
+}
+
+/// Bone entity of each cluster of a mesh's skin, in cluster order, or `None`
+/// if a cluster's bone was not spawned.
+fn skin_joints(mesh: &ufbx::Mesh, bones: &HashMap<usize, Entity>) -> Option<Vec<Entity>> {
+    let skin = mesh.skin_deformers.as_ref().first()?;
+    skin.clusters
+        .as_ref()
+        .iter()
+        .map(|cluster| {
+            let bone_node = cluster.bone_node.as_ref()?;
+            bones.get(&(bone_node.element.typed_id as usize)).copied()
+        })
+        .collect()
+}
+
+/// User property listing the comma-separated light link groups of a light or
+/// mesh node.
+pub const LIGHT_LINK_PROPERTY: &str = "LightLink";
+
+/// Light link groups of a node from its [`LIGHT_LINK_PROPERTY`].
+pub fn light_link_groups(node: &ufbx::Node) -> Vec<&str> {
+    node.element
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == LIGHT_LINK_PROPERTY)
+        .map(|prop| {
+            (prop.value_str.as_ref() as &str)
+                .split(',')
+                .map(str::trim)
+                .filter(|group| !group.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Add the render layers of light link groups to `render_layers`.
+///
+/// Groups are translated through [`FbxLoaderSettings::light_links`]. Linked
+/// meshes stay on their layers and gain the group layers, while linked
+/// lights are moved to the group layers only, so they no longer illuminate
+/// meshes outside their groups.
+pub fn apply_light_links(
+    scene: &ufbx::Scene,
+    settings: &FbxLoaderSettings,
+    render_layers: &mut HashMap<u32, RenderLayers>,
+) {
+    if settings.light_links.is_empty() {
+        return;
+    }
+
+    for node in scene.nodes.as_ref().iter() {
+        let layers: Vec<usize> = light_link_groups(node)
+            .into_iter()
+            .filter_map(|group| settings.light_links.get(group).copied())
+            .collect();
+        if layers.is_empty() {
+            continue;
+        }
 
-    Ok(scene_handle)
+        let id = node.element.element_id;
+        let base = if node.light.is_some() {
+            RenderLayers::none()
+        } else {
+            render_layers.get(&id).cloned().unwrap_or_default()
+        };
+        let linked = layers
+            .into_iter()
+            .fold(base, |layers, layer| layers.with(layer));
+        render_layers.insert(id, linked);
+    }
+}
+
+/// Whether a node is a shadow-only occluder, exported to shape shadows
+/// without being seen, e.g. a low-poly stand-in for a detailed mesh.
+///
+/// A `ShadowOnly` user property decides, with `0` disabling it; otherwise
+/// names starting with `SHADOW_` are occluders.
+pub fn is_shadow_only(node: &ufbx::Node) -> bool {
+    let property = node
+        .element
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == "ShadowOnly");
+    match property {
+        Some(property) => property.value_int != 0,
+        None => (node.element.name.as_ref() as &str).starts_with("SHADOW_"),
+    }
+}
+
+/// Move shadow-only mesh nodes to
+/// [`FbxLoaderSettings::shadow_only_layer`] and add the layer to every light,
+/// so the meshes are culled from cameras but still cast shadows.
+pub fn apply_shadow_only_layer(
+    scene: &ufbx::Scene,
+    settings: &FbxLoaderSettings,
+    render_layers: &mut HashMap<u32, RenderLayers>,
+) {
+    let Some(layer) = settings.shadow_only_layer else {
+        return;
+    };
+
+    for node in scene.nodes.as_ref().iter() {
+        let id = node.element.element_id;
+        if node.light.is_some() {
+            let layers = render_layers.get(&id).cloned().unwrap_or_default();
+            render_layers.insert(id, layers.with(layer));
+        } else if node.mesh.is_some() && is_shadow_only(node) {
+            render_layers.insert(id, RenderLayers::layer(layer));
+        }
+    }
+}
+
+/// Whether a node's geometry casts and receives shadows.
+///
+/// Read from the FBX `Casts Shadows` and `Receive Shadows` properties, which
+/// the FBX SDK writes on the geometry and 3ds Max on the node, or Maya's
+/// `castsShadows` and `receiveShadows` attributes exported as user
+/// properties. A flag set on the node wins over its geometry's; missing flags
+/// default to `true`.
+pub fn node_shadow_flags(node: &ufbx::Node) -> (bool, bool) {
+    let find_bool = |names: [&str; 2]| {
+        [Some(&node.element), node.attrib.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(|element| {
+                element
+                    .props
+                    .props
+                    .as_ref()
+                    .iter()
+                    .find(|prop| names.contains(&(prop.name.as_ref() as &str)))
+                    .map(|prop| prop.value_int != 0)
+            })
+            .unwrap_or(true)
+    };
+    (
+        find_bool(["Casts Shadows", "castsShadows"]),
+        find_bool(["Receive Shadows", "receiveShadows"]),
+    )
+}
+
+/// Map node element ids to the render layers of their display layers.
+///
+/// Display layers are translated through
+/// [`FbxLoaderSettings::display_layers`]; unmapped layers are ignored.
+pub fn display_layer_render_layers(
+    scene: &ufbx::Scene,
+    settings: &FbxLoaderSettings,
+) -> HashMap<u32, RenderLayers> {
+    let mut render_layers: HashMap<u32, RenderLayers> = HashMap::new();
+    if settings.display_layers.is_empty() {
+        return render_layers;
+    }
+
+    for display_layer in scene.display_layers.as_ref().iter() {
+        let Some(&layer) = settings
+            .display_layers
+            .get(display_layer.element.name.as_ref())
+        else {
+            continue;
+        };
+        for node in display_layer.nodes.as_ref().iter() {
+            let entry = render_layers
+                .entry(node.element.element_id)
+                .or_insert_with(RenderLayers::none);
+            *entry = entry.clone().with(layer);
+        }
+    }
+
+    render_layers
+}
+
+/// Visibility of a node's entity, or `None` if the node should be skipped.
+fn node_visibility(node: &ufbx::Node, settings: &FbxLoaderSettings) -> Option<Visibility> {
+    if is_visible_in_hierarchy(node) {
+        Some(Visibility::Inherited)
+    } else if settings.skip_hidden_nodes {
+        None
+    } else {
+        Some(Visibility::Hidden)
+    }
 }
 
 /// Spawn lights in the scene.
-pub fn spawn_lights(scene: &ufbx::Scene, world: &mut World) {
+///
+/// Lights outside `root`'s subtree are skipped and the rest are placed
+/// relative to `root`, if given. Returns the light entities keyed by node index.
+pub fn spawn_lights(
+    scene: &ufbx::Scene,
+    root: Option<&ufbx::Node>,
+    settings: &FbxLoaderSettings,
+    render_layers: &HashMap<u32, RenderLayers>,
+    area_lights: &AreaLightAssets,
+    world: &mut World,
+) -> HashMap<usize, Entity> {
+    let conversion = &settings.light_conversion;
+    let origin = subtree_origin(root);
+    let mut entities = HashMap::new();
+    let mut approximated = Vec::new();
+
     for light in scene.lights.as_ref().iter() {
         if let Some(light_node) = scene.nodes.as_ref().iter().find(|n| {
             n.light.is_some()
                 && n.light.as_ref().unwrap().element.element_id == light.element.element_id
         }) {
-            let transform = Transform::from_matrix(convert_matrix(&light_node.node_to_world));
+            if !is_spawned(light_node, root, settings) {
+                continue;
+            }
+            let Some(mut visibility) = node_visibility(light_node, settings) else {
+                continue;
+            };
+            // Lights that don't illuminate anything are kept, but hidden
+            if !light.cast_light {
+                visibility = Visibility::Hidden;
+            }
+            let transform =
+                Transform::from_matrix(origin * convert_matrix(&light_node.node_to_world));
+            let intensity = light.intensity as f32;
+            let decay = FbxLightDecay::from(light.decay);
 
-            match light.type_ {
-                ufbx::LightType::Directional => {
-                    world.spawn((
+            let entity = match light.type_ {
+                ufbx::LightType::Directional => world
+                    .spawn((
                         DirectionalLight {
                             color: Color::srgb(
                                 light.color.x as f32,
                                 light.color.y as f32,
                                 light.color.z as f32,
                             ),
-                            illuminance: light.intensity as f32 * 10000.0,
+                            illuminance: conversion.illuminance(intensity),
                             shadows_enabled: light.cast_shadows,
                             ..Default::default()
                         },
                         transform,
                         GlobalTransform::default(),
-                        Visibility::default(),
-                    ));
-                }
-                ufbx::LightType::Point => {
-                    world.spawn((
+                        visibility,
+                    ))
+                    .id(),
+                ufbx::LightType::Point => world
+                    .spawn((
                         PointLight {
                             color: Color::srgb(
                                 light.color.x as f32,
                                 light.color.y as f32,
                                 light.color.z as f32,
                             ),
-                            intensity: light.intensity as f32 * 1000.0,
+                            intensity: conversion.luminous_power(intensity, decay),
                             shadows_enabled: light.cast_shadows,
                             ..Default::default()
                         },
                         transform,
                         GlobalTransform::default(),
-                        Visibility::default(),
-                    ));
-                }
-                ufbx::LightType::Spot => {
-                    world.spawn((
+                        visibility,
+                    ))
+                    .id(),
+                ufbx::LightType::Spot => world
+                    .spawn((
                         SpotLight {
                             color: Color::srgb(
                                 light.color.x as f32,
                                 light.color.y as f32,
                                 light.color.z as f32,
                             ),
-                            intensity: light.intensity as f32 * 1000.0,
+                            intensity: conversion.luminous_power(intensity, decay),
                             shadows_enabled: light.cast_shadows,
                             inner_angle: light.inner_angle as f32,
                             outer_angle: light.outer_angle as f32,
@@ -132,11 +1465,165 @@ pub fn spawn_lights(scene: &ufbx::Scene, world: &mut World) {
                         },
                         transform,
                         GlobalTransform::default(),
-                        Visibility::default(),
+                        visibility,
+                    ))
+                    .id(),
+                ufbx::LightType::Area if settings.area_lights != FbxAreaLightFallback::Skip => {
+                    approximated.push(light_node.element.name.to_string());
+                    let mut entity = world.spawn((
+                        PointLight {
+                            color: Color::srgb(
+                                light.color.x as f32,
+                                light.color.y as f32,
+                                light.color.z as f32,
+                            ),
+                            intensity: conversion.luminous_power(intensity, decay),
+                            shadows_enabled: light.cast_shadows,
+                            ..Default::default()
+                        },
+                        transform,
+                        GlobalTransform::default(),
+                        visibility,
                     ));
+                    let material = area_lights.materials.get(&light.element.element_id);
+                    if let (Some(quad), Some(material)) = (&area_lights.quad, material) {
+                        let direction = Vec3::new(
+                            light.local_direction.x as f32,
+                            light.local_direction.y as f32,
+                            light.local_direction.z as f32,
+                        );
+                        entity.with_child((
+                            Mesh3d(quad.clone()),
+                            MeshMaterial3d(material.clone()),
+                            Transform::from_rotation(area_light_rotation(direction)),
+                            // The quad would block its own light
+                            NotShadowCaster,
+                        ));
+                    }
+                    entity.id()
                 }
-                _ => {}
+                _ => continue,
+            };
+
+            if let Some(layers) = render_layers.get(&light_node.element.element_id) {
+                world.entity_mut(entity).insert(layers.clone());
             }
+            entities.insert(light_node.element.typed_id as usize, entity);
         }
     }
+
+    if !approximated.is_empty() {
+        warn!(
+            "Approximated {} FBX area light(s) as {:?}: {}",
+            approximated.len(),
+            settings.area_lights,
+            approximated.join(", ")
+        );
+    }
+    entities
+}
+
+/// Spawn an inactive camera entity for each camera node.
+///
+/// Cameras outside `root`'s subtree are skipped and the rest are placed
+/// relative to `root`, if given. With
+/// [`FbxLoaderSettings::mark_active_camera`], the camera named in the
+/// `DefaultCamera` global setting gets [`FbxActiveCamera`]. Returns the
+/// camera entities keyed by node index.
+pub fn spawn_cameras(
+    scene: &ufbx::Scene,
+    root: Option<&ufbx::Node>,
+    settings: &FbxLoaderSettings,
+    render_layers: &HashMap<u32, RenderLayers>,
+    world: &mut World,
+) -> HashMap<usize, Entity> {
+    let origin = subtree_origin(root);
+    let active_camera = settings
+        .mark_active_camera
+        .then(|| default_camera_name(scene))
+        .flatten();
+    let mut entities = HashMap::new();
+
+    for node in scene.nodes.as_ref().iter() {
+        let Some(camera) = node.camera.as_ref() else {
+            continue;
+        };
+        if !is_spawned(node, root, settings) {
+            continue;
+        }
+        let Some(visibility) = node_visibility(node, settings) else {
+            continue;
+        };
+
+        let projection = match camera.projection_mode {
+            ufbx::ProjectionMode::Orthographic => {
+                Projection::Orthographic(OrthographicProjection {
+                    near: camera.near_plane as f32,
+                    far: camera.far_plane as f32,
+                    scaling_mode: ScalingMode::FixedVertical {
+                        viewport_height: camera.orthographic_size.y as f32,
+                    },
+                    ..OrthographicProjection::default_3d()
+                })
+            }
+            _ => Projection::Perspective(PerspectiveProjection {
+                fov: (camera.field_of_view_deg.y as f32).to_radians(),
+                near: camera.near_plane as f32,
+                far: camera.far_plane as f32,
+                ..Default::default()
+            }),
+        };
+        let lens = camera_lens(camera);
+        let (projection, sub_camera_view) = match projection {
+            Projection::Perspective(mut perspective) => {
+                let aspect_ratio = camera.aspect_ratio as f32;
+                match lens_shift_sub_view(lens.film_offset, aspect_ratio, perspective.fov) {
+                    Some((sub_view, fov)) => {
+                        perspective.fov = fov;
+                        (Projection::Perspective(perspective), Some(sub_view))
+                    }
+                    None => (Projection::Perspective(perspective), None),
+                }
+            }
+            projection => (projection, None),
+        };
+        let name: &str = node.element.name.as_ref();
+        let mut entity = world.spawn((
+            Name::new(name.to_string()),
+            Camera3d::default(),
+            Camera {
+                is_active: false,
+                sub_camera_view,
+                ..Default::default()
+            },
+            projection,
+            lens,
+            Transform::from_matrix(origin * convert_matrix(&node.node_to_world)),
+            GlobalTransform::default(),
+            visibility,
+        ));
+
+        if active_camera.as_deref() == Some(name) {
+            entity.insert(FbxActiveCamera);
+        }
+        #[cfg(feature = "depth_of_field")]
+        if lens.depth_of_field
+            && let Some(focal_distance) = lens.focus_distance
+        {
+            entity.insert(DepthOfField {
+                focal_distance,
+                aperture_f_stops: lens
+                    .f_stop
+                    .unwrap_or(DepthOfField::default().aperture_f_stops),
+                sensor_height: lens.film_size_mm.y / 1000.0,
+                ..Default::default()
+            });
+        }
+        if let Some(layers) = render_layers.get(&node.element.element_id) {
+            entity.insert(layers.clone());
+        }
+        entities.insert(node.element.typed_id as usize, entity.id());
+    }
+
+    entities
 }
@@ -0,0 +1,188 @@
+//! Shader model detection and property tables for exporter-specific materials.
+//!
+//! Maya's Stingray PBS and Arnold `aiStandardSurface` materials store their
+//! parameters under `Maya|`-prefixed property names instead of the classic
+//! FBX `DiffuseColor`/`EmissiveColor` properties.
+
+/// Shader model a material was authored with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FbxShaderModel {
+    /// Classic FBX Lambert/Phong or anything not recognized
+    #[default]
+    Fbx,
+    /// Maya Stingray PBS (ShaderFX graph)
+    StingrayPbs,
+    /// Arnold `aiStandardSurface`
+    ArnoldStandardSurface,
+}
+
+/// Texture slot of a shader model, optionally enabled by a toggle property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShaderTextureSlot {
+    /// Material property the texture is connected to
+    pub property: &'static str,
+    /// Boolean property that must be non-zero for the texture to be used
+    pub toggle: Option<&'static str>,
+}
+
+/// FBX property names used by a shader model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShaderPropertyMap {
+    pub base_color: &'static str,
+    /// Scalar multiplied into the base color
+    pub base_color_weight: Option<&'static str>,
+    pub metallic: &'static str,
+    pub roughness: &'static str,
+    pub emissive: &'static str,
+    /// Scalar multiplied into the emissive color
+    pub emissive_weight: Option<&'static str>,
+    pub opacity: Option<&'static str>,
+    pub base_color_texture: ShaderTextureSlot,
+    pub normal_texture: ShaderTextureSlot,
+    pub metallic_texture: ShaderTextureSlot,
+    pub roughness_texture: ShaderTextureSlot,
+    pub emissive_texture: ShaderTextureSlot,
+    pub occlusion_texture: Option<ShaderTextureSlot>,
+}
+
+/// Property names of Maya's Stingray PBS material.
+pub const STINGRAY_PBS_PROPERTIES: ShaderPropertyMap = ShaderPropertyMap {
+    base_color: "Maya|base_color",
+    base_color_weight: None,
+    metallic: "Maya|metallic",
+    roughness: "Maya|roughness",
+    emissive: "Maya|emissive",
+    emissive_weight: Some("Maya|emissive_intensity"),
+    opacity: None,
+    base_color_texture: ShaderTextureSlot {
+        property: "Maya|TEX_color_map",
+        toggle: Some("Maya|use_color_map"),
+    },
+    normal_texture: ShaderTextureSlot {
+        property: "Maya|TEX_normal_map",
+        toggle: Some("Maya|use_normal_map"),
+    },
+    metallic_texture: ShaderTextureSlot {
+        property: "Maya|TEX_metallic_map",
+        toggle: Some("Maya|use_metallic_map"),
+    },
+    roughness_texture: ShaderTextureSlot {
+        property: "Maya|TEX_roughness_map",
+        toggle: Some("Maya|use_roughness_map"),
+    },
+    emissive_texture: ShaderTextureSlot {
+        property: "Maya|TEX_emissive_map",
+        toggle: Some("Maya|use_emissive_map"),
+    },
+    occlusion_texture: Some(ShaderTextureSlot {
+        property: "Maya|TEX_ao_map",
+        toggle: Some("Maya|use_ao_map"),
+    }),
+};
+
+/// Property names of Arnold's `aiStandardSurface` material.
+pub const ARNOLD_STANDARD_SURFACE_PROPERTIES: ShaderPropertyMap = ShaderPropertyMap {
+    base_color: "Maya|baseColor",
+    base_color_weight: Some("Maya|base"),
+    metallic: "Maya|metalness",
+    roughness: "Maya|specularRoughness",
+    emissive: "Maya|emissionColor",
+    emissive_weight: Some("Maya|emission"),
+    opacity: Some("Maya|opacity"),
+    base_color_texture: ShaderTextureSlot {
+        property: "Maya|baseColor",
+        toggle: None,
+    },
+    normal_texture: ShaderTextureSlot {
+        property: "Maya|normalCamera",
+        toggle: None,
+    },
+    metallic_texture: ShaderTextureSlot {
+        property: "Maya|metalness",
+        toggle: None,
+    },
+    roughness_texture: ShaderTextureSlot {
+        property: "Maya|specularRoughness",
+        toggle: None,
+    },
+    emissive_texture: ShaderTextureSlot {
+        property: "Maya|emissionColor",
+        toggle: None,
+    },
+    occlusion_texture: None,
+};
+
+impl FbxShaderModel {
+    /// Detect the shader model of a ufbx material.
+    pub fn detect(material: &ufbx::Material) -> Self {
+        match material.shader_type {
+            ufbx::ShaderType::ArnoldStandardSurface => FbxShaderModel::ArnoldStandardSurface,
+            _ => Self::from_property_names(
+                material
+                    .props
+                    .props
+                    .as_ref()
+                    .iter()
+                    .map(|prop| prop.name.as_ref()),
+            ),
+        }
+    }
+
+    /// Detect the shader model from the material's property names.
+    pub fn from_property_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        for name in names {
+            match name {
+                "Maya|TEX_color_map" | "Maya|use_color_map" => {
+                    return FbxShaderModel::StingrayPbs;
+                }
+                "Maya|specularRoughness" | "Maya|baseColor" => {
+                    return FbxShaderModel::ArnoldStandardSurface;
+                }
+                _ => {}
+            }
+        }
+        FbxShaderModel::Fbx
+    }
+
+    /// Property table for this shader model, or `None` for classic FBX materials.
+    pub fn property_map(&self) -> Option<&'static ShaderPropertyMap> {
+        match self {
+            FbxShaderModel::Fbx => None,
+            FbxShaderModel::StingrayPbs => Some(&STINGRAY_PBS_PROPERTIES),
+            FbxShaderModel::ArnoldStandardSurface => Some(&ARNOLD_STANDARD_SURFACE_PROPERTIES),
+        }
+    }
+}
+
+impl ShaderPropertyMap {
+    /// Texture slots in the order base color, normal, metallic, roughness,
+    /// emissive, occlusion.
+    pub fn texture_slots(&self) -> impl Iterator<Item = &ShaderTextureSlot> {
+        [
+            &self.base_color_texture,
+            &self.normal_texture,
+            &self.metallic_texture,
+            &self.roughness_texture,
+            &self.emissive_texture,
+        ]
+        .into_iter()
+        .chain(self.occlusion_texture.as_ref())
+    }
+
+    /// Whether `name` is one of the properties this table maps.
+    pub fn contains(&self, name: &str) -> bool {
+        let scalars = [
+            Some(self.base_color),
+            self.base_color_weight,
+            Some(self.metallic),
+            Some(self.roughness),
+            Some(self.emissive),
+            self.emissive_weight,
+            self.opacity,
+        ];
+        scalars.contains(&Some(name))
+            || self
+                .texture_slots()
+                .any(|slot| slot.property == name || slot.toggle == Some(name))
+    }
+}
@@ -0,0 +1,170 @@
+//! Mesh simplification at import time.
+//!
+//! With
+//! [`FbxLoaderSettings::simplification`](crate::FbxLoaderSettings::simplification),
+//! primitives are decimated and LOD levels generated from high-poly sources
+//! while loading. The simplifier is meshoptimizer's, available with the
+//! `simplify` feature; without it, rules are ignored with a warning.
+
+use crate::mesh::gather_vertex_attribute;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Simplification rule of
+/// [`FbxLoaderSettings::simplification`](crate::FbxLoaderSettings::simplification).
+///
+/// Meshes with blend shapes or a vertex cache are not simplified, as their
+/// deformations are stored per source vertex.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FbxSimplification {
+    /// Simplification of the primitives themselves; `None` keeps them
+    /// unchanged
+    pub base: Option<FbxLodLevel>,
+    /// Levels added as `Mesh{}/Primitive{}/Lod{}` sub-assets and listed in
+    /// [`FbxPrimitive::lods`](crate::FbxPrimitive::lods), each simplified
+    /// from the source primitive
+    pub lods: Vec<FbxLodLevel>,
+    /// Whether vertices on open borders stay in place, so primitives of one
+    /// mesh don't crack apart along their shared edges
+    pub lock_border: bool,
+}
+
+impl Default for FbxSimplification {
+    fn default() -> Self {
+        Self {
+            base: None,
+            lods: Vec::new(),
+            lock_border: true,
+        }
+    }
+}
+
+/// Target of one simplification pass.
+///
+/// Simplification stops at whichever limit is reached first, so a `ratio`
+/// of `0.0` simplifies until `max_error` alone.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FbxLodLevel {
+    /// Fraction of triangles to keep, between `0.0` and `1.0`
+    pub ratio: f32,
+    /// Maximum deviation from the source surface relative to the mesh
+    /// extents, e.g. `0.01` for 1%; roughly the screen-space error when the
+    /// mesh fills the screen
+    pub max_error: f32,
+}
+
+impl FbxLodLevel {
+    /// Keep `ratio` of the triangles, with an error limit of 1%.
+    pub fn ratio(ratio: f32) -> Self {
+        Self {
+            ratio,
+            max_error: 0.01,
+        }
+    }
+
+    /// Simplify as far as an error of `max_error` allows.
+    pub fn max_error(max_error: f32) -> Self {
+        Self {
+            ratio: 0.0,
+            max_error,
+        }
+    }
+}
+
+/// Whether simplification is available, i.e. the `simplify` feature is
+/// enabled.
+pub const fn simplification_available() -> bool {
+    cfg!(feature = "simplify")
+}
+
+/// Simplify a `TriangleList` mesh to `level`.
+///
+/// Unused vertices are removed from the result; other mesh properties are
+/// kept. Returns `None` if the mesh has no indexed positions, an attribute
+/// format this loader doesn't produce, or could not be simplified, which
+/// is always the case without the `simplify` feature.
+pub fn simplify_mesh(mesh: &Mesh, level: FbxLodLevel, lock_border: bool) -> Option<Mesh> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    let indices: Vec<u32> = match mesh.indices()? {
+        Indices::U16(values) => values.iter().map(|&i| i as u32).collect(),
+        Indices::U32(values) => values.clone(),
+    };
+
+    let triangles = indices.len() / 3;
+    let target = (triangles as f32 * level.ratio.clamp(0.0, 1.0)).round() as usize;
+    let simplified = simplify_indices(
+        &indices,
+        positions,
+        target * 3,
+        level.max_error.max(0.0),
+        lock_border,
+    )?;
+    if simplified.is_empty() || simplified.len() >= indices.len() {
+        return None;
+    }
+
+    // Keep only the referenced vertices, in first-use order
+    let mut remap = vec![u32::MAX; positions.len()];
+    let mut vertices = Vec::new();
+    let indices: Vec<u32> = simplified
+        .into_iter()
+        .map(|vertex| {
+            let slot = &mut remap[vertex as usize];
+            if *slot == u32::MAX {
+                *slot = vertices.len() as u32;
+                vertices.push(vertex);
+            }
+            *slot
+        })
+        .collect();
+
+    let mut result = mesh.clone();
+    for (attribute, values) in mesh.attributes() {
+        result.insert_attribute(*attribute, gather_vertex_attribute(values, &vertices)?);
+    }
+    result.insert_indices(Indices::U32(indices));
+    Some(result)
+}
+
+#[cfg(feature = "simplify")]
+fn simplify_indices(
+    indices: &[u32],
+    positions: &[[f32; 3]],
+    target_count: usize,
+    target_error: f32,
+    lock_border: bool,
+) -> Option<Vec<u32>> {
+    let options = if lock_border {
+        meshopt::SimplifyOptions::LockBorder
+    } else {
+        meshopt::SimplifyOptions::None
+    };
+    Some(meshopt::simplify_decoder(
+        indices,
+        positions,
+        target_count,
+        target_error,
+        options,
+        None,
+    ))
+}
+
+#[cfg(not(feature = "simplify"))]
+fn simplify_indices(
+    _indices: &[u32],
+    _positions: &[[f32; 3]],
+    _target_count: usize,
+    _target_error: f32,
+    _lock_border: bool,
+) -> Option<Vec<u32>> {
+    None
+}
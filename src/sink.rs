@@ -0,0 +1,293 @@
+//! Destinations for the sub-assets produced while converting an FBX file.
+
+use crate::cache::{FbxVertexCache, FbxVertexCacheLoaderSettings};
+use crate::external::media_dir;
+use crate::label::FbxAssetLabel;
+use crate::texture::{FbxColorSpace, FbxImageSettings};
+use bevy::asset::{Asset, AssetPath, Handle, LoadContext};
+use bevy::image::ImageLoaderSettings;
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Receives the sub-assets created while converting an FBX scene.
+///
+/// Implemented for [`LoadContext`] when loading through the asset server and
+/// for [`WorldAssetSink`] when importing FBX data at runtime.
+pub trait FbxAssetSink {
+    /// Store a labeled sub-asset and return its handle.
+    fn add_labeled_asset<A: Asset>(&mut self, label: String, asset: A) -> Handle<A>;
+
+    /// Store a sub-asset under `label` and, if `name` is given, a copy under
+    /// the name-based form of the label (see [`FbxAssetLabel::named`]).
+    ///
+    /// Returns the handle of the index-labeled asset.
+    fn add_named_labeled_asset<A: Asset + Clone>(
+        &mut self,
+        label: FbxAssetLabel,
+        name: Option<&str>,
+        asset: A,
+    ) -> Handle<A> {
+        let named = label.clone().named(name);
+        if named != label {
+            self.add_labeled_asset(named.to_string(), asset.clone());
+        }
+        self.add_labeled_asset(label.to_string(), asset)
+    }
+
+    /// Register `label`, and its name-based form if `name` is given, for
+    /// `asset` that is already stored at `handle`, and return `handle`.
+    ///
+    /// Sinks without labels add nothing. The asset server resolves each label
+    /// to its own asset, so [`LoadContext`] stores `asset` under the labels.
+    fn add_labeled_alias<A: Asset + Clone>(
+        &mut self,
+        label: FbxAssetLabel,
+        name: Option<&str>,
+        handle: &Handle<A>,
+        asset: A,
+    ) -> Handle<A> {
+        let _ = (label, name, asset);
+        handle.clone()
+    }
+
+    /// Load an external texture referenced by the FBX file.
+    fn load_texture(&mut self, path: AssetPath<'static>) -> Handle<Image>;
+
+    /// Load an external texture in an explicit color space.
+    ///
+    /// Defaults to [`load_texture`](Self::load_texture), ignoring the color
+    /// space.
+    fn load_texture_with_color_space(
+        &mut self,
+        path: AssetPath<'static>,
+        color_space: FbxColorSpace,
+    ) -> Handle<Image> {
+        let _ = color_space;
+        self.load_texture(path)
+    }
+
+    /// Load an external texture with image loader overrides.
+    ///
+    /// Defaults to [`load_texture_with_color_space`](Self::load_texture_with_color_space)
+    /// or [`load_texture`](Self::load_texture), ignoring the sampler.
+    fn load_texture_with_settings(
+        &mut self,
+        path: AssetPath<'static>,
+        settings: &FbxImageSettings,
+    ) -> Handle<Image> {
+        match settings.color_space {
+            Some(color_space) => self.load_texture_with_color_space(path, color_space),
+            None => self.load_texture(path),
+        }
+    }
+
+    /// Load an external vertex cache referenced by a cache deformer.
+    ///
+    /// `frame_rate` converts `.pc2` frame numbers to seconds. Defaults to a
+    /// default handle, leaving the cache unloaded.
+    fn load_vertex_cache(
+        &mut self,
+        path: AssetPath<'static>,
+        frame_rate: f32,
+    ) -> Handle<FbxVertexCache> {
+        let _ = (path, frame_rate);
+        Handle::default()
+    }
+
+    /// Directory used to resolve relative texture paths.
+    fn base_dir(&self) -> PathBuf;
+
+    /// Asset path of a file resolved against [`base_dir`](Self::base_dir).
+    ///
+    /// Defaults to `path` in the default asset source.
+    fn asset_path(&self, path: &Path) -> AssetPath<'static> {
+        AssetPath::from(path.to_path_buf())
+    }
+
+    /// `.fbm` folder searched for textures by file name, see
+    /// [`media_dir`](crate::external::media_dir). Defaults to none.
+    fn media_dir(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Name of the file in logs and trace spans. Defaults to
+    /// `in-memory FBX`.
+    fn source_name(&self) -> String {
+        "in-memory FBX".to_string()
+    }
+}
+
+impl FbxAssetSink for LoadContext<'_> {
+    fn add_labeled_asset<A: Asset>(&mut self, label: String, asset: A) -> Handle<A> {
+        LoadContext::add_labeled_asset(self, label, asset)
+    }
+
+    fn add_labeled_alias<A: Asset + Clone>(
+        &mut self,
+        label: FbxAssetLabel,
+        name: Option<&str>,
+        handle: &Handle<A>,
+        asset: A,
+    ) -> Handle<A> {
+        self.add_named_labeled_asset(label, name, asset);
+        handle.clone()
+    }
+
+    fn load_texture(&mut self, path: AssetPath<'static>) -> Handle<Image> {
+        self.load(path)
+    }
+
+    fn load_texture_with_color_space(
+        &mut self,
+        path: AssetPath<'static>,
+        color_space: FbxColorSpace,
+    ) -> Handle<Image> {
+        let settings = FbxImageSettings {
+            color_space: Some(color_space),
+            sampler: None,
+        };
+        self.load_texture_with_settings(path, &settings)
+    }
+
+    fn load_texture_with_settings(
+        &mut self,
+        path: AssetPath<'static>,
+        settings: &FbxImageSettings,
+    ) -> Handle<Image> {
+        if settings.is_default() {
+            return self.load(path);
+        }
+        let settings = settings.clone();
+        self.loader()
+            .with_settings(move |loader_settings: &mut ImageLoaderSettings| {
+                settings.apply(loader_settings);
+            })
+            .load(path)
+    }
+
+    fn load_vertex_cache(
+        &mut self,
+        path: AssetPath<'static>,
+        frame_rate: f32,
+    ) -> Handle<FbxVertexCache> {
+        self.loader()
+            .with_settings(move |settings: &mut FbxVertexCacheLoaderSettings| {
+                settings.frame_rate = frame_rate;
+            })
+            .load(path)
+    }
+
+    fn base_dir(&self) -> PathBuf {
+        match self.path().parent() {
+            Some(parent) => parent.path().to_path_buf(),
+            None => PathBuf::from(""),
+        }
+    }
+
+    fn media_dir(&self) -> Option<PathBuf> {
+        Some(media_dir(self.path().path()))
+    }
+
+    fn source_name(&self) -> String {
+        self.path().to_string()
+    }
+
+    fn asset_path(&self, path: &Path) -> AssetPath<'static> {
+        source_asset_path(self, path)
+    }
+}
+
+/// `path` in the asset source of the file `load_context` is loading, so the
+/// files an FBX file references in an embedded, HTTP or custom source load
+/// from that source rather than the default one.
+pub fn source_asset_path(load_context: &LoadContext<'_>, path: &Path) -> AssetPath<'static> {
+    AssetPath::from(path.to_path_buf()).with_source(load_context.path().source().clone_owned())
+}
+
+/// Adds sub-assets directly to the [`Assets`] collections of a [`World`].
+///
+/// Textures and vertex caches are loaded through the world's [`AssetServer`]
+/// if one exists; otherwise they are left as default handles.
+pub struct WorldAssetSink<'w> {
+    world: &'w mut World,
+    base_dir: PathBuf,
+}
+
+impl<'w> WorldAssetSink<'w> {
+    /// Create a sink resolving relative texture paths against `base_dir`.
+    pub fn new(world: &'w mut World, base_dir: impl AsRef<Path>) -> Self {
+        Self {
+            world,
+            base_dir: base_dir.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl FbxAssetSink for WorldAssetSink<'_> {
+    fn add_labeled_asset<A: Asset>(&mut self, _label: String, asset: A) -> Handle<A> {
+        self.world.resource_mut::<Assets<A>>().add(asset)
+    }
+
+    fn load_texture(&mut self, path: AssetPath<'static>) -> Handle<Image> {
+        self.world
+            .get_resource::<AssetServer>()
+            .map(|asset_server| asset_server.load(path))
+            .unwrap_or_default()
+    }
+
+    fn load_texture_with_color_space(
+        &mut self,
+        path: AssetPath<'static>,
+        color_space: FbxColorSpace,
+    ) -> Handle<Image> {
+        let settings = FbxImageSettings {
+            color_space: Some(color_space),
+            sampler: None,
+        };
+        self.load_texture_with_settings(path, &settings)
+    }
+
+    fn load_texture_with_settings(
+        &mut self,
+        path: AssetPath<'static>,
+        settings: &FbxImageSettings,
+    ) -> Handle<Image> {
+        if settings.is_default() {
+            return self.load_texture(path);
+        }
+        let settings = settings.clone();
+        self.world
+            .get_resource::<AssetServer>()
+            .map(|asset_server| {
+                asset_server.load_with_settings(
+                    path,
+                    move |loader_settings: &mut ImageLoaderSettings| {
+                        settings.apply(loader_settings);
+                    },
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn load_vertex_cache(
+        &mut self,
+        path: AssetPath<'static>,
+        frame_rate: f32,
+    ) -> Handle<FbxVertexCache> {
+        self.world
+            .get_resource::<AssetServer>()
+            .map(|asset_server| {
+                asset_server.load_with_settings(
+                    path,
+                    move |settings: &mut FbxVertexCacheLoaderSettings| {
+                        settings.frame_rate = frame_rate;
+                    },
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn base_dir(&self) -> PathBuf {
+        self.base_dir.clone()
+    }
+}
@@ -0,0 +1,204 @@
+//! Headless import harness for regression tests against FBX assets, enabled
+//! with the `test-fixtures` feature.
+//!
+//! [`import_fixture`] converts FBX bytes into a [`World`] without an asset
+//! server, and [`summarize`] renders the result as stable text that
+//! [`assert_golden`] compares against a checked-in golden file.
+
+use crate::FbxPlugin;
+use crate::error::FbxError;
+use crate::loader::FbxLoaderSettings;
+use crate::types::{Fbx, FbxMesh, FbxNode, FbxSkin};
+use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
+use bevy::prelude::*;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Environment variable making [`assert_golden`] rewrite golden files
+/// instead of comparing against them.
+pub const BLESS_ENV: &str = "BEVY_UFBX_BLESS";
+
+/// FBX files covering the importer's main paths.
+pub mod fixtures {
+    /// Binary FBX 7.4 cube exported from Maya
+    pub const BINARY_CUBE: &[u8] = include_bytes!("../assets/maya_cube_7400_binary.fbx");
+    /// ASCII FBX 7.4 cube with one material
+    pub const ASCII_CUBE: &[u8] = include_bytes!("../tests/fixtures/ascii_cube.fbx");
+    /// Cube with two materials assigned per face
+    pub const MULTI_MATERIAL: &[u8] = include_bytes!("../tests/fixtures/multi_material.fbx");
+    /// Cube translated 10 units along X over the one second `Take 001` stack
+    pub const ANIMATED: &[u8] = include_bytes!("../tests/fixtures/animated.fbx");
+    /// Cube skinned to the `Root` and `Tip` bones
+    pub const SKINNED: &[u8] = include_bytes!("../tests/fixtures/skinned.fbx");
+}
+
+/// Minimal headless app with [`FbxPlugin`] and the asset types imports
+/// produce.
+pub fn fixture_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), FbxPlugin))
+        .init_asset::<Mesh>()
+        .init_asset::<AnimationClip>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Image>()
+        .init_asset::<Scene>()
+        .init_asset::<SkinnedMeshInverseBindposes>();
+    app
+}
+
+/// Import `bytes` into the world of `app`, see [`Fbx::from_bytes`].
+pub fn import_fixture(
+    app: &mut App,
+    bytes: &[u8],
+    settings: FbxLoaderSettings,
+) -> Result<Fbx, FbxError> {
+    Fbx::from_bytes(bytes, settings, app.world_mut())
+}
+
+/// Stable text description of an imported file: its meshes with vertex and
+/// index counts, materials, nodes, skins and animations.
+///
+/// Floats are rounded to three decimals so the summary is unaffected by
+/// platform rounding differences.
+pub fn summarize(fbx: &Fbx, world: &World) -> String {
+    let fbx_meshes = world.resource::<Assets<FbxMesh>>();
+    let meshes = world.resource::<Assets<Mesh>>();
+    let materials = world.resource::<Assets<StandardMaterial>>();
+    let nodes = world.resource::<Assets<FbxNode>>();
+    let skins = world.resource::<Assets<FbxSkin>>();
+    let clips = world.resource::<Assets<AnimationClip>>();
+    let node_name = |handle: &Handle<FbxNode>| {
+        nodes
+            .get(handle)
+            .map_or("?", |node| node.name.as_str())
+            .to_string()
+    };
+
+    let mut out = String::new();
+    let format = fbx.metadata.format.map(|format| format!("{format:?}"));
+    let _ = writeln!(
+        out,
+        "format: {} {}",
+        format.as_deref().unwrap_or("?"),
+        fbx.metadata.version.unwrap_or(0)
+    );
+
+    let _ = writeln!(out, "meshes: {}", fbx.meshes.len());
+    for fbx_mesh in fbx
+        .meshes
+        .iter()
+        .filter_map(|handle| fbx_meshes.get(handle))
+    {
+        let _ = writeln!(out, "  mesh {} {:?}", fbx_mesh.index, fbx_mesh.name);
+        for primitive in &fbx_mesh.primitives {
+            let mesh = meshes.get(&primitive.mesh);
+            let _ = writeln!(
+                out,
+                "    primitive {}: material {}, {} vertices, {} indices",
+                primitive.index,
+                primitive.material_index,
+                mesh.map_or(0, Mesh::count_vertices),
+                mesh.and_then(Mesh::indices)
+                    .map_or(0, |indices| indices.len()),
+            );
+        }
+    }
+
+    let _ = writeln!(out, "materials: {}", fbx.materials.len());
+    for (index, handle) in fbx.materials.iter().enumerate() {
+        let mut names: Vec<&str> = fbx
+            .named_materials
+            .iter()
+            .filter(|(_, named)| *named == handle)
+            .map(|(name, _)| name.as_ref())
+            .collect();
+        names.sort_unstable();
+        let Some(material) = materials.get(handle) else {
+            continue;
+        };
+        let [r, g, b, a] = material.base_color.to_srgba().to_f32_array();
+        let _ = writeln!(
+            out,
+            "  material {index} {names:?}: base color [{r:.3}, {g:.3}, {b:.3}, {a:.3}], {:?}",
+            material.alpha_mode
+        );
+    }
+
+    let _ = writeln!(out, "nodes: {}", fbx.nodes.len());
+    for node in fbx.nodes.iter().filter_map(|handle| nodes.get(handle)) {
+        let children: Vec<String> = node.children.iter().map(node_name).collect();
+        let t = node.transform.translation;
+        let _ = writeln!(
+            out,
+            "  node {} {:?}: translation [{:.3}, {:.3}, {:.3}], children {children:?}{}{}",
+            node.index,
+            node.name,
+            t.x,
+            t.y,
+            t.z,
+            if node.mesh.is_some() { ", mesh" } else { "" },
+            if node.skin.is_some() { ", skin" } else { "" },
+        );
+    }
+
+    let _ = writeln!(out, "skins: {}", fbx.skins.len());
+    for skin in fbx.skins.iter().filter_map(|handle| skins.get(handle)) {
+        let _ = writeln!(
+            out,
+            "  skin {} {:?}: joints {:?}, {} weighted vertices",
+            skin.index,
+            skin.name,
+            skin.joint_names,
+            skin.vertex_weights.len()
+        );
+    }
+
+    let _ = writeln!(out, "animations: {}", fbx.animations.len());
+    let mut named_animations: Vec<_> = fbx.named_animations.iter().collect();
+    named_animations.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    for (name, handle) in named_animations {
+        let duration = clips.get(handle).map_or(0.0, AnimationClip::duration);
+        let _ = writeln!(out, "  animation {name:?}: duration {duration:.3}");
+    }
+
+    out
+}
+
+/// Compare `actual` with the golden file at `path`.
+///
+/// A missing golden file is written from `actual`, as is any golden file
+/// when the [`BLESS_ENV`] environment variable is set, e.g. after an
+/// intended change to the importer.
+///
+/// # Panics
+///
+/// If the golden file differs from `actual` or cannot be read or written.
+pub fn assert_golden(actual: &str, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let bless = std::env::var_os(BLESS_ENV).is_some();
+    match std::fs::read_to_string(path) {
+        Ok(expected) if !bless => {
+            // Checkouts may convert line endings
+            let expected = expected.replace("\r\n", "\n");
+            assert!(
+                expected == actual,
+                "{} does not match the import; rerun with {BLESS_ENV}=1 if the change is \
+                 intended\n--- expected\n{expected}--- actual\n{actual}",
+                path.display()
+            );
+        }
+        Err(error) if !bless && error.kind() != std::io::ErrorKind::NotFound => {
+            panic!("Failed to read golden file {}: {error}", path.display());
+        }
+        _ => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).unwrap_or_else(|error| {
+                    panic!("Failed to create {}: {error}", parent.display())
+                });
+            }
+            std::fs::write(path, actual).unwrap_or_else(|error| {
+                panic!("Failed to write golden file {}: {error}", path.display())
+            });
+        }
+    }
+}
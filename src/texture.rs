@@ -0,0 +1,589 @@
+//! Texture path resolution for FBX files.
+//!
+//! FBX files often reference textures by absolute paths from the artist's
+//! machine. Paths are resolved through a chain of candidates: the relative
+//! filename, then the file's basename in the FBX directory and in the
+//! configured search directories, matching case-insensitively.
+
+use crate::label::FbxAssetLabel;
+use crate::loader::FbxLoaderSettings;
+use crate::sink::FbxAssetSink;
+use crate::types::Fbx;
+use crate::utils::glob_match;
+use bevy::asset::uuid::Uuid;
+use bevy::asset::{AssetId, AssetPath, Handle, LoadContext, RenderAssetUsages};
+use bevy::image::{Image, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor};
+use bevy::pbr::StandardMaterial;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::futures_lite::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// How deep directories are scanned when building a [`TextureIndex`].
+const INDEX_DEPTH: usize = 2;
+
+/// Size in pixels of the [`checkerboard_image`] placeholder.
+const CHECKERBOARD_SIZE: u32 = 8;
+
+/// High half of the placeholder handle ids of deferred textures.
+const DEFERRED_TEXTURE_NAMESPACE: u64 = 0x6662_785f_7465_7874;
+
+/// When the images of material textures are requested.
+///
+/// The asset server has no load priorities, so there is no mode loading
+/// textures ahead of other assets; `Immediate` already requests them before
+/// meshes are converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FbxTextureLoading {
+    /// Request every material texture while the file loads
+    #[default]
+    Immediate,
+    /// Request a material's textures once an entity using the material is
+    /// spawned, see [`load_deferred_textures`]; materials that are never used
+    /// never load their textures
+    Deferred,
+}
+
+/// Material texture postponed by [`FbxTextureLoading::Deferred`], listed in
+/// [`Fbx::deferred_textures`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FbxDeferredTexture {
+    pub path: AssetPath<'static>,
+    /// Color space override from
+    /// [`texture_color_spaces`](crate::FbxLoaderSettings::texture_color_spaces)
+    pub color_space: Option<FbxColorSpace>,
+    /// Sampler from
+    /// [`texture_sampler`](crate::FbxLoaderSettings::texture_sampler)
+    pub sampler: Option<ImageSamplerDescriptor>,
+}
+
+impl FbxDeferredTexture {
+    /// Placeholder handle materials hold until the texture is requested,
+    /// the same for every texture with this path and color space.
+    pub fn handle(&self) -> Handle<Image> {
+        let mut hasher = DefaultHasher::new();
+        self.path.hash(&mut hasher);
+        self.color_space.hash(&mut hasher);
+        let uuid = Uuid::from_u64_pair(DEFERRED_TEXTURE_NAMESPACE, hasher.finish());
+        Handle::Uuid(uuid, PhantomData)
+    }
+
+    /// Request the image from `asset_server`.
+    pub fn load(&self, asset_server: &AssetServer) -> Handle<Image> {
+        let settings = FbxImageSettings {
+            color_space: self.color_space,
+            sampler: self.sampler.clone(),
+        };
+        if settings.is_default() {
+            return asset_server.load(self.path.clone());
+        }
+        asset_server.load_with_settings(
+            self.path.clone(),
+            move |loader_settings: &mut ImageLoaderSettings| settings.apply(loader_settings),
+        )
+    }
+}
+
+/// Texture slots of `material` the loader assigns.
+fn material_texture_slots(material: &mut StandardMaterial) -> Vec<&mut Option<Handle<Image>>> {
+    let mut slots = vec![
+        &mut material.base_color_texture,
+        &mut material.emissive_texture,
+        &mut material.metallic_roughness_texture,
+        &mut material.normal_map_texture,
+        &mut material.occlusion_texture,
+    ];
+    #[cfg(feature = "pbr_anisotropy_texture")]
+    slots.push(&mut material.anisotropy_texture);
+    slots
+}
+
+/// Replace the deferred texture placeholders of `material` with loading
+/// images. Returns whether any slot changed.
+pub fn resolve_deferred_textures(
+    material: &mut StandardMaterial,
+    deferred: &HashMap<AssetId<Image>, &FbxDeferredTexture>,
+    asset_server: &AssetServer,
+) -> bool {
+    let mut changed = false;
+    for slot in material_texture_slots(material) {
+        if let Some(texture) = slot.as_ref().and_then(|handle| deferred.get(&handle.id())) {
+            *slot = Some(texture.load(asset_server));
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Request the deferred textures of materials on newly spawned entities.
+pub fn load_deferred_textures(
+    asset_server: Res<AssetServer>,
+    fbx_assets: Res<Assets<Fbx>>,
+    added: Query<&MeshMaterial3d<StandardMaterial>, Added<MeshMaterial3d<StandardMaterial>>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if added.is_empty() {
+        return;
+    }
+    let deferred: HashMap<AssetId<Image>, &FbxDeferredTexture> = fbx_assets
+        .iter()
+        .flat_map(|(_, fbx)| fbx.deferred_textures.iter())
+        .map(|(&id, texture)| (id, texture))
+        .collect();
+    if deferred.is_empty() {
+        return;
+    }
+
+    let mut visited = HashSet::new();
+    for material in &added {
+        if !visited.insert(material.id()) {
+            continue;
+        }
+        let Some(mut resolved) = materials.get(material.id()).cloned() else {
+            continue;
+        };
+        if resolve_deferred_textures(&mut resolved, &deferred, &asset_server)
+            && let Some(mut target) = materials.get_mut(material.id())
+        {
+            *target = resolved;
+        }
+    }
+}
+
+/// What to do with material textures whose file cannot be found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MissingTexturePolicy {
+    /// Fail the load with [`FbxError::TextureLoad`](crate::FbxError::TextureLoad)
+    Error,
+    /// Log a warning and leave the material slot empty
+    #[default]
+    WarnAndSkip,
+    /// Log a warning and use a magenta/black checkerboard, shared as the
+    /// `MissingTexture` sub-asset
+    Placeholder,
+}
+
+/// Magenta and black checkerboard marking missing textures.
+pub fn checkerboard_image() -> Image {
+    let data = (0..CHECKERBOARD_SIZE * CHECKERBOARD_SIZE)
+        .flat_map(|pixel| {
+            let (x, y) = (pixel % CHECKERBOARD_SIZE, pixel / CHECKERBOARD_SIZE);
+            if (x + y) % 2 == 0 {
+                [255, 0, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            }
+        })
+        .collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: CHECKERBOARD_SIZE,
+            height: CHECKERBOARD_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Files known to exist around an FBX file.
+#[derive(Debug, Clone, Default)]
+pub struct TextureIndex {
+    /// Known files, or `None` if existence cannot be checked
+    files: Option<Vec<PathBuf>>,
+}
+
+impl TextureIndex {
+    /// An index of the given files.
+    pub fn from_files(files: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            files: Some(files.into_iter().collect()),
+        }
+    }
+
+    /// Index the files of `dirs` through the asset source of `load_context`.
+    ///
+    /// Directories that cannot be read are skipped. If none can be listed, as
+    /// with the HTTP asset reader used on the web, the index is left unchecked
+    /// so texture paths are still requested from the asset server.
+    pub async fn scan(load_context: &LoadContext<'_>, dirs: &[PathBuf]) -> Self {
+        let Ok(source) = load_context
+            .asset_server()
+            .get_source(load_context.path().source().clone())
+        else {
+            return Self::default();
+        };
+        let reader = source.reader();
+
+        let mut files = Vec::new();
+        let mut listed_any = false;
+        let mut pending: Vec<(PathBuf, usize)> = dirs.iter().map(|dir| (dir.clone(), 0)).collect();
+        while let Some((dir, depth)) = pending.pop() {
+            let Ok(mut entries) = reader.read_directory(&dir).await else {
+                continue;
+            };
+            listed_any = true;
+            while let Some(path) = entries.next().await {
+                if reader.is_directory(&path).await.unwrap_or(false) {
+                    if depth + 1 < INDEX_DEPTH {
+                        pending.push((path, depth + 1));
+                    }
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+
+        if !listed_any {
+            return Self::default();
+        }
+        Self::from_files(files)
+    }
+
+    /// Resolve the first candidate that exists, ignoring case if needed.
+    ///
+    /// Without a file list the first candidate is returned unchecked.
+    pub fn resolve(&self, candidates: &[PathBuf]) -> Option<PathBuf> {
+        let Some(files) = &self.files else {
+            return candidates.first().cloned();
+        };
+
+        candidates
+            .iter()
+            .find(|candidate| files.contains(candidate))
+            .or_else(|| {
+                candidates.iter().find_map(|candidate| {
+                    let candidate = candidate.to_string_lossy().to_lowercase();
+                    files
+                        .iter()
+                        .find(|file| file.to_string_lossy().to_lowercase() == candidate)
+                })
+            })
+            .cloned()
+    }
+}
+
+/// Image handles of one load, keyed by resolved asset path.
+///
+/// Texture elements of different materials often point at the same file;
+/// each distinct path is only requested from the sink once. Loads across
+/// files are deduplicated by the asset server itself.
+#[derive(Debug, Default)]
+pub struct TextureCache {
+    handles: HashMap<(PathBuf, Option<FbxColorSpace>), Handle<Image>>,
+    placeholder: Option<Handle<Image>>,
+    deferred: HashMap<AssetId<Image>, FbxDeferredTexture>,
+    sampler: Option<ImageSamplerDescriptor>,
+}
+
+impl TextureCache {
+    /// Cache requesting every image with `sampler`, or the image loader's
+    /// default sampler if `None`.
+    pub fn with_sampler(sampler: Option<ImageSamplerDescriptor>) -> Self {
+        Self {
+            sampler,
+            ..Default::default()
+        }
+    }
+
+    /// Handle for `path`, requesting the image from `sink` on first use.
+    pub fn load(&mut self, path: &Path, sink: &mut impl FbxAssetSink) -> Handle<Image> {
+        self.load_with_color_space(path, None, sink)
+    }
+
+    /// Handle for `path` decoded in `color_space`, or with the image loader's
+    /// default if `None`.
+    ///
+    /// The same file loaded in different color spaces gets separate handles.
+    pub fn load_with_color_space(
+        &mut self,
+        path: &Path,
+        color_space: Option<FbxColorSpace>,
+        sink: &mut impl FbxAssetSink,
+    ) -> Handle<Image> {
+        self.handles
+            .entry((texture_cache_key(path), color_space))
+            .or_insert_with(|| {
+                let settings = FbxImageSettings {
+                    color_space,
+                    sampler: self.sampler.clone(),
+                };
+                sink.load_texture_with_settings(sink.asset_path(path), &settings)
+            })
+            .clone()
+    }
+
+    /// Placeholder handle for `path`, recording it as a deferred texture
+    /// instead of requesting it.
+    pub fn defer(
+        &mut self,
+        path: impl Into<AssetPath<'static>>,
+        color_space: Option<FbxColorSpace>,
+    ) -> Handle<Image> {
+        let texture = FbxDeferredTexture {
+            path: path.into(),
+            color_space,
+            sampler: self.sampler.clone(),
+        };
+        let handle = texture.handle();
+        self.deferred.insert(handle.id(), texture);
+        handle
+    }
+
+    /// Take the textures recorded by [`defer`](Self::defer).
+    pub fn take_deferred(&mut self) -> HashMap<AssetId<Image>, FbxDeferredTexture> {
+        std::mem::take(&mut self.deferred)
+    }
+
+    /// Handle of the [`checkerboard_image`] used for missing textures, added
+    /// to `sink` on first use.
+    pub fn placeholder(&mut self, sink: &mut impl FbxAssetSink) -> Handle<Image> {
+        self.placeholder
+            .get_or_insert_with(|| {
+                sink.add_labeled_asset(
+                    FbxAssetLabel::MissingTexture.to_string(),
+                    checkerboard_image(),
+                )
+            })
+            .clone()
+    }
+
+    /// Images requested so far sorted by path, excluding deferred textures
+    /// and the placeholder.
+    pub fn images(&self) -> Vec<Handle<Image>> {
+        let mut images: Vec<_> = self.handles.iter().collect();
+        images.sort_unstable_by(|((a, a_space), _), ((b, b_space), _)| {
+            let srgb = |space: &Option<FbxColorSpace>| space.map(FbxColorSpace::is_srgb);
+            a.cmp(b).then_with(|| srgb(a_space).cmp(&srgb(b_space)))
+        });
+        images
+            .into_iter()
+            .map(|(_, handle)| handle.clone())
+            .collect()
+    }
+
+    /// Number of distinct texture paths loaded.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Whether no textures have been loaded.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+}
+
+/// Color space a texture is decoded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FbxColorSpace {
+    /// Gamma-encoded color data, such as albedo maps
+    Srgb,
+    /// Linear data, such as roughness, normal or HDR maps
+    Linear,
+}
+
+impl FbxColorSpace {
+    /// Whether images in this color space are loaded as sRGB.
+    pub fn is_srgb(self) -> bool {
+        self == FbxColorSpace::Srgb
+    }
+}
+
+/// Image loader settings a texture is requested with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FbxImageSettings {
+    /// Color space override, or the image loader's default if `None`
+    pub color_space: Option<FbxColorSpace>,
+    /// Sampler override, or the image loader's default if `None`
+    pub sampler: Option<ImageSamplerDescriptor>,
+}
+
+impl FbxImageSettings {
+    /// Whether the image loader's defaults are kept.
+    pub fn is_default(&self) -> bool {
+        self.color_space.is_none() && self.sampler.is_none()
+    }
+
+    /// Apply the overrides to `settings`.
+    pub fn apply(&self, settings: &mut ImageLoaderSettings) {
+        if let Some(color_space) = self.color_space {
+            settings.is_srgb = color_space.is_srgb();
+        }
+        if let Some(sampler) = &self.sampler {
+            settings.sampler = ImageSampler::Descriptor(sampler.clone());
+        }
+    }
+}
+
+/// Color space override of
+/// [`FbxLoaderSettings::texture_color_spaces`](crate::FbxLoaderSettings::texture_color_spaces).
+///
+/// A rule applies to a texture if both its patterns match; a missing pattern
+/// matches anything.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FbxColorSpaceRule {
+    /// Glob pattern (`*`, `?`) of the FBX material property the texture is
+    /// connected to, e.g. `"Roughness*"` or `"DiffuseColor"`
+    pub slot: Option<String>,
+    /// Glob pattern of the resolved texture path, e.g. `"*.exr"`
+    pub path: Option<String>,
+    pub color_space: FbxColorSpace,
+}
+
+impl FbxColorSpaceRule {
+    /// Whether the rule applies to a texture at `path` used in `slots`.
+    pub fn matches(&self, slots: &[&str], path: &str) -> bool {
+        let slot_matches = self
+            .slot
+            .as_deref()
+            .is_none_or(|pattern| slots.iter().any(|slot| glob_match(pattern, slot)));
+        let path_matches = self
+            .path
+            .as_deref()
+            .is_none_or(|pattern| glob_match(pattern, &normalize_texture_path(path)));
+        slot_matches && path_matches
+    }
+}
+
+/// Color space of the first rule in `rules` matching a texture, if any.
+pub fn texture_color_space(
+    rules: &[FbxColorSpaceRule],
+    slots: &[&str],
+    path: &str,
+) -> Option<FbxColorSpace> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(slots, path))
+        .map(|rule| rule.color_space)
+}
+
+/// Lexically normalized form of a texture path, resolving `.` and `..`.
+pub fn texture_cache_key(path: &Path) -> PathBuf {
+    let mut key = PathBuf::new();
+    for component in Path::new(&normalize_texture_path(&path.to_string_lossy())).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if key.file_name().is_some() => {
+                key.pop();
+            }
+            component => key.push(component),
+        }
+    }
+    key
+}
+
+/// Normalize Windows path separators in an FBX texture path.
+pub fn normalize_texture_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Whether a texture path is absolute on any platform (`/...`, `C:/...`).
+pub fn is_absolute_texture_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+/// Build the ordered list of candidate asset paths for a texture.
+pub fn texture_candidates(
+    relative_filename: &str,
+    filename: &str,
+    base_dir: &Path,
+    search_dirs: &[PathBuf],
+) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    let mut push = |path: PathBuf| {
+        if !candidates.contains(&path) {
+            candidates.push(path);
+        }
+    };
+
+    for name in [relative_filename, filename] {
+        let name = normalize_texture_path(name);
+        if !name.is_empty() && !is_absolute_texture_path(&name) {
+            push(base_dir.join(name));
+        }
+    }
+
+    let basename = [filename, relative_filename]
+        .into_iter()
+        .map(normalize_texture_path)
+        .find_map(|name| {
+            name.rsplit('/')
+                .next()
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+        });
+    if let Some(basename) = basename {
+        push(base_dir.join(&basename));
+        for dir in search_dirs {
+            push(dir.join(&basename));
+        }
+    }
+
+    candidates
+}
+
+/// Callback rewriting texture asset paths before they are loaded.
+///
+/// Returning `None` keeps the path unchanged.
+#[derive(Clone)]
+pub struct TexturePathHook(pub Arc<dyn Fn(&Path) -> Option<PathBuf> + Send + Sync>);
+
+impl TexturePathHook {
+    /// Wrap a path rewriting function.
+    pub fn new(hook: impl Fn(&Path) -> Option<PathBuf> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+}
+
+impl fmt::Debug for TexturePathHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TexturePathHook")
+    }
+}
+
+impl PartialEq for TexturePathHook {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Rewrite a texture path through the remap tables and hook in `settings`.
+///
+/// [`FbxLoaderSettings::texture_path_remap`] is applied first, then
+/// [`FbxLoaderSettings::texture_extension_remap`], then
+/// [`FbxLoaderSettings::texture_path_hook`].
+pub fn remap_texture_path(path: &Path, settings: &FbxLoaderSettings) -> PathBuf {
+    let key = normalize_texture_path(&path.to_string_lossy());
+    let mut path = match settings.texture_path_remap.get(&key) {
+        Some(remapped) => PathBuf::from(remapped),
+        None => path.to_path_buf(),
+    };
+
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    if let Some(new_extension) = extension
+        .as_ref()
+        .and_then(|extension| settings.texture_extension_remap.get(extension))
+    {
+        path.set_extension(new_extension);
+    }
+
+    match &settings.texture_path_hook {
+        Some(hook) => (hook.0)(&path).unwrap_or(path),
+        None => path,
+    }
+}
@@ -0,0 +1,56 @@
+//! ufbx thread pool backed by Bevy's [`AsyncComputeTaskPool`].
+//!
+//! ufbx can split deflate decompression and array parsing of large binary
+//! files into tasks. Enabled with
+//! [`FbxLoaderSettings::multithreaded_parsing`](crate::FbxLoaderSettings::multithreaded_parsing).
+
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Runs ufbx's parsing tasks on the [`AsyncComputeTaskPool`].
+///
+/// Tasks are spawned in groups by [`run`](ufbx::ThreadPoolImpl::run) and
+/// joined by [`wait`](ufbx::ThreadPoolImpl::wait).
+#[derive(Default)]
+pub struct FbxThreadPool {
+    groups: Mutex<HashMap<u32, Vec<Task<()>>>>,
+}
+
+impl ufbx::ThreadPoolImpl for FbxThreadPool {
+    fn run(&self, ctx: ufbx::ThreadPoolContext, group: u32, start_index: u32, count: u32) {
+        let pool = AsyncComputeTaskPool::get();
+        let tasks: Vec<Task<()>> = (start_index..start_index + count)
+            .map(|index| pool.spawn(async move { ufbx::thread_pool_run_task(ctx, index) }))
+            .collect();
+        if let Ok(mut groups) = self.groups.lock() {
+            groups.entry(group).or_default().extend(tasks);
+        }
+    }
+
+    fn wait(&self, _ctx: ufbx::ThreadPoolContext, group: u32, _max_index: u32) {
+        let tasks = match self.groups.lock() {
+            Ok(mut groups) => groups.remove(&group).unwrap_or_default(),
+            Err(_) => return,
+        };
+        for task in tasks {
+            block_on(task);
+        }
+    }
+}
+
+/// ufbx thread options running on Bevy's task pool, or the default
+/// single-threaded options if the pool has a single thread or isn't set up.
+pub fn thread_opts(enabled: bool) -> ufbx::ThreadOpts {
+    let threads = AsyncComputeTaskPool::try_get().map_or(1, |pool| pool.thread_num());
+    if !enabled || threads < 2 || cfg!(target_arch = "wasm32") {
+        return ufbx::ThreadOpts::default();
+    }
+
+    ufbx::ThreadOpts {
+        pool: ufbx::ThreadPool::new(FbxThreadPool::default()),
+        // A few tasks per thread keeps all threads busy with uneven chunks
+        num_tasks: threads * 4,
+        ..Default::default()
+    }
+}
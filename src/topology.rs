@@ -0,0 +1,97 @@
+//! Edge, crease and smoothing data kept for runtime subdivision or normal
+//! recalculation.
+
+use bevy::prelude::*;
+
+/// Topology metadata of an FBX mesh, available as the `MeshTopology{}`
+/// sub-asset when
+/// [`FbxLoaderSettings::load_mesh_topology`](crate::FbxLoaderSettings::load_mesh_topology)
+/// is set.
+///
+/// Vertex indices refer to the mesh's unique ufbx vertices, which match the
+/// primitive vertices unless vertices were welded or split by material.
+#[derive(Asset, Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxMeshTopology {
+    /// Vertex index pairs of every edge
+    pub edges: Vec<[u32; 2]>,
+    /// Crease of each edge, from 0 (smooth) to 1 (sharp); empty if the mesh
+    /// has no edge creases
+    pub edge_creases: Vec<f32>,
+    /// Whether each edge is smooth; empty if the mesh has no edge smoothing
+    pub edge_smoothing: Vec<bool>,
+    /// Crease of each vertex; empty if the mesh has no vertex creases
+    pub vertex_creases: Vec<f32>,
+    /// Whether each face uses smooth normals; empty if the mesh has no
+    /// smoothing groups
+    pub face_smoothing: Vec<bool>,
+    /// Vertex indices of each face, before triangulation
+    pub faces: Vec<Vec<u32>>,
+}
+
+impl FbxMeshTopology {
+    /// Whether any edge or vertex has a non-zero crease.
+    pub fn has_creases(&self) -> bool {
+        self.edge_creases
+            .iter()
+            .chain(&self.vertex_creases)
+            .any(|&crease| crease > 0.0)
+    }
+
+    /// Indices of the edges marked as hard, either by a full crease or by
+    /// edge smoothing.
+    pub fn hard_edges(&self) -> Vec<usize> {
+        (0..self.edges.len())
+            .filter(|&edge| {
+                self.edge_creases
+                    .get(edge)
+                    .is_some_and(|&crease| crease >= 1.0)
+                    || self.edge_smoothing.get(edge).is_some_and(|&smooth| !smooth)
+            })
+            .collect()
+    }
+}
+
+/// Collect the edges, creases and smoothing groups of a ufbx mesh.
+pub fn extract_mesh_topology(mesh: &ufbx::Mesh) -> FbxMeshTopology {
+    let vertex_indices = mesh.vertex_indices.as_ref();
+    let vertex = |corner: u32| vertex_indices.get(corner as usize).copied().unwrap_or(0);
+
+    // Creases are stored per face corner; all corners of a vertex share one
+    let mut vertex_creases = Vec::new();
+    if mesh.vertex_crease.exists {
+        vertex_creases = vec![0.0; mesh.num_vertices];
+        for (corner, &v) in vertex_indices.iter().enumerate() {
+            if let Some(crease) = vertex_creases.get_mut(v as usize) {
+                *crease = mesh.vertex_crease[corner] as f32;
+            }
+        }
+    }
+
+    FbxMeshTopology {
+        edges: mesh
+            .edges
+            .as_ref()
+            .iter()
+            .map(|edge| [vertex(edge.a), vertex(edge.b)])
+            .collect(),
+        edge_creases: mesh
+            .edge_crease
+            .as_ref()
+            .iter()
+            .map(|&c| c as f32)
+            .collect(),
+        edge_smoothing: mesh.edge_smoothing.as_ref().to_vec(),
+        vertex_creases,
+        face_smoothing: mesh.face_smoothing.as_ref().to_vec(),
+        faces: mesh
+            .faces
+            .as_ref()
+            .iter()
+            .map(|face| {
+                (face.index_begin..face.index_begin + face.num_indices)
+                    .map(vertex)
+                    .collect()
+            })
+            .collect(),
+    }
+}
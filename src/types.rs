@@ -1,10 +1,26 @@
 //! Type definitions for the FBX loader.
 
-use bevy::asset::{Asset, Handle};
-use bevy::math::Affine2;
+use crate::animation::{FbxAnimationEvent, FbxClipTiming, FbxKeyframeStats, FbxRootMotion};
+use crate::bind_pose::FbxBindPose;
+use crate::cache::FbxVertexCache;
+use crate::collider::FbxColliders;
+use crate::diagnostics::{FbxLoadDiagnostics, FbxWarnings};
+use crate::geometry::FbxGeometry;
+use crate::gltf::FbxOrmTextures;
+use crate::humanoid::FbxHumanoidRig;
+use crate::media::FbxMedia;
+use crate::preset::ImportPreset;
+use crate::texture::FbxDeferredTexture;
+use crate::topology::FbxMeshTopology;
+use crate::validation::FbxReport;
+use crate::version::FbxFileFormat;
+use bevy::animation::AnimationClip;
+use bevy::asset::{Asset, AssetId, Handle};
+use bevy::camera::primitives::Aabb;
+use bevy::math::{Affine2, DVec3};
+use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
 use bevy::pbr::StandardMaterial;
 use bevy::prelude::*;
-use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
 use bevy::scene::Scene;
 use std::collections::HashMap;
 
@@ -13,30 +29,74 @@ use std::collections::HashMap;
 // ============================================================================
 
 /// Handedness of a coordinate system.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum Handedness {
     Right,
     Left,
 }
 
 /// Coordinate axes definition.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
 pub struct FbxAxisSystem {
     pub up: Vec3,
     pub front: Vec3,
     pub handedness: Handedness,
 }
 
+impl FbxAxisSystem {
+    /// Bevy's right-handed Y-up axis system, which loaded scenes are converted to.
+    pub const BEVY: Self = Self {
+        up: Vec3::Y,
+        front: Vec3::Z,
+        handedness: Handedness::Right,
+    };
+
+    /// Right axis, completing `up` and `front` with the system's handedness.
+    pub fn right(&self) -> Vec3 {
+        match self.handedness {
+            Handedness::Right => self.up.cross(self.front),
+            Handedness::Left => self.front.cross(self.up),
+        }
+    }
+}
+
 // ============================================================================
 // Metadata
 // ============================================================================
 
 /// Metadata from FBX header.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Reflect)]
 pub struct FbxMeta {
     pub creator: Option<String>,
     pub creation_time: Option<String>,
     pub original_application: Option<String>,
+    /// Preset applied from
+    /// [`import_preset`](crate::FbxLoaderSettings::import_preset), with
+    /// [`ImportPreset::Auto`] resolved
+    pub import_preset: Option<ImportPreset>,
+    /// Point moved to the origin by
+    /// [`world_origin`](crate::FbxLoaderSettings::world_origin), in meters in
+    /// Bevy's axes; add it to a spawned position to get the file's world
+    /// position
+    pub world_offset: Option<DVec3>,
+    /// FBX version, e.g. `7400` for FBX 2014/2015
+    pub version: Option<u32>,
+    pub format: Option<FbxFileFormat>,
+    /// Name of the camera the file was saved looking through, from the
+    /// `DefaultCamera` global setting
+    pub active_camera: Option<String>,
+    /// Background color of the active camera
+    pub background_color: Option<Color>,
+    /// Ambient color from the `GlobalSettings`, `None` if black
+    pub ambient_color: Option<Color>,
+    /// Frames per second of the file's time mode
+    pub frame_rate: Option<f32>,
+    /// Name of the ufbx time mode the frame rate was taken from
+    pub time_mode: Option<String>,
+    /// Stable hash of the imported content, unaffected by header fields such
+    /// as the creation time; compare it between exports to skip rebuilding
+    /// unchanged files, see [`hash`](crate::hash)
+    pub content_hash: u64,
 }
 
 // ============================================================================
@@ -44,14 +104,23 @@ pub struct FbxMeta {
 // ============================================================================
 
 /// Texture wrapping modes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum FbxWrapMode {
     Repeat,
     Clamp,
 }
 
+impl From<ufbx::WrapMode> for FbxWrapMode {
+    fn from(mode: ufbx::WrapMode) -> Self {
+        match mode {
+            ufbx::WrapMode::Repeat => FbxWrapMode::Repeat,
+            ufbx::WrapMode::Clamp => FbxWrapMode::Clamp,
+        }
+    }
+}
+
 /// Types of textures in FBX materials.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
 pub enum FbxTextureType {
     BaseColor,
     Normal,
@@ -62,12 +131,27 @@ pub enum FbxTextureType {
     Height,
 }
 
-/// Texture information.
-#[derive(Debug, Clone)]
+/// FBX file texture with the path and image the loader resolved for it,
+/// added as a `Texture{}` sub-asset.
+///
+/// Layered textures are described by their top layer, like the image
+/// assigned to materials.
+#[derive(Asset, Debug, Clone, Reflect)]
 pub struct FbxTexture {
     pub name: String,
+    /// Path relative to the FBX file as written by the exporter, or the
+    /// absolute path if there is none
     pub filename: String,
     pub absolute_filename: String,
+    /// Asset path the texture was resolved to, including the asset source of
+    /// the FBX file; `None` if no candidate exists
+    pub path: Option<String>,
+    /// Image loaded for the texture, the placeholder for missing textures
+    /// with [`MissingTexturePolicy::Placeholder`](crate::MissingTexturePolicy::Placeholder)
+    pub image: Option<Handle<Image>>,
+    /// Material properties the texture is connected to, e.g. `DiffuseColor`
+    pub slots: Vec<String>,
+    /// Name of the UV set sampled, empty for the default set
     pub uv_set: String,
     pub uv_transform: Affine2,
     pub wrap_u: FbxWrapMode,
@@ -75,7 +159,7 @@ pub struct FbxTexture {
 }
 
 /// Material representation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
 pub struct FbxMaterial {
     pub name: String,
     pub base_color: Color,
@@ -89,12 +173,44 @@ pub struct FbxMaterial {
     pub textures: HashMap<FbxTextureType, FbxTexture>,
 }
 
+/// Value of a raw FBX property.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum FbxPropertyValue {
+    Bool(bool),
+    Int(i64),
+    Number(f64),
+    /// Vector or color property; unused components are zero
+    Vector(Vec4),
+    String(String),
+}
+
+/// FBX material properties not mapped onto [`StandardMaterial`].
+///
+/// Available as the `MaterialExtras{}` sub-asset and as a component on the
+/// primitive entities using the material, so custom shaders can read
+/// exporter-specific parameters.
+#[derive(Asset, Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct FbxMaterialExtras {
+    /// Unmapped property values by FBX property name
+    pub properties: HashMap<String, FbxPropertyValue>,
+    /// Texture file paths of unmapped texture slots by FBX property name
+    pub textures: HashMap<String, String>,
+}
+
+impl FbxMaterialExtras {
+    /// Whether no unmapped properties or textures were found.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty() && self.textures.is_empty()
+    }
+}
+
 // ============================================================================
 // Lights and Cameras
 // ============================================================================
 
 /// Light types.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum FbxLightType {
     Directional,
     Point,
@@ -104,7 +220,7 @@ pub enum FbxLightType {
 }
 
 /// Light definition.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
 pub struct FbxLight {
     pub name: String,
     pub light_type: FbxLightType,
@@ -116,14 +232,14 @@ pub struct FbxLight {
 }
 
 /// Camera projection modes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum FbxProjectionMode {
     Perspective,
     Orthographic,
 }
 
 /// Camera definition.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
 pub struct FbxCamera {
     pub name: String,
     pub projection_mode: FbxProjectionMode,
@@ -134,37 +250,416 @@ pub struct FbxCamera {
     pub focal_length_mm: f32,
 }
 
+// ============================================================================
+// Meshes
+// ============================================================================
+
+/// Part of an FBX mesh using a single material.
+#[derive(Debug, Clone, Reflect)]
+pub struct FbxPrimitive {
+    pub index: usize,
+    pub mesh: Handle<Mesh>,
+    /// Index into the FBX mesh's material slots
+    pub material_index: usize,
+    /// Material assigned to this primitive, if materials were loaded
+    pub material: Option<Handle<StandardMaterial>>,
+    /// Unmapped properties of the assigned material
+    pub material_extras: Option<Handle<FbxMaterialExtras>>,
+    /// Original skin joint index of each joint referenced by this primitive,
+    /// set when a skinned mesh was split to stay within
+    /// [`MAX_JOINTS`](crate::mesh::MAX_JOINTS)
+    pub joints: Option<Vec<u16>>,
+    /// Local-space bounding box, `None` if the mesh has no positions
+    pub aabb: Option<Aabb>,
+    /// Corner count of each source face, in triangle order, if
+    /// [`record_face_sizes`](crate::FbxLoaderSettings::record_face_sizes) is
+    /// set; see [`face_triangle_ranges`](crate::mesh::face_triangle_ranges).
+    /// Not recorded for primitives split by joints
+    pub face_sizes: Option<Vec<u32>>,
+    /// Vertex cache point of each vertex, set when the mesh has a
+    /// [`vertex_cache`](FbxMesh::vertex_cache)
+    pub cache_vertices: Option<Vec<u32>>,
+    /// Simplified levels of detail, one per level of the matching
+    /// [`simplification`](crate::FbxLoaderSettings::simplification) rule;
+    /// levels that could not be simplified are this primitive's `mesh`
+    pub lods: Vec<Handle<Mesh>>,
+}
+
+/// FBX mesh split into one primitive per material section.
+///
+/// The FBX counterpart of `GltfMesh`: each primitive keeps its material, so
+/// mesh/material pairs can be used without spawning the scene.
+#[derive(Asset, Debug, Clone, Reflect)]
+pub struct FbxMesh {
+    pub index: usize,
+    pub name: String,
+    pub primitives: Vec<FbxPrimitive>,
+    /// Local-space bounding box of all primitives
+    pub aabb: Option<Aabb>,
+    /// Culling bounds of a skinned mesh inserted on its spawned primitives,
+    /// see [`skinned_bounds`](crate::FbxLoaderSettings::skinned_bounds)
+    pub skinned_aabb: Option<Aabb>,
+    /// Blend shape channels driving the primitives' morph targets
+    pub blend_channels: Vec<FbxBlendChannel>,
+    /// Edge, crease and smoothing data, if
+    /// [`load_mesh_topology`](crate::FbxLoaderSettings::load_mesh_topology) is set
+    pub topology: Option<Handle<FbxMeshTopology>>,
+    /// Triangulated source geometry, if
+    /// [`load_geometry`](crate::FbxLoaderSettings::load_geometry) is set
+    pub geometry: Option<Handle<FbxGeometry>>,
+    /// Baked vertex animation, if
+    /// [`load_vertex_caches`](crate::FbxLoaderSettings::load_vertex_caches)
+    /// is set and the mesh has a cache deformer
+    pub vertex_cache: Option<Handle<FbxVertexCache>>,
+}
+
+impl FbxMesh {
+    /// Morph target weights for the given per-channel weights.
+    ///
+    /// `channel_weights` are indexed like [`blend_channels`](Self::blend_channels);
+    /// missing channels use their default weight.
+    pub fn morph_weights(&self, channel_weights: &[f32]) -> Vec<f32> {
+        self.blend_channels
+            .iter()
+            .enumerate()
+            .flat_map(|(index, channel)| {
+                let weight = channel_weights
+                    .get(index)
+                    .copied()
+                    .unwrap_or(channel.weight);
+                channel.target_weights_at(weight)
+            })
+            .collect()
+    }
+}
+
+/// Blend shape channel of a mesh.
+///
+/// Each keyframe of the channel, including in-between shapes, is imported as
+/// a separate morph target starting at `first_target`.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct FbxBlendChannel {
+    pub name: String,
+    /// Morph target index of the channel's first keyframe
+    pub first_target: usize,
+    /// Channel weight at which each target is fully applied, ascending
+    pub target_weights: Vec<f32>,
+    /// Default channel weight, 1.0 being the full shape
+    pub weight: f32,
+}
+
+impl FbxBlendChannel {
+    /// Weights of the channel's morph targets at channel weight `weight`.
+    ///
+    /// See [`in_between_weights`](crate::morph::in_between_weights).
+    pub fn target_weights_at(&self, weight: f32) -> Vec<f32> {
+        crate::morph::in_between_weights(&self.target_weights, weight)
+    }
+}
+
 // ============================================================================
 // Scene Elements
 // ============================================================================
 
+/// Mesh merged from the primitives sharing a material, with node transforms
+/// baked in (see [`optimize`](crate::optimize)).
+#[derive(Debug, Clone, Reflect)]
+pub struct FbxMergedMesh {
+    pub mesh: Handle<Mesh>,
+    /// Shared material, or `None` for primitives without a material
+    pub material: Option<Handle<StandardMaterial>>,
+}
+
+/// Euler rotation order of an FBX node, naming the axes in the order they
+/// are applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum FbxRotationOrder {
+    #[default]
+    Xyz,
+    Xzy,
+    Yzx,
+    Yxz,
+    Zxy,
+    Zyx,
+    Spheric,
+}
+
+impl From<ufbx::RotationOrder> for FbxRotationOrder {
+    fn from(order: ufbx::RotationOrder) -> Self {
+        match order {
+            ufbx::RotationOrder::Xyz => FbxRotationOrder::Xyz,
+            ufbx::RotationOrder::Xzy => FbxRotationOrder::Xzy,
+            ufbx::RotationOrder::Yzx => FbxRotationOrder::Yzx,
+            ufbx::RotationOrder::Yxz => FbxRotationOrder::Yxz,
+            ufbx::RotationOrder::Zxy => FbxRotationOrder::Zxy,
+            ufbx::RotationOrder::Zyx => FbxRotationOrder::Zyx,
+            ufbx::RotationOrder::Spheric => FbxRotationOrder::Spheric,
+        }
+    }
+}
+
+/// Transform properties of a node as written in the file, before ufbx
+/// composes them and converts units and axes.
+///
+/// FBX composes the local matrix as
+/// `T * Roff * Rp * Rpre * R * Rpost⁻¹ * Rp⁻¹ * Soff * Sp * S * Sp⁻¹`;
+/// the geometric transform applies to the node's attribute only.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct FbxTransformData {
+    /// `Lcl Translation`
+    pub translation: Vec3,
+    /// `Lcl Rotation` as Euler angles in degrees
+    pub rotation: Vec3,
+    /// `Lcl Scaling`
+    pub scale: Vec3,
+    pub rotation_order: FbxRotationOrder,
+    /// `PreRotation` in degrees
+    pub pre_rotation: Vec3,
+    /// `PostRotation` in degrees
+    pub post_rotation: Vec3,
+    pub rotation_pivot: Vec3,
+    pub rotation_offset: Vec3,
+    pub scaling_pivot: Vec3,
+    pub scaling_offset: Vec3,
+    pub geometric_translation: Vec3,
+    /// `GeometricRotation` in degrees
+    pub geometric_rotation: Vec3,
+    pub geometric_scale: Vec3,
+}
+
+impl Default for FbxTransformData {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Vec3::ZERO,
+            scale: Vec3::ONE,
+            rotation_order: FbxRotationOrder::Xyz,
+            pre_rotation: Vec3::ZERO,
+            post_rotation: Vec3::ZERO,
+            rotation_pivot: Vec3::ZERO,
+            rotation_offset: Vec3::ZERO,
+            scaling_pivot: Vec3::ZERO,
+            scaling_offset: Vec3::ZERO,
+            geometric_translation: Vec3::ZERO,
+            geometric_rotation: Vec3::ZERO,
+            geometric_scale: Vec3::ONE,
+        }
+    }
+}
+
 /// FBX node with hierarchy.
-#[derive(Asset, Debug, Clone, TypePath)]
+#[derive(Asset, Debug, Clone, Reflect)]
 pub struct FbxNode {
     pub index: usize,
     pub name: String,
     pub children: Vec<Handle<FbxNode>>,
-    pub mesh: Option<Handle<Mesh>>,
+    pub mesh: Option<Handle<FbxMesh>>,
     pub skin: Option<Handle<FbxSkin>>,
     pub transform: Transform,
+    /// World position in f64, unaffected by
+    /// [`world_origin`](crate::FbxLoaderSettings::world_origin)
+    pub world_translation: DVec3,
+    /// Transform properties `transform` is composed from, as in the file
+    pub transform_data: FbxTransformData,
+    /// Geometric (pivot) transform applied to the mesh but not to children
+    pub geometry_transform: Transform,
+    /// World-space matrix of the node's mesh, including the geometric transform
+    pub geometry_to_world: Mat4,
     pub visible: bool,
+    /// Animated visibility as `(time in seconds, visible)` keys
+    pub visibility_keyframes: Vec<(f32, bool)>,
 }
 
 /// FBX skin for skeletal animation.
-#[derive(Asset, Debug, Clone, TypePath)]
+///
+/// Primitives carry the skinning data as `Mesh::ATTRIBUTE_JOINT_INDEX` and
+/// `Mesh::ATTRIBUTE_JOINT_WEIGHT`; with
+/// [`keep_skin_weights`](crate::FbxLoaderSettings::keep_skin_weights) the skin
+/// keeps a copy per ufbx vertex for CPU tooling such as cloth or navmesh
+/// generation.
+#[derive(Asset, Debug, Clone, Reflect)]
 pub struct FbxSkin {
     pub index: usize,
     pub name: String,
     pub joints: Vec<Handle<FbxNode>>,
+    /// Bone node name of each joint index, empty for clusters without a bone
+    pub joint_names: Vec<String>,
+    /// Up to four joint indices per vertex of the skinned mesh, if
+    /// `keep_skin_weights` is set
+    pub vertex_joints: Vec<[u16; 4]>,
+    /// Normalized weights matching [`vertex_joints`](Self::vertex_joints)
+    pub vertex_weights: Vec<[f32; 4]>,
     pub inverse_bind_matrices: Handle<SkinnedMeshInverseBindposes>,
+    /// Joint transforms the mesh was bound in, as the `BindPose{}` sub-asset
+    pub bind_pose: Handle<FbxBindPose>,
+}
+
+impl FbxSkin {
+    /// Joint names and weights influencing `vertex`, skipping zero weights.
+    ///
+    /// Empty unless the skin was loaded with `keep_skin_weights`.
+    pub fn vertex_influences(&self, vertex: usize) -> Vec<(&str, f32)> {
+        let (Some(joints), Some(weights)) = (
+            self.vertex_joints.get(vertex),
+            self.vertex_weights.get(vertex),
+        ) else {
+            return Vec::new();
+        };
+        joints
+            .iter()
+            .zip(weights)
+            .filter(|&(_, &weight)| weight > 0.0)
+            .map(|(&joint, &weight)| {
+                let name = self
+                    .joint_names
+                    .get(joint as usize)
+                    .map_or("", String::as_str);
+                (name, weight)
+            })
+            .collect()
+    }
+}
+
+/// Marker on spawned bone entities.
+///
+/// `skeleton_index` is the index of the first skin deformer using the bone and
+/// `bone_index` the bone's joint index within that skin. Skeleton bones no
+/// skin uses get one past the last skin deformer as `skeleton_index` and are
+/// numbered in file order.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct FbxBone {
+    pub skeleton_index: usize,
+    pub bone_index: usize,
+}
+
+/// Display shape of a null node in the authoring tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum FbxNullLook {
+    #[default]
+    None,
+    Cross,
+}
+
+/// Marker on entities spawned for null (locator) nodes, such as spawn points
+/// and attachment markers.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct FbxNull {
+    /// Display size from the FBX `Size` property (100 by default)
+    pub size: f32,
+    pub look: FbxNullLook,
+}
+
+/// Rotation a billboard is limited to when turning towards the camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum FbxBillboardAxis {
+    /// Rotate about the local Y axis only, keeping vegetation cards upright
+    #[default]
+    Cylindrical,
+    /// Face the camera fully, as for distant imposters
+    Spherical,
+}
+
+/// Marker on entities spawned for nodes marked as billboards by name prefix
+/// or user property, see [`node_billboard`](crate::scene::node_billboard).
+///
+/// The loader doesn't orient billboards; a billboard system of the app
+/// rotates them towards the camera within `axis`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct FbxBillboard {
+    pub axis: FbxBillboardAxis,
+}
+
+/// Marker on entities spawned for mesh nodes marked as shadow-only
+/// occluders, see [`is_shadow_only`](crate::scene::is_shadow_only).
+///
+/// Their primitives sit on
+/// [`shadow_only_layer`](crate::FbxLoaderSettings::shadow_only_layer) only,
+/// which cameras don't render but lights including the layer cast shadows
+/// from.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct FbxShadowOnly;
+
+/// Physical lens settings of a spawned FBX camera.
+///
+/// The film offset is applied as a [`SubCameraView`](bevy::camera::SubCameraView);
+/// with the `depth_of_field` feature, cameras with depth of field enabled in
+/// the DCC also get a `DepthOfField` component.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct FbxCameraLens {
+    /// Film back (aperture) size in millimeters
+    pub film_size_mm: Vec2,
+    /// Lens shift as a fraction of the film size, positive X right and Y up
+    pub film_offset: Vec2,
+    /// Focus distance in scene units, if set
+    pub focus_distance: Option<f32>,
+    /// Aperture in f-stops, if set
+    pub f_stop: Option<f32>,
+    /// Whether depth of field is enabled on the FBX camera
+    pub depth_of_field: bool,
+}
+
+/// Marker on the camera spawned for the file's active camera, see
+/// [`FbxMeta::active_camera`].
+///
+/// Spawned cameras are inactive; viewers can activate this one to start from
+/// the DCC's view.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct FbxActiveCamera;
+
+/// Selection sets (Maya sets, object groups) the node of an entity belongs
+/// to, see [`Fbx::named_node_groups`].
+///
+/// Enables bulk operations such as hiding every entity in `collision_set`.
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct FbxGroupMember {
+    /// Sorted group names
+    pub groups: Vec<String>,
+}
+
+impl FbxGroupMember {
+    /// Whether the entity belongs to the group named `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.groups.iter().any(|group| group == name)
+    }
+}
+
+/// `/`-separated name path from the file's root to the node an entity was
+/// spawned from, e.g. `Turret/Barrel`.
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub struct FbxNodePath(pub String);
+
+/// Spawned entities of a scene instance keyed by [`FbxNodePath`], inserted on
+/// the scene root once the scene has spawned.
+///
+/// Avoids walking the hierarchy by [`Name`] to find a specific node. When
+/// several nodes share a path, the first one spawned is kept.
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct FbxSceneMap {
+    pub entities: HashMap<String, Entity>,
+}
+
+impl FbxSceneMap {
+    /// Entity spawned from the node at `path`, e.g. `"Turret/Barrel"`.
+    pub fn get(&self, path: &str) -> Option<Entity> {
+        self.entities.get(path).copied()
+    }
 }
 
 /// Placeholder for skeleton data.
-#[derive(Asset, Debug, Clone, TypePath)]
+#[derive(Asset, Debug, Clone, Reflect)]
 pub struct Skeleton;
 
 /// Animation interpolation modes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum FbxInterpolation {
     Constant,
     Linear,
@@ -176,20 +671,98 @@ pub enum FbxInterpolation {
 // ============================================================================
 
 /// Representation of a loaded FBX file.
-#[derive(Asset, Debug, TypePath)]
+///
+/// The `named_*` maps are skipped by reflection because `Box<str>` keys are
+/// not reflectable; use the indexed lists instead.
+#[derive(Asset, Debug, Reflect)]
 pub struct Fbx {
+    /// Main scene followed by the `Node/{path}` subtree scenes and the
+    /// `Take/{name}` scenes
     pub scenes: Vec<Handle<Scene>>,
+    /// Subtree scenes by node path and take scenes by animation stack name
+    #[reflect(ignore)]
     pub named_scenes: HashMap<Box<str>, Handle<Scene>>,
-    pub meshes: Vec<Handle<Mesh>>,
-    pub named_meshes: HashMap<Box<str>, Handle<Mesh>>,
+    pub meshes: Vec<Handle<FbxMesh>>,
+    /// World-space bounds of all mesh instances in the scene
+    pub bounds: Option<Aabb>,
+    #[reflect(ignore)]
+    pub named_meshes: HashMap<Box<str>, Handle<FbxMesh>>,
+    /// `LineList` meshes of the file's line curves, if
+    /// [`load_lines_and_points`](crate::FbxLoaderSettings::load_lines_and_points) is set
+    pub line_curves: Vec<Handle<Mesh>>,
     pub materials: Vec<Handle<StandardMaterial>>,
+    #[reflect(ignore)]
     pub named_materials: HashMap<Box<str>, Handle<StandardMaterial>>,
+    /// Texture elements with their resolved paths and images
+    pub textures: Vec<Handle<FbxTexture>>,
+    #[reflect(ignore)]
+    pub named_textures: HashMap<Box<str>, Handle<FbxTexture>>,
+    /// Embedded audio and other non-image files, if
+    /// [`load_media`](crate::FbxLoaderSettings::load_media) is set
+    pub media: Vec<Handle<FbxMedia>>,
+    /// Unmapped material properties, indexed like `materials`
+    pub material_extras: Vec<Handle<FbxMaterialExtras>>,
     pub nodes: Vec<Handle<FbxNode>>,
+    #[reflect(ignore)]
     pub named_nodes: HashMap<Box<str>, Handle<FbxNode>>,
+    /// Node indices of each selection set, keyed by set name
+    #[reflect(ignore)]
+    pub named_node_groups: HashMap<Box<str>, Vec<usize>>,
     pub skins: Vec<Handle<FbxSkin>>,
+    #[reflect(ignore)]
     pub named_skins: HashMap<Box<str>, Handle<FbxSkin>>,
+    pub colliders: Handle<FbxColliders>,
+    pub animations: Vec<Handle<AnimationClip>>,
+    #[reflect(ignore)]
+    pub named_animations: HashMap<Box<str>, Handle<AnimationClip>>,
+    /// Clips restricted to each of
+    /// [`bone_masks`](crate::FbxLoaderSettings::bone_masks) by mask name,
+    /// indexed like `animations`
+    #[reflect(ignore)]
+    pub masked_animations: HashMap<Box<str>, Vec<Handle<AnimationClip>>>,
+    /// Event markers of each animation, indexed like `animations`
+    pub animation_events: Vec<Vec<FbxAnimationEvent>>,
+    /// Extracted root motion of each animation, indexed like `animations`
+    pub root_motion: Vec<Option<FbxRootMotion>>,
+    /// Keyframe reduction stats of each animation, indexed like `animations`
+    pub keyframe_stats: Vec<FbxKeyframeStats>,
+    /// Time range and frame rate of each animation, indexed like `animations`
+    pub clip_timings: Vec<FbxClipTiming>,
+    pub humanoid_rig: Option<Handle<FbxHumanoidRig>>,
     pub default_scene: Option<Handle<Scene>>,
+    /// Axis system the file was authored in
     pub axis_system: FbxAxisSystem,
+    /// Axis system the scene was converted to, or `None` if the file already
+    /// used it
+    pub converted_axis_system: Option<FbxAxisSystem>,
     pub unit_scale: f32,
     pub metadata: FbxMeta,
+    pub diagnostics: FbxLoadDiagnostics,
+    /// Non-fatal problems ufbx reported while parsing
+    pub warnings: FbxWarnings,
+    /// Validation findings, if [`validation`](crate::FbxLoaderSettings::validation) is set
+    pub report: Option<Handle<FbxReport>>,
+    /// Environment or sky texture, if
+    /// [`load_environment_map`](crate::FbxLoaderSettings::load_environment_map)
+    /// is set and the file references one
+    pub environment_map: Option<Handle<Image>>,
+    /// Primitives merged per material, if
+    /// [`merge_by_material`](crate::FbxLoaderSettings::merge_by_material) is set
+    pub merged_meshes: Vec<FbxMergedMesh>,
+    /// Sorted names of the material variants declared through
+    /// `MaterialVariant_<name>` user properties, see
+    /// [`apply_variant`](crate::apply_variant)
+    pub material_variants: Vec<String>,
+    /// Material textures getting mips once loaded, if
+    /// [`generate_mipmaps`](crate::FbxLoaderSettings::generate_mipmaps) is set
+    #[reflect(ignore)]
+    pub mipmap_images: Vec<Handle<Image>>,
+    /// Material textures not requested yet, keyed by the placeholder id the
+    /// materials hold, with
+    /// [`FbxTextureLoading::Deferred`](crate::FbxTextureLoading::Deferred)
+    #[reflect(ignore)]
+    pub deferred_textures: HashMap<AssetId<Image>, FbxDeferredTexture>,
+    /// Separate occlusion, roughness and metallic maps of each material,
+    /// indexed like `materials`, see [`fbx_gltf_materials`](crate::fbx_gltf_materials)
+    pub orm_textures: Vec<FbxOrmTextures>,
 }
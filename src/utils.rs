@@ -1,5 +1,7 @@
 //! Utility functions for converting between ufbx and Bevy types.
 
+use crate::types::{FbxAxisSystem, FbxCameraLens, Handedness};
+use bevy::camera::SubCameraView;
 use bevy::math::{Affine2, Mat4};
 use bevy::prelude::*;
 
@@ -56,3 +58,187 @@ pub fn convert_transform(t: &ufbx::Transform) -> Transform {
         scale: Vec3::new(t.scale.x as f32, t.scale.y as f32, t.scale.z as f32),
     }
 }
+
+/// Unit vector of an FBX `GlobalSettings` axis index (0 = X, 1 = Y, 2 = Z)
+/// and sign.
+pub fn fbx_axis_vector(axis: i64, sign: i64) -> Vec3 {
+    let vector = match axis {
+        0 => Vec3::X,
+        2 => Vec3::Z,
+        _ => Vec3::Y,
+    };
+    if sign < 0 { -vector } else { vector }
+}
+
+/// Build an axis system, deriving handedness from the right, up and front axes.
+pub fn fbx_axis_system(right: Vec3, up: Vec3, front: Vec3) -> FbxAxisSystem {
+    let handedness = if right.cross(up).dot(front) > 0.0 {
+        Handedness::Right
+    } else {
+        Handedness::Left
+    };
+    FbxAxisSystem {
+        up,
+        front,
+        handedness,
+    }
+}
+
+/// Axis system stored in the file's `GlobalSettings`, before ufbx converts the
+/// scene to [`FbxAxisSystem::BEVY`].
+///
+/// Missing properties fall back to the FBX SDK defaults (right-handed Y-up).
+pub fn original_axis_system(scene: &ufbx::Scene) -> FbxAxisSystem {
+    let find_int = |name: &str, default: i64| {
+        scene
+            .settings
+            .props
+            .props
+            .as_ref()
+            .iter()
+            .find(|prop| prop.name.as_ref() as &str == name)
+            .map_or(default, |prop| prop.value_int)
+    };
+
+    fbx_axis_system(
+        fbx_axis_vector(find_int("CoordAxis", 0), find_int("CoordAxisSign", 1)),
+        fbx_axis_vector(find_int("UpAxis", 1), find_int("UpAxisSign", 1)),
+        fbx_axis_vector(find_int("FrontAxis", 2), find_int("FrontAxisSign", 1)),
+    )
+}
+
+/// Millimeters per inch, for FBX aperture sizes given in inches.
+const MM_PER_INCH: f32 = 25.4;
+
+/// Lens settings of an FBX camera from its film, offset and focus properties.
+pub fn camera_lens(camera: &ufbx::Camera) -> FbxCameraLens {
+    let find_prop = |name: &str| {
+        camera
+            .element
+            .props
+            .props
+            .as_ref()
+            .iter()
+            .find(|prop| prop.name.as_ref() as &str == name)
+    };
+    let find_real = |name: &str| find_prop(name).map(|prop| prop.value_real as f32);
+
+    let film_size_inch = Vec2::new(
+        camera.aperture_size_inch.x as f32,
+        camera.aperture_size_inch.y as f32,
+    );
+    let offset_inch = Vec2::new(
+        find_real("FilmOffsetX").unwrap_or(0.0),
+        find_real("FilmOffsetY").unwrap_or(0.0),
+    );
+    let film_offset = if film_size_inch.x > 0.0 && film_size_inch.y > 0.0 {
+        offset_inch / film_size_inch
+    } else {
+        Vec2::ZERO
+    };
+
+    FbxCameraLens {
+        film_size_mm: film_size_inch * MM_PER_INCH,
+        film_offset,
+        focus_distance: find_real("FocusDistance").filter(|&distance| distance > 0.0),
+        f_stop: find_real("FStop").filter(|&f_stop| f_stop > 0.0),
+        depth_of_field: find_prop("UseDepthOfField").is_some_and(|prop| prop.value_int != 0),
+    }
+}
+
+/// Sub view and vertical field of view reproducing a lens shift.
+///
+/// A shifted frustum is an off-center part of a larger frustum: the full view
+/// is grown by the offset on both sides and the original view is cropped
+/// from it. `film_offset` is a fraction of the film size as in
+/// [`FbxCameraLens::film_offset`]; returns `None` without an offset.
+pub fn lens_shift_sub_view(
+    film_offset: Vec2,
+    aspect_ratio: f32,
+    fov: f32,
+) -> Option<(SubCameraView, f32)> {
+    if film_offset == Vec2::ZERO || aspect_ratio <= 0.0 {
+        return None;
+    }
+    // Resolution of the virtual image, only the ratios matter
+    const WIDTH: f32 = 4096.0;
+    let size = Vec2::new(WIDTH, WIDTH / aspect_ratio);
+    let full_size = size * (Vec2::ONE + 2.0 * film_offset.abs());
+    // Image Y points down, film offset Y up
+    let offset = size * (film_offset.abs() + Vec2::new(film_offset.x, -film_offset.y));
+    let full_fov = 2.0 * ((fov * 0.5).tan() * full_size.y / size.y).atan();
+
+    Some((
+        SubCameraView {
+            full_size: full_size.round().as_uvec2(),
+            offset,
+            size: size.round().as_uvec2(),
+        },
+        full_fov,
+    ))
+}
+
+/// Name of the camera in the `DefaultCamera` global setting, if any.
+pub fn default_camera_name(scene: &ufbx::Scene) -> Option<String> {
+    scene
+        .settings
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == "DefaultCamera")
+        .map(|prop| (prop.value_str.as_ref() as &str).to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Background color of the camera attached to the node named `name`.
+pub fn camera_background_color(scene: &ufbx::Scene, name: &str) -> Option<Color> {
+    let node = scene
+        .nodes
+        .as_ref()
+        .iter()
+        .find(|node| node.element.name.as_ref() as &str == name)?;
+    let camera = node.camera.as_ref()?;
+    let color = camera
+        .element
+        .props
+        .props
+        .as_ref()
+        .iter()
+        .find(|prop| prop.name.as_ref() as &str == "BackgroundColor")?
+        .value_vec3;
+    Some(Color::srgb(color.x as f32, color.y as f32, color.z as f32))
+}
+
+/// Match `text` against a glob `pattern` where `*` matches any run of
+/// characters and `?` matches a single character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,249 @@
+//! Optional validation pass reporting common export problems.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Thresholds of the validation pass enabled by
+/// [`FbxLoaderSettings::validation`](crate::FbxLoaderSettings::validation).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FbxValidation {
+    /// Largest allowed width or height of embedded textures, in pixels
+    pub max_texture_size: u32,
+    /// Allowed deviation of a vertex's summed skin weights from 1
+    pub weight_tolerance: f32,
+    /// Triangles with a smaller area are reported as degenerate
+    pub min_triangle_area: f32,
+}
+
+impl Default for FbxValidation {
+    fn default() -> Self {
+        Self {
+            max_texture_size: 4096,
+            weight_tolerance: 0.001,
+            min_triangle_area: 1e-10,
+        }
+    }
+}
+
+/// Kind of problem found by the validation pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum FbxIssueKind {
+    /// Triangles with (nearly) zero area or repeated vertices
+    DegenerateTriangles,
+    /// Edges shared by more than two triangles
+    NonManifoldEdges,
+    MissingUvs,
+    MissingNormals,
+    /// Skinned vertices whose weights don't sum to 1
+    UnnormalizedSkinWeights,
+    /// Embedded texture larger than [`FbxValidation::max_texture_size`]
+    OversizedTexture,
+    /// Vertex or material indices pointing past the end of their arrays
+    IndexOutOfRange,
+}
+
+/// Single finding of the validation pass.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub struct FbxIssue {
+    pub kind: FbxIssueKind,
+    /// Name of the mesh or texture the issue was found in
+    pub element: String,
+    /// Number of occurrences, e.g. degenerate triangles in the mesh
+    pub count: usize,
+}
+
+/// Validation findings of an FBX file, available as the `Report` sub-asset.
+#[derive(Asset, Debug, Clone, Default, PartialEq, Reflect)]
+pub struct FbxReport {
+    pub issues: Vec<FbxIssue>,
+}
+
+impl FbxReport {
+    /// Whether no issues were found.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Whether any issue of `kind` was found.
+    pub fn has(&self, kind: FbxIssueKind) -> bool {
+        self.issues.iter().any(|issue| issue.kind == kind)
+    }
+
+    /// Total occurrences of issues of `kind`.
+    pub fn count(&self, kind: FbxIssueKind) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.kind == kind)
+            .map(|issue| issue.count)
+            .sum()
+    }
+
+    fn push(&mut self, kind: FbxIssueKind, element: &str, count: usize) {
+        if count > 0 {
+            self.issues.push(FbxIssue {
+                kind,
+                element: element.to_string(),
+                count,
+            });
+        }
+    }
+}
+
+/// Run the validation pass over every mesh and embedded texture.
+///
+/// Texture sizes are only known for embedded PNG textures; external files are
+/// not read.
+pub fn validate_scene(scene: &ufbx::Scene, validation: &FbxValidation) -> FbxReport {
+    let mut report = FbxReport::default();
+
+    for mesh in scene.meshes.as_ref().iter() {
+        let name: &str = mesh.element.name.as_ref();
+        let positions: Vec<Vec3> = mesh
+            .vertex_position
+            .values
+            .as_ref()
+            .iter()
+            .map(|v| Vec3::new(v.x as f32, v.y as f32, v.z as f32))
+            .collect();
+
+        let out_of_range_vertices = mesh
+            .vertex_indices
+            .as_ref()
+            .iter()
+            .filter(|&&index| index as usize >= positions.len())
+            .count();
+        let material_count = mesh.materials.as_ref().len();
+        let out_of_range_materials = mesh
+            .face_material
+            .as_ref()
+            .iter()
+            .filter(|&&index| material_count > 0 && index as usize >= material_count)
+            .count();
+        report.push(
+            FbxIssueKind::IndexOutOfRange,
+            name,
+            out_of_range_vertices + out_of_range_materials,
+        );
+
+        // Triangles as indices into the unique vertex positions
+        let mut triangles = Vec::new();
+        let mut scratch = Vec::new();
+        for &face in mesh.faces.as_ref().iter() {
+            scratch.clear();
+            ufbx::triangulate_face_vec(&mut scratch, mesh, face);
+            triangles.extend(
+                scratch
+                    .iter()
+                    .filter_map(|&corner| mesh.vertex_indices.as_ref().get(corner as usize)),
+            );
+        }
+        report.push(
+            FbxIssueKind::DegenerateTriangles,
+            name,
+            count_degenerate_triangles(&positions, &triangles, validation.min_triangle_area),
+        );
+        report.push(
+            FbxIssueKind::NonManifoldEdges,
+            name,
+            count_non_manifold_edges(&triangles),
+        );
+
+        if !mesh.vertex_uv.exists {
+            report.push(FbxIssueKind::MissingUvs, name, 1);
+        }
+        if !mesh.vertex_normal.exists {
+            report.push(FbxIssueKind::MissingNormals, name, 1);
+        }
+
+        if let Some(skin) = mesh.skin_deformers.as_ref().first() {
+            let mut weight_sums = vec![0.0f32; positions.len()];
+            for cluster in skin.clusters.as_ref().iter() {
+                for (&vertex, &weight) in cluster.vertices.iter().zip(cluster.weights.iter()) {
+                    if let Some(sum) = weight_sums.get_mut(vertex as usize) {
+                        *sum += weight as f32;
+                    }
+                }
+            }
+            report.push(
+                FbxIssueKind::UnnormalizedSkinWeights,
+                name,
+                count_unnormalized_weights(&weight_sums, validation.weight_tolerance),
+            );
+        }
+    }
+
+    for texture in scene.textures.as_ref().iter() {
+        let Some((width, height)) = png_dimensions(texture.content.as_ref()) else {
+            continue;
+        };
+        if width.max(height) > validation.max_texture_size {
+            report.push(
+                FbxIssueKind::OversizedTexture,
+                texture.element.name.as_ref(),
+                1,
+            );
+        }
+    }
+
+    report
+}
+
+/// Count triangles with repeated vertices or an area below `min_area`.
+///
+/// `triangles` are indices into `positions`, three per triangle; triangles
+/// with out-of-range indices are skipped.
+pub fn count_degenerate_triangles(positions: &[Vec3], triangles: &[u32], min_area: f32) -> usize {
+    triangles
+        .chunks_exact(3)
+        .filter(|triangle| {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+            if a == b || b == c || a == c {
+                return true;
+            }
+            let (Some(&pa), Some(&pb), Some(&pc)) = (
+                positions.get(a as usize),
+                positions.get(b as usize),
+                positions.get(c as usize),
+            ) else {
+                return false;
+            };
+            (pb - pa).cross(pc - pa).length() * 0.5 < min_area
+        })
+        .count()
+}
+
+/// Count edges shared by more than two triangles.
+pub fn count_non_manifold_edges(triangles: &[u32]) -> usize {
+    let mut edge_faces: HashMap<(u32, u32), usize> = HashMap::new();
+    for triangle in triangles.chunks_exact(3) {
+        for (a, b) in [
+            (triangle[0], triangle[1]),
+            (triangle[1], triangle[2]),
+            (triangle[2], triangle[0]),
+        ] {
+            *edge_faces.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+    }
+    edge_faces.values().filter(|&&faces| faces > 2).count()
+}
+
+/// Count weighted vertices whose weight sum differs from 1 by more than
+/// `tolerance`. Vertices without any weight are ignored.
+pub fn count_unnormalized_weights(weight_sums: &[f32], tolerance: f32) -> usize {
+    weight_sums
+        .iter()
+        .filter(|&&sum| sum > 0.0 && (sum - 1.0).abs() > tolerance)
+        .count()
+}
+
+/// Width and height from the header of PNG data.
+pub fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !bytes.starts_with(PNG_SIGNATURE) || bytes.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(bytes.get(20..24)?.try_into().ok()?);
+    Some((width, height))
+}
@@ -0,0 +1,115 @@
+//! Material variants declared through user properties, in the spirit of
+//! glTF's `KHR_materials_variants`.
+//!
+//! A mesh node lists the materials of a variant in a string user property
+//! named `MaterialVariant_<variant>`, e.g. `MaterialVariant_damaged` set to
+//! `Rust, Glass_Cracked`. Entries are material names for the material slots
+//! of the node's mesh in order; an empty entry keeps the slot's material and
+//! a single entry applies to every slot. The property may also be set on the
+//! mesh itself.
+
+use bevy::pbr::StandardMaterial;
+use bevy::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// Prefix of the user properties declaring material variants.
+pub const MATERIAL_VARIANT_PREFIX: &str = "MaterialVariant_";
+
+/// Materials of a spawned primitive per variant, see
+/// [`Fbx::material_variants`](crate::Fbx::material_variants) and
+/// [`apply_variant`].
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct FbxMaterialVariants {
+    /// Material the primitive was spawned with
+    pub default: Handle<StandardMaterial>,
+    /// Material of each variant changing this primitive
+    pub variants: HashMap<String, Handle<StandardMaterial>>,
+}
+
+impl FbxMaterialVariants {
+    /// Material of the primitive in `variant`, or its default material if
+    /// the variant doesn't change it.
+    pub fn material(&self, variant: &str) -> &Handle<StandardMaterial> {
+        self.variants.get(variant).unwrap_or(&self.default)
+    }
+}
+
+/// Material names of each variant declared on `node` or its mesh, by
+/// material slot; `None` keeps the slot's material. A variant declared on
+/// both uses the node's list.
+pub fn node_material_variants(node: &ufbx::Node) -> Vec<(String, Vec<Option<String>>)> {
+    let mut variants: Vec<(String, Vec<Option<String>>)> = Vec::new();
+    let elements = [
+        Some(&node.element),
+        node.mesh.as_ref().map(|mesh| &mesh.element),
+    ];
+    for element in elements.into_iter().flatten() {
+        for prop in element.props.props.as_ref().iter() {
+            let name = prop.name.as_ref() as &str;
+            let Some(variant) = name.strip_prefix(MATERIAL_VARIANT_PREFIX) else {
+                continue;
+            };
+            if variant.is_empty() || variants.iter().any(|(name, _)| name == variant) {
+                continue;
+            }
+            let value = prop.value_str.as_ref() as &str;
+            let slots = value
+                .split([',', ';'])
+                .map(str::trim)
+                .map(|name| (!name.is_empty()).then(|| name.to_string()))
+                .collect();
+            variants.push((variant.to_string(), slots));
+        }
+    }
+    variants
+}
+
+/// Material name for `material_index` in a variant's slot list.
+pub fn variant_slot_material(slots: &[Option<String>], material_index: usize) -> Option<&str> {
+    let slot = if slots.len() == 1 {
+        &slots[0]
+    } else {
+        slots.get(material_index)?
+    };
+    slot.as_deref()
+}
+
+/// Sorted names of the material variants declared in the scene.
+pub fn scene_material_variants(scene: &ufbx::Scene) -> Vec<String> {
+    let variants: BTreeSet<String> = scene
+        .nodes
+        .as_ref()
+        .iter()
+        .filter(|node| node.mesh.is_some())
+        .flat_map(|node| node_material_variants(node))
+        .map(|(variant, _)| variant)
+        .collect();
+    variants.into_iter().collect()
+}
+
+/// Switch every primitive below `scene_root` with [`FbxMaterialVariants`]
+/// to its material in `variant`. Returns the number of primitives updated.
+///
+/// Primitives the variant doesn't change get their default material back,
+/// so switching to a variant name no primitive uses, e.g. `""`, restores
+/// the authored materials.
+pub fn apply_variant(world: &mut World, scene_root: Entity, variant: &str) -> usize {
+    let mut count = 0;
+    let mut stack = vec![scene_root];
+    while let Some(entity) = stack.pop() {
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            continue;
+        };
+        if let Some(children) = entity.get::<Children>() {
+            stack.extend(children.iter());
+        }
+        let Some(variants) = entity.get::<FbxMaterialVariants>() else {
+            continue;
+        };
+        let material = variants.material(variant).clone();
+        entity.insert(MeshMaterial3d(material));
+        count += 1;
+    }
+    count
+}
@@ -0,0 +1,92 @@
+//! FBX file version detection.
+//!
+//! The version is read from the file header before parsing, so files from
+//! tools too old for ufbx can be rejected with a clear error and legacy
+//! (pre-7.0) files can be parsed with more lenient options.
+
+use crate::error::FbxError;
+use crate::limits::convert_load_error;
+use bevy::prelude::*;
+use std::ops::RangeInclusive;
+
+/// FBX versions ufbx can parse, e.g. `7400` for FBX 2014/2015.
+pub const SUPPORTED_FBX_VERSIONS: RangeInclusive<u32> = 3000..=7700;
+
+/// First FBX 7.x version; older files take ufbx's legacy code paths.
+pub const FIRST_MODERN_FBX_VERSION: u32 = 7000;
+
+/// Number of bytes searched for the version of ASCII files.
+const ASCII_HEADER_SIZE: usize = 64 * 1024;
+
+/// Magic bytes at the start of binary FBX files.
+const BINARY_MAGIC: &[u8] = b"Kaydara FBX Binary  \0\x1a\0";
+
+/// Encoding of an FBX file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum FbxFileFormat {
+    Binary,
+    Ascii,
+}
+
+/// Read the format and version from the header of an FBX file.
+///
+/// Binary files store the version right after the magic bytes. ASCII files
+/// are searched for an `FBXVersion:` property, falling back to the
+/// `; FBX 6.1.0 project file` comment written by older exporters.
+pub fn detect_fbx_version(bytes: &[u8]) -> Option<(FbxFileFormat, u32)> {
+    if let Some(rest) = bytes.strip_prefix(BINARY_MAGIC) {
+        let version = u32::from_le_bytes(rest.get(..4)?.try_into().ok()?);
+        return Some((FbxFileFormat::Binary, version));
+    }
+
+    let header = String::from_utf8_lossy(&bytes[..bytes.len().min(ASCII_HEADER_SIZE)]);
+    if let Some((_, rest)) = header.split_once("FBXVersion:") {
+        let version = leading_digits(rest.trim_start()).parse().ok()?;
+        return Some((FbxFileFormat::Ascii, version));
+    }
+    let comment = header
+        .lines()
+        .find_map(|line| line.trim_start().strip_prefix("; FBX "))?;
+    let mut parts = comment
+        .split('.')
+        .map(|part| leading_digits(part).parse::<u32>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().and_then(Result::ok).unwrap_or(0);
+    let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+    Some((
+        FbxFileFormat::Ascii,
+        major * 1000 + minor * 100 + patch * 10,
+    ))
+}
+
+/// Whether `version` predates FBX 7.0.
+pub fn is_legacy_version(version: u32) -> bool {
+    version < FIRST_MODERN_FBX_VERSION
+}
+
+/// Error for a file whose version ufbx cannot parse.
+pub fn unsupported_version(found: u32) -> FbxError {
+    FbxError::UnsupportedVersion {
+        found,
+        supported_range: SUPPORTED_FBX_VERSIONS,
+    }
+}
+
+/// Convert a ufbx error for a legacy file, pointing at re-exporting as a fix.
+pub fn convert_legacy_load_error(version: u32, error: ufbx::Error) -> FbxError {
+    match convert_load_error(error) {
+        FbxError::UfbxError(message) => FbxError::UfbxError(format!(
+            "{} (legacy FBX {} file; re-exporting as FBX 7.x usually fixes this)",
+            message, version
+        )),
+        error => error,
+    }
+}
+
+/// Longest prefix of `text` made of ASCII digits.
+fn leading_digits(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    &text[..end]
+}
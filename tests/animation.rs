@@ -0,0 +1,353 @@
+//! Tests for animation clip helpers.
+
+use bevy::animation::{AnimatedBy, AnimationTargetId};
+use bevy::prelude::*;
+use bevy_ufbx::animation::{
+    bind_animation_targets, compose_fbx_rotation, fbx_euler_to_quat, path_target_id,
+    retarget_bone_name,
+};
+use bevy_ufbx::{FbxBoneMask, FbxLoaderSettings, FbxSceneMap};
+
+#[test]
+fn test_retarget_bone_name_unchanged() {
+    let settings = FbxLoaderSettings::default();
+    assert_eq!(
+        retarget_bone_name("mixamorig:Hips", &settings),
+        "mixamorig:Hips"
+    );
+}
+
+#[test]
+fn test_retarget_bone_name_strip_prefix() {
+    let settings = FbxLoaderSettings {
+        bone_strip_prefixes: vec!["mixamorig:".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(retarget_bone_name("mixamorig:Hips", &settings), "Hips");
+    assert_eq!(retarget_bone_name("Hips", &settings), "Hips");
+}
+
+#[test]
+fn test_retarget_bone_name_rename_map() {
+    let mut settings = FbxLoaderSettings {
+        bone_strip_prefixes: vec!["mixamorig:".to_string()],
+        ..Default::default()
+    };
+    settings
+        .bone_rename_map
+        .insert("LeftArm".to_string(), "upperarm_l".to_string());
+    settings
+        .bone_rename_map
+        .insert("mixamorig:Hips".to_string(), "pelvis".to_string());
+
+    assert_eq!(
+        retarget_bone_name("mixamorig:LeftArm", &settings),
+        "upperarm_l"
+    );
+    assert_eq!(retarget_bone_name("mixamorig:Hips", &settings), "pelvis");
+}
+
+#[test]
+fn test_animation_event_settings_serialization() {
+    let original = FbxLoaderSettings {
+        animation_event_prefix: Some("EVT_".to_string()),
+        register_animation_events: true,
+        ..Default::default()
+    };
+
+    let serialized = serde_json::to_string(&original).expect("Failed to serialize");
+    let deserialized: FbxLoaderSettings =
+        serde_json::from_str(&serialized).expect("Failed to deserialize");
+
+    assert_eq!(deserialized.animation_event_prefix.as_deref(), Some("EVT_"));
+    assert!(deserialized.register_animation_events);
+}
+
+#[test]
+fn test_extract_root_motion() {
+    use bevy::prelude::*;
+    use bevy_ufbx::animation::{NodeKeys, extract_root_motion};
+
+    let mut keys = NodeKeys {
+        translation: vec![
+            (0.0, Vec3::new(1.0, 1.0, 0.0)),
+            (1.0, Vec3::new(2.0, 1.5, 3.0)),
+        ],
+        ..Default::default()
+    };
+
+    let motion = extract_root_motion(&mut keys);
+
+    assert_eq!(
+        motion,
+        vec![(0.0, Vec3::ZERO), (1.0, Vec3::new(1.0, 0.0, 3.0))]
+    );
+    assert_eq!(keys.translation[1].1, Vec3::new(1.0, 1.5, 0.0));
+}
+
+#[test]
+fn test_root_motion_sample() {
+    use bevy::prelude::*;
+    use bevy_ufbx::FbxRootMotion;
+
+    let motion = FbxRootMotion {
+        bone: "Hips".to_string(),
+        translation: vec![(0.0, Vec3::ZERO), (2.0, Vec3::new(4.0, 0.0, 0.0))],
+    };
+
+    assert_eq!(motion.sample(-1.0), Vec3::ZERO);
+    assert_eq!(motion.sample(1.0), Vec3::new(2.0, 0.0, 0.0));
+    assert_eq!(motion.sample(3.0), Vec3::new(4.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_reduce_keys_linear() {
+    use bevy::prelude::*;
+    use bevy_ufbx::animation::reduce_keys;
+
+    let keys: Vec<(f32, Vec3)> = (0..5)
+        .map(|i| (i as f32, Vec3::new(i as f32, 0.0, 0.0)))
+        .collect();
+
+    let reduced = reduce_keys(&keys, 0.001, Vec3::lerp, Vec3::distance);
+
+    assert_eq!(reduced, vec![keys[0], keys[4]]);
+}
+
+#[test]
+fn test_reduce_keys_keeps_corners() {
+    use bevy::prelude::*;
+    use bevy_ufbx::animation::reduce_keys;
+
+    let keys = vec![
+        (0.0, Vec3::ZERO),
+        (1.0, Vec3::X),
+        (2.0, Vec3::new(2.0, 0.0, 0.0)),
+        (3.0, Vec3::new(2.0, 1.0, 0.0)),
+    ];
+
+    let reduced = reduce_keys(&keys, 0.001, Vec3::lerp, Vec3::distance);
+
+    assert_eq!(reduced, vec![keys[0], keys[2], keys[3]]);
+}
+
+#[test]
+fn test_node_keys_reduce_stats() {
+    use bevy::prelude::*;
+    use bevy_ufbx::FbxKeyframeReduction;
+    use bevy_ufbx::animation::NodeKeys;
+
+    let mut keys = NodeKeys {
+        rotation: (0..10).map(|i| (i as f32, Quat::IDENTITY)).collect(),
+        ..Default::default()
+    };
+    assert_eq!(keys.len(), 10);
+
+    keys.reduce(&FbxKeyframeReduction::default());
+
+    assert_eq!(keys.len(), 2);
+}
+
+#[test]
+fn test_track_filter_apply() {
+    use bevy_ufbx::FbxTrackFilter;
+    use bevy_ufbx::animation::NodeKeys;
+
+    let keys = NodeKeys {
+        translation: vec![(0.0, Vec3::ZERO), (1.0, Vec3::X)],
+        rotation: vec![(0.0, Quat::IDENTITY), (1.0, Quat::IDENTITY)],
+        scale: vec![(0.0, Vec3::ONE), (1.0, Vec3::splat(1.01))],
+    };
+    let filter = FbxTrackFilter::rotation_only_bones();
+
+    let mut bone = keys.clone();
+    filter.apply(&mut bone, true, false);
+    assert!(bone.translation.is_empty());
+    assert_eq!(bone.rotation.len(), 2);
+    assert!(bone.scale.is_empty());
+
+    let mut root = keys.clone();
+    filter.apply(&mut root, true, true);
+    assert_eq!(root.translation.len(), 2);
+    assert!(root.scale.is_empty());
+
+    let mut prop = keys;
+    filter.apply(&mut prop, false, false);
+    assert_eq!(prop.translation.len(), 2);
+}
+
+#[test]
+fn test_clip_track_filter() {
+    use bevy_ufbx::animation::clip_track_filter;
+    use bevy_ufbx::{FbxTrackFilter, FbxTrackMask};
+
+    let settings = FbxLoaderSettings {
+        track_filter: FbxTrackFilter::rotation_only_bones(),
+        clip_track_filters: vec![("Facial_*".to_string(), FbxTrackFilter::default())],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        clip_track_filter("Facial_Smile", &settings).scale,
+        FbxTrackMask::All
+    );
+    assert_eq!(
+        clip_track_filter("Run", &settings).scale,
+        FbxTrackMask::None
+    );
+}
+
+#[test]
+fn test_focal_length_to_fov() {
+    use bevy_ufbx::animation::focal_length_to_fov;
+
+    // 50 mm lens on a 24 mm high full-frame film back
+    let fov = focal_length_to_fov(50.0, 24.0);
+    assert!((fov.to_degrees() - 26.99).abs() < 0.01);
+    assert!(focal_length_to_fov(100.0, 24.0) < fov);
+}
+
+#[test]
+fn test_fbx_euler_xyz_matches_bevy() {
+    let degrees = Vec3::new(10.0, 20.0, 30.0);
+    let expected = Quat::from_euler(
+        EulerRot::ZYX,
+        30f32.to_radians(),
+        20f32.to_radians(),
+        10f32.to_radians(),
+    );
+
+    let rotation = fbx_euler_to_quat(degrees, ufbx::RotationOrder::Xyz);
+    assert!(rotation.angle_between(expected) < 1e-5);
+}
+
+#[test]
+fn test_fbx_euler_rotation_order() {
+    // Maya rotate order xyz: X first, so Y goes to Z, then Y turns Z into X
+    let degrees = Vec3::new(90.0, 90.0, 0.0);
+    let xyz = fbx_euler_to_quat(degrees, ufbx::RotationOrder::Xyz) * Vec3::Y;
+    assert!(xyz.abs_diff_eq(Vec3::X, 1e-5));
+
+    // Maya rotate order yxz: Y first leaves Y in place, then X turns it into Z
+    let yxz = fbx_euler_to_quat(degrees, ufbx::RotationOrder::Yxz) * Vec3::Y;
+    assert!(yxz.abs_diff_eq(Vec3::Z, 1e-5));
+}
+
+#[test]
+fn test_compose_fbx_rotation_pre_post() {
+    let quarter_z = Vec3::new(0.0, 0.0, 90.0);
+
+    let pre = compose_fbx_rotation(quarter_z, Vec3::ZERO, Vec3::ZERO, ufbx::RotationOrder::Xyz);
+    assert!((pre * Vec3::X).abs_diff_eq(Vec3::Y, 1e-5));
+
+    // Post rotation is applied inverted
+    let post = compose_fbx_rotation(Vec3::ZERO, Vec3::ZERO, quarter_z, ufbx::RotationOrder::Xyz);
+    assert!((post * Vec3::X).abs_diff_eq(Vec3::NEG_Y, 1e-5));
+
+    // Joint orient style pre rotation cancelled by the animated rotation
+    let cancelled =
+        compose_fbx_rotation(quarter_z, -quarter_z, Vec3::ZERO, ufbx::RotationOrder::Zxy);
+    assert!(cancelled.angle_between(Quat::IDENTITY) < 1e-5);
+}
+
+#[test]
+fn test_clip_timing_frames() {
+    use bevy_ufbx::FbxClipTiming;
+
+    let timing = FbxClipTiming {
+        start: 1.0,
+        end: 3.5,
+        frame_rate: 24.0,
+    };
+
+    assert_eq!(timing.duration(), 2.5);
+    assert_eq!(timing.start_frame(), 24.0);
+    assert_eq!(timing.end_frame(), 84.0);
+    assert_eq!(timing.frame_at(2.0), 48.0);
+}
+
+#[test]
+fn test_path_target_id() {
+    let settings = FbxLoaderSettings {
+        bone_strip_prefixes: vec!["mixamorig:".to_string()],
+        ..Default::default()
+    };
+    let names = [Name::new("Armature"), Name::new("Hips")];
+    assert_eq!(
+        path_target_id("Armature/mixamorig:Hips", &settings),
+        AnimationTargetId::from_names(names.iter())
+    );
+}
+
+#[test]
+fn test_bind_animation_targets() {
+    let mut world = World::new();
+    let player = world.spawn_empty().id();
+    let hips = world.spawn_empty().id();
+    let mut scene_map = FbxSceneMap::default();
+    scene_map.entities.insert("Armature/Hips".to_string(), hips);
+
+    let settings = FbxLoaderSettings::default();
+    let mut commands = world.commands();
+    bind_animation_targets(&mut commands, &scene_map, player, &settings);
+    world.flush();
+
+    assert_eq!(
+        world.get::<AnimationTargetId>(hips),
+        Some(&path_target_id("Armature/Hips", &settings))
+    );
+    assert_eq!(world.get::<AnimatedBy>(hips).map(|by| by.0), Some(player));
+}
+
+#[test]
+fn test_bone_mask_contains_path() {
+    let upper_body = FbxBoneMask {
+        name: "upper_body".to_string(),
+        include: vec!["Spine*".to_string()],
+        exclude: vec!["Neck".to_string()],
+        include_children: true,
+    };
+    assert!(upper_body.contains_path(&["Hips", "Spine"]));
+    assert!(upper_body.contains_path(&["Hips", "Spine", "Spine1", "LeftArm"]));
+    assert!(!upper_body.contains_path(&["Hips", "Spine", "Spine1", "Neck", "Head"]));
+    assert!(!upper_body.contains_path(&["Hips", "LeftUpLeg"]));
+    assert!(!upper_body.contains_path(&["Hips"]));
+
+    let spine_only = FbxBoneMask {
+        include_children: false,
+        ..upper_body
+    };
+    assert!(spine_only.contains_path(&["Hips", "Spine", "Spine1"]));
+    assert!(!spine_only.contains_path(&["Hips", "Spine", "Spine1", "LeftArm"]));
+}
+
+#[test]
+fn test_convert_world_space_keys() {
+    use bevy_ufbx::animation::{bake_animation, convert_world_space_keys};
+
+    let bytes = include_bytes!("fixtures/animated_prop.fbx");
+    let scene = ufbx::load_memory(bytes, ufbx::LoadOpts::default()).expect("Failed to parse");
+    let stack = &scene.anim_stacks.as_ref()[0];
+    let (mut node_keys, _) = bake_animation(&scene, &stack.anim).expect("Failed to bake");
+    convert_world_space_keys(
+        &scene,
+        &stack.anim,
+        stack.time_begin,
+        stack.time_end,
+        &mut node_keys,
+    );
+
+    let node_index = |name: &str| {
+        let nodes = scene.nodes.as_ref();
+        let node = nodes
+            .iter()
+            .find(|node| node.element.name.as_ref() as &str == name);
+        node.expect("Missing node").element.typed_id as usize
+    };
+    let last_translation = |name: &str| node_keys[&node_index(name)].translation.last().unwrap().1;
+    // Door keys include the hinge's offset and scale
+    assert!(last_translation("Door").abs_diff_eq(Vec3::new(20.0, 5.0, 0.0), 1e-4));
+    // The static handle is moved by the animated door
+    assert!(last_translation("Handle").abs_diff_eq(Vec3::new(22.0, 5.0, 0.0), 1e-4));
+    assert!(!node_keys.contains_key(&node_index("Hinge")));
+}
@@ -0,0 +1,69 @@
+//! Tests for attaching entities to bones of spawned scenes.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::{FbxBone, FbxBoneAttachment, FbxSceneInstanceReady, FbxSceneMap};
+use common::test_app;
+
+fn spawn_bone(world: &mut World, name: &str, parent: Entity) -> Entity {
+    world
+        .spawn((
+            Name::new(name.to_string()),
+            Transform::default(),
+            FbxBone {
+                skeleton_index: 0,
+                bone_index: 0,
+            },
+            ChildOf(parent),
+        ))
+        .id()
+}
+
+#[test]
+fn test_bone_attachment_on_scene_ready() {
+    let mut app = test_app();
+    let offset = Transform::from_xyz(0.0, 0.1, 0.0);
+    let root = app.world_mut().spawn_empty().id();
+    let sword = app
+        .world_mut()
+        .spawn((
+            FbxBoneAttachment::new("Hand_R").with_offset(offset),
+            ChildOf(root),
+        ))
+        .id();
+    let missing = app
+        .world_mut()
+        .spawn((FbxBoneAttachment::new("Tail"), ChildOf(root)))
+        .id();
+    app.update();
+    assert_eq!(app.world().get::<ChildOf>(sword), Some(&ChildOf(root)));
+
+    let hips = spawn_bone(app.world_mut(), "Hips", root);
+    let hand = spawn_bone(app.world_mut(), "Hand_R", hips);
+    app.world_mut().trigger(FbxSceneInstanceReady {
+        entity: root,
+        fbx: Handle::default(),
+    });
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.get::<ChildOf>(sword), Some(&ChildOf(hand)));
+    assert_eq!(world.get::<Transform>(sword), Some(&offset));
+    assert_eq!(world.get::<ChildOf>(missing), Some(&ChildOf(root)));
+}
+
+#[test]
+fn test_bone_attachment_added_after_ready() {
+    let mut app = test_app();
+    let root = app.world_mut().spawn(FbxSceneMap::default()).id();
+    let hand = spawn_bone(app.world_mut(), "Hand_L", root);
+
+    let shield = app
+        .world_mut()
+        .spawn((FbxBoneAttachment::new("Hand_L"), ChildOf(root)))
+        .id();
+    app.update();
+
+    assert_eq!(app.world().get::<ChildOf>(shield), Some(&ChildOf(hand)));
+}
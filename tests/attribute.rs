@@ -0,0 +1,123 @@
+//! Tests for custom vertex attributes.
+
+mod common;
+
+use bevy::mesh::{MeshVertexAttribute, VertexAttributeValues, VertexFormat};
+use bevy::prelude::*;
+use bevy_ufbx::attribute::attribute_values;
+use bevy_ufbx::{Fbx, FbxCustomAttribute, FbxLoaderSettings, FbxMesh};
+use common::test_app;
+
+#[test]
+fn test_attribute_values_formats() {
+    let values = [[0.25, 0.5, 0.75, 1.0], [-1.0, 2.0, 0.0, 0.5]];
+
+    assert_eq!(
+        attribute_values(values.into_iter(), VertexFormat::Float32),
+        Some(VertexAttributeValues::Float32(vec![0.25, -1.0]))
+    );
+    assert_eq!(
+        attribute_values(values.into_iter(), VertexFormat::Float32x3),
+        Some(VertexAttributeValues::Float32x3(vec![
+            [0.25, 0.5, 0.75],
+            [-1.0, 2.0, 0.0]
+        ]))
+    );
+    // Normalized formats clamp
+    assert_eq!(
+        attribute_values(values.into_iter(), VertexFormat::Unorm8x4),
+        Some(VertexAttributeValues::Unorm8x4(vec![
+            [64, 128, 191, 255],
+            [0, 255, 0, 128]
+        ]))
+    );
+    assert_eq!(
+        attribute_values(values.into_iter(), VertexFormat::Sint32x4),
+        None
+    );
+}
+
+#[test]
+fn test_custom_attribute_eq() {
+    const ATTRIBUTE_WIND: MeshVertexAttribute =
+        MeshVertexAttribute::new("Wind", 0x5749_4e44, VertexFormat::Float32);
+    const ATTRIBUTE_WIND_RGBA: MeshVertexAttribute =
+        MeshVertexAttribute::new("Wind", 0x5749_4e44, VertexFormat::Float32x4);
+
+    let wind = FbxCustomAttribute::color("Wind", ATTRIBUTE_WIND);
+    assert_eq!(wind, FbxCustomAttribute::color("Wind", ATTRIBUTE_WIND));
+    assert_ne!(wind, FbxCustomAttribute::uv("Wind", ATTRIBUTE_WIND));
+    assert_ne!(wind, FbxCustomAttribute::color("Wind", ATTRIBUTE_WIND_RGBA));
+}
+
+#[test]
+fn test_custom_attributes() {
+    const ATTRIBUTE_WIND: MeshVertexAttribute =
+        MeshVertexAttribute::new("Wind", 0x5749_4e44, VertexFormat::Float32);
+    const ATTRIBUTE_DETAIL_UV: MeshVertexAttribute =
+        MeshVertexAttribute::new("DetailUv", 0x4454_4c55, VertexFormat::Float32x2);
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/vertex_layers.fbx");
+
+    for weld_vertices in [false, true] {
+        let settings = FbxLoaderSettings {
+            weld_vertices,
+            custom_attributes: vec![
+                (
+                    "Grass".to_string(),
+                    FbxCustomAttribute::color("Wind", ATTRIBUTE_WIND),
+                ),
+                (
+                    "*".to_string(),
+                    FbxCustomAttribute::uv("Detail", ATTRIBUTE_DETAIL_UV),
+                ),
+                (
+                    "Rock".to_string(),
+                    FbxCustomAttribute::color("Col", Mesh::ATTRIBUTE_COLOR),
+                ),
+            ],
+            ..Default::default()
+        };
+        let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+            .expect("Failed to import vertex_layers.fbx");
+        let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+        let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+        let meshes = app.world().resource::<Assets<Mesh>>();
+        let mesh = meshes
+            .get(&fbx_mesh.primitives[0].mesh)
+            .expect("Mesh missing");
+
+        let Some(VertexAttributeValues::Float32(wind)) = mesh.attribute(ATTRIBUTE_WIND) else {
+            panic!("Wind attribute missing");
+        };
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("Positions missing");
+        };
+        assert_eq!(wind.len(), positions.len());
+        // Each vertex keeps the wind of its own corners
+        for (position, wind) in positions.iter().zip(wind) {
+            let expected = match (position[0] > 0.0, position[2] > 0.0) {
+                (false, false) => 0.25,
+                (true, false) => 0.5,
+                (true, true) => 0.75,
+                (false, true) => 1.0,
+            };
+            assert_eq!(*wind, expected);
+        }
+        let mut wind = wind.clone();
+        wind.sort_by(f32::total_cmp);
+        assert_eq!(wind, vec![0.25, 0.5, 0.75, 1.0]);
+
+        let Some(VertexAttributeValues::Float32x2(detail)) = mesh.attribute(ATTRIBUTE_DETAIL_UV)
+        else {
+            panic!("Detail UV attribute missing");
+        };
+        assert_eq!(detail.len(), mesh.count_vertices());
+        assert!(detail.contains(&[4.0, 4.0]));
+
+        // Rules for other meshes don't apply
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_COLOR).is_none());
+    }
+}
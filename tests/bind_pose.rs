@@ -0,0 +1,71 @@
+//! Tests for bind poses and resetting spawned bones to them.
+
+use bevy::prelude::*;
+use bevy_ufbx::{FbxBindPose, FbxBone, FbxPlugin, FbxResetToBindPose};
+
+fn bind_pose() -> FbxBindPose {
+    let hips = Transform::from_xyz(0.0, 1.0, 0.0);
+    let spine = Transform::from_xyz(0.0, 0.5, 0.0);
+    FbxBindPose {
+        joint_names: vec!["Hips".to_string(), "Spine".to_string()],
+        global_matrices: vec![hips.to_matrix(), hips.to_matrix() * spine.to_matrix()],
+        local_transforms: vec![hips, spine],
+    }
+}
+
+fn spawn_bone(world: &mut World, name: &str, parent: Entity) -> Entity {
+    world
+        .spawn((
+            Name::new(name.to_string()),
+            Transform::from_xyz(5.0, 5.0, 5.0),
+            FbxBone {
+                skeleton_index: 0,
+                bone_index: 0,
+            },
+            ChildOf(parent),
+        ))
+        .id()
+}
+
+#[test]
+fn test_bind_pose_lookup() {
+    let bind_pose = bind_pose();
+    assert_eq!(bind_pose.joint_index("Spine"), Some(1));
+    assert_eq!(
+        bind_pose.local_transform("Spine"),
+        Some(Transform::from_xyz(0.0, 0.5, 0.0))
+    );
+    assert_eq!(bind_pose.local_transform("Head"), None);
+}
+
+#[test]
+fn test_reset_to_bind_pose() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), FbxPlugin));
+
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<FbxBindPose>>()
+        .add(bind_pose());
+    let root = app.world_mut().spawn(FbxResetToBindPose(handle)).id();
+    let hips = spawn_bone(app.world_mut(), "Hips", root);
+    let spine = spawn_bone(app.world_mut(), "Spine", hips);
+    let head = spawn_bone(app.world_mut(), "Head", spine);
+
+    app.update();
+
+    let world = app.world();
+    assert_eq!(
+        world.get::<Transform>(hips),
+        Some(&Transform::from_xyz(0.0, 1.0, 0.0))
+    );
+    assert_eq!(
+        world.get::<Transform>(spine),
+        Some(&Transform::from_xyz(0.0, 0.5, 0.0))
+    );
+    assert_eq!(
+        world.get::<Transform>(head),
+        Some(&Transform::from_xyz(5.0, 5.0, 5.0))
+    );
+    assert!(world.get::<FbxResetToBindPose>(root).is_none());
+}
@@ -0,0 +1,137 @@
+//! Tests for PC2 and MC vertex cache parsing and sampling.
+
+use bevy::prelude::*;
+use bevy_ufbx::cache::{parse_mc, parse_pc2, vertex_cache_path};
+use bevy_ufbx::{FbxError, FbxVertexCache};
+use std::path::{Path, PathBuf};
+
+fn pc2_bytes(start_frame: f32, frames: &[[Vec3; 2]]) -> Vec<u8> {
+    let mut bytes = b"POINTCACHE2\0".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(2u32.to_le_bytes());
+    bytes.extend(start_frame.to_le_bytes());
+    bytes.extend(1.0f32.to_le_bytes());
+    bytes.extend((frames.len() as u32).to_le_bytes());
+    for point in frames.iter().flatten() {
+        for component in point.to_array() {
+            bytes.extend(component.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+fn iff_chunk(tag: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut bytes = tag.to_vec();
+    bytes.extend((data.len() as u32).to_be_bytes());
+    bytes.extend(data);
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+    bytes
+}
+
+fn iff_group(kind: &[u8; 4], chunks: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = kind.to_vec();
+    data.extend(chunks.concat());
+    iff_chunk(b"FOR4", &data)
+}
+
+fn mc_frame(ticks: i32, channel: &str, points: &[Vec3]) -> Vec<u8> {
+    let mut name = channel.as_bytes().to_vec();
+    name.push(0);
+    let positions: Vec<u8> = points
+        .iter()
+        .flat_map(|point| point.to_array())
+        .flat_map(f32::to_be_bytes)
+        .collect();
+    iff_group(
+        b"MYCH",
+        &[
+            iff_chunk(b"TIME", &ticks.to_be_bytes()),
+            iff_chunk(b"CHNM", &name),
+            iff_chunk(b"SIZE", &(points.len() as u32).to_be_bytes()),
+            iff_chunk(b"FVCA", &positions),
+        ],
+    )
+}
+
+#[test]
+fn test_parse_pc2() {
+    let bytes = pc2_bytes(
+        12.0,
+        &[[Vec3::ZERO, Vec3::X], [Vec3::Y, Vec3::new(1.0, 2.0, 3.0)]],
+    );
+    let cache = parse_pc2(&bytes, 24.0).unwrap();
+
+    assert_eq!(cache.times, vec![0.5, 13.0 / 24.0]);
+    assert_eq!(cache.frames[1], vec![Vec3::Y, Vec3::new(1.0, 2.0, 3.0)]);
+}
+
+#[test]
+fn test_parse_pc2_rejects_invalid_data() {
+    assert!(matches!(
+        parse_pc2(b"POINTCACHE", 30.0),
+        Err(FbxError::InvalidData(_))
+    ));
+    let mut bytes = pc2_bytes(0.0, &[[Vec3::ZERO, Vec3::X]]);
+    bytes.pop();
+    assert!(matches!(
+        parse_pc2(&bytes, 30.0),
+        Err(FbxError::InvalidData(_))
+    ));
+    bytes[0] = b'X';
+    assert!(matches!(
+        parse_pc2(&bytes, 30.0),
+        Err(FbxError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_parse_mc() {
+    let header = iff_group(b"CACH", &[iff_chunk(b"STIM", &0i32.to_be_bytes())]);
+    let bytes = [
+        header,
+        mc_frame(0, "clothShape", &[Vec3::ZERO]),
+        mc_frame(6000, "clothShape", &[Vec3::X]),
+        mc_frame(6000, "otherShape", &[Vec3::Z]),
+    ]
+    .concat();
+    let cache = parse_mc(&bytes).unwrap();
+
+    assert_eq!(cache.times, vec![0.0, 1.0]);
+    assert_eq!(cache.frames, vec![vec![Vec3::ZERO], vec![Vec3::X]]);
+}
+
+#[test]
+fn test_parse_mc_rejects_unsupported_data() {
+    assert!(matches!(parse_mc(&[]), Err(FbxError::InvalidData(_))));
+    let bytes = iff_chunk(b"FOR8", &[0; 8]);
+    assert!(matches!(parse_mc(&bytes), Err(FbxError::InvalidData(_))));
+}
+
+#[test]
+fn test_sample_vertex_cache() {
+    let cache = FbxVertexCache {
+        times: vec![1.0, 2.0],
+        frames: vec![vec![Vec3::ZERO], vec![Vec3::new(2.0, 0.0, 0.0)]],
+    };
+
+    assert_eq!(cache.start_time(), 1.0);
+    assert_eq!(cache.duration(), 1.0);
+    assert_eq!(cache.sample(0.0), Some(vec![Vec3::ZERO]));
+    assert_eq!(cache.sample(1.5), Some(vec![Vec3::X]));
+    assert_eq!(cache.sample(3.0), Some(vec![Vec3::new(2.0, 0.0, 0.0)]));
+    assert_eq!(FbxVertexCache::default().sample(0.0), None);
+}
+
+#[test]
+fn test_vertex_cache_path() {
+    let base_dir = Path::new("models");
+    assert_eq!(
+        vertex_cache_path("cache\\cloth.pc2", "", base_dir),
+        Some(PathBuf::from("models/cache/cloth.pc2"))
+    );
+    assert_eq!(
+        vertex_cache_path("", "C:\\Projects\\cache\\cloth.xml", base_dir),
+        Some(PathBuf::from("models/cloth.mc"))
+    );
+    assert_eq!(vertex_cache_path("", "", base_dir), None);
+}
@@ -0,0 +1,68 @@
+//! Tests for collider extraction by naming convention.
+
+use bevy::prelude::*;
+use bevy_ufbx::{FbxColliderKind, FbxColliderShape};
+
+#[test]
+fn test_collider_kind_from_name() {
+    assert_eq!(
+        FbxColliderKind::from_name("UCX_Wall_01"),
+        Some(FbxColliderKind::ConvexHull)
+    );
+    assert_eq!(
+        FbxColliderKind::from_name("UBX_Crate"),
+        Some(FbxColliderKind::Box)
+    );
+    assert_eq!(
+        FbxColliderKind::from_name("USP_Ball"),
+        Some(FbxColliderKind::Sphere)
+    );
+    assert_eq!(
+        FbxColliderKind::from_name("UCP_Pillar"),
+        Some(FbxColliderKind::Capsule)
+    );
+    assert_eq!(FbxColliderKind::from_name("Wall_01"), None);
+    assert_eq!(FbxColliderKind::from_name("ucx_wall"), None);
+}
+
+#[test]
+fn test_fit_box() {
+    let points = [Vec3::new(1.0, 2.0, 3.0), Vec3::new(3.0, 6.0, 5.0)];
+    let (shape, center) = FbxColliderShape::fit(FbxColliderKind::Box, &points).unwrap();
+
+    assert_eq!(center, Vec3::new(2.0, 4.0, 4.0));
+    assert_eq!(
+        shape,
+        FbxColliderShape::Box {
+            half_extents: Vec3::new(1.0, 2.0, 1.0)
+        }
+    );
+}
+
+#[test]
+fn test_fit_sphere() {
+    let points = [Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)];
+    let (shape, center) = FbxColliderShape::fit(FbxColliderKind::Sphere, &points).unwrap();
+
+    assert_eq!(center, Vec3::ZERO);
+    assert_eq!(shape, FbxColliderShape::Sphere { radius: 1.0 });
+}
+
+#[test]
+fn test_fit_convex_hull_is_centered() {
+    let points = [Vec3::new(2.0, 2.0, 2.0), Vec3::new(4.0, 4.0, 4.0)];
+    let (shape, center) = FbxColliderShape::fit(FbxColliderKind::ConvexHull, &points).unwrap();
+
+    assert_eq!(center, Vec3::splat(3.0));
+    assert_eq!(
+        shape,
+        FbxColliderShape::ConvexHull {
+            points: vec![Vec3::splat(-1.0), Vec3::splat(1.0)]
+        }
+    );
+}
+
+#[test]
+fn test_fit_empty() {
+    assert!(FbxColliderShape::fit(FbxColliderKind::Box, &[]).is_none());
+}
@@ -0,0 +1,25 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
+use bevy::prelude::*;
+use bevy_ufbx::FbxPlugin;
+
+/// App with [`FbxPlugin`] and every asset type an import adds.
+pub fn test_app() -> App {
+    test_app_with_plugin(FbxPlugin)
+}
+
+/// Like [`test_app`], with `plugin` in place of the default [`FbxPlugin`].
+pub fn test_app_with_plugin(plugin: impl Plugin) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), plugin));
+    app.init_asset::<Mesh>()
+        .init_asset::<AnimationClip>()
+        .init_asset::<StandardMaterial>()
+        .init_asset::<Image>()
+        .init_asset::<Scene>()
+        .init_asset::<SkinnedMeshInverseBindposes>();
+    app
+}
@@ -0,0 +1,352 @@
+//! Tests for converting between axis systems.
+
+mod common;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::skinning::SkinnedMeshInverseBindposes;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+use bevy_ufbx::animation::{NodeKeys, bake_animation, convert_world_space_keys};
+use bevy_ufbx::types::{FbxAxisSystem, Handedness};
+use bevy_ufbx::utils::convert_transform;
+use bevy_ufbx::{
+    Fbx, FbxCoordinateConverter, FbxLoaderSettings, FbxMesh, FbxNode, FbxSkin, FbxSpaceConversion,
+    axis_load_opts,
+};
+use common::test_app;
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_2;
+
+/// 3ds Max: right-handed Z-up with -Y front.
+const Z_UP: FbxAxisSystem = FbxAxisSystem {
+    up: Vec3::Z,
+    front: Vec3::NEG_Y,
+    handedness: Handedness::Right,
+};
+
+/// Left-handed Y-up with -Z front.
+const LEFT_Y_UP: FbxAxisSystem = FbxAxisSystem {
+    up: Vec3::Y,
+    front: Vec3::NEG_Z,
+    handedness: Handedness::Left,
+};
+
+/// Z-up 2 cm cube skinned to a root bone rotating 90 degrees about X over
+/// one second, and a static tip bone.
+const SKINNED_Z_UP: &[u8] = include_bytes!("fixtures/skinned_z_up.fbx");
+
+const SPACE_CONVERSIONS: [FbxSpaceConversion; 3] = [
+    FbxSpaceConversion::TransformRoot,
+    FbxSpaceConversion::AdjustTransforms,
+    FbxSpaceConversion::ModifyGeometry,
+];
+
+fn space_settings(space_conversion: FbxSpaceConversion) -> FbxLoaderSettings {
+    FbxLoaderSettings {
+        space_conversion,
+        ..Default::default()
+    }
+}
+
+/// Value of the last key at or before `time`.
+fn sample<T: Copy>(keys: &[(f32, T)], time: f32) -> T {
+    keys.iter()
+        .rev()
+        .find(|(key_time, _)| *key_time <= time + 1e-4)
+        .unwrap_or(&keys[0])
+        .1
+}
+
+/// World matrix of a bone at `time`, posed like the spawned scene: bones
+/// below another bone use their local transform, others their world-space
+/// keys.
+fn joint_world(node: &ufbx::Node, node_keys: &HashMap<usize, NodeKeys>, time: f32) -> Mat4 {
+    let parent_bone = node
+        .parent
+        .as_deref()
+        .filter(|parent| parent.attrib_type == ufbx::ElementType::Bone);
+    match parent_bone {
+        Some(parent) => {
+            let local = convert_transform(&node.local_transform);
+            joint_world(parent, node_keys, time) * local.to_matrix()
+        }
+        None => {
+            let keys = &node_keys[&(node.element.typed_id as usize)];
+            Transform {
+                translation: sample(&keys.translation, time),
+                rotation: sample(&keys.rotation, time),
+                scale: sample(&keys.scale, time),
+            }
+            .to_matrix()
+        }
+    }
+}
+
+/// Rotation key times of the root bone, and the world position of every
+/// skinned vertex at each of them, skinned with the loaded inverse bind
+/// matrices.
+fn skinned_poses(app: &mut App, space_conversion: FbxSpaceConversion) -> Vec<(f32, Vec<Vec3>)> {
+    let settings = space_settings(space_conversion);
+    let fbx = Fbx::from_bytes(SKINNED_Z_UP, settings.clone(), app.world_mut())
+        .expect("Failed to import skinned_z_up.fbx");
+    let world = app.world();
+    let skin = world
+        .resource::<Assets<FbxSkin>>()
+        .get(&fbx.skins[0])
+        .expect("FbxSkin missing");
+    let inverse_binds = world
+        .resource::<Assets<SkinnedMeshInverseBindposes>>()
+        .get(&skin.inverse_bind_matrices)
+        .expect("Inverse bind matrices missing");
+
+    let scene =
+        ufbx::load_memory(SKINNED_Z_UP, axis_load_opts(&settings)).expect("Failed to parse");
+    let stack = &scene.anim_stacks.as_ref()[0];
+    let (mut node_keys, _) = bake_animation(&scene, &stack.anim).expect("Failed to bake");
+    convert_world_space_keys(
+        &scene,
+        &stack.anim,
+        stack.time_begin,
+        stack.time_end,
+        &mut node_keys,
+    );
+
+    let mesh = &scene.meshes.as_ref()[0];
+    let clusters = mesh.skin_deformers.as_ref()[0].clusters.as_ref();
+    let root = clusters[0].bone_node.as_ref().expect("Root bone missing");
+    let times: Vec<f32> = node_keys[&(root.element.typed_id as usize)]
+        .rotation
+        .iter()
+        .map(|&(time, _)| time)
+        .collect();
+    times
+        .into_iter()
+        .map(|time| {
+            let mut positions = vec![Vec3::ZERO; mesh.vertices.as_ref().len()];
+            for (cluster, inverse_bind) in clusters.iter().zip(inverse_binds.iter()) {
+                let bone = cluster.bone_node.as_ref().expect("Bone missing");
+                let skinning = joint_world(bone, &node_keys, time) * *inverse_bind;
+                let weights = cluster
+                    .vertices
+                    .as_ref()
+                    .iter()
+                    .zip(cluster.weights.as_ref());
+                for (&vertex, &weight) in weights {
+                    let p = &mesh.vertices.as_ref()[vertex as usize];
+                    let position = Vec3::new(p.x as f32, p.y as f32, p.z as f32);
+                    let skinned = skinning.transform_point3(position);
+                    positions[vertex as usize] += weight as f32 * skinned;
+                }
+            }
+            (time, positions)
+        })
+        .collect()
+}
+
+#[test]
+fn test_converter_identity() {
+    let converter = FbxCoordinateConverter::new(FbxAxisSystem::BEVY, FbxAxisSystem::BEVY);
+    assert!(converter.is_identity());
+    assert!(!converter.flips_handedness());
+    assert_eq!(
+        converter.convert_point(Vec3::new(1.0, 2.0, 3.0)),
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+}
+
+#[test]
+fn test_converter_z_up() {
+    let converter = FbxCoordinateConverter::new(Z_UP, FbxAxisSystem::BEVY);
+    assert!(!converter.flips_handedness());
+    assert!(converter.convert_point(Vec3::Z).abs_diff_eq(Vec3::Y, 1e-6));
+    assert!(
+        converter
+            .convert_point(Vec3::NEG_Y)
+            .abs_diff_eq(Vec3::Z, 1e-6)
+    );
+    assert!(converter.convert_point(Vec3::X).abs_diff_eq(Vec3::X, 1e-6));
+
+    // Rotating about Z-up's up axis rotates about Bevy's
+    let rotation = converter.convert_rotation(Quat::from_rotation_z(0.5));
+    assert!(rotation.abs_diff_eq(Quat::from_rotation_y(0.5), 1e-5));
+
+    let scale = converter.convert_scale(Vec3::new(1.0, 2.0, 3.0));
+    assert!(scale.abs_diff_eq(Vec3::new(1.0, 3.0, 2.0), 1e-6));
+
+    let point = Vec3::new(1.0, -4.0, 2.0);
+    let back = converter
+        .inverse()
+        .convert_point(converter.convert_point(point));
+    assert!(back.abs_diff_eq(point, 1e-6));
+}
+
+#[test]
+fn test_converter_unit_scale() {
+    let converter = FbxCoordinateConverter::default().with_unit_scale(0.01);
+    assert!(
+        converter
+            .convert_point(Vec3::splat(100.0))
+            .abs_diff_eq(Vec3::ONE, 1e-6)
+    );
+    assert_eq!(converter.convert_direction(Vec3::X), Vec3::X);
+
+    let matrix = Mat4::from_translation(Vec3::new(100.0, 0.0, 0.0));
+    let converted = converter.convert_matrix(matrix);
+    assert!(
+        converted
+            .w_axis
+            .abs_diff_eq(Vec4::new(1.0, 0.0, 0.0, 1.0), 1e-6)
+    );
+}
+
+#[test]
+fn test_converter_matches_transform() {
+    let converter = FbxCoordinateConverter::new(Z_UP, FbxAxisSystem::BEVY).with_unit_scale(0.01);
+    let transform = Transform::from_xyz(100.0, 200.0, 300.0)
+        .with_rotation(Quat::from_rotation_x(0.3))
+        .with_scale(Vec3::new(1.0, 2.0, 1.0));
+
+    let converted = converter.convert_transform(&transform);
+    let expected = converter.convert_matrix(transform.to_matrix());
+    assert!(converted.to_matrix().abs_diff_eq(expected, 1e-4));
+}
+
+#[test]
+fn test_converter_mirrors_mesh() {
+    let converter = FbxCoordinateConverter::new(LEFT_Y_UP, FbxAxisSystem::BEVY);
+    assert!(converter.flips_handedness());
+    assert!(
+        converter
+            .convert_point(Vec3::NEG_Z)
+            .abs_diff_eq(Vec3::Z, 1e-6)
+    );
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, -1.0]; 3])
+    .with_inserted_indices(Indices::U32(vec![0, 1, 2]));
+    converter.convert_mesh(&mut mesh);
+
+    let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        panic!("Normals missing");
+    };
+    assert!(Vec3::from(normals[0]).abs_diff_eq(Vec3::Z, 1e-6));
+    let indices: Vec<usize> = mesh.indices().expect("Indices missing").iter().collect();
+    assert_eq!(indices, vec![0, 2, 1]);
+}
+
+#[test]
+fn test_space_conversion_conversion() {
+    assert_eq!(
+        ufbx::SpaceConversion::from(FbxSpaceConversion::ModifyGeometry),
+        ufbx::SpaceConversion::ModifyGeometry
+    );
+    assert_eq!(
+        ufbx::SpaceConversion::from(FbxSpaceConversion::default()),
+        ufbx::SpaceConversion::TransformRoot
+    );
+}
+
+#[test]
+fn test_skin_binds_to_mesh_under_space_conversions() {
+    let mut app = test_app();
+    for space_conversion in SPACE_CONVERSIONS {
+        let fbx = Fbx::from_bytes(
+            SKINNED_Z_UP,
+            space_settings(space_conversion),
+            app.world_mut(),
+        )
+        .expect("Failed to import skinned_z_up.fbx");
+        let world = app.world();
+        let nodes = world.resource::<Assets<FbxNode>>();
+        let skin = world
+            .resource::<Assets<FbxSkin>>()
+            .get(&fbx.skins[0])
+            .expect("FbxSkin missing");
+        let inverse_binds = world
+            .resource::<Assets<SkinnedMeshInverseBindposes>>()
+            .get(&skin.inverse_bind_matrices)
+            .expect("Inverse bind matrices missing");
+        let body = nodes
+            .get(&fbx.named_nodes["Body"])
+            .expect("Body node missing");
+
+        // Bound at rest, so every joint maps the vertices to where the mesh
+        // node places them
+        assert_eq!(skin.joints.len(), inverse_binds.len());
+        for (joint, inverse_bind) in skin.joints.iter().zip(inverse_binds.iter()) {
+            let joint = nodes.get(joint).expect("Joint node missing");
+            let skinning = joint.geometry_to_world * *inverse_bind;
+            assert!(
+                skinning.abs_diff_eq(body.geometry_to_world, 1e-5),
+                "{space_conversion:?}: {} is bound at {skinning}",
+                joint.name
+            );
+        }
+
+        // Centimeters in Z-up end up as the same meters in Y-up
+        let fbx_mesh = world.resource::<Assets<FbxMesh>>().get(&fbx.meshes[0]);
+        let aabb = fbx_mesh
+            .and_then(|mesh| mesh.aabb)
+            .expect("Mesh bounds missing");
+        let corners = (0..8).map(|corner| {
+            let sign = Vec3::new(
+                if corner & 1 == 0 { -1.0 } else { 1.0 },
+                if corner & 2 == 0 { -1.0 } else { 1.0 },
+                if corner & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            let local = Vec3::from(aabb.center) + sign * Vec3::from(aabb.half_extents);
+            body.geometry_to_world.transform_point3(local)
+        });
+        let (min, max) = corners.fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), p| {
+            (min.min(p), max.max(p))
+        });
+        assert!(
+            min.abs_diff_eq(Vec3::splat(-0.01), 1e-6),
+            "{space_conversion:?}: {min}"
+        );
+        assert!(
+            max.abs_diff_eq(Vec3::splat(0.01), 1e-6),
+            "{space_conversion:?}: {max}"
+        );
+    }
+}
+
+#[test]
+fn test_skinned_animation_rigid_under_space_conversions() {
+    let mut app = test_app();
+    let reference = skinned_poses(&mut app, FbxSpaceConversion::TransformRoot);
+    let (start, rest) = &reference[0];
+    assert_eq!(*start, 0.0);
+    assert!(reference.len() >= 2);
+    // Corners of the 2 cm cube
+    let corner_distance = 0.01 * 3f32.sqrt();
+    assert!(
+        rest.iter()
+            .all(|p| (p.length() - corner_distance).abs() < 1e-6)
+    );
+
+    for space_conversion in SPACE_CONVERSIONS {
+        let poses = skinned_poses(&mut app, space_conversion);
+        assert_eq!(poses.len(), reference.len(), "{space_conversion:?}");
+        for ((time, positions), (_, expected)) in poses.iter().zip(&reference) {
+            // The root bone carries the whole cube, rotating about Bevy's X
+            // axis like it does about the file's
+            let rotation = Quat::from_rotation_x(time * FRAC_PI_2);
+            for (vertex, position) in positions.iter().enumerate() {
+                assert!(
+                    position.abs_diff_eq(rotation * rest[vertex], 1e-5),
+                    "{space_conversion:?}: vertex {vertex} at {time}s is {position}",
+                );
+                assert!(position.abs_diff_eq(expected[vertex], 1e-5));
+            }
+        }
+    }
+}
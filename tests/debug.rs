@@ -0,0 +1,63 @@
+//! Tests for the debug visualization plugin.
+#![cfg(feature = "debug")]
+
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes};
+use bevy::prelude::*;
+use bevy_ufbx::debug::joint_bind_poses;
+use bevy_ufbx::{Fbx, FbxDebugDraw, FbxDebugPlugin, FbxLoaderSettings};
+use common::test_app;
+
+#[test]
+fn test_bind_poses_of_spawned_skin() {
+    let mut app = test_app();
+    app.add_plugins(FbxDebugPlugin);
+    let bytes = include_bytes!("fixtures/skinned.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import skinned.fbx");
+
+    let inverse_bindposes = app
+        .world_mut()
+        .remove_resource::<Assets<SkinnedMeshInverseBindposes>>()
+        .expect("Inverse bind pose assets missing");
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    scene.world.insert_resource(inverse_bindposes);
+
+    // Draw the whole scene, like a `SceneRoot` with `FbxDebugDraw` would
+    let mut top_level = scene.world.query_filtered::<Entity, Without<ChildOf>>();
+    let top_level: Vec<Entity> = top_level.iter(&scene.world).collect();
+    let root = scene
+        .world
+        .spawn(FbxDebugDraw {
+            bind_poses: true,
+            ..Default::default()
+        })
+        .id();
+    for entity in top_level {
+        scene.world.entity_mut(entity).insert(ChildOf(root));
+    }
+
+    let bind_poses = scene
+        .world
+        .run_system_once(
+            move |children: Query<&Children>,
+                  skinned_meshes: Query<(&GlobalTransform, &SkinnedMesh)>,
+                  inverse_bindposes: Res<Assets<SkinnedMeshInverseBindposes>>| {
+                joint_bind_poses(root, &children, &skinned_meshes, &inverse_bindposes)
+            },
+        )
+        .unwrap();
+
+    let mut skinned_meshes = scene.world.query::<&SkinnedMesh>();
+    let joint_count: usize = skinned_meshes
+        .iter(&scene.world)
+        .map(|skinned_mesh| skinned_mesh.joints.len())
+        .sum();
+    assert!(joint_count > 0);
+    assert_eq!(bind_poses.len(), joint_count);
+}
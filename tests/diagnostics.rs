@@ -0,0 +1,46 @@
+//! Tests for FBX load diagnostics.
+
+use bevy::prelude::*;
+use bevy_ufbx::{FbxDiagnosticsPlugin, FbxLoadDiagnostics, FbxPlugin, FbxWarning};
+use std::time::Duration;
+
+#[test]
+fn test_diagnostics_total_time() {
+    let diagnostics = FbxLoadDiagnostics {
+        parse_time: Duration::from_millis(10),
+        mesh_time: Duration::from_millis(20),
+        material_time: Duration::from_millis(5),
+        scene_time: Duration::from_millis(1),
+        ..Default::default()
+    };
+
+    assert_eq!(diagnostics.total_time(), Duration::from_millis(36));
+}
+
+#[test]
+fn test_diagnostics_plugin_builds() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.add_plugins(FbxPlugin);
+    app.add_plugins(FbxDiagnosticsPlugin);
+
+    app.update();
+}
+
+#[test]
+fn test_warning_display() {
+    let warning = FbxWarning {
+        kind: "IndexClamped".to_string(),
+        description: "Clamped index".to_string(),
+        element_id: Some(3),
+        count: 4,
+    };
+    assert_eq!(warning.to_string(), "IndexClamped: Clamped index (x4)");
+
+    let single = FbxWarning {
+        count: 1,
+        ..warning
+    };
+    assert_eq!(single.to_string(), "IndexClamped: Clamped index");
+}
@@ -0,0 +1,26 @@
+//! Tests for environment texture detection.
+
+use bevy_ufbx::environment::is_environment_texture;
+
+#[test]
+fn test_environment_texture_by_extension() {
+    assert!(is_environment_texture("Texture", "C:\\maps\\studio.hdr"));
+    assert!(is_environment_texture("Texture", "textures/studio.EXR"));
+    assert!(!is_environment_texture("Texture", "textures/brick.png"));
+}
+
+#[test]
+fn test_environment_texture_by_name() {
+    assert!(is_environment_texture("SkyDome", "textures/dome.png"));
+    assert!(is_environment_texture("Map", "textures/EnvMap_Studio.jpg"));
+    assert!(is_environment_texture("Environment", ""));
+    assert!(!is_environment_texture("Wood_Diffuse", "textures/wood.png"));
+}
+
+#[test]
+fn test_directory_names_are_ignored() {
+    assert!(!is_environment_texture(
+        "Albedo",
+        "sky_project/textures/albedo.png"
+    ));
+}
@@ -0,0 +1,66 @@
+//! Tests for exporting imported scenes to Bevy's scene format.
+
+#![cfg(feature = "scene_export")]
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::{
+    Fbx, FbxError, FbxLoaderSettings, fbx_export_manifest, fbx_to_dynamic_scene,
+    serialize_fbx_manifest, serialize_fbx_materials,
+};
+use common::test_app;
+
+#[test]
+fn test_dynamic_scene_from_fbx() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+
+    let dynamic_scene = fbx_to_dynamic_scene(&fbx, app.world()).expect("Export failed");
+    assert!(!dynamic_scene.entities.is_empty());
+}
+
+#[test]
+fn test_export_manifest_and_materials() {
+    let mut app = test_app();
+    app.register_type::<StandardMaterial>();
+    let bytes = include_bytes!("fixtures/animated.fbx");
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import animated.fbx");
+
+    let manifest = fbx_export_manifest(&fbx, app.world());
+    assert_eq!(manifest.meshes.len(), fbx.meshes.len());
+    assert_eq!(manifest.meshes[0].label, "Mesh0");
+    assert!(
+        manifest.meshes[0]
+            .primitives
+            .iter()
+            .all(|&(vertices, _)| vertices > 0)
+    );
+    assert_eq!(manifest.materials.len(), fbx.materials.len());
+    assert_eq!(manifest.clips.len(), 1);
+    assert_eq!(manifest.clips[0].label, "Animation0");
+    assert!((manifest.clips[0].duration - 1.0).abs() < 1e-3);
+
+    let ron = serialize_fbx_manifest(&fbx, app.world()).expect("Manifest export failed");
+    assert!(ron.contains("Animation0"));
+    let materials = serialize_fbx_materials(&fbx, app.world()).expect("Material export failed");
+    for label in &manifest.materials {
+        assert!(materials.contains(&format!("\"{label}\"")));
+    }
+}
+
+#[test]
+fn test_export_without_scene() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let mut fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+    fbx.default_scene = None;
+    fbx.scenes.clear();
+
+    let result = fbx_to_dynamic_scene(&fbx, app.world());
+    assert!(matches!(result, Err(FbxError::InvalidData(_))));
+}
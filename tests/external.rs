@@ -0,0 +1,34 @@
+//! Tests for external files served to ufbx.
+
+use bevy_ufbx::FbxExternalFiles;
+use bevy_ufbx::external::{external_file_names, media_dir};
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_media_dir() {
+    assert_eq!(
+        media_dir(Path::new("models/car.fbx")),
+        PathBuf::from("models/car.fbm")
+    );
+}
+
+#[test]
+fn test_external_files_match_by_name() {
+    let mut files = FbxExternalFiles::default();
+    assert!(files.is_empty());
+    files.insert("caches/Cloth.mc", vec![1, 2, 3]);
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files.get(r"D:\sim\cache\cloth.MC"), Some(&[1, 2, 3][..]));
+    assert_eq!(files.get("/tmp/cloth.mc"), Some(&[1, 2, 3][..]));
+    assert!(files.get("cloth.xml").is_none());
+    assert!(files.open("cloth.mc").is_some());
+    assert!(files.open("").is_none());
+}
+
+#[test]
+fn test_external_file_names_without_caches() {
+    let bytes = include_bytes!("../assets/cube.fbx");
+    assert!(external_file_names(bytes).is_empty());
+    assert!(external_file_names(&[]).is_empty());
+}
@@ -0,0 +1,81 @@
+//! Regression tests against the bundled FBX fixtures and their golden summaries.
+
+#![cfg(feature = "test-fixtures")]
+
+use bevy_ufbx::FbxLoaderSettings;
+use bevy_ufbx::testing::{assert_golden, fixture_app, fixtures, import_fixture, summarize};
+use bevy_ufbx::version::FbxFileFormat;
+
+fn golden(name: &str) -> String {
+    format!(
+        "{}/tests/fixtures/golden/{name}.txt",
+        env!("CARGO_MANIFEST_DIR")
+    )
+}
+
+#[test]
+fn test_fixture_binary_cube() {
+    let mut app = fixture_app();
+    let fbx = import_fixture(
+        &mut app,
+        fixtures::BINARY_CUBE,
+        FbxLoaderSettings::default(),
+    )
+    .expect("Failed to import binary cube");
+
+    assert_eq!(fbx.metadata.format, Some(FbxFileFormat::Binary));
+    assert_eq!(fbx.meshes.len(), 1);
+    assert_golden(&summarize(&fbx, app.world()), golden("binary_cube"));
+}
+
+#[test]
+fn test_fixture_ascii_cube() {
+    let mut app = fixture_app();
+    let fbx = import_fixture(&mut app, fixtures::ASCII_CUBE, FbxLoaderSettings::default())
+        .expect("Failed to import ASCII cube");
+
+    assert_eq!(fbx.metadata.format, Some(FbxFileFormat::Ascii));
+    assert_eq!(fbx.metadata.version, Some(7400));
+    assert_eq!(fbx.meshes.len(), 1);
+    assert_eq!(fbx.materials.len(), 1);
+    assert_golden(&summarize(&fbx, app.world()), golden("ascii_cube"));
+}
+
+#[test]
+fn test_fixture_multi_material() {
+    let mut app = fixture_app();
+    let fbx = import_fixture(
+        &mut app,
+        fixtures::MULTI_MATERIAL,
+        FbxLoaderSettings::default(),
+    )
+    .expect("Failed to import multi-material cube");
+
+    assert_eq!(fbx.materials.len(), 2);
+    assert!(fbx.named_materials.contains_key("Red"));
+    assert!(fbx.named_materials.contains_key("Blue"));
+    assert_golden(&summarize(&fbx, app.world()), golden("multi_material"));
+}
+
+#[test]
+fn test_fixture_animated() {
+    let mut app = fixture_app();
+    let fbx = import_fixture(&mut app, fixtures::ANIMATED, FbxLoaderSettings::default())
+        .expect("Failed to import animated cube");
+
+    assert_eq!(fbx.animations.len(), 1);
+    assert!(fbx.named_animations.contains_key("Take 001"));
+    assert_golden(&summarize(&fbx, app.world()), golden("animated"));
+}
+
+#[test]
+fn test_fixture_skinned() {
+    let mut app = fixture_app();
+    let fbx = import_fixture(&mut app, fixtures::SKINNED, FbxLoaderSettings::default())
+        .expect("Failed to import skinned cube");
+
+    assert_eq!(fbx.skins.len(), 1);
+    assert!(fbx.named_nodes.contains_key("Root"));
+    assert!(fbx.named_nodes.contains_key("Tip"));
+    assert_golden(&summarize(&fbx, app.world()), golden("skinned"));
+}
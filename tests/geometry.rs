@@ -0,0 +1,108 @@
+//! Tests for intermediate mesh geometry.
+
+mod common;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use bevy_ufbx::{Fbx, FbxGeometry, FbxLoaderSettings, FbxMesh, FbxUvLayer};
+use common::test_app;
+
+/// Two triangles of a quad, each with its own material.
+fn quad_geometry() -> FbxGeometry {
+    FbxGeometry {
+        positions: vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ],
+        uv_sets: vec![FbxUvLayer {
+            name: "UVMap".to_string(),
+            uvs: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+        }],
+        source_vertices: vec![0, 1, 2, 3],
+        indices: vec![0, 1, 2, 0, 2, 3],
+        triangle_materials: vec![1, 0],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_material_indices() {
+    let geometry = quad_geometry();
+    assert_eq!(geometry.triangle_count(), 2);
+    assert_eq!(geometry.material_indices(), vec![0, 1]);
+}
+
+#[test]
+fn test_to_mesh_all_triangles() {
+    let mesh = quad_geometry()
+        .to_mesh(None, RenderAssetUsages::default())
+        .expect("mesh");
+    assert_eq!(mesh.count_vertices(), 4);
+    assert_eq!(mesh.indices().map(|indices| indices.len()), Some(6));
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_UV_0).is_some());
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_none());
+}
+
+#[test]
+fn test_to_mesh_compacts_vertices() {
+    let geometry = quad_geometry();
+    let mesh = geometry
+        .to_mesh(Some(0), RenderAssetUsages::default())
+        .expect("mesh");
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("positions missing");
+    };
+    assert_eq!(
+        positions,
+        &vec![[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]
+    );
+    let indices: Vec<usize> = mesh.indices().expect("indices").iter().collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+
+    assert!(
+        geometry
+            .to_mesh(Some(2), RenderAssetUsages::default())
+            .is_none()
+    );
+    let meshes = geometry.to_meshes(RenderAssetUsages::default());
+    assert_eq!(
+        meshes
+            .iter()
+            .map(|(material, _)| *material)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+}
+
+#[test]
+fn test_load_geometry() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/multi_material.fbx");
+    let settings = FbxLoaderSettings {
+        load_geometry: true,
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+        .expect("Failed to import multi_material.fbx");
+
+    let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+    let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+    let handle = fbx_mesh.geometry.as_ref().expect("Geometry missing");
+    let geometries = app.world().resource::<Assets<FbxGeometry>>();
+    let geometry = geometries.get(handle).expect("FbxGeometry missing");
+    assert_eq!(geometry.positions.len(), 24);
+    assert_eq!(geometry.triangle_count(), 12);
+    assert_eq!(geometry.material_indices(), vec![0, 1]);
+
+    let meshes = geometry.to_meshes(RenderAssetUsages::default());
+    assert_eq!(meshes.len(), 2);
+    for (_, mesh) in &meshes {
+        assert_eq!(mesh.indices().map(|indices| indices.len()), Some(18));
+    }
+}
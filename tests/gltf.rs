@@ -0,0 +1,81 @@
+//! Tests for the glTF material conversion.
+
+use bevy::asset::RenderAssetUsages;
+use bevy::image::Image;
+use bevy::pbr::StandardMaterial;
+use bevy::prelude::*;
+use bevy::render::alpha::AlphaMode;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_ufbx::{FbxGltfAlphaMode, FbxGltfMaterial, pack_orm_image};
+
+fn gray_image(width: u32, height: u32, values: &[u8]) -> Image {
+    let data = values
+        .iter()
+        .flat_map(|&value| [value, value, value, 255])
+        .collect();
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    )
+}
+
+#[test]
+fn test_gltf_material_from_standard_material() {
+    let material = StandardMaterial {
+        base_color: Color::linear_rgba(0.5, 0.25, 1.0, 0.8),
+        metallic: 0.3,
+        perceptual_roughness: 0.7,
+        emissive: LinearRgba::rgb(4.0, 2.0, 0.0),
+        alpha_mode: AlphaMode::Premultiplied,
+        double_sided: true,
+        ..Default::default()
+    };
+    let gltf_material = FbxGltfMaterial::from(&material);
+
+    assert_eq!(gltf_material.base_color_factor, [0.5, 0.25, 1.0, 0.8]);
+    assert_eq!(gltf_material.metallic_factor, 0.3);
+    assert_eq!(gltf_material.roughness_factor, 0.7);
+    assert_eq!(gltf_material.emissive_factor, [1.0, 0.5, 0.0]);
+    assert_eq!(gltf_material.emissive_strength, 4.0);
+    assert_eq!(gltf_material.alpha_mode, FbxGltfAlphaMode::Blend);
+    assert!(gltf_material.double_sided);
+
+    let roundtrip = gltf_material.to_standard_material();
+    assert_eq!(roundtrip.emissive, material.emissive);
+    assert_eq!(roundtrip.alpha_mode, AlphaMode::Blend);
+    assert_eq!(roundtrip.cull_mode, None);
+    assert_eq!(FbxGltfMaterial::from(&roundtrip), gltf_material);
+}
+
+#[test]
+fn test_gltf_material_default_matches_gltf() {
+    let material = FbxGltfMaterial::default().to_standard_material();
+    assert_eq!(material.metallic, 1.0);
+    assert_eq!(material.perceptual_roughness, 1.0);
+    assert_eq!(material.emissive, LinearRgba::BLACK);
+    assert!(material.cull_mode.is_some());
+}
+
+#[test]
+fn test_pack_orm_image() {
+    let occlusion = gray_image(1, 1, &[64]);
+    let roughness = gray_image(2, 2, &[0, 51, 102, 153]);
+
+    let packed = pack_orm_image(Some(&occlusion), Some(&roughness), None).unwrap();
+    assert_eq!(packed.width(), 2);
+    assert_eq!(packed.height(), 2);
+    assert_eq!(packed.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+    let data = packed.data.as_ref().unwrap();
+    // Occlusion is stretched, a missing metallic map is white
+    assert_eq!(&data[0..4], &[64, 0, 255, 255]);
+    assert_eq!(&data[12..16], &[64, 153, 255, 255]);
+
+    assert!(pack_orm_image(None, None, None).is_none());
+}
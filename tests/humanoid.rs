@@ -0,0 +1,56 @@
+//! Tests for humanoid rig detection.
+
+use bevy_ufbx::humanoid::normalize_bone_name;
+use bevy_ufbx::{FbxHumanoidRig, FbxRigConvention, HumanoidBone};
+
+#[test]
+fn test_normalize_bone_name() {
+    assert_eq!(normalize_bone_name("mixamorig:LeftHand"), "lefthand");
+    assert_eq!(normalize_bone_name("Character1:Hips"), "hips");
+    assert_eq!(normalize_bone_name("pelvis"), "pelvis");
+}
+
+#[test]
+fn test_detect_mixamo_rig() {
+    let names = [
+        "RootNode",
+        "mixamorig:Hips",
+        "mixamorig:Spine",
+        "mixamorig:Head",
+        "mixamorig:LeftArm",
+        "mixamorig:RightArm",
+        "mixamorig:LeftUpLeg",
+        "mixamorig:RightUpLeg",
+        "mixamorig:LeftHand",
+    ];
+
+    let rig = FbxHumanoidRig::detect(names).expect("Rig not detected");
+    assert_eq!(rig.convention, FbxRigConvention::HumanIk);
+    assert_eq!(rig.bone(HumanoidBone::Hips), Some(1));
+    assert_eq!(rig.bone(HumanoidBone::LeftHand), Some(8));
+    assert_eq!(rig.bone(HumanoidBone::RightHand), None);
+}
+
+#[test]
+fn test_detect_unreal_rig() {
+    let names = [
+        "root",
+        "pelvis",
+        "spine_01",
+        "head",
+        "upperarm_l",
+        "upperarm_r",
+        "thigh_l",
+        "thigh_r",
+    ];
+
+    let rig = FbxHumanoidRig::detect(names).expect("Rig not detected");
+    assert_eq!(rig.convention, FbxRigConvention::Unreal);
+    assert_eq!(rig.bone(HumanoidBone::LeftUpperLeg), Some(6));
+}
+
+#[test]
+fn test_detect_incomplete_rig() {
+    let names = ["Hips", "Spine", "Head"];
+    assert!(FbxHumanoidRig::detect(names).is_none());
+}
@@ -2,7 +2,7 @@
 
 use bevy::asset::{AssetPlugin, AssetServer};
 use bevy::prelude::*;
-use bevy_ufbx::{Fbx, FbxPlugin};
+use bevy_ufbx::{Fbx, FbxPlugin, FbxSceneInstanceReady};
 
 #[test]
 fn test_plugin_initialization() {
@@ -91,9 +91,67 @@ fn test_multiple_plugins() {
 
     // Verify resources exist
     assert!(app.world().get_resource::<Assets<Mesh>>().is_some());
-    assert!(app
-        .world()
-        .get_resource::<Assets<StandardMaterial>>()
-        .is_some());
+    assert!(
+        app.world()
+            .get_resource::<Assets<StandardMaterial>>()
+            .is_some()
+    );
     assert!(app.world().get_resource::<Assets<Fbx>>().is_some());
 }
+
+#[derive(Resource, Default)]
+struct ReadyScenes(Vec<Entity>);
+
+#[test]
+fn test_fbx_scene_instance_ready_observer() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.add_plugins(FbxPlugin);
+    app.init_resource::<ReadyScenes>();
+    app.add_observer(
+        |ready: On<FbxSceneInstanceReady>, mut scenes: ResMut<ReadyScenes>| {
+            scenes.0.push(ready.entity);
+        },
+    );
+
+    let entity = app.world_mut().spawn_empty().id();
+    app.world_mut().trigger(FbxSceneInstanceReady {
+        entity,
+        fbx: Handle::default(),
+    });
+    app.update();
+
+    assert_eq!(app.world().resource::<ReadyScenes>().0, vec![entity]);
+}
+
+#[test]
+fn test_scene_map() {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_ufbx::FbxNodePath;
+    use bevy_ufbx::scene::scene_map;
+
+    let mut world = World::new();
+    let root = world.spawn_empty().id();
+    let turret = world
+        .spawn((FbxNodePath("Turret".to_string()), ChildOf(root)))
+        .id();
+    let barrel = world
+        .spawn((FbxNodePath("Turret/Barrel".to_string()), ChildOf(turret)))
+        .id();
+    world.spawn((Name::new("Unrelated"), ChildOf(root)));
+
+    let map = world
+        .run_system_once(
+            move |children: Query<&Children>, paths: Query<&FbxNodePath>| {
+                scene_map(root, &children, &paths)
+            },
+        )
+        .unwrap();
+
+    assert_eq!(map.entities.len(), 2);
+    assert_eq!(map.get("Turret"), Some(turret));
+    assert_eq!(map.get("Turret/Barrel"), Some(barrel));
+    assert_eq!(map.get("Turret/Missing"), None);
+}
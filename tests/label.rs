@@ -1,6 +1,10 @@
 //! Tests for FBX asset labels.
 
-use bevy_ufbx::FbxAssetLabel;
+mod common;
+
+use bevy_ufbx::label::label_names;
+use bevy_ufbx::{Fbx, FbxAssetLabel, FbxLabelParseError, FbxLoaderSettings};
+use common::test_app;
 
 #[test]
 fn test_scene_label() {
@@ -26,18 +30,59 @@ fn test_mesh_label() {
     assert_eq!(label.to_string(), "Mesh10");
 }
 
+#[test]
+fn test_primitive_label() {
+    let label = FbxAssetLabel::Primitive {
+        mesh: 2,
+        primitive: 1,
+    };
+    assert_eq!(label.to_string(), "Mesh2/Primitive1");
+}
+
+#[test]
+fn test_morph_targets_label() {
+    let label = FbxAssetLabel::MorphTargets {
+        mesh: 2,
+        primitive: 1,
+    };
+    assert_eq!(label.to_string(), "Mesh2/Primitive1/MorphTargets");
+}
+
+#[test]
+fn test_primitive_lod_label() {
+    let label = FbxAssetLabel::PrimitiveLod {
+        mesh: 2,
+        primitive: 1,
+        lod: 3,
+    };
+    assert_eq!(label.to_string(), "Mesh2/Primitive1/Lod3");
+    assert_eq!(FbxAssetLabel::parse("Mesh2/Primitive1/Lod"), None);
+}
+
 #[test]
 fn test_material_label() {
     let label = FbxAssetLabel::Material(3);
     assert_eq!(label.to_string(), "Material3");
 }
 
+#[test]
+fn test_material_extras_label() {
+    let label = FbxAssetLabel::MaterialExtras(3);
+    assert_eq!(label.to_string(), "MaterialExtras3");
+}
+
 #[test]
 fn test_texture_label() {
     let label = FbxAssetLabel::Texture(7);
     assert_eq!(label.to_string(), "Texture7");
 }
 
+#[test]
+fn test_media_label() {
+    let label = FbxAssetLabel::Media(1);
+    assert_eq!(label.to_string(), "Media1");
+}
+
 #[test]
 fn test_animation_label() {
     let label = FbxAssetLabel::Animation(2);
@@ -50,12 +95,24 @@ fn test_skin_label() {
     assert_eq!(label.to_string(), "Skin4");
 }
 
+#[test]
+fn test_bind_pose_label() {
+    let label = FbxAssetLabel::BindPose(4);
+    assert_eq!(label.to_string(), "BindPose4");
+}
+
 #[test]
 fn test_default_material_label() {
     let label = FbxAssetLabel::DefaultMaterial;
     assert_eq!(label.to_string(), "DefaultMaterial");
 }
 
+#[test]
+fn test_humanoid_rig_label() {
+    let label = FbxAssetLabel::HumanoidRig;
+    assert_eq!(label.to_string(), "HumanoidRig");
+}
+
 #[test]
 fn test_label_uniqueness() {
     // Test that different label types with same index produce different strings
@@ -76,3 +133,180 @@ fn test_label_consistency() {
 
     assert_eq!(label1.to_string(), label2.to_string());
 }
+
+#[test]
+fn test_line_curve_label() {
+    let label = FbxAssetLabel::LineCurve(2);
+    assert_eq!(label.to_string(), "LineCurve2");
+}
+
+#[test]
+fn test_node_scene_label() {
+    let label = FbxAssetLabel::NodeScene("Root/Floor2".to_string());
+    assert_eq!(label.to_string(), "Node/Root/Floor2");
+}
+
+#[test]
+fn test_take_scene_labels() {
+    assert_eq!(
+        FbxAssetLabel::TakeScene("Run".to_string()).to_string(),
+        "Take/Run"
+    );
+    assert_eq!(
+        FbxAssetLabel::TakeGraph("Run".to_string()).to_string(),
+        "Take/Run/Graph"
+    );
+    assert_eq!(FbxAssetLabel::parse("Take/"), None);
+}
+
+#[test]
+fn test_label_parse_roundtrip() {
+    let labels = [
+        FbxAssetLabel::Scene(0),
+        FbxAssetLabel::Mesh(3),
+        FbxAssetLabel::Primitive {
+            mesh: 1,
+            primitive: 2,
+        },
+        FbxAssetLabel::MorphTargets {
+            mesh: 1,
+            primitive: 2,
+        },
+        FbxAssetLabel::PrimitiveLod {
+            mesh: 1,
+            primitive: 2,
+            lod: 0,
+        },
+        FbxAssetLabel::MeshTopology(3),
+        FbxAssetLabel::Geometry(3),
+        FbxAssetLabel::Batch(0),
+        FbxAssetLabel::MergedMesh(1),
+        FbxAssetLabel::LineCurve(2),
+        FbxAssetLabel::Material(4),
+        FbxAssetLabel::MaterialExtras(4),
+        FbxAssetLabel::Animation(5),
+        FbxAssetLabel::AnimationStack(5),
+        FbxAssetLabel::MaskedAnimation {
+            animation: 5,
+            mask: "upper_body".to_string(),
+        },
+        FbxAssetLabel::Node(6),
+        FbxAssetLabel::NodeScene("Root/Floor2".to_string()),
+        FbxAssetLabel::TakeScene("Walk Cycle".to_string()),
+        FbxAssetLabel::TakeGraph("Walk Cycle".to_string()),
+        FbxAssetLabel::Skin(7),
+        FbxAssetLabel::BindPose(7),
+        FbxAssetLabel::AreaLight(1),
+        FbxAssetLabel::AreaLightQuad,
+        FbxAssetLabel::Media(2),
+        FbxAssetLabel::MissingTexture,
+        FbxAssetLabel::Colliders,
+        FbxAssetLabel::Report,
+        FbxAssetLabel::DefaultMaterial,
+        FbxAssetLabel::Named {
+            kind: "Material".to_string(),
+            name: "ns:Brick".to_string(),
+        },
+    ];
+    for label in labels {
+        assert_eq!(FbxAssetLabel::parse(&label.to_string()), Some(label));
+    }
+}
+
+#[test]
+fn test_label_parse_invalid() {
+    assert_eq!(FbxAssetLabel::parse("Mesh"), None);
+    assert_eq!(FbxAssetLabel::parse("Mesh+1"), None);
+    assert_eq!(FbxAssetLabel::parse("Node/"), None);
+    assert_eq!(FbxAssetLabel::parse("Unknown3"), None);
+    assert_eq!(FbxAssetLabel::parse("MaterialName/"), None);
+    assert_eq!(FbxAssetLabel::parse("UnknownName/Brick"), None);
+}
+
+#[test]
+fn test_named_labels() {
+    let primitive = FbxAssetLabel::Primitive {
+        mesh: 3,
+        primitive: 1,
+    };
+    assert_eq!(
+        primitive.clone().named(Some("Door")).to_string(),
+        "MeshName/Door/Primitive1"
+    );
+    assert_eq!(primitive.clone().named(None), primitive);
+    assert_eq!(
+        FbxAssetLabel::Scene(0).named(Some("Main")),
+        FbxAssetLabel::Scene(0)
+    );
+
+    let label: FbxAssetLabel = "MeshName/Door/Primitive1/MorphTargets"
+        .parse()
+        .expect("valid label");
+    assert_eq!(
+        label,
+        FbxAssetLabel::Named {
+            kind: "Mesh".to_string(),
+            name: "Door/Primitive1/MorphTargets".to_string(),
+        }
+    );
+    // Node scene paths may contain `Name/`
+    assert_eq!(
+        FbxAssetLabel::parse("Node/LevelName/Door"),
+        Some(FbxAssetLabel::NodeScene("LevelName/Door".to_string()))
+    );
+}
+
+#[test]
+fn test_label_names() {
+    let names = ["Wood", "", "Wood", "Metal#2", "Wood_2", "Wood"];
+    assert!(label_names(&FbxLoaderSettings::default(), names).is_empty());
+
+    let settings = FbxLoaderSettings {
+        name_based_labels: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        label_names(&settings, names),
+        vec![
+            Some("Wood".to_string()),
+            None,
+            Some("Wood_1".to_string()),
+            Some("Metal_2".to_string()),
+            Some("Wood_2".to_string()),
+            Some("Wood_3".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_label_from_str() {
+    let label: FbxAssetLabel = "Mesh2/Primitive1".parse().expect("valid label");
+    assert_eq!(
+        label,
+        FbxAssetLabel::Primitive {
+            mesh: 2,
+            primitive: 1
+        }
+    );
+
+    let error = "Mesh".parse::<FbxAssetLabel>().unwrap_err();
+    assert_eq!(error, FbxLabelParseError("Mesh".to_string()));
+}
+
+#[test]
+fn test_label_helpers() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+
+    for (name, handle) in &fbx.named_meshes {
+        let Some(FbxAssetLabel::Mesh(index)) = fbx.label_for_mesh(name) else {
+            panic!("Missing label for mesh {name}");
+        };
+        assert_eq!(&fbx.meshes[index], handle);
+    }
+    assert_eq!(fbx.label_for_mesh("NoSuchMesh"), None);
+    assert_eq!(fbx.label_for_node_scene("NoSuchNode"), None);
+}
@@ -0,0 +1,75 @@
+//! Tests for light intensity conversion.
+
+use bevy::prelude::*;
+use bevy_ufbx::light::area_light_rotation;
+use bevy_ufbx::{FbxLightConversion, FbxLightDecay, FbxLightPreset};
+
+#[test]
+fn test_light_conversion_default() {
+    let conversion = FbxLightConversion::default();
+
+    assert_eq!(conversion.preset, FbxLightPreset::Generic);
+    assert_eq!(conversion.multiplier, 1.0);
+    assert_eq!(
+        conversion.luminous_power(1.0, FbxLightDecay::Quadratic),
+        1000.0
+    );
+    assert_eq!(conversion.illuminance(1.0), 10000.0);
+}
+
+#[test]
+fn test_light_conversion_multiplier() {
+    let conversion = FbxLightConversion {
+        multiplier: 0.5,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        conversion.luminous_power(2.0, FbxLightDecay::Quadratic),
+        1000.0
+    );
+    assert_eq!(conversion.illuminance(2.0), 10000.0);
+}
+
+#[test]
+fn test_light_conversion_custom_preset() {
+    let conversion = FbxLightConversion {
+        preset: FbxLightPreset::Custom {
+            lumens_per_unit: 10.0,
+            lux_per_unit: 100.0,
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(
+        conversion.luminous_power(3.0, FbxLightDecay::Quadratic),
+        30.0
+    );
+    assert_eq!(conversion.illuminance(3.0), 300.0);
+}
+
+#[test]
+fn test_light_conversion_decay_reference_distance() {
+    let conversion = FbxLightConversion {
+        decay_reference_distance: 2.0,
+        ..Default::default()
+    };
+
+    assert_eq!(conversion.luminous_power(1.0, FbxLightDecay::None), 4000.0);
+    assert_eq!(
+        conversion.luminous_power(1.0, FbxLightDecay::Linear),
+        2000.0
+    );
+    assert_eq!(
+        conversion.luminous_power(1.0, FbxLightDecay::Quadratic),
+        1000.0
+    );
+    assert_eq!(conversion.luminous_power(1.0, FbxLightDecay::Cubic), 500.0);
+}
+
+#[test]
+fn test_area_light_rotation() {
+    let rotation = area_light_rotation(Vec3::NEG_Y);
+    assert!((rotation * Vec3::Z).abs_diff_eq(Vec3::NEG_Y, 1e-6));
+    assert_eq!(area_light_rotation(Vec3::ZERO), Quat::IDENTITY);
+}
@@ -0,0 +1,123 @@
+//! Tests for resource limits on untrusted FBX files.
+
+mod common;
+
+use bevy_ufbx::{Fbx, FbxError, FbxLoadLimits, FbxLoaderSettings};
+use common::test_app;
+
+fn load_with_limits(limits: FbxLoadLimits) -> Result<Fbx, FbxError> {
+    let mut app = test_app();
+    let settings = FbxLoaderSettings {
+        limits,
+        ..Default::default()
+    };
+    Fbx::from_bytes(
+        include_bytes!("../assets/cube.fbx"),
+        settings,
+        app.world_mut(),
+    )
+}
+
+#[test]
+fn test_limits_default_unlimited() {
+    let limits = FbxLoadLimits::default();
+
+    assert!(limits.max_file_size.is_none());
+    assert!(limits.max_memory.is_none());
+    assert!(limits.max_vertices.is_none());
+    assert!(limits.max_nodes.is_none());
+    assert!(limits.max_hierarchy_depth.is_none());
+    assert!(limits.check_file_size(usize::MAX).is_ok());
+    assert!(load_with_limits(limits).is_ok());
+}
+
+#[test]
+fn test_limits_file_size() {
+    let limits = FbxLoadLimits {
+        max_file_size: Some(64),
+        ..Default::default()
+    };
+
+    assert!(limits.check_file_size(64).is_ok());
+    assert!(matches!(
+        limits.check_file_size(65),
+        Err(FbxError::LimitExceeded(_))
+    ));
+    assert!(matches!(
+        load_with_limits(limits),
+        Err(FbxError::LimitExceeded(_))
+    ));
+}
+
+#[test]
+fn test_limits_memory() {
+    let result = load_with_limits(FbxLoadLimits {
+        max_memory: Some(1024),
+        ..Default::default()
+    });
+
+    assert!(matches!(result, Err(FbxError::LimitExceeded(_))));
+}
+
+#[test]
+fn test_limits_vertices() {
+    let result = load_with_limits(FbxLoadLimits {
+        max_vertices: Some(3),
+        ..Default::default()
+    });
+
+    assert!(matches!(result, Err(FbxError::LimitExceeded(_))));
+}
+
+#[test]
+fn test_limits_hierarchy() {
+    // Root node, `Body`, and the `Root` > `Tip` bone chain
+    let load = |limits| {
+        let mut app = test_app();
+        let settings = FbxLoaderSettings {
+            limits,
+            ..Default::default()
+        };
+        Fbx::from_bytes(
+            include_bytes!("fixtures/skinned.fbx"),
+            settings,
+            app.world_mut(),
+        )
+    };
+
+    let within = FbxLoadLimits {
+        max_nodes: Some(4),
+        max_hierarchy_depth: Some(2),
+        ..Default::default()
+    };
+    assert!(load(within).is_ok());
+    assert!(matches!(
+        load(FbxLoadLimits {
+            max_nodes: Some(3),
+            ..Default::default()
+        }),
+        Err(FbxError::LimitExceeded(_))
+    ));
+    assert!(matches!(
+        load(FbxLoadLimits {
+            max_hierarchy_depth: Some(1),
+            ..Default::default()
+        }),
+        Err(FbxError::LimitExceeded(_))
+    ));
+}
+
+#[test]
+fn test_limits_serialization() {
+    let original = FbxLoadLimits {
+        max_file_size: Some(1 << 20),
+        max_textures: Some(16),
+        ..Default::default()
+    };
+
+    let serialized = serde_json::to_string(&original).expect("Failed to serialize");
+    let deserialized: FbxLoadLimits =
+        serde_json::from_str(&serialized).expect("Failed to deserialize");
+
+    assert_eq!(deserialized, original);
+}
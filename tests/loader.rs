@@ -0,0 +1,108 @@
+//! Tests for importing FBX data from memory without the asset server.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::{
+    Fbx, FbxAssetSink, FbxAxisSystem, FbxError, FbxLoaderSettings, FbxMesh, WorldAssetSink,
+};
+use common::test_app;
+
+#[test]
+fn test_from_bytes_cube() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+
+    assert!(!fbx.meshes.is_empty());
+    assert!(fbx.default_scene.is_some());
+
+    let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+    let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+    assert!(!fbx_mesh.primitives.is_empty());
+
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    assert!(meshes.get(&fbx_mesh.primitives[0].mesh).is_some());
+
+    assert!(fbx_mesh.aabb.is_some());
+    assert!(fbx_mesh.primitives[0].aabb.is_some());
+    assert!(fbx.bounds.is_some());
+}
+
+#[test]
+fn test_from_bytes_rejects_empty() {
+    let mut app = test_app();
+
+    let result = Fbx::from_bytes(&[], FbxLoaderSettings::default(), app.world_mut());
+    assert!(matches!(result, Err(FbxError::InvalidData(_))));
+}
+
+#[test]
+fn test_content_hash() {
+    let mut app = test_app();
+    let source = include_str!("fixtures/ascii_cube.fbx");
+    let mut content_hash = |text: &str| {
+        Fbx::from_bytes(
+            text.as_bytes(),
+            FbxLoaderSettings::default(),
+            app.world_mut(),
+        )
+        .expect("Failed to import ascii_cube.fbx")
+        .metadata
+        .content_hash
+    };
+
+    let original = content_hash(source);
+    assert_eq!(content_hash(source), original);
+    // Re-exporting with another tool leaves the content untouched
+    let reexported = source.replace("bevy_ufbx test fixtures", "another exporter");
+    assert_eq!(content_hash(&reexported), original);
+    let moved = source.replace("a: -1,-1,-1,1,-1,-1", "a: -2,-1,-1,1,-1,-1");
+    assert_ne!(content_hash(&moved), original);
+}
+
+#[test]
+fn test_axis_system_reported() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/maya_cube_7400_binary.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import maya_cube_7400_binary.fbx");
+
+    // Maya exports right-handed Y-up, which needs no conversion
+    assert_eq!(fbx.axis_system, FbxAxisSystem::BEVY);
+    assert!(fbx.converted_axis_system.is_none());
+}
+
+#[test]
+fn test_multithreaded_parsing() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/maya_cube_7400_binary.fbx");
+    let settings = FbxLoaderSettings {
+        multithreaded_parsing: true,
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+        .expect("Failed to import maya_cube_7400_binary.fbx");
+    assert!(!fbx.meshes.is_empty());
+}
+
+#[test]
+fn test_verbose_logging() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        verbose_logging: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        WorldAssetSink::new(app.world_mut(), "").source_name(),
+        "in-memory FBX"
+    );
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+    assert!(!fbx.meshes.is_empty());
+}
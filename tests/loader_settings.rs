@@ -1,7 +1,7 @@
 //! Tests for FBX loader settings.
 
 use bevy::asset::RenderAssetUsages;
-use bevy_ufbx::FbxLoaderSettings;
+use bevy_ufbx::{FbxGeometryTransformHandling, FbxLoaderSettings, FbxPivotHandling, RecenterMode};
 
 #[test]
 fn test_loader_settings_default() {
@@ -24,6 +24,7 @@ fn test_loader_settings_custom() {
         load_lights: false,
         include_source: true,
         convert_coordinates: true,
+        ..Default::default()
     };
 
     assert_eq!(settings.load_meshes, RenderAssetUsages::RENDER_WORLD);
@@ -43,6 +44,7 @@ fn test_loader_settings_serialization() {
         load_lights: true,
         include_source: false,
         convert_coordinates: true,
+        ..Default::default()
     };
 
     // Serialize
@@ -62,4 +64,62 @@ fn test_loader_settings_serialization() {
         deserialized.convert_coordinates,
         original.convert_coordinates
     );
+    assert_eq!(deserialized.weld_vertices, original.weld_vertices);
+}
+
+#[test]
+fn test_geometry_transform_handling_conversion() {
+    assert_eq!(
+        ufbx::GeometryTransformHandling::from(FbxGeometryTransformHandling::ModifyGeometry),
+        ufbx::GeometryTransformHandling::ModifyGeometry
+    );
+    assert_eq!(
+        ufbx::GeometryTransformHandling::from(FbxGeometryTransformHandling::HelperNodes),
+        ufbx::GeometryTransformHandling::HelperNodes
+    );
+}
+
+#[test]
+fn test_pivot_handling_conversion() {
+    assert_eq!(
+        ufbx::PivotHandling::from(FbxPivotHandling::AdjustToPivot),
+        ufbx::PivotHandling::AdjustToPivot
+    );
+    assert_eq!(
+        ufbx::PivotHandling::from(FbxPivotHandling::default()),
+        ufbx::PivotHandling::Retain
+    );
+}
+
+#[test]
+fn test_display_layers_serialization() {
+    let mut original = FbxLoaderSettings::default();
+    original.display_layers.insert("Reflections".to_string(), 2);
+
+    let serialized = serde_json::to_string(&original).expect("Failed to serialize");
+    let deserialized: FbxLoaderSettings =
+        serde_json::from_str(&serialized).expect("Failed to deserialize");
+
+    assert_eq!(deserialized.display_layers.get("Reflections"), Some(&2));
+}
+
+#[test]
+fn test_root_adjustment_serialization() {
+    use bevy::prelude::*;
+
+    let original = FbxLoaderSettings {
+        root_transform_override: Some(Transform::from_scale(Vec3::splat(0.01))),
+        recenter: RecenterMode::BottomCenter,
+        ..Default::default()
+    };
+
+    let serialized = serde_json::to_string(&original).expect("Failed to serialize");
+    let deserialized: FbxLoaderSettings =
+        serde_json::from_str(&serialized).expect("Failed to deserialize");
+
+    assert_eq!(
+        deserialized.root_transform_override,
+        original.root_transform_override
+    );
+    assert_eq!(deserialized.recenter, RecenterMode::BottomCenter);
 }
@@ -0,0 +1,224 @@
+//! Tests for material conversion helpers.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::material::{
+    DEFAULT_PARALLAX_DEPTH_SCALE, FbxAlphaMode, FbxDefaultMaterial, FbxMaterialOverride,
+    material_key, material_override, parallax_depth_scale, reflectance, reflectance_from_f0,
+    resolve_texture_path,
+};
+use bevy_ufbx::texture::TextureIndex;
+use bevy_ufbx::{Fbx, FbxLoaderSettings};
+use common::test_app;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_material_key_identical() {
+    let a = StandardMaterial {
+        base_color: Color::srgb(0.8, 0.2, 0.1),
+        perceptual_roughness: 0.4,
+        ..Default::default()
+    };
+    let b = a.clone();
+
+    assert_eq!(material_key(&a), material_key(&b));
+}
+
+#[test]
+fn test_material_key_differs() {
+    let a = StandardMaterial::default();
+    let b = StandardMaterial {
+        metallic: 0.5,
+        ..Default::default()
+    };
+    let c = StandardMaterial {
+        base_color_texture: Some(Handle::default()),
+        ..Default::default()
+    };
+
+    assert_ne!(material_key(&a), material_key(&b));
+    assert_ne!(material_key(&a), material_key(&c));
+}
+
+#[test]
+fn test_merge_identical_materials() {
+    let bytes = include_bytes!("fixtures/duplicate_materials.fbx");
+
+    let mut app = test_app();
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import duplicate_materials.fbx");
+    assert_eq!(fbx.materials.len(), 2);
+    assert_ne!(fbx.materials[0], fbx.materials[1]);
+
+    let mut app = test_app();
+    let settings = FbxLoaderSettings {
+        merge_identical_materials: true,
+        ..Default::default()
+    };
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+        .expect("Failed to import duplicate_materials.fbx");
+    assert_eq!(fbx.materials.len(), 2);
+    assert_eq!(fbx.materials[0], fbx.materials[1]);
+    assert_eq!(fbx.named_materials["Red"], fbx.materials[0]);
+    assert_eq!(fbx.named_materials["Red.001"], fbx.materials[0]);
+    assert_eq!(app.world().resource::<Assets<StandardMaterial>>().len(), 1);
+}
+
+#[test]
+fn test_parallax_depth_scale() {
+    assert_eq!(parallax_depth_scale(1.0), DEFAULT_PARALLAX_DEPTH_SCALE);
+    assert!((parallax_depth_scale(0.5) - 0.05).abs() < 1e-6);
+    assert_eq!(parallax_depth_scale(-2.0), 0.0);
+}
+
+#[test]
+fn test_reflectance() {
+    let default = StandardMaterial::default().reflectance;
+    assert!((reflectance(1.5, 1.0) - default).abs() < 1e-4);
+    assert!((reflectance(1.5, 0.25) - default * 0.5).abs() < 1e-4);
+    assert_eq!(reflectance(1.5, 0.0), 0.0);
+    assert!(reflectance(2.0, 1.0) > default);
+    assert_eq!(reflectance_from_f0(0.16), 1.0);
+    assert_eq!(reflectance_from_f0(1.0), 1.0);
+    assert_eq!(reflectance_from_f0(-1.0), 0.0);
+}
+
+#[test]
+fn test_absent_material_properties_keep_defaults() {
+    use bevy_ufbx::material::{create_standard_material, extract_material_extras};
+    use std::collections::HashMap;
+
+    let bytes = include_bytes!("fixtures/sparse_materials.fbx");
+    let scene = ufbx::load_memory(bytes, ufbx::LoadOpts::default()).expect("Failed to parse");
+    let material = |name: &str| {
+        let ufbx_material = scene
+            .materials
+            .as_ref()
+            .iter()
+            .find(|material| material.element.name.as_ref() as &str == name)
+            .expect("Material missing");
+        let material = create_standard_material(
+            ufbx_material,
+            &HashMap::new(),
+            &FbxLoaderSettings::default(),
+        )
+        .expect("Failed to create material");
+        (material, extract_material_extras(ufbx_material))
+    };
+    let default = StandardMaterial::default();
+
+    let (bare, _) = material("Bare");
+    assert_eq!(bare.base_color, default.base_color);
+    assert_eq!(bare.metallic, default.metallic);
+    assert_eq!(bare.perceptual_roughness, default.perceptual_roughness);
+    assert_eq!(bare.reflectance, default.reflectance);
+    assert_eq!(bare.specular_tint, default.specular_tint);
+
+    // A Phong specular color tints highlights, not reflections
+    let (plastic, extras) = material("Plastic");
+    assert_eq!(plastic.base_color, Color::srgb(0.0, 0.5, 0.0));
+    assert_eq!(plastic.specular_tint, default.specular_tint);
+    assert!(extras.properties.contains_key("SpecularColor"));
+}
+
+#[test]
+fn test_default_material_matches_standard_material() {
+    let material = FbxDefaultMaterial::default().to_standard_material();
+    assert_eq!(
+        material_key(&material),
+        material_key(&StandardMaterial::default())
+    );
+}
+
+#[test]
+fn test_default_material_settings() {
+    let material = FbxDefaultMaterial {
+        base_color: [0.5, 0.5, 0.5, 0.5],
+        double_sided: true,
+        unlit: true,
+        ..Default::default()
+    }
+    .to_standard_material();
+    assert_eq!(material.base_color, Color::srgba(0.5, 0.5, 0.5, 0.5));
+    assert_eq!(material.alpha_mode, AlphaMode::Blend);
+    assert!(material.double_sided);
+    assert!(material.cull_mode.is_none());
+    assert!(material.unlit);
+}
+
+#[test]
+fn test_material_override_apply() {
+    let mut material = StandardMaterial {
+        alpha_mode: AlphaMode::Blend,
+        ..Default::default()
+    };
+    FbxMaterialOverride {
+        alpha_mode: Some(FbxAlphaMode::Mask(0.5)),
+        double_sided: Some(true),
+        depth_bias: None,
+    }
+    .apply(&mut material);
+    assert_eq!(material.alpha_mode, AlphaMode::Mask(0.5));
+    assert!(material.double_sided);
+    assert!(material.cull_mode.is_none());
+    assert_eq!(material.depth_bias, 0.0);
+
+    FbxMaterialOverride {
+        double_sided: Some(false),
+        ..Default::default()
+    }
+    .apply(&mut material);
+    assert_eq!(material.alpha_mode, AlphaMode::Mask(0.5));
+    assert_eq!(material.cull_mode, StandardMaterial::default().cull_mode);
+}
+
+#[test]
+fn test_material_override_first_match() {
+    let glass = FbxMaterialOverride {
+        alpha_mode: Some(FbxAlphaMode::Premultiplied),
+        ..Default::default()
+    };
+    let settings = FbxLoaderSettings {
+        material_overrides: vec![
+            ("Glass*".to_string(), glass),
+            ("*".to_string(), FbxMaterialOverride::default()),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(material_override("Glass_Window", &settings), Some(&glass));
+    assert_eq!(
+        material_override("Wood", &settings),
+        Some(&FbxMaterialOverride::default())
+    );
+    assert!(material_override("Glass", &FbxLoaderSettings::default()).is_none());
+}
+
+#[test]
+fn test_resolve_texture_path_in_media_dir() {
+    let index = TextureIndex::from_files([
+        PathBuf::from("models/car.fbm/paint.png"),
+        PathBuf::from("textures/paint.png"),
+    ]);
+    let settings = FbxLoaderSettings {
+        texture_search_dirs: vec!["textures".to_string()],
+        ..Default::default()
+    };
+    let resolve = |media_dir: Option<&Path>| {
+        resolve_texture_path(
+            "",
+            r"C:\export\car.fbm\paint.png",
+            &settings,
+            &index,
+            Path::new("models"),
+            media_dir,
+        )
+    };
+
+    // The `.fbm` folder is searched before the configured directories
+    assert_eq!(
+        resolve(Some(Path::new("models/car.fbm"))),
+        Some(PathBuf::from("models/car.fbm/paint.png"))
+    );
+    assert_eq!(resolve(None), Some(PathBuf::from("textures/paint.png")));
+}
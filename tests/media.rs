@@ -0,0 +1,55 @@
+//! Tests for embedded media extraction.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::media::{extract_media, is_image_filename};
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxMedia, FbxMediaKind};
+use common::test_app;
+
+#[test]
+fn test_is_image_filename() {
+    assert!(is_image_filename("textures/checker.png"));
+    assert!(is_image_filename(r"C:\project\albedo.TGA"));
+    assert!(!is_image_filename("audio/dialogue.wav"));
+    assert!(!is_image_filename("blob"));
+}
+
+#[test]
+fn test_extract_media() {
+    let bytes = include_bytes!("fixtures/media.fbx");
+    let scene = ufbx::load_memory(bytes, ufbx::LoadOpts::default()).expect("Failed to parse");
+
+    // The embedded PNG is left to the texture pipeline
+    let media = extract_media(&scene);
+    assert_eq!(media.len(), 1);
+    let dialogue = &media[0];
+    assert_eq!(dialogue.name, "Dialogue");
+    assert_eq!(dialogue.kind, FbxMediaKind::Video);
+    assert_eq!(dialogue.filename, "audio/dialogue.wav");
+    assert_eq!(dialogue.extension().as_deref(), Some("wav"));
+    assert_eq!(dialogue.coder_type.as_deref(), Some("PCM"));
+    assert_eq!(&dialogue.data[..4], b"RIFF");
+}
+
+#[test]
+fn test_load_media() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/media.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import media.fbx");
+    assert!(fbx.media.is_empty());
+
+    let settings = FbxLoaderSettings {
+        load_media: true,
+        ..Default::default()
+    };
+    let fbx =
+        Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import media.fbx");
+    assert_eq!(fbx.media.len(), 1);
+    let media = app.world().resource::<Assets<FbxMedia>>();
+    let dialogue = media.get(&fbx.media[0]).expect("FbxMedia missing");
+    assert_eq!(dialogue.filename, "audio/dialogue.wav");
+    assert_eq!(dialogue.data.len(), 12);
+}
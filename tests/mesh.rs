@@ -0,0 +1,467 @@
+//! Tests for mesh post-processing.
+
+mod common;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::primitives::Aabb;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+use bevy_ufbx::mesh::{
+    face_triangle_ranges, merge_aabbs, merge_meshes, split_mesh_by_joints, tangent_with_sign,
+    transform_aabb,
+};
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxMesh, FbxSkinnedBounds};
+use common::test_app;
+
+/// Two disjoint triangles, the first skinned to joints 0..3, the second to 4..7.
+fn skinned_mesh() -> Mesh {
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    let positions: Vec<[f32; 3]> = (0..6).map(|i| [i as f32, 0.0, 0.0]).collect();
+    let joints: Vec<[u16; 4]> = (0..6)
+        .map(|i| if i < 3 { [0, 1, 2, 3] } else { [4, 5, 6, 7] })
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_JOINT_INDEX,
+        VertexAttributeValues::Uint16x4(joints),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT, vec![[0.25f32; 4]; 6]);
+    mesh.insert_indices(Indices::U32(vec![0, 1, 2, 3, 4, 5]));
+    mesh
+}
+
+#[test]
+fn test_split_within_limit() {
+    assert!(split_mesh_by_joints(&skinned_mesh(), 8).is_none());
+}
+
+#[test]
+fn test_split_unskinned() {
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 3]);
+    mesh.insert_indices(Indices::U32(vec![0, 1, 2]));
+
+    assert!(split_mesh_by_joints(&mesh, 1).is_none());
+}
+
+#[test]
+fn test_split_over_limit() {
+    let splits = split_mesh_by_joints(&skinned_mesh(), 4).expect("Mesh should be split");
+
+    assert_eq!(splits.len(), 2);
+    assert_eq!(splits[0].1, vec![0, 1, 2, 3]);
+    assert_eq!(splits[1].1, vec![4, 5, 6, 7]);
+
+    let (second, _) = &splits[1];
+    assert_eq!(second.count_vertices(), 3);
+    let Some(VertexAttributeValues::Uint16x4(joints)) =
+        second.attribute(Mesh::ATTRIBUTE_JOINT_INDEX)
+    else {
+        panic!("Missing joint indices");
+    };
+    assert!(joints.iter().all(|j| *j == [0, 1, 2, 3]));
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        second.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("Missing positions");
+    };
+    assert_eq!(positions[0], [3.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_merge_aabbs() {
+    let a = Aabb::from_min_max(Vec3::ZERO, Vec3::ONE);
+    let b = Aabb::from_min_max(Vec3::new(-1.0, 0.5, 0.5), Vec3::new(0.5, 2.0, 0.5));
+
+    let merged = merge_aabbs(a, b);
+    assert_eq!(Vec3::from(merged.min()), Vec3::new(-1.0, 0.0, 0.0));
+    assert_eq!(Vec3::from(merged.max()), Vec3::new(1.0, 2.0, 1.0));
+}
+
+#[test]
+fn test_transform_aabb() {
+    let aabb = Aabb::from_min_max(Vec3::ZERO, Vec3::new(2.0, 1.0, 1.0));
+    let matrix = Mat4::from_rotation_translation(
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+        Vec3::new(10.0, 0.0, 0.0),
+    );
+
+    let transformed = transform_aabb(&aabb, matrix);
+    // The long X side now lies along -Z
+    assert!(Vec3::from(transformed.min()).abs_diff_eq(Vec3::new(10.0, 0.0, -2.0), 1e-5));
+    assert!(Vec3::from(transformed.max()).abs_diff_eq(Vec3::new(11.0, 1.0, 0.0), 1e-5));
+}
+
+#[test]
+fn test_face_triangle_ranges() {
+    // A triangle, a quad and a pentagon
+    assert_eq!(face_triangle_ranges(&[3, 4, 5]), vec![0..1, 1..3, 3..6]);
+    assert!(face_triangle_ranges(&[]).is_empty());
+}
+
+/// One triangle in the XY plane facing +Z.
+fn triangle() -> Mesh {
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0f32, 0.0, 1.0]; 3]);
+    mesh.insert_indices(Indices::U32(vec![0, 1, 2]));
+    mesh
+}
+
+#[test]
+fn test_merge_meshes() {
+    let mesh = triangle();
+    let mut uv_mesh = triangle();
+    uv_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0f32; 2]; 3]);
+    let meshes = [
+        (&mesh, Mat4::from_translation(Vec3::new(5.0, 0.0, 0.0))),
+        (&uv_mesh, Mat4::from_scale(Vec3::new(1.0, 1.0, -2.0))),
+    ];
+
+    let merged = merge_meshes(&meshes, RenderAssetUsages::default()).expect("Meshes should merge");
+    assert_eq!(merged.count_vertices(), 6);
+    // UVs are missing from the first mesh
+    assert!(merged.attribute(Mesh::ATTRIBUTE_UV_0).is_none());
+
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        merged.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("Missing positions");
+    };
+    assert_eq!(positions[1], [6.0, 0.0, 0.0]);
+    let Some(VertexAttributeValues::Float32x3(normals)) = merged.attribute(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        panic!("Missing normals");
+    };
+    assert_eq!(normals[3], [0.0, 0.0, -1.0]);
+
+    // The mirrored second triangle has its winding flipped
+    let Some(Indices::U32(indices)) = merged.indices() else {
+        panic!("Missing indices");
+    };
+    assert_eq!(indices, &vec![0, 1, 2, 3, 5, 4]);
+}
+
+#[test]
+fn test_merge_meshes_rejects_lines() {
+    let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default());
+    assert!(merge_meshes(&[(&lines, Mat4::IDENTITY)], RenderAssetUsages::default()).is_none());
+    assert!(merge_meshes(&[], RenderAssetUsages::default()).is_none());
+}
+
+/// Quad in the XY plane facing +Z with the given UVs, with mikktspace
+/// tangents.
+fn mikktspace_quad(uvs: Vec<[f32; 2]>) -> Vec<[f32; 4]> {
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![
+            [0.0f32, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ],
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0f32, 0.0, 1.0]; 4]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(vec![0, 1, 2, 0, 2, 3]));
+    mesh.generate_tangents().unwrap();
+    match mesh.attribute(Mesh::ATTRIBUTE_TANGENT) {
+        Some(VertexAttributeValues::Float32x4(tangents)) => tangents.clone(),
+        _ => panic!("Missing tangents"),
+    }
+}
+
+#[test]
+fn test_tangent_sign_matches_mikktspace() {
+    // FBX tangents and binormals are the position derivatives along U and V
+    let cases = [
+        (
+            vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+            Vec3::X,
+            Vec3::Y,
+        ),
+        (
+            vec![[1.0, 0.0], [0.0, 0.0], [0.0, 1.0], [1.0, 1.0]],
+            Vec3::NEG_X,
+            Vec3::Y,
+        ),
+        (
+            vec![[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]],
+            Vec3::X,
+            Vec3::NEG_Y,
+        ),
+    ];
+    for (uvs, tangent, binormal) in cases {
+        let expected = tangent_with_sign(Vec3::Z, tangent, binormal);
+        for generated in mikktspace_quad(uvs) {
+            assert!(Vec4::from(generated).abs_diff_eq(Vec4::from(expected), 1e-5));
+        }
+    }
+}
+
+#[test]
+fn test_tangent_sign_normalizes_tangent() {
+    let tangent = tangent_with_sign(Vec3::Z, Vec3::new(2.0, 0.0, 0.0), Vec3::NEG_Y);
+    assert_eq!(tangent, [1.0, 0.0, 0.0, -1.0]);
+}
+
+#[test]
+fn test_secondary_uv_set() {
+    use bevy_ufbx::FbxLoaderSettings;
+    use bevy_ufbx::mesh::{missing_uv_sets, secondary_uv_set, texture_uv_sets};
+
+    let bytes = include_bytes!("fixtures/detail_uv.fbx");
+    let scene = ufbx::load_memory(bytes, ufbx::LoadOpts::default()).expect("Failed to parse");
+    let mesh = &scene.meshes.as_ref()[0];
+    let settings = FbxLoaderSettings::default();
+
+    assert_eq!(texture_uv_sets(mesh), vec!["UVMap", "Detail", "Bake"]);
+    let uv_set = secondary_uv_set(mesh, &settings).expect("Secondary UV set missing");
+    assert_eq!(uv_set.name.as_ref() as &str, "Detail");
+    assert_eq!(missing_uv_sets(mesh, &settings), vec!["Bake"]);
+
+    // A lightmap UV set takes the place of the textures', even if missing
+    let lightmap = FbxLoaderSettings {
+        lightmap_uv_set: Some("Lightmap".to_string()),
+        ..Default::default()
+    };
+    assert!(secondary_uv_set(mesh, &lightmap).is_none());
+    assert_eq!(missing_uv_sets(mesh, &lightmap), vec!["Detail", "Bake"]);
+}
+
+/// Material, joints, attribute bytes and indices of converted primitives.
+type PrimitiveData = (usize, Option<Vec<u16>>, Vec<Vec<u8>>, Option<Vec<usize>>);
+
+fn primitive_data(primitives: &[bevy_ufbx::mesh::ConvertedPrimitive]) -> Vec<PrimitiveData> {
+    primitives
+        .iter()
+        .map(|(material, joints, mesh)| {
+            let attributes = mesh.attributes();
+            (
+                *material,
+                joints.clone(),
+                attributes
+                    .map(|(_, values)| values.get_bytes().to_vec())
+                    .collect(),
+                mesh.indices().map(|indices| indices.iter().collect()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_parallel_conversion_matches_sequential() {
+    use bevy::tasks::{AsyncComputeTaskPool, TaskPoolBuilder};
+    use bevy_ufbx::FbxLoaderSettings;
+    use bevy_ufbx::mesh::{convert_mesh_primitives, convert_meshes_parallel};
+
+    AsyncComputeTaskPool::get_or_init(|| TaskPoolBuilder::new().num_threads(4).build());
+    let scenes = [
+        include_bytes!("fixtures/multi_material.fbx").as_slice(),
+        include_bytes!("fixtures/skinned.fbx"),
+        include_bytes!("fixtures/vertex_layers.fbx"),
+        include_bytes!("fixtures/detail_uv.fbx"),
+    ]
+    .map(|bytes| ufbx::load_memory(bytes, ufbx::LoadOpts::default()).expect("Failed to parse"));
+    let meshes: Vec<&ufbx::Mesh> = scenes
+        .iter()
+        .flat_map(|scene| scene.meshes.as_ref().iter().map(|mesh| &**mesh))
+        .collect();
+    let settings = FbxLoaderSettings::default();
+
+    let parallel = convert_meshes_parallel(&meshes, &settings);
+    assert_eq!(parallel.len(), meshes.len());
+    for (mesh, primitives) in meshes.iter().zip(&parallel) {
+        let sequential = convert_mesh_primitives(mesh, &settings);
+        assert!(!primitives.is_empty());
+        assert_eq!(primitive_data(primitives), primitive_data(&sequential));
+    }
+}
+
+#[test]
+fn test_skinned_bounds() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/skinned.fbx");
+
+    let bounds = |app: &mut App, skinned_bounds| {
+        let settings = FbxLoaderSettings {
+            skinned_bounds,
+            ..Default::default()
+        };
+        let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+            .expect("Failed to import skinned.fbx");
+        let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+        let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+        (
+            fbx_mesh.aabb.expect("Mesh bounds missing"),
+            fbx_mesh.skinned_aabb,
+        )
+    };
+
+    let (_, skinned_aabb) = bounds(&mut app, FbxSkinnedBounds::BindPose);
+    assert!(skinned_aabb.is_none());
+
+    let (aabb, skinned_aabb) = bounds(&mut app, FbxSkinnedBounds::Inflate(2.0));
+    let skinned_aabb = skinned_aabb.expect("Inflated bounds missing");
+    assert_eq!(skinned_aabb.center, aabb.center);
+    assert_eq!(skinned_aabb.half_extents, aabb.half_extents * 2.0);
+
+    // The rest pose is always covered
+    let (aabb, skinned_aabb) = bounds(&mut app, FbxSkinnedBounds::Animated);
+    let skinned_aabb = skinned_aabb.expect("Animated bounds missing");
+    assert!(skinned_aabb.min().cmple(aabb.min() + 1e-4).all());
+    assert!(skinned_aabb.max().cmpge(aabb.max() - 1e-4).all());
+}
+
+#[test]
+fn test_secondary_uvs() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/detail_uv.fbx");
+
+    for weld_vertices in [false, true] {
+        let settings = FbxLoaderSettings {
+            weld_vertices,
+            ..Default::default()
+        };
+        let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+            .expect("Failed to import detail_uv.fbx");
+        let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+        let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+        let meshes = app.world().resource::<Assets<Mesh>>();
+        let mesh = meshes
+            .get(&fbx_mesh.primitives[0].mesh)
+            .expect("Mesh missing");
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("Positions missing");
+        };
+        let Some(VertexAttributeValues::Float32x2(uv0)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("UV0 missing");
+        };
+        let Some(VertexAttributeValues::Float32x2(uv1)) = mesh.attribute(Mesh::ATTRIBUTE_UV_1)
+        else {
+            panic!("UV1 missing");
+        };
+        assert_eq!(uv1.len(), positions.len());
+        // Both UV sets map the quad's corners, the detail set tiling four times
+        for ((position, uv0), uv1) in positions.iter().zip(uv0).zip(uv1) {
+            let corner = [
+                (position[0].signum() + 1.0) / 2.0,
+                (position[2].signum() + 1.0) / 2.0,
+            ];
+            assert_eq!(*uv0, corner);
+            assert_eq!(*uv1, corner.map(|uv| uv * 4.0));
+        }
+    }
+}
+
+#[test]
+fn test_tangents() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/detail_uv.fbx");
+
+    for weld_vertices in [false, true] {
+        let settings = FbxLoaderSettings {
+            weld_vertices,
+            ..Default::default()
+        };
+        let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+            .expect("Failed to import detail_uv.fbx");
+        let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+        let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+        let meshes = app.world().resource::<Assets<Mesh>>();
+        let mesh = meshes
+            .get(&fbx_mesh.primitives[0].mesh)
+            .expect("Mesh missing");
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("Positions missing");
+        };
+        let Some(VertexAttributeValues::Float32x4(tangents)) =
+            mesh.attribute(Mesh::ATTRIBUTE_TANGENT)
+        else {
+            panic!("Tangents missing");
+        };
+        assert_eq!(tangents.len(), positions.len());
+        // Each corner's tangent points away from the quad's center
+        for (position, tangent) in positions.iter().zip(tangents) {
+            let expected = Vec3::from_array(*position).normalize();
+            assert!(Vec3::from_slice(&tangent[..3]).abs_diff_eq(expected, 1e-5));
+            assert_eq!(tangent[3], 1.0);
+        }
+    }
+}
+
+#[test]
+fn test_weld_vertices_with_vertex_cache() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/vertex_cache.fbx");
+
+    // The quads' shared edge is made of four vertices at two positions
+    for (load_vertex_caches, vertex_count) in [(false, 6), (true, 8)] {
+        let settings = FbxLoaderSettings {
+            weld_vertices: true,
+            load_vertex_caches,
+            ..Default::default()
+        };
+        let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+            .expect("Failed to import vertex_cache.fbx");
+        let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+        let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+        let primitive = &fbx_mesh.primitives[0];
+        let meshes = app.world().resource::<Assets<Mesh>>();
+        let mesh = meshes.get(&primitive.mesh).expect("Mesh missing");
+
+        assert_eq!(mesh.count_vertices(), vertex_count);
+        if load_vertex_caches {
+            let mut cache_vertices = primitive.cache_vertices.clone().expect("No cache vertices");
+            cache_vertices.sort_unstable();
+            assert_eq!(cache_vertices, (0..8).collect::<Vec<u32>>());
+        } else {
+            assert!(primitive.cache_vertices.is_none());
+        }
+    }
+}
+
+#[test]
+fn test_lines_and_points_without_curves() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        load_lines_and_points: true,
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+
+    // The cube only has polygons, so no line or point primitives are added
+    assert!(fbx.line_curves.is_empty());
+    let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+    for primitive in &fbx_mesh.primitives {
+        let mesh = meshes.get(&primitive.mesh).expect("Mesh missing");
+        assert_eq!(mesh.primitive_topology(), PrimitiveTopology::TriangleList);
+    }
+}
@@ -0,0 +1,90 @@
+//! Tests for blend shape weight mapping.
+
+use bevy_ufbx::FbxBlendChannel;
+use bevy_ufbx::morph::in_between_weights;
+
+#[test]
+fn test_single_target_scales_linearly() {
+    assert_eq!(in_between_weights(&[1.0], 0.0), vec![0.0]);
+    assert_eq!(in_between_weights(&[1.0], 0.25), vec![0.25]);
+    assert_eq!(in_between_weights(&[1.0], 1.0), vec![1.0]);
+}
+
+#[test]
+fn test_in_between_target() {
+    let targets = [0.5, 1.0];
+    assert_eq!(in_between_weights(&targets, 0.0), vec![0.0, 0.0]);
+    assert_eq!(in_between_weights(&targets, 0.25), vec![0.5, 0.0]);
+    assert_eq!(in_between_weights(&targets, 0.5), vec![1.0, 0.0]);
+    assert_eq!(in_between_weights(&targets, 0.75), vec![0.5, 0.5]);
+    assert_eq!(in_between_weights(&targets, 1.0), vec![0.0, 1.0]);
+}
+
+#[test]
+fn test_weight_beyond_last_target() {
+    assert_eq!(in_between_weights(&[0.5, 1.0], 1.5), vec![0.0, 1.5]);
+}
+
+#[test]
+fn test_no_targets() {
+    assert!(in_between_weights(&[], 0.5).is_empty());
+}
+
+#[test]
+fn test_channel_target_weights() {
+    let channel = FbxBlendChannel {
+        name: "Blink".to_string(),
+        first_target: 2,
+        target_weights: vec![0.3, 0.6, 1.0],
+        weight: 0.0,
+    };
+    let weights = channel.target_weights_at(0.45);
+    assert!((weights[0] - 0.5).abs() < 1e-6);
+    assert!((weights[1] - 0.5).abs() < 1e-6);
+    assert_eq!(weights[2], 0.0);
+}
+
+#[test]
+fn test_morph_weight_keys() {
+    use bevy_ufbx::morph::morph_weight_keys;
+
+    let channels = [
+        FbxBlendChannel {
+            name: "JawOpen".to_string(),
+            first_target: 0,
+            target_weights: vec![1.0],
+            weight: 0.0,
+        },
+        FbxBlendChannel {
+            name: "Blink".to_string(),
+            first_target: 1,
+            target_weights: vec![0.5, 1.0],
+            weight: 0.5,
+        },
+    ];
+    let channel_keys = [vec![(0.0, 0.0), (1.0, 1.0)], Vec::new()];
+
+    let (times, weights) = morph_weight_keys(&channels, &channel_keys).unwrap();
+    assert_eq!(times, vec![0.0, 1.0]);
+    assert_eq!(weights, vec![0.0, 1.0, 0.0, 1.0, 1.0, 0.0]);
+
+    assert!(morph_weight_keys(&channels, &[Vec::new(), Vec::new()]).is_none());
+    assert!(morph_weight_keys(&channels, &[vec![(0.5, 1.0)], Vec::new()]).is_none());
+}
+
+#[test]
+fn test_morph_weight_keys_interpolates_channels() {
+    use bevy_ufbx::morph::morph_weight_keys;
+
+    let channel = FbxBlendChannel {
+        name: "Smile".to_string(),
+        first_target: 0,
+        target_weights: vec![1.0],
+        weight: 0.0,
+    };
+    let channel_keys = [vec![(0.0, 0.0), (2.0, 1.0)], vec![(1.0, 0.0), (2.0, 0.0)]];
+
+    let (times, weights) = morph_weight_keys(&[channel.clone(), channel], &channel_keys).unwrap();
+    assert_eq!(times, vec![0.0, 1.0, 2.0]);
+    assert_eq!(weights, vec![0.0, 0.0, 0.5, 0.0, 1.0, 0.0]);
+}
@@ -0,0 +1,64 @@
+//! Tests for FBX node import.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxNode, FbxPivotHandling, FbxRotationOrder};
+use common::test_app;
+
+#[test]
+fn test_transform_data() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/skinned.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import skinned.fbx");
+
+    let handle = fbx.named_nodes.get("Tip").expect("Tip node missing");
+    let nodes = app.world().resource::<Assets<FbxNode>>();
+    let data = nodes.get(handle).expect("FbxNode missing").transform_data;
+    assert_eq!(data.translation, Vec3::Y);
+    assert_eq!(data.rotation, Vec3::ZERO);
+    assert_eq!(data.scale, Vec3::ONE);
+    assert_eq!(data.rotation_order, FbxRotationOrder::Xyz);
+    assert_eq!(data.rotation_pivot, Vec3::ZERO);
+}
+
+#[test]
+fn test_pivot_handling() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/pivot.fbx");
+    let lid = |app: &mut App, pivot_handling| {
+        let settings = FbxLoaderSettings {
+            pivot_handling,
+            ..Default::default()
+        };
+        let fbx =
+            Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import pivot.fbx");
+        let nodes = app.world().resource::<Assets<FbxNode>>();
+        nodes
+            .get(&fbx.named_nodes["Lid"])
+            .expect("FbxNode missing")
+            .clone()
+    };
+
+    let retained = lid(&mut app, FbxPivotHandling::Retain);
+    assert_eq!(retained.geometry_transform, Transform::IDENTITY);
+
+    // The origin moves to the hinge, the geometry is offset back
+    let adjusted = lid(&mut app, FbxPivotHandling::AdjustToPivot);
+    assert!(
+        adjusted
+            .transform
+            .translation
+            .abs_diff_eq(Vec3::new(0.0, 0.0, -1.0), 1e-5)
+    );
+    let offset = adjusted.geometry_transform.translation;
+    assert!(offset.abs_diff_eq(Vec3::new(0.0, 0.0, 1.0), 1e-5));
+    assert!(
+        adjusted
+            .transform
+            .rotation
+            .abs_diff_eq(retained.transform.rotation, 1e-5)
+    );
+}
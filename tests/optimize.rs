@@ -0,0 +1,83 @@
+//! Tests for mesh batching passes.
+
+mod common;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxMesh};
+use common::test_app;
+
+#[test]
+fn test_merge_by_material() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        merge_by_material: true,
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+    assert!(!fbx.merged_meshes.is_empty());
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    assert!(meshes.get(&fbx.merged_meshes[0].mesh).is_some());
+
+    // The standalone pass gives the same groups on the loaded assets
+    let merged = app
+        .world_mut()
+        .resource_scope(|world, mut meshes: Mut<Assets<Mesh>>| {
+            bevy_ufbx::optimize::merge_by_material(
+                &fbx,
+                world.resource::<Assets<bevy_ufbx::FbxNode>>(),
+                world.resource::<Assets<FbxMesh>>(),
+                &mut meshes,
+            )
+        });
+    assert_eq!(merged.len(), fbx.merged_meshes.len());
+}
+
+#[test]
+fn test_flatten_static_hierarchy() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        flatten_static_hierarchy: true,
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut names = scene.world.query_filtered::<&Name, With<Mesh3d>>();
+    assert!(
+        names
+            .iter(&scene.world)
+            .any(|name| name.as_str() == "Batch0")
+    );
+}
+
+#[test]
+fn test_flatten_static_hierarchy_asset_usage() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        flatten_static_hierarchy: true,
+        mesh_usage_overrides: vec![("*".to_string(), RenderAssetUsages::MAIN_WORLD)],
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut batches = scene.world.query::<(&Name, &Mesh3d)>();
+    let batch = batches
+        .iter(&scene.world)
+        .find(|(name, _)| name.as_str() == "Batch0")
+        .map(|(_, mesh)| mesh.0.clone())
+        .expect("Batch0 missing");
+
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    let mesh = meshes.get(&batch).expect("Batch mesh missing");
+    assert_eq!(mesh.asset_usage, RenderAssetUsages::MAIN_WORLD);
+}
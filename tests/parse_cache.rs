@@ -0,0 +1,49 @@
+//! Tests for the shared parse cache.
+
+mod common;
+
+use bevy_ufbx::{Fbx, FbxLoaderSettings};
+use common::test_app;
+
+#[test]
+fn test_parse_cache() {
+    use bevy_ufbx::parse_cache::{cached_scene_count, clear_parse_cache};
+
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        parse_cache_size: 1,
+        ..Default::default()
+    };
+    clear_parse_cache();
+
+    let first = Fbx::from_bytes(bytes, settings.clone(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+    let second = Fbx::from_bytes(bytes, settings.clone(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+    assert!(!first.diagnostics.parse_cache_hit);
+    assert!(second.diagnostics.parse_cache_hit);
+    assert_eq!(first.meshes.len(), second.meshes.len());
+
+    // Loads on other threads, like the asset server's, share the cache
+    let threaded = std::thread::spawn({
+        let settings = settings.clone();
+        move || {
+            let mut app = test_app();
+            Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx")
+        }
+    })
+    .join()
+    .expect("Loading thread panicked");
+    assert!(threaded.diagnostics.parse_cache_hit);
+    assert_eq!(cached_scene_count(), 1);
+
+    // Other parse options need their own parse, evicting the cached one
+    let other = FbxLoaderSettings {
+        geometry_transform_handling: bevy_ufbx::FbxGeometryTransformHandling::ModifyGeometry,
+        ..settings
+    };
+    let third = Fbx::from_bytes(bytes, other, app.world_mut()).expect("Failed to import cube.fbx");
+    assert!(!third.diagnostics.parse_cache_hit);
+    assert_eq!(cached_scene_count(), 1);
+}
@@ -0,0 +1,66 @@
+//! Tests for plugin-wide default loader settings.
+
+mod common;
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxPlugin, FbxValidation};
+use common::test_app_with_plugin;
+use std::time::Duration;
+
+/// Load `cube.fbx` through the asset server of an app built with `plugin`.
+/// Returns whether a validation report was added.
+fn load_cube_report(plugin: impl Plugin) -> bool {
+    let mut app = test_app_with_plugin(plugin);
+    let handle: Handle<Fbx> = app.world().resource::<AssetServer>().load("cube.fbx");
+    for _ in 0..1000 {
+        app.update();
+        if let Some(fbx) = app.world().resource::<Assets<Fbx>>().get(&handle) {
+            return fbx.report.is_some();
+        }
+        let load_state = app.world().resource::<AssetServer>().load_state(&handle);
+        if let LoadState::Failed(error) = load_state {
+            panic!("Failed to load cube.fbx: {error}");
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("cube.fbx didn't finish loading");
+}
+
+#[test]
+fn test_with_defaults_keeps_changed_fields() {
+    let defaults = FbxLoaderSettings {
+        load_lights: false,
+        load_cameras: false,
+        weld_vertices: true,
+        ..Default::default()
+    };
+    let per_load = FbxLoaderSettings {
+        load_cameras: false,
+        load_animations: false,
+        ..Default::default()
+    };
+
+    let settings = per_load.with_defaults(&defaults);
+    assert!(!settings.load_lights);
+    assert!(!settings.load_cameras);
+    assert!(settings.weld_vertices);
+    assert!(!settings.load_animations);
+    assert_eq!(
+        settings.load_materials,
+        FbxLoaderSettings::default().load_materials
+    );
+}
+
+#[test]
+fn test_plugin_defaults_applied_per_app() {
+    let defaults = FbxLoaderSettings {
+        validation: Some(FbxValidation::default()),
+        ..Default::default()
+    };
+
+    assert!(load_cube_report(FbxPlugin::default_settings(defaults)));
+    // Neither another app nor `Default` sees the first app's defaults
+    assert!(!load_cube_report(FbxPlugin));
+    assert_eq!(FbxLoaderSettings::default().validation, None);
+}
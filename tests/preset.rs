@@ -0,0 +1,46 @@
+//! Tests for per-exporter import presets.
+
+use bevy_ufbx::{FbxLightPreset, FbxLoaderSettings, ImportPreset};
+
+#[test]
+fn test_detect_import_preset() {
+    assert_eq!(
+        ImportPreset::detect("Blender (stable FBX IO) - 4.1.0 - 5.11.6", ""),
+        Some(ImportPreset::Blender)
+    );
+    assert_eq!(
+        ImportPreset::detect("FBX SDK/FBX Plugins version 2020.3", "Maya"),
+        Some(ImportPreset::Maya)
+    );
+    assert_eq!(
+        ImportPreset::detect("FBX SDK/FBX Plugins version 2020.3", "3ds Max"),
+        Some(ImportPreset::Max3ds)
+    );
+    assert_eq!(
+        ImportPreset::detect("Unity FBX Exporter", ""),
+        Some(ImportPreset::Unity)
+    );
+    assert_eq!(
+        ImportPreset::detect("FBX SDK/FBX Plugins version 2020.3", ""),
+        None
+    );
+}
+
+#[test]
+fn test_apply_import_preset() {
+    let mut settings = FbxLoaderSettings::default();
+    ImportPreset::Max3ds.apply(&mut settings);
+    assert!(settings.bump_as_normal_map);
+    assert_eq!(
+        settings.light_conversion.preset,
+        FbxLightPreset::Photometric
+    );
+
+    ImportPreset::Blender.apply(&mut settings);
+    assert!(!settings.bump_as_normal_map);
+    assert_eq!(settings.light_conversion.preset, FbxLightPreset::Blender);
+
+    let before = settings.clone();
+    ImportPreset::Auto.apply(&mut settings);
+    assert_eq!(settings, before);
+}
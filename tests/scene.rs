@@ -0,0 +1,407 @@
+//! Tests for spawning FBX scenes.
+
+mod common;
+
+use bevy::animation::{AnimatedBy, AnimationTargetId};
+use bevy::math::DVec3;
+use bevy::mesh::skinning::{SkinnedMesh, SkinnedMeshInverseBindposes};
+use bevy::prelude::*;
+use bevy_ufbx::mesh::MAX_JOINTS;
+use bevy_ufbx::{
+    Fbx, FbxActiveCamera, FbxBillboard, FbxBillboardAxis, FbxLoaderSettings, FbxMesh, FbxNode,
+    FbxPlugin, FbxSceneExtension, FbxShadowOnly, FbxTakeScenes, FbxWorldOrigin,
+};
+use common::{test_app, test_app_with_plugin};
+use std::collections::HashMap;
+
+#[test]
+fn test_scene_without_bones() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut bones = scene.world.query::<&bevy_ufbx::FbxBone>();
+    assert_eq!(bones.iter(&scene.world).count(), 0);
+
+    // Mesh nodes are not nulls
+    let mut nulls = scene
+        .world
+        .query_filtered::<&Name, With<bevy_ufbx::FbxNull>>();
+    assert!(nulls.iter(&scene.world).all(|name| name.as_str() != "Cube"));
+
+    let mut names = scene.world.query::<&Name>();
+    assert!(names.iter(&scene.world).count() > 0);
+}
+
+#[test]
+fn test_shadow_flags() {
+    use bevy::light::{NotShadowCaster, NotShadowReceiver};
+
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/shadow_flags.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import shadow_flags.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut primitives = scene
+        .world
+        .query_filtered::<(&ChildOf, Has<NotShadowCaster>, Has<NotShadowReceiver>), With<Mesh3d>>();
+    let flags: Vec<(Entity, bool, bool)> = primitives
+        .iter(&scene.world)
+        .map(|(child_of, caster, receiver)| (child_of.parent(), caster, receiver))
+        .collect();
+    let flags_of = |name: &str| {
+        flags
+            .iter()
+            .find(|(parent, ..)| {
+                scene
+                    .world
+                    .get::<Name>(*parent)
+                    .is_some_and(|n| n.as_str() == name)
+            })
+            .map(|&(_, caster, receiver)| (caster, receiver))
+    };
+
+    // `Casts Shadows` comes from the geometry, `Receive Shadows` from the node
+    assert_eq!(flags_of("Crate"), Some((true, true)));
+    assert_eq!(flags_of("Plain"), Some((false, false)));
+}
+
+#[test]
+fn test_shadow_only() {
+    use bevy::camera::visibility::RenderLayers;
+    use bevy::light::NotShadowCaster;
+
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/shadow_only.fbx");
+    let settings = FbxLoaderSettings {
+        shadow_only_layer: Some(5),
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+        .expect("Failed to import shadow_only.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut primitives = scene
+        .world
+        .query_filtered::<(&ChildOf, Option<&RenderLayers>, Has<NotShadowCaster>), With<Mesh3d>>();
+    let primitives: Vec<(Entity, Option<RenderLayers>, bool)> = primitives
+        .iter(&scene.world)
+        .map(|(child_of, layers, caster)| (child_of.parent(), layers.cloned(), caster))
+        .collect();
+    let primitive_of = |name: &str| {
+        primitives
+            .iter()
+            .find(|(parent, ..)| {
+                scene
+                    .world
+                    .get::<Name>(*parent)
+                    .is_some_and(|n| n.as_str() == name)
+            })
+            .map(|(parent, layers, caster)| {
+                let marked = scene.world.get::<FbxShadowOnly>(*parent).is_some();
+                (marked, layers.clone(), *caster)
+            })
+    };
+
+    // Occluders cast shadows even if the file says otherwise
+    assert_eq!(
+        primitive_of("SHADOW_Hull"),
+        Some((true, Some(RenderLayers::layer(5)), false))
+    );
+    assert_eq!(
+        primitive_of("Statue"),
+        Some((true, Some(RenderLayers::layer(5)), false))
+    );
+    assert_eq!(primitive_of("SHADOW_Kept"), Some((false, None, true)));
+
+    let mut lights = scene
+        .world
+        .query_filtered::<&RenderLayers, With<DirectionalLight>>();
+    let layers = lights.single(&scene.world).expect("Sun missing");
+    assert_eq!(*layers, RenderLayers::layer(0).with(5));
+}
+
+#[test]
+fn test_billboards() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/billboards.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import billboards.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut nodes = scene.world.query::<(&Name, Option<&FbxBillboard>)>();
+    let billboards: HashMap<String, Option<FbxBillboardAxis>> = nodes
+        .iter(&scene.world)
+        .map(|(name, billboard)| (name.to_string(), billboard.map(|b| b.axis)))
+        .collect();
+
+    assert_eq!(billboards["BB_Fern"], Some(FbxBillboardAxis::Cylindrical));
+    assert_eq!(billboards["BBS_Leaf"], Some(FbxBillboardAxis::Spherical));
+    // The user property wins over the name
+    assert_eq!(billboards["Card"], Some(FbxBillboardAxis::Spherical));
+    assert_eq!(billboards["BB_Trunk"], None);
+    assert_eq!(billboards["Rock"], None);
+}
+
+#[test]
+fn test_take_scenes() {
+    let mut app = test_app();
+    app.init_asset::<AnimationGraph>();
+    let bytes = include_bytes!("fixtures/animated.fbx");
+
+    for take_scenes in [FbxTakeScenes::Posed, FbxTakeScenes::Animated] {
+        let settings = FbxLoaderSettings {
+            take_scenes,
+            ..Default::default()
+        };
+        let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+            .expect("Failed to import animated.fbx");
+        assert_eq!(fbx.scenes.len(), 2);
+        let scene_handle = fbx
+            .named_scenes
+            .get("Take 001")
+            .expect("Missing take scene")
+            .clone();
+
+        let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+        let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+        let mut players = scene.world.query::<&AnimationPlayer>();
+        let mut targets = scene.world.query::<&AnimationTargetId>();
+        let mut bound = scene.world.query::<&AnimatedBy>();
+        let animated = take_scenes == FbxTakeScenes::Animated;
+        assert_eq!(players.iter(&scene.world).count(), usize::from(animated));
+        assert!(targets.iter(&scene.world).count() > 0);
+        assert_eq!(bound.iter(&scene.world).count() > 0, animated);
+    }
+}
+
+#[test]
+fn test_world_origin() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+
+    let authored = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+    assert!(authored.metadata.world_offset.is_none());
+
+    let offset = DVec3::new(2_500_000.0, 0.0, -1_000_000.0);
+    let settings = FbxLoaderSettings {
+        world_origin: FbxWorldOrigin::Fixed(offset.to_array()),
+        ..Default::default()
+    };
+    let moved =
+        Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+    assert_eq!(moved.metadata.world_offset, Some(offset));
+
+    // The f64 world position is unaffected by the offset
+    let nodes = app.world().resource::<Assets<FbxNode>>();
+    let world_translation = |fbx: &Fbx| {
+        let node = nodes
+            .get(&fbx.named_nodes["Cube"])
+            .expect("FbxNode missing");
+        node.world_translation
+    };
+    let delta = world_translation(&moved) - world_translation(&authored);
+    assert!(delta.length() < 1e-6);
+}
+
+#[test]
+fn test_node_scenes() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        node_scenes: vec!["Cube".to_string(), "Missing/Node".to_string()],
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+
+    // Unknown paths are skipped
+    assert_eq!(fbx.scenes.len(), 2);
+    let scene_handle = fbx
+        .named_scenes
+        .get("Cube")
+        .expect("Missing node scene")
+        .clone();
+
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut meshes = scene.world.query::<(&Name, &Transform)>();
+    let (name, transform) = meshes
+        .iter(&scene.world)
+        .find(|(name, _)| name.as_str() == "Cube")
+        .expect("Cube entity missing");
+    assert_eq!(name.as_str(), "Cube");
+    // The subtree root is moved to the scene origin
+    assert!(transform.translation.abs_diff_eq(Vec3::ZERO, 1e-4));
+}
+
+#[derive(Component)]
+struct NodeMarker(String);
+
+struct MarkNodes;
+
+impl FbxSceneExtension for MarkNodes {
+    fn process_node(&self, node: &ufbx::Node, entity: &mut EntityWorldMut) {
+        entity.insert(NodeMarker(node.element.name.to_string()));
+    }
+}
+
+#[test]
+fn test_scene_extension() {
+    let mut app = test_app_with_plugin(FbxPlugin::with_extension(MarkNodes));
+    let bytes = include_bytes!("../assets/cube.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut markers = scene.world.query::<(&Name, &NodeMarker)>();
+    let marked: Vec<_> = markers.iter(&scene.world).collect();
+    assert!(!marked.is_empty());
+    for (name, marker) in marked {
+        assert_eq!(name.as_str(), marker.0);
+    }
+}
+
+#[test]
+fn test_exclude_nodes() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        exclude_nodes: vec!["Cu*".to_string()],
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut names = scene.world.query::<&Name>();
+    assert!(names.iter(&scene.world).all(|name| name.as_str() != "Cube"));
+}
+
+#[test]
+fn test_cameras_spawn_inactive() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        mark_active_camera: true,
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut cameras = scene
+        .world
+        .query::<(&Camera, &Name, Has<FbxActiveCamera>)>();
+    for (camera, name, is_active_camera) in cameras.iter(&scene.world) {
+        assert!(!camera.is_active);
+        assert_eq!(
+            is_active_camera,
+            fbx.metadata.active_camera.as_deref() == Some(name.as_str())
+        );
+    }
+}
+
+#[test]
+fn test_primitive_materials() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+
+    let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    for handle in &fbx.meshes {
+        let fbx_mesh = fbx_meshes.get(handle).expect("FbxMesh missing");
+        for primitive in &fbx_mesh.primitives {
+            if let Some(material) = &primitive.material {
+                assert!(materials.get(material).is_some());
+                assert!(fbx.materials.contains(material));
+                assert!(primitive.material_extras.is_some());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_recenter() {
+    use bevy_ufbx::RecenterMode;
+
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+    let settings = FbxLoaderSettings {
+        recenter: RecenterMode::BottomCenter,
+        root_transform_override: Some(Transform::from_scale(Vec3::splat(2.0))),
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+    let bounds = fbx.bounds.expect("Missing bounds");
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+
+    let mut roots = scene
+        .world
+        .query_filtered::<(&Name, &Transform), Without<ChildOf>>();
+    let roots: Vec<_> = roots.iter(&scene.world).collect();
+    assert_eq!(roots.len(), 1);
+    let (name, transform) = roots[0];
+    assert_eq!(name.as_str(), "FbxRoot");
+    let pivot = Vec3::from(bounds.center).with_y(bounds.min().y);
+    assert!(transform.translation.abs_diff_eq(-2.0 * pivot, 1e-5));
+    assert_eq!(transform.scale, Vec3::splat(2.0));
+}
+
+#[test]
+fn test_skinned_primitives_bound_to_bones() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/skinned.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import skinned.fbx");
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let world = app.world_mut();
+    world.resource_scope(|world, mut scenes: Mut<Assets<Scene>>| {
+        let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+        let mut skinned = scene.world.query::<&SkinnedMesh>();
+        let skinned: Vec<_> = skinned.iter(&scene.world).collect();
+        assert!(!skinned.is_empty());
+        let inverse_bindposes = world.resource::<Assets<SkinnedMeshInverseBindposes>>();
+        for skinned_mesh in skinned {
+            assert!(skinned_mesh.joints.len() <= MAX_JOINTS);
+            let bindposes = inverse_bindposes
+                .get(&skinned_mesh.inverse_bindposes)
+                .expect("Inverse bind poses missing");
+            assert_eq!(bindposes.len(), skinned_mesh.joints.len());
+            for &joint in &skinned_mesh.joints {
+                assert!(scene.world.get::<bevy_ufbx::FbxBone>(joint).is_some());
+            }
+        }
+    });
+}
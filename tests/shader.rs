@@ -0,0 +1,61 @@
+//! Tests for exporter-specific shader model detection.
+
+use bevy_ufbx::FbxShaderModel;
+use bevy_ufbx::shader::{ARNOLD_STANDARD_SURFACE_PROPERTIES, STINGRAY_PBS_PROPERTIES};
+
+#[test]
+fn test_detect_stingray_pbs() {
+    let names = [
+        "Maya|base_color",
+        "Maya|use_color_map",
+        "Maya|TEX_color_map",
+    ];
+
+    assert_eq!(
+        FbxShaderModel::from_property_names(names),
+        FbxShaderModel::StingrayPbs
+    );
+}
+
+#[test]
+fn test_detect_arnold_standard_surface() {
+    let names = ["Maya|base", "Maya|baseColor", "Maya|specularRoughness"];
+
+    assert_eq!(
+        FbxShaderModel::from_property_names(names),
+        FbxShaderModel::ArnoldStandardSurface
+    );
+}
+
+#[test]
+fn test_detect_classic_fbx() {
+    let names = ["DiffuseColor", "EmissiveColor", "ShadingModel"];
+
+    assert_eq!(
+        FbxShaderModel::from_property_names(names),
+        FbxShaderModel::Fbx
+    );
+    assert!(FbxShaderModel::Fbx.property_map().is_none());
+}
+
+#[test]
+fn test_property_map_contains() {
+    assert!(STINGRAY_PBS_PROPERTIES.contains("Maya|emissive_intensity"));
+    assert!(STINGRAY_PBS_PROPERTIES.contains("Maya|use_ao_map"));
+    assert!(!STINGRAY_PBS_PROPERTIES.contains("Maya|uv_offset"));
+
+    assert!(ARNOLD_STANDARD_SURFACE_PROPERTIES.contains("Maya|normalCamera"));
+    assert!(!ARNOLD_STANDARD_SURFACE_PROPERTIES.contains("Maya|coat"));
+    assert_eq!(
+        FbxShaderModel::ArnoldStandardSurface.property_map(),
+        Some(&ARNOLD_STANDARD_SURFACE_PROPERTIES)
+    );
+}
+
+#[test]
+fn test_anisotropy_rotation() {
+    use bevy_ufbx::material::anisotropy_rotation;
+
+    assert_eq!(anisotropy_rotation(0.0), 0.0);
+    assert!((anisotropy_rotation(0.5) - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+}
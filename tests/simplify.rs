@@ -0,0 +1,107 @@
+//! Tests for mesh simplification.
+
+mod common;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+use bevy_ufbx::simplify::{simplification_available, simplify_mesh};
+use bevy_ufbx::{Fbx, FbxLoaderSettings, FbxLodLevel, FbxMesh, FbxSimplification};
+use common::test_app;
+
+/// Flat `size` x `size` quad grid with normals and UVs.
+fn grid(size: u32) -> Mesh {
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    for y in 0..=size {
+        for x in 0..=size {
+            positions.push([x as f32, 0.0, y as f32]);
+            uvs.push([x as f32 / size as f32, y as f32 / size as f32]);
+        }
+    }
+    let mut indices = Vec::new();
+    let row = size + 1;
+    for y in 0..size {
+        for x in 0..size {
+            let corner = y * row + x;
+            indices.extend([corner, corner + row, corner + 1]);
+            indices.extend([corner + 1, corner + row, corner + row + 1]);
+        }
+    }
+    let normals = vec![[0.0, 1.0, 0.0]; positions.len()];
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+#[test]
+fn test_lod_level_constructors() {
+    assert_eq!(FbxLodLevel::ratio(0.5).ratio, 0.5);
+    assert_eq!(FbxLodLevel::max_error(0.05).ratio, 0.0);
+    assert_eq!(FbxLodLevel::max_error(0.05).max_error, 0.05);
+}
+
+#[test]
+fn test_simplify_requires_triangle_list() {
+    let mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 2]);
+    assert!(simplify_mesh(&mesh, FbxLodLevel::ratio(0.5), true).is_none());
+}
+
+#[test]
+fn test_simplify_grid() {
+    let mesh = grid(16);
+    let simplified = simplify_mesh(&mesh, FbxLodLevel::ratio(0.1), false);
+    if !simplification_available() {
+        assert!(simplified.is_none());
+        return;
+    }
+
+    let simplified = simplified.expect("Grid not simplified");
+    let index_count = simplified.indices().map(Indices::len).unwrap_or(0);
+    assert!(index_count > 0);
+    assert!(index_count < mesh.indices().map(Indices::len).unwrap_or(0));
+
+    // Unused vertices are dropped from every attribute
+    let vertex_count = simplified.count_vertices();
+    assert!(vertex_count < mesh.count_vertices());
+    let uvs = simplified
+        .attribute(Mesh::ATTRIBUTE_UV_0)
+        .expect("UVs missing");
+    assert_eq!(uvs.len(), vertex_count);
+    let indices = simplified.indices().expect("Indices missing");
+    assert!(indices.iter().all(|index| index < vertex_count));
+}
+
+#[test]
+fn test_simplification_lods() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/multi_material.fbx");
+    let simplification = FbxSimplification {
+        lods: vec![FbxLodLevel::ratio(0.5), FbxLodLevel::max_error(0.1)],
+        ..Default::default()
+    };
+    let settings = FbxLoaderSettings {
+        simplification: vec![("*".to_string(), simplification)],
+        ..Default::default()
+    };
+
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut())
+        .expect("Failed to import multi_material.fbx");
+
+    let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+    let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    for primitive in &fbx_mesh.primitives {
+        assert_eq!(primitive.lods.len(), 2);
+        for lod in &primitive.lods {
+            assert!(meshes.get(lod).is_some());
+        }
+    }
+}
@@ -0,0 +1,534 @@
+//! Tests for texture path resolution.
+
+mod common;
+
+use bevy::asset::{Asset, AssetPath, AssetServer, Assets, Handle, RenderAssetUsages};
+use bevy::image::{Image, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor};
+use bevy::mesh::{Mesh, VertexAttributeValues};
+use bevy::pbr::{StandardMaterial, UvChannel};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_ufbx::mipmap::generate_mipmaps;
+use bevy_ufbx::texture::{
+    TextureCache, TextureIndex, checkerboard_image, is_absolute_texture_path,
+    normalize_texture_path, remap_texture_path, resolve_deferred_textures, texture_cache_key,
+    texture_candidates, texture_color_space,
+};
+use bevy_ufbx::{
+    Fbx, FbxAssetLabel, FbxAssetSink, FbxColorSpace, FbxColorSpaceRule, FbxImageSettings,
+    FbxLoaderSettings, FbxMesh, FbxTexture, FbxTextureLoading, FbxWrapMode, TexturePathHook,
+};
+use common::test_app;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_normalize_texture_path() {
+    assert_eq!(
+        normalize_texture_path(r"C:\Users\artist\textures\wood.png"),
+        "C:/Users/artist/textures/wood.png"
+    );
+}
+
+#[test]
+fn test_is_absolute_texture_path() {
+    assert!(is_absolute_texture_path("C:/Users/artist/wood.png"));
+    assert!(is_absolute_texture_path("/home/artist/wood.png"));
+    assert!(!is_absolute_texture_path("textures/wood.png"));
+}
+
+#[test]
+fn test_texture_candidates_order() {
+    let candidates = texture_candidates(
+        r"textures\wood.png",
+        r"C:\Users\artist\textures\wood.png",
+        Path::new("models"),
+        &[PathBuf::from("shared/textures")],
+    );
+
+    assert_eq!(
+        candidates,
+        vec![
+            PathBuf::from("models/textures/wood.png"),
+            PathBuf::from("models/wood.png"),
+            PathBuf::from("shared/textures/wood.png"),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_without_index_uses_first_candidate() {
+    let index = TextureIndex::default();
+    let candidates = vec![PathBuf::from("a.png"), PathBuf::from("b.png")];
+
+    assert_eq!(index.resolve(&candidates), Some(PathBuf::from("a.png")));
+}
+
+#[test]
+fn test_resolve_with_index() {
+    let index = TextureIndex::from_files([PathBuf::from("shared/textures/wood.png")]);
+    let candidates = vec![
+        PathBuf::from("models/wood.png"),
+        PathBuf::from("shared/textures/wood.png"),
+    ];
+
+    assert_eq!(
+        index.resolve(&candidates),
+        Some(PathBuf::from("shared/textures/wood.png"))
+    );
+}
+
+#[test]
+fn test_resolve_case_insensitive() {
+    let index = TextureIndex::from_files([PathBuf::from("models/Wood.PNG")]);
+    let candidates = vec![PathBuf::from("models/wood.png")];
+
+    assert_eq!(
+        index.resolve(&candidates),
+        Some(PathBuf::from("models/Wood.PNG"))
+    );
+}
+
+#[test]
+fn test_resolve_missing() {
+    let index = TextureIndex::from_files([PathBuf::from("models/other.png")]);
+    let candidates = vec![PathBuf::from("models/wood.png")];
+
+    assert_eq!(index.resolve(&candidates), None);
+}
+
+#[test]
+fn test_remap_texture_path_exact() {
+    let mut settings = FbxLoaderSettings::default();
+    settings.texture_path_remap.insert(
+        "models/foo.psd".to_string(),
+        "converted/foo.png".to_string(),
+    );
+
+    assert_eq!(
+        remap_texture_path(Path::new("models/foo.psd"), &settings),
+        PathBuf::from("converted/foo.png")
+    );
+    assert_eq!(
+        remap_texture_path(Path::new("models/bar.png"), &settings),
+        PathBuf::from("models/bar.png")
+    );
+}
+
+#[test]
+fn test_remap_texture_path_extension() {
+    let mut settings = FbxLoaderSettings::default();
+    settings
+        .texture_extension_remap
+        .insert("psd".to_string(), "png".to_string());
+
+    assert_eq!(
+        remap_texture_path(Path::new("models/foo.PSD"), &settings),
+        PathBuf::from("models/foo.png")
+    );
+}
+
+#[test]
+fn test_remap_texture_path_hook() {
+    let settings = FbxLoaderSettings {
+        texture_path_hook: Some(TexturePathHook::new(|path| {
+            Some(
+                Path::new("ktx2")
+                    .join(path.file_name()?)
+                    .with_extension("ktx2"),
+            )
+        })),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        remap_texture_path(Path::new("models/foo.png"), &settings),
+        PathBuf::from("ktx2/foo.ktx2")
+    );
+}
+
+/// Sink recording requested texture paths.
+#[derive(Default)]
+struct RecordingSink {
+    labels: Vec<String>,
+    loaded: Vec<AssetPath<'static>>,
+    color_spaces: Vec<FbxColorSpace>,
+    samplers: Vec<Option<ImageSamplerDescriptor>>,
+    /// Asset source of the FBX file, like a `LoadContext` for a file in it
+    source: Option<&'static str>,
+}
+
+impl FbxAssetSink for RecordingSink {
+    fn add_labeled_asset<A: Asset>(&mut self, label: String, _asset: A) -> Handle<A> {
+        self.labels.push(label);
+        Handle::default()
+    }
+
+    fn load_texture(&mut self, path: AssetPath<'static>) -> Handle<Image> {
+        self.loaded.push(path);
+        Handle::default()
+    }
+
+    fn load_texture_with_color_space(
+        &mut self,
+        path: AssetPath<'static>,
+        color_space: FbxColorSpace,
+    ) -> Handle<Image> {
+        self.color_spaces.push(color_space);
+        self.load_texture(path)
+    }
+
+    fn load_texture_with_settings(
+        &mut self,
+        path: AssetPath<'static>,
+        settings: &FbxImageSettings,
+    ) -> Handle<Image> {
+        self.samplers.push(settings.sampler.clone());
+        match settings.color_space {
+            Some(color_space) => self.load_texture_with_color_space(path, color_space),
+            None => self.load_texture(path),
+        }
+    }
+
+    fn base_dir(&self) -> PathBuf {
+        PathBuf::new()
+    }
+
+    fn asset_path(&self, path: &Path) -> AssetPath<'static> {
+        let path = AssetPath::from(path.to_path_buf());
+        match self.source {
+            Some(source) => path.with_source(source),
+            None => path,
+        }
+    }
+}
+
+#[test]
+fn test_texture_cache_key() {
+    assert_eq!(
+        texture_cache_key(Path::new("models/./textures/../textures/wall.png")),
+        PathBuf::from("models/textures/wall.png")
+    );
+    assert_eq!(
+        texture_cache_key(Path::new(r"models\textures\wall.png")),
+        PathBuf::from("models/textures/wall.png")
+    );
+}
+
+#[test]
+fn test_texture_cache_dedupes_paths() {
+    let mut sink = RecordingSink::default();
+    let mut cache = TextureCache::default();
+
+    for _ in 0..10 {
+        cache.load(Path::new("models/textures/wall_albedo.png"), &mut sink);
+    }
+    cache.load(Path::new("models/./textures/wall_albedo.png"), &mut sink);
+    cache.load(Path::new("models/textures/wall_normal.png"), &mut sink);
+
+    assert_eq!(sink.loaded.len(), 2);
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn test_texture_cache_separates_color_spaces() {
+    let mut sink = RecordingSink::default();
+    let mut cache = TextureCache::default();
+    let path = Path::new("models/textures/mask.png");
+
+    cache.load(path, &mut sink);
+    cache.load_with_color_space(path, Some(FbxColorSpace::Linear), &mut sink);
+    cache.load_with_color_space(path, Some(FbxColorSpace::Linear), &mut sink);
+
+    assert_eq!(sink.loaded.len(), 2);
+    assert_eq!(sink.color_spaces, vec![FbxColorSpace::Linear]);
+}
+
+#[test]
+fn test_texture_cache_sampler() {
+    let mut sink = RecordingSink::default();
+    let sampler = ImageSamplerDescriptor {
+        anisotropy_clamp: 16,
+        ..ImageSamplerDescriptor::linear()
+    };
+    let mut cache = TextureCache::with_sampler(Some(sampler.clone()));
+
+    cache.load(Path::new("models/textures/wall.png"), &mut sink);
+    cache.load_with_color_space(
+        Path::new("models/textures/mask.png"),
+        Some(FbxColorSpace::Linear),
+        &mut sink,
+    );
+    assert_eq!(
+        sink.samplers,
+        vec![Some(sampler.clone()), Some(sampler.clone())]
+    );
+    assert_eq!(sink.color_spaces, vec![FbxColorSpace::Linear]);
+    assert_eq!(cache.images().len(), 2);
+
+    let settings = FbxImageSettings {
+        color_space: Some(FbxColorSpace::Linear),
+        sampler: Some(sampler.clone()),
+    };
+    let mut loader_settings = ImageLoaderSettings::default();
+    settings.apply(&mut loader_settings);
+    assert!(!loader_settings.is_srgb);
+    assert_eq!(loader_settings.sampler, ImageSampler::Descriptor(sampler));
+    assert!(FbxImageSettings::default().is_default());
+}
+
+#[test]
+fn test_generate_mipmaps() {
+    let size = Extent3d {
+        width: 4,
+        height: 2,
+        depth_or_array_layers: 1,
+    };
+    let mut data = vec![0; 4 * 2 * 4];
+    for pixel in data.chunks_exact_mut(4).step_by(2) {
+        pixel.copy_from_slice(&[255, 255, 255, 255]);
+    }
+    let mut image = Image::new(
+        size,
+        TextureDimension::D2,
+        data.clone(),
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    );
+
+    assert!(generate_mipmaps(&mut image));
+    assert_eq!(image.texture_descriptor.mip_level_count, 3);
+    let mips = image.data.as_ref().unwrap();
+    assert_eq!(mips.len(), data.len() + 2 * 4 + 4);
+    assert_eq!(&mips[data.len()..data.len() + 4], &[128, 128, 128, 128]);
+    assert!(!generate_mipmaps(&mut image));
+
+    let mut srgb = Image::new(
+        size,
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    assert!(generate_mipmaps(&mut srgb));
+    let mips = srgb.data.as_ref().unwrap();
+    // Half-covered texels average to linear 0.5, i.e. sRGB 188
+    assert_eq!(&mips[32..36], &[188, 188, 188, 128]);
+}
+
+#[test]
+fn test_texture_cache_keeps_asset_source() {
+    let mut sink = RecordingSink {
+        source: Some("remote"),
+        ..Default::default()
+    };
+    let mut cache = TextureCache::default();
+
+    cache.load(Path::new("models/textures/wall.png"), &mut sink);
+    assert_eq!(
+        sink.loaded,
+        vec![AssetPath::from("remote://models/textures/wall.png")]
+    );
+}
+
+#[test]
+fn test_missing_texture_placeholder() {
+    let mut sink = RecordingSink::default();
+    let mut cache = TextureCache::default();
+
+    cache.placeholder(&mut sink);
+    cache.placeholder(&mut sink);
+    assert_eq!(sink.labels, vec!["MissingTexture".to_string()]);
+    assert!(sink.loaded.is_empty());
+
+    let image = checkerboard_image();
+    let data = image.data.unwrap();
+    assert_eq!(&data[..8], &[255, 0, 255, 255, 0, 0, 0, 255]);
+}
+
+#[test]
+fn test_texture_color_space_rules() {
+    let rules = vec![
+        FbxColorSpaceRule {
+            slot: Some("*Roughness*".to_string()),
+            path: None,
+            color_space: FbxColorSpace::Linear,
+        },
+        FbxColorSpaceRule {
+            slot: Some("DiffuseColor".to_string()),
+            path: Some("*.exr".to_string()),
+            color_space: FbxColorSpace::Srgb,
+        },
+    ];
+
+    assert_eq!(
+        texture_color_space(&rules, &["SpecularRoughness"], "textures/rough.png"),
+        Some(FbxColorSpace::Linear)
+    );
+    assert_eq!(
+        texture_color_space(&rules, &["DiffuseColor"], r"textures\albedo.exr"),
+        Some(FbxColorSpace::Srgb)
+    );
+    assert_eq!(
+        texture_color_space(&rules, &["DiffuseColor"], "textures/albedo.png"),
+        None
+    );
+    assert_eq!(texture_color_space(&rules, &[], "textures/rough.png"), None);
+}
+
+#[test]
+fn test_texture_cache_defers_textures() {
+    let mut cache = TextureCache::default();
+    let path = Path::new("models/textures/wall_albedo.png");
+
+    let first = cache.defer(path, None);
+    let second = cache.defer(path, None);
+    let linear = cache.defer(path, Some(FbxColorSpace::Linear));
+
+    assert_eq!(first, second);
+    assert_ne!(first, linear);
+    assert!(cache.is_empty());
+
+    let deferred = cache.take_deferred();
+    assert_eq!(deferred.len(), 2);
+    assert_eq!(
+        deferred[&first.id()].path,
+        AssetPath::from("models/textures/wall_albedo.png")
+    );
+    assert!(cache.take_deferred().is_empty());
+}
+
+#[test]
+fn test_resolve_deferred_textures() {
+    use bevy::prelude::*;
+    use std::collections::HashMap;
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>();
+    let asset_server = app.world().resource::<AssetServer>().clone();
+
+    let mut cache = TextureCache::default();
+    let placeholder = cache.defer(Path::new("textures/wall.png"), None);
+    let recorded = cache.take_deferred();
+    let deferred: HashMap<_, _> = recorded
+        .iter()
+        .map(|(&id, texture)| (id, texture))
+        .collect();
+
+    let mut material = StandardMaterial {
+        base_color_texture: Some(placeholder.clone()),
+        ..Default::default()
+    };
+    assert!(resolve_deferred_textures(
+        &mut material,
+        &deferred,
+        &asset_server
+    ));
+
+    let loaded = material.base_color_texture.clone().unwrap();
+    assert_ne!(loaded, placeholder);
+    assert_eq!(
+        asset_server.get_path(loaded.id()),
+        Some(AssetPath::from("textures/wall.png"))
+    );
+    assert!(!resolve_deferred_textures(
+        &mut material,
+        &deferred,
+        &asset_server
+    ));
+}
+
+#[test]
+fn test_texture_sub_assets() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/textured.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import textured.fbx");
+
+    assert_eq!(fbx.textures.len(), 1);
+    assert_eq!(
+        fbx.label_for_texture("Checker"),
+        Some(FbxAssetLabel::Texture(0))
+    );
+    let textures = app.world().resource::<Assets<FbxTexture>>();
+    let texture = textures
+        .get(&fbx.named_textures["Checker"])
+        .expect("FbxTexture missing");
+    assert!(texture.filename.ends_with("checker.png"));
+    assert!(
+        texture
+            .path
+            .as_deref()
+            .is_some_and(|path| path.ends_with("checker.png"))
+    );
+    assert!(texture.image.is_some());
+    assert_eq!(texture.slots, vec!["DiffuseColor".to_string()]);
+    assert_eq!(texture.uv_set, "UVMap");
+    assert_eq!(texture.wrap_u, FbxWrapMode::Clamp);
+    assert_eq!(texture.wrap_v, FbxWrapMode::Repeat);
+
+    // Materials sample the image the texture resolved to
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    let material = materials.get(&fbx.materials[0]).expect("Material missing");
+    assert_eq!(material.base_color_texture, texture.image);
+}
+
+#[test]
+fn test_texture_uv_sets() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/detail_uv.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import detail_uv.fbx");
+
+    // The detail normal map samples the second UV set, imported as UV1
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    let material = materials.get(&fbx.materials[0]).expect("Material missing");
+    assert!(material.normal_map_texture.is_some());
+    assert_eq!(material.base_color_channel, UvChannel::Uv0);
+    assert_eq!(material.normal_map_channel, UvChannel::Uv1);
+    // The `Bake` set doesn't exist, so occlusion falls back to UV0
+    assert_eq!(material.occlusion_channel, UvChannel::Uv0);
+
+    let fbx_meshes = app.world().resource::<Assets<FbxMesh>>();
+    let fbx_mesh = fbx_meshes.get(&fbx.meshes[0]).expect("FbxMesh missing");
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    let mesh = meshes
+        .get(&fbx_mesh.primitives[0].mesh)
+        .expect("Mesh missing");
+    let Some(VertexAttributeValues::Float32x2(uv1)) = mesh.attribute(Mesh::ATTRIBUTE_UV_1) else {
+        panic!("UV1 missing");
+    };
+    assert!(uv1.contains(&[4.0, 4.0]));
+}
+
+#[test]
+fn test_eager_dependencies() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/textured.fbx");
+    let deferred = FbxLoaderSettings {
+        texture_loading: FbxTextureLoading::Deferred,
+        ..Default::default()
+    };
+    let eager = FbxLoaderSettings {
+        eager_dependencies: true,
+        ..deferred.clone()
+    };
+
+    let fbx =
+        Fbx::from_bytes(bytes, deferred, app.world_mut()).expect("Failed to import textured.fbx");
+    assert_eq!(fbx.deferred_textures.len(), 1);
+
+    // Eager loads request the texture right away instead of deferring it
+    let fbx =
+        Fbx::from_bytes(bytes, eager, app.world_mut()).expect("Failed to import textured.fbx");
+    assert!(fbx.deferred_textures.is_empty());
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    let material = materials.get(&fbx.materials[0]).expect("Material missing");
+    let image = material
+        .base_color_texture
+        .as_ref()
+        .expect("Texture missing");
+    let path = app.world().resource::<AssetServer>().get_path(image);
+    assert!(path.is_some_and(|path| path.to_string().ends_with("checker.png")));
+}
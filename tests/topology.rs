@@ -0,0 +1,47 @@
+//! Tests for mesh topology metadata.
+
+use bevy_ufbx::FbxMeshTopology;
+
+fn quad_topology() -> FbxMeshTopology {
+    FbxMeshTopology {
+        edges: vec![[0, 1], [1, 2], [2, 3], [3, 0]],
+        faces: vec![vec![0, 1, 2, 3]],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_no_creases() {
+    let topology = quad_topology();
+    assert!(!topology.has_creases());
+    assert!(topology.hard_edges().is_empty());
+}
+
+#[test]
+fn test_hard_edges_from_creases() {
+    let topology = FbxMeshTopology {
+        edge_creases: vec![0.0, 1.0, 0.5, 0.0],
+        ..quad_topology()
+    };
+    assert!(topology.has_creases());
+    assert_eq!(topology.hard_edges(), vec![1]);
+}
+
+#[test]
+fn test_hard_edges_from_smoothing() {
+    let topology = FbxMeshTopology {
+        edge_smoothing: vec![true, true, false, true],
+        ..quad_topology()
+    };
+    assert!(!topology.has_creases());
+    assert_eq!(topology.hard_edges(), vec![2]);
+}
+
+#[test]
+fn test_vertex_creases() {
+    let topology = FbxMeshTopology {
+        vertex_creases: vec![0.0, 0.0, 0.8, 0.0],
+        ..quad_topology()
+    };
+    assert!(topology.has_creases());
+}
@@ -1,6 +1,7 @@
 //! Tests for FBX types.
 
 use bevy::prelude::*;
+use bevy_ufbx::node::node_group_names;
 use bevy_ufbx::types::*;
 use std::collections::HashMap;
 
@@ -31,6 +32,8 @@ fn test_fbx_meta_default() {
     assert!(meta.creator.is_none());
     assert!(meta.creation_time.is_none());
     assert!(meta.original_application.is_none());
+    assert!(meta.version.is_none());
+    assert!(meta.format.is_none());
 }
 
 #[test]
@@ -144,3 +147,90 @@ fn test_fbx_camera_creation() {
     assert_eq!(camera.field_of_view_deg, 60.0);
     assert!((camera.aspect_ratio - 1.777).abs() < 0.001);
 }
+
+#[test]
+fn test_fbx_material_extras() {
+    let mut extras = FbxMaterialExtras::default();
+    assert!(extras.is_empty());
+
+    extras
+        .properties
+        .insert("Maya|roughness".to_string(), FbxPropertyValue::Number(0.5));
+    extras
+        .textures
+        .insert("Maya|TEX_ao_map".to_string(), "textures/ao.png".to_string());
+
+    assert!(!extras.is_empty());
+    assert_eq!(
+        extras.properties.get("Maya|roughness"),
+        Some(&FbxPropertyValue::Number(0.5))
+    );
+}
+
+#[test]
+fn test_types_registered_for_reflection() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.add_plugins(bevy_ufbx::FbxPlugin);
+
+    let registry = app.world().resource::<AppTypeRegistry>().read();
+    assert!(registry.get(std::any::TypeId::of::<Fbx>()).is_some());
+    assert!(registry.get(std::any::TypeId::of::<FbxMesh>()).is_some());
+    assert!(registry.get(std::any::TypeId::of::<FbxNode>()).is_some());
+    assert!(registry.get(std::any::TypeId::of::<FbxSkin>()).is_some());
+    assert!(registry.get(std::any::TypeId::of::<FbxMeta>()).is_some());
+    assert!(
+        registry
+            .get(std::any::TypeId::of::<FbxAxisSystem>())
+            .is_some()
+    );
+    assert!(
+        registry
+            .get_type_data::<bevy::asset::ReflectAsset>(std::any::TypeId::of::<Fbx>())
+            .is_some()
+    );
+}
+
+#[test]
+fn test_fbx_group_member_contains() {
+    let member = FbxGroupMember {
+        groups: vec!["collision_set".to_string(), "props".to_string()],
+    };
+    assert!(member.contains("collision_set"));
+    assert!(!member.contains("lights"));
+}
+
+#[test]
+fn test_node_group_names() {
+    let mut groups: HashMap<Box<str>, Vec<usize>> = HashMap::new();
+    groups.insert("props".into(), vec![1, 2]);
+    groups.insert("collision_set".into(), vec![2]);
+
+    let names = node_group_names(&groups);
+    assert_eq!(names[&1], vec!["props".to_string()]);
+    assert_eq!(
+        names[&2],
+        vec!["collision_set".to_string(), "props".to_string()]
+    );
+    assert!(!names.contains_key(&0));
+}
+
+#[test]
+fn test_fbx_skin_vertex_influences() {
+    let skin = FbxSkin {
+        index: 0,
+        name: "Body_Skin".to_string(),
+        joints: Vec::new(),
+        joint_names: vec!["Hips".to_string(), "Spine".to_string()],
+        vertex_joints: vec![[1, 0, 0, 0]],
+        vertex_weights: vec![[0.75, 0.25, 0.0, 0.0]],
+        inverse_bind_matrices: Handle::default(),
+        bind_pose: Handle::default(),
+    };
+    assert_eq!(
+        skin.vertex_influences(0),
+        vec![("Spine", 0.75), ("Hips", 0.25)]
+    );
+    assert!(skin.vertex_influences(1).is_empty());
+}
@@ -1,7 +1,11 @@
 //! Tests for utility conversion functions.
 
 use bevy::prelude::*;
-use bevy_ufbx::utils::{convert_matrix, convert_transform};
+use bevy_ufbx::types::{FbxAxisSystem, Handedness};
+use bevy_ufbx::utils::{
+    convert_matrix, convert_transform, fbx_axis_system, fbx_axis_vector, glob_match,
+    lens_shift_sub_view,
+};
 
 #[test]
 fn test_convert_matrix() {
@@ -94,3 +98,68 @@ fn test_convert_transform_with_rotation() {
     assert!((transform.rotation.z - 0.0).abs() < 0.001);
     assert!((transform.rotation.w - half_sqrt2 as f32).abs() < 0.001);
 }
+
+#[test]
+fn test_fbx_axis_vector() {
+    assert_eq!(fbx_axis_vector(0, 1), Vec3::X);
+    assert_eq!(fbx_axis_vector(1, 1), Vec3::Y);
+    assert_eq!(fbx_axis_vector(2, -1), Vec3::NEG_Z);
+}
+
+#[test]
+fn test_fbx_axis_system_handedness() {
+    // Maya default: right-handed Y-up
+    let maya = fbx_axis_system(Vec3::X, Vec3::Y, Vec3::Z);
+    assert_eq!(maya, FbxAxisSystem::BEVY);
+
+    // 3ds Max: right-handed Z-up with -Y front
+    let max = fbx_axis_system(Vec3::X, Vec3::Z, Vec3::NEG_Y);
+    assert_eq!(max.up, Vec3::Z);
+    assert_eq!(max.handedness, Handedness::Right);
+
+    // Left-handed Y-up
+    let left = fbx_axis_system(Vec3::X, Vec3::Y, Vec3::NEG_Z);
+    assert_eq!(left.handedness, Handedness::Left);
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("_proxy*", "_proxy_wall"));
+    assert!(glob_match("*_blockout", "tower_blockout"));
+    assert!(glob_match("Floor?", "Floor2"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("a*b*c", "axxbyyc"));
+    assert!(!glob_match("_proxy*", "wall_proxy"));
+    assert!(!glob_match("Floor?", "Floor12"));
+    assert!(!glob_match("a*b", "acbd"));
+}
+
+#[test]
+fn test_lens_shift_without_offset() {
+    assert!(lens_shift_sub_view(Vec2::ZERO, 1.5, 0.8).is_none());
+}
+
+#[test]
+fn test_lens_shift_sub_view() {
+    let fov = 60f32.to_radians();
+    let (sub_view, full_fov) =
+        lens_shift_sub_view(Vec2::new(0.25, 0.0), 2.0, fov).expect("Offset should shift");
+
+    // The view is grown by a quarter on each side and cropped at the right
+    assert_eq!(sub_view.size, UVec2::new(4096, 2048));
+    assert_eq!(sub_view.full_size, UVec2::new(6144, 2048));
+    assert_eq!(sub_view.offset, Vec2::new(2048.0, 0.0));
+    assert!((full_fov - fov).abs() < 1e-6);
+}
+
+#[test]
+fn test_lens_shift_up_grows_vertical_fov() {
+    let fov = 40f32.to_radians();
+    let (sub_view, full_fov) =
+        lens_shift_sub_view(Vec2::new(0.0, 0.1), 1.0, fov).expect("Offset should shift");
+
+    // Shifting up crops the top of the grown view
+    assert_eq!(sub_view.offset, Vec2::ZERO);
+    let expected = 2.0 * ((fov * 0.5).tan() * 1.2).atan();
+    assert!((full_fov - expected).abs() < 1e-6);
+}
@@ -0,0 +1,92 @@
+//! Tests for the validation pass helpers.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::validation::{
+    count_degenerate_triangles, count_non_manifold_edges, count_unnormalized_weights,
+    png_dimensions,
+};
+use bevy_ufbx::{Fbx, FbxIssue, FbxIssueKind, FbxLoaderSettings, FbxReport, FbxValidation};
+use common::test_app;
+
+#[test]
+fn test_degenerate_triangles() {
+    let positions = [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::X * 2.0];
+    // Valid, repeated vertex, collinear
+    let triangles = [0, 1, 2, 0, 0, 1, 0, 1, 3];
+
+    assert_eq!(count_degenerate_triangles(&positions, &triangles, 1e-10), 2);
+}
+
+#[test]
+fn test_non_manifold_edges() {
+    // Two triangles sharing edge 0-1 form a manifold strip
+    assert_eq!(count_non_manifold_edges(&[0, 1, 2, 1, 0, 3]), 0);
+    // A third triangle on the same edge makes it non-manifold
+    assert_eq!(count_non_manifold_edges(&[0, 1, 2, 1, 0, 3, 0, 1, 4]), 1);
+}
+
+#[test]
+fn test_unnormalized_weights() {
+    let sums = [1.0, 0.0, 0.5, 1.0005, 1.2];
+    assert_eq!(count_unnormalized_weights(&sums, 0.001), 2);
+}
+
+#[test]
+fn test_png_dimensions() {
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend_from_slice(&8192u32.to_be_bytes());
+    png.extend_from_slice(&1024u32.to_be_bytes());
+
+    assert_eq!(png_dimensions(&png), Some((8192, 1024)));
+    assert_eq!(png_dimensions(b"not a png"), None);
+}
+
+#[test]
+fn test_report_queries() {
+    let report = FbxReport {
+        issues: vec![
+            FbxIssue {
+                kind: FbxIssueKind::DegenerateTriangles,
+                element: "Cube".to_string(),
+                count: 3,
+            },
+            FbxIssue {
+                kind: FbxIssueKind::DegenerateTriangles,
+                element: "Sphere".to_string(),
+                count: 2,
+            },
+        ],
+    };
+
+    assert!(!report.is_clean());
+    assert!(report.has(FbxIssueKind::DegenerateTriangles));
+    assert!(!report.has(FbxIssueKind::MissingUvs));
+    assert_eq!(report.count(FbxIssueKind::DegenerateTriangles), 5);
+    assert!(FbxReport::default().is_clean());
+}
+
+#[test]
+fn test_validation_report() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/cube.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import cube.fbx");
+    assert!(fbx.report.is_none());
+
+    let settings = FbxLoaderSettings {
+        validation: Some(FbxValidation::default()),
+        ..Default::default()
+    };
+    let fbx = Fbx::from_bytes(bytes, settings, app.world_mut()).expect("Failed to import cube.fbx");
+
+    let reports = app.world().resource::<Assets<FbxReport>>();
+    let report = reports
+        .get(&fbx.report.expect("Missing report"))
+        .expect("FbxReport missing");
+    assert!(!report.has(FbxIssueKind::DegenerateTriangles));
+    assert!(!report.has(FbxIssueKind::NonManifoldEdges));
+    assert!(!report.has(FbxIssueKind::IndexOutOfRange));
+}
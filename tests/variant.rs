@@ -0,0 +1,46 @@
+//! Tests for material variants.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_ufbx::{Fbx, FbxLoaderSettings, apply_variant};
+use common::test_app;
+
+#[test]
+fn test_material_variants() {
+    let mut app = test_app();
+    let bytes = include_bytes!("fixtures/material_variants.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import material_variants.fbx");
+    assert_eq!(fbx.material_variants, vec!["damaged".to_string()]);
+    let red = fbx.named_materials["Red"].clone();
+    let rust = fbx.named_materials["Rust"].clone();
+
+    let scene_handle = fbx.default_scene.expect("Missing default scene");
+    let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+    let scene = scenes.get_mut(&scene_handle).expect("Scene missing");
+    let mut nodes = scene.world.query::<(Entity, &Name)>();
+    let mut node = |world: &World, name: &str| {
+        let mut nodes = nodes.iter(world);
+        nodes
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(entity, _)| entity)
+    };
+    let crate_node = node(&scene.world, "Crate").expect("Crate missing");
+    let spare_node = node(&scene.world, "Spare").expect("Spare missing");
+    let material_of = |world: &World, node: Entity| {
+        let children = world.get::<Children>(node).expect("Primitives missing");
+        world
+            .get::<MeshMaterial3d<StandardMaterial>>(children[0])
+            .map(|m| m.0.clone())
+    };
+
+    assert_eq!(apply_variant(&mut scene.world, crate_node, "damaged"), 1);
+    assert_eq!(material_of(&scene.world, crate_node), Some(rust));
+    assert_eq!(apply_variant(&mut scene.world, spare_node, "damaged"), 0);
+
+    // Unknown variants restore the authored materials
+    assert_eq!(apply_variant(&mut scene.world, crate_node, ""), 1);
+    assert_eq!(material_of(&scene.world, crate_node), Some(red));
+}
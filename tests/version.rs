@@ -0,0 +1,72 @@
+//! Tests for FBX version detection.
+
+mod common;
+
+use bevy_ufbx::version::{SUPPORTED_FBX_VERSIONS, detect_fbx_version, is_legacy_version};
+use bevy_ufbx::{Fbx, FbxError, FbxFileFormat, FbxLoaderSettings};
+use common::test_app;
+
+#[test]
+fn test_detect_binary_version() {
+    let bytes = include_bytes!("../assets/maya_cube_7400_binary.fbx");
+    assert_eq!(
+        detect_fbx_version(bytes),
+        Some((FbxFileFormat::Binary, 7400))
+    );
+}
+
+#[test]
+fn test_detect_ascii_version() {
+    let bytes = b"; FBX 7.3.0 project file\nFBXHeaderExtension:  {\n\tFBXVersion: 7300\n}\n";
+    assert_eq!(
+        detect_fbx_version(bytes),
+        Some((FbxFileFormat::Ascii, 7300))
+    );
+}
+
+#[test]
+fn test_detect_legacy_ascii_version() {
+    let bytes = b"; FBX 6.1.0 project file\n; Copyright 1993-2008 Autodesk\n";
+    assert_eq!(
+        detect_fbx_version(bytes),
+        Some((FbxFileFormat::Ascii, 6100))
+    );
+    assert!(is_legacy_version(6100));
+    assert!(!is_legacy_version(7400));
+}
+
+#[test]
+fn test_detect_unknown_version() {
+    assert_eq!(detect_fbx_version(b"not an fbx file"), None);
+}
+
+#[test]
+fn test_rejects_ancient_version() {
+    let mut app = test_app();
+    let mut bytes = b"; FBX 2.0.0 project file\n".to_vec();
+    bytes.resize(64, b'\n');
+
+    let result = Fbx::from_bytes(&bytes, FbxLoaderSettings::default(), app.world_mut());
+    match result {
+        Err(FbxError::UnsupportedVersion {
+            found,
+            supported_range,
+        }) => {
+            assert_eq!(found, 2000);
+            assert_eq!(supported_range, SUPPORTED_FBX_VERSIONS);
+        }
+        other => panic!("Expected UnsupportedVersion, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_version_recorded_in_meta() {
+    let mut app = test_app();
+    let bytes = include_bytes!("../assets/maya_cube_7400_binary.fbx");
+
+    let fbx = Fbx::from_bytes(bytes, FbxLoaderSettings::default(), app.world_mut())
+        .expect("Failed to import maya_cube_7400_binary.fbx");
+
+    assert_eq!(fbx.metadata.version, Some(7400));
+    assert_eq!(fbx.metadata.format, Some(FbxFileFormat::Binary));
+}